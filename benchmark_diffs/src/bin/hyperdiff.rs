@@ -0,0 +1,265 @@
+//! Diff two Java files, two directories or two commits of a local git repository,
+//! the result is printed like GumTree does.
+//!
+//! eg. `hyperdiff A.java B.java`, `hyperdiff --repository . HEAD~1 HEAD --format json`
+//! or as a git difftool `git difftool -x hyperdiff`
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+use hyper_ast::{
+    store::{defaults::NodeIdentifier, SimpleStores},
+    types::{HyperAST, WithSerialization},
+};
+use hyper_ast_benchmark_diffs::preprocess::{parse_dir_pair, JavaPreprocessFileSys};
+use hyper_ast_cvs_git::{
+    git::{open_local_repository, Repository},
+    preprocessed::PreProcessedRepository,
+    TStore,
+};
+use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+use hyper_diff::{
+    actions::script_generator2::SimpleAction,
+    algorithms::{self, DiffResult},
+    decompressed_tree_store::CompletePostOrder,
+    matchers::{mapping_store::VecStore, Mapper},
+    output::{gumtree, Resolver},
+    tree::tree_path::CompressedTreePath,
+};
+
+#[cfg(not(target_env = "msvc"))]
+use jemallocator::Jemalloc;
+
+#[cfg(not(target_env = "msvc"))]
+#[global_allocator]
+static GLOBAL: Jemalloc = Jemalloc;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Cli {
+    /// The source version: a Java file, a directory,
+    /// or a commit or reference of the repository given with --repository
+    src: String,
+
+    /// The destination version, of the same kind as the source
+    dst: String,
+
+    /// A local git repository, whose Maven modules are diffed
+    #[clap(short, long)]
+    repository: Option<PathBuf>,
+
+    #[clap(short, long, value_enum, default_value = "gumtree")]
+    algorithm: Algorithm,
+
+    /// The minimal height of the subtrees matched in the top-down phase
+    #[clap(long, default_value_t = 1)]
+    min_height: usize,
+
+    /// The similarity threshold of the bottom-up phase, between 0 and 1
+    #[clap(long, default_value_t = 0.5)]
+    sim_threshold: f64,
+
+    /// The maximal size of the subtrees matched optimally in the bottom-up phase
+    #[clap(long, default_value_t = 1000)]
+    max_size: usize,
+
+    #[clap(short, long, value_enum, default_value = "text")]
+    format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Algorithm {
+    #[clap(name = "gumtree")]
+    Gumtree,
+    #[clap(name = "gumtree_lazy")]
+    GumtreeLazy,
+    #[clap(name = "gumtree_partial_lazy")]
+    GumtreePartialLazy,
+    /// Zhang and Shasha, ignores the hyperparameters and is only practical on small trees
+    #[clap(name = "zs")]
+    Zs,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    Text,
+    Json,
+    Xml,
+}
+
+/// The hyperparameters of the matchers
+struct Hyperparameters {
+    min_height: usize,
+    max_size: usize,
+    sim_threshold: f64,
+}
+
+impl TryFrom<&Cli> for Hyperparameters {
+    type Error = String;
+
+    fn try_from(cli: &Cli) -> Result<Self, Self::Error> {
+        if !(0. ..=1.).contains(&cli.sim_threshold) {
+            return Err(format!(
+                "--sim-threshold {} should be between 0 and 1",
+                cli.sim_threshold
+            ));
+        }
+        Ok(Self {
+            min_height: cli.min_height,
+            max_size: cli.max_size,
+            sim_threshold: cli.sim_threshold,
+        })
+    }
+}
+
+fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    let cli = Cli::parse();
+    if let Err(e) = run(&cli) {
+        eprintln!("error: {}", e);
+        std::process::exit(2);
+    }
+}
+
+fn run(cli: &Cli) -> Result<(), String> {
+    let p = Hyperparameters::try_from(cli)?;
+    if let Some(repository) = &cli.repository {
+        let mut preprocessed = PreProcessedRepository::new(&repository.to_string_lossy());
+        let mut repository = open_local_repository(repository);
+        let src = resolve_commit(&repository, &cli.src)?;
+        let dst = resolve_commit(&repository, &cli.dst)?;
+        let src = preprocessed.pre_process_single(&mut repository, &src, "");
+        let dst = preprocessed.pre_process_single(&mut repository, &dst, "");
+        let src = preprocessed.commits[&src].ast_root;
+        let dst = preprocessed.commits[&dst].ast_root;
+        return diff(cli, &p, &preprocessed.processor.main_stores, src, dst);
+    }
+    let (src, dst) = (Path::new(&cli.src), Path::new(&cli.dst));
+    if src.is_dir() && dst.is_dir() {
+        let mut java_gen = JavaPreprocessFileSys {
+            main_stores: SimpleStores::<TStore>::default(),
+            java_md_cache: Default::default(),
+        };
+        let (src, dst) = parse_dir_pair(&mut java_gen, src, dst);
+        diff(
+            cli,
+            &p,
+            &java_gen.main_stores,
+            src.compressed_node,
+            dst.compressed_node,
+        )
+    } else if src.is_file() && dst.is_file() {
+        let mut stores = SimpleStores::<TStore>::default();
+        let mut md_cache = Default::default();
+        let mut java_tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+        let src = parse_java_file(&mut java_tree_gen, src)?;
+        let dst = parse_java_file(&mut java_tree_gen, dst)?;
+        diff(cli, &p, &stores, src, dst)
+    } else {
+        Err(format!(
+            "{} and {} should both be files or both be directories",
+            cli.src, cli.dst
+        ))
+    }
+}
+
+/// Resolve a revision, eg. `HEAD~1`, a branch, a tag or a short hash, to the id of its commit
+fn resolve_commit(repository: &Repository, rev: &str) -> Result<String, String> {
+    let commit = repository
+        .revparse_single(rev)
+        .and_then(|x| x.peel_to_commit())
+        .map_err(|e| format!("{} is not a commit: {}", rev, e.message()))?;
+    Ok(commit.id().to_string())
+}
+
+fn parse_java_file(
+    java_tree_gen: &mut JavaTreeGen<'_, '_, TStore>,
+    path: &Path,
+) -> Result<NodeIdentifier, String> {
+    if path.extension().map_or(true, |x| x != "java") {
+        return Err(format!("{:?} is not a Java file", path));
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{:?}: {}", path, e))?;
+    let tree = match JavaTreeGen::<TStore>::tree_sitter_parse(text.as_bytes()) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let name = path.file_name().unwrap().to_string_lossy();
+    let full_node = java_tree_gen.generate_file(name.as_bytes(), text.as_bytes(), tree.walk());
+    Ok(full_node.local.compressed_node)
+}
+
+fn diff(
+    cli: &Cli,
+    p: &Hyperparameters,
+    stores: &SimpleStores<TStore>,
+    src: NodeIdentifier,
+    dst: NodeIdentifier,
+) -> Result<(), String> {
+    let (src, dst) = (&src, &dst);
+    let out = match cli.algorithm {
+        Algorithm::Gumtree => {
+            let diff = algorithms::gumtree::diff_with_hyperparameters(
+                stores,
+                src,
+                dst,
+                p.min_height,
+                p.max_size,
+                p.sim_threshold,
+            );
+            render(stores, diff, cli.format)
+        }
+        Algorithm::GumtreeLazy => {
+            let diff = algorithms::gumtree_lazy::diff_with_hyperparameters(
+                stores,
+                src,
+                dst,
+                p.min_height,
+                p.max_size,
+                p.sim_threshold,
+            );
+            render(stores, diff, cli.format)
+        }
+        Algorithm::GumtreePartialLazy => {
+            let diff = algorithms::gumtree_partial_lazy::diff_with_hyperparameters(
+                stores,
+                src,
+                dst,
+                p.min_height,
+                p.max_size,
+                p.sim_threshold,
+            );
+            render(stores, diff, cli.format)
+        }
+        Algorithm::Zs => render(stores, algorithms::zs::diff(stores, src, dst), cli.format),
+    }?;
+    print!("{}", out);
+    Ok(())
+}
+
+type CDS<T> = CompletePostOrder<T, u32>;
+
+fn render<'store, HAST, MD>(
+    stores: &'store HAST,
+    diff: DiffResult<
+        SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>,
+        Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+        MD,
+    >,
+    format: Format,
+) -> Result<String, String>
+where
+    HAST: HyperAST<'store>,
+    HAST::IdN: Clone,
+    HAST::T: WithSerialization,
+{
+    let actions = diff.actions.ok_or("failed to generate the edit script")?;
+    let resolver = Resolver::new(stores, &diff.mapper.mapping);
+    let actions: Vec<_> = actions.iter().map(|a| resolver.resolve(a)).collect();
+    let matches = resolver.matches();
+    Ok(match format {
+        Format::Text => gumtree::render_text(&matches, &actions),
+        Format::Json => gumtree::render(&matches, &actions),
+        Format::Xml => gumtree::render_xml(&matches, &actions),
+    })
+}
@@ -2,18 +2,23 @@ use std::time::Duration;
 
 use axum::{
     error_handling::HandleErrorLayer,
-    response::{IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
     BoxError, Json, Router,
 };
+use futures::{Stream, StreamExt};
 use http::StatusCode;
 use tower::ServiceBuilder;
 use tower_http::trace::TraceLayer;
 
 use crate::{
-    commit, fetch, file,
+    commit, diff, fetch, file, modules, querying,
     scripting::{
-        self, ScriptContent, ScriptContentDepth, ScriptingError, ScriptingParam,
+        self, Cancellation, ScriptContent, ScriptContentBatch, ScriptContentDepth,
+        ScriptingError, ScriptingParam,
     },
     track, view, SharedState,
 };
@@ -36,7 +41,12 @@ async fn scripting(
     axum::extract::State(state): axum::extract::State<SharedState>,
     axum::extract::Json(script): axum::extract::Json<ScriptContent>,
 ) -> axum::response::Result<Json<scripting::ComputeResult>> {
-    let r = scripting::simple(script, state, path)?;
+    // dropped with this future, eg. if the client disconnects or on timeout
+    let cancellation = Cancellation::default();
+    let cancelled = cancellation.flag();
+    let r = tokio::task::spawn_blocking(move || scripting::simple(script, state, path, cancelled))
+        .await
+        .map_err(|e| ScriptingError::Other(e.to_string()))??;
     Ok(r)
 }
 async fn scripting_depth(
@@ -44,10 +54,61 @@ async fn scripting_depth(
     axum::extract::State(state): axum::extract::State<SharedState>,
     axum::extract::Json(script): axum::extract::Json<ScriptContentDepth>,
 ) -> axum::response::Result<Json<scripting::ComputeResults>> {
-    let r = scripting::simple_depth(script, state, path)?;
+    let cancellation = Cancellation::default();
+    let cancelled = cancellation.flag();
+    let r = tokio::task::spawn_blocking(move || {
+        scripting::simple_depth(script, state, path, cancelled)
+    })
+    .await
+    .map_err(|e| ScriptingError::Other(e.to_string()))??;
+    Ok(r)
+}
+
+async fn scripting_batch(
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    axum::extract::Json(script): axum::extract::Json<ScriptContentBatch>,
+) -> axum::response::Result<Json<scripting::ComputeResults>> {
+    let cancellation = Cancellation::default();
+    let cancelled = cancellation.flag();
+    let r = tokio::task::spawn_blocking(move || scripting::simple_batch(script, state, cancelled))
+        .await
+        .map_err(|e| ScriptingError::Other(e.to_string()))??;
     Ok(r)
 }
 
+/// Streams the [`scripting::DepthEvent`]s as server-sent events,
+/// followed by a `failed` event with the [`ScriptingError`] or by a `done` event.
+async fn scripting_depth_stream(
+    axum::extract::Path(path): axum::extract::Path<ScriptingParam>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    axum::extract::Json(script): axum::extract::Json<ScriptContentDepth>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let cancellation = Cancellation::default();
+    let cancelled = cancellation.flag();
+    let (tx, rx) = futures::channel::mpsc::unbounded();
+    tokio::task::spawn_blocking(move || {
+        let r = scripting::simple_depth_stream(script, state, path, cancelled, |event| {
+            let name = match &event {
+                scripting::DepthEvent::Preparing { .. } => "preparing",
+                scripting::DepthEvent::Prepared { .. } => "prepared",
+                scripting::DepthEvent::Computed { .. } => "computed",
+            };
+            let _ = tx.unbounded_send(Event::default().event(name).json_data(event));
+        });
+        let end = match r {
+            Ok(()) => Ok(Event::default().event("done").data("")),
+            Err(e) => Event::default().event("failed").json_data(e),
+        };
+        let _ = tx.unbounded_send(end);
+    });
+    // the stream is dropped when the client disconnects, cancelling the evaluation
+    let stream = rx.map(move |x| {
+        let _cancellation = &cancellation;
+        x
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 pub fn scripting_app(_st: SharedState) -> Router<SharedState> {
     let scripting_service_config = ServiceBuilder::new()
         .layer(HandleErrorLayer::new(|e: BoxError| async move {
@@ -60,6 +121,17 @@ pub fn scripting_app(_st: SharedState) -> Router<SharedState> {
         // .request_body_limit(1024 * 5_000 /* ~5mb */)
         .timeout(Duration::from_secs(10))
         .layer(TraceLayer::new_for_http());
+    // building many commits can take longer than the timeout,
+    // then evaluations are bounded by the limits of the scripts, see [`scripting::ScriptLimits`]
+    let long_scripting_service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(16)
+        .buffer(200)
+        .rate_limit(10, Duration::from_secs(5))
+        .layer(TraceLayer::new_for_http());
     Router::new()
         .route(
             "/script/github/:user/:name/:commit",
@@ -67,7 +139,15 @@ pub fn scripting_app(_st: SharedState) -> Router<SharedState> {
         )
         .route(
             "/script-depth/github/:user/:name/:commit",
-            post(scripting_depth).layer(scripting_service_config.clone()), // .with_state(Arc::clone(&shared_state)),
+            post(scripting_depth).layer(long_scripting_service_config.clone()), // .with_state(Arc::clone(&shared_state)),
+        )
+        .route(
+            "/script-depth-stream/github/:user/:name/:commit",
+            post(scripting_depth_stream).layer(long_scripting_service_config.clone()),
+        )
+        .route(
+            "/script-batch",
+            post(scripting_batch).layer(long_scripting_service_config.clone()),
         )
         .route(
             "/shared-scripts-db",
@@ -309,6 +389,124 @@ async fn commit_metadata(
     commit::commit_metadata(state, path).map_err(|err| err.into())
 }
 
+pub fn diff_route(_st: SharedState) -> Router<SharedState> {
+    // building both commits then matching them can take longer than a timeout, like long scripts
+    let service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(8)
+        .buffer(20)
+        .rate_limit(2, Duration::from_secs(5))
+        // .request_body_limit(1024 * 5_000 /* ~5mb */)
+        .layer(TraceLayer::new_for_http());
+    Router::new()
+        .route(
+            "/diff/github/:user/:name/:before/:after/*path",
+            get(diff_code).layer(service_config.clone()),
+        )
+        .route(
+            "/diff/github/:user/:name/:before/:after/",
+            get(diff_code).layer(service_config.clone()),
+        )
+}
+
+async fn diff_code(
+    axum::extract::Path(path): axum::extract::Path<diff::DiffParam>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+) -> axum::response::Result<diff::DiffResult> {
+    dbg!(&path);
+    let r = tokio::task::spawn_blocking(move || diff::diff(state, path))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(r)
+}
+
+pub fn modules_route(_st: SharedState) -> Router<SharedState> {
+    let service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(8)
+        .buffer(20)
+        .rate_limit(2, Duration::from_secs(5))
+        // .request_body_limit(1024 * 5_000 /* ~5mb */)
+        // like the queries, up to two commits are built before computing the graphs
+        .timeout(Duration::from_secs(60))
+        .layer(TraceLayer::new_for_http());
+    Router::new().route(
+        "/modules/github/:user/:name/:commit",
+        get(module_graph).layer(service_config.clone()),
+    )
+}
+
+async fn module_graph(
+    axum::extract::Path(path): axum::extract::Path<modules::Param>,
+    axum::extract::Query(query): axum::extract::Query<modules::ModulesQuery>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+) -> axum::response::Result<modules::ModulesResult> {
+    dbg!(&path, &query);
+    // building the commits can take a while, it must not block the runtime
+    let r = tokio::task::spawn_blocking(move || modules::modules(state, path, query))
+        .await
+        .map_err(|err| err.to_string())?;
+    r.map_err(|err| err.into())
+}
+
+pub fn query_route(_st: SharedState) -> Router<SharedState> {
+    let service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(8)
+        .buffer(20)
+        .rate_limit(2, Duration::from_secs(5))
+        // .request_body_limit(1024 * 5_000 /* ~5mb */)
+        .timeout(Duration::from_secs(60))
+        .layer(TraceLayer::new_for_http());
+    // a batch builds commits of many repositories, it would not fit in the timeout
+    let batch_service_config = ServiceBuilder::new()
+        .layer(HandleErrorLayer::new(|e: BoxError| async move {
+            dbg!(e);
+        }))
+        .load_shed()
+        .concurrency_limit(8)
+        .buffer(20)
+        .rate_limit(2, Duration::from_secs(5))
+        .layer(TraceLayer::new_for_http());
+    Router::new()
+        .route(
+            "/query-history/github/:user/:name/:commit",
+            post(query_history).layer(service_config.clone()),
+        )
+        .route(
+            "/query-batch",
+            post(query_batch).layer(batch_service_config.clone()),
+        )
+}
+
+async fn query_history(
+    axum::extract::Path(path): axum::extract::Path<querying::QueryParam>,
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    axum::extract::Json(content): axum::extract::Json<querying::QueryContent>,
+) -> axum::response::Result<querying::QueryResults> {
+    dbg!(&path);
+    querying::query_history(state, path, content).map_err(|err| err.into())
+}
+
+async fn query_batch(
+    axum::extract::State(state): axum::extract::State<SharedState>,
+    axum::extract::Json(content): axum::extract::Json<querying::QueryContentBatch>,
+) -> axum::response::Result<querying::QueryBatchResults> {
+    let r = tokio::task::spawn_blocking(move || querying::query_batch(state, content))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(r)
+}
+
 pub struct Timed<T> {
     pub(crate) time: f64,
     pub(crate) content: T,
@@ -2,6 +2,8 @@ use std::net::SocketAddr;
 
 use clap::Parser;
 
+use crate::scripting::ScriptLimits;
+
 #[derive(Parser)]
 #[clap(version, about, long_about = None)]
 pub(super) struct Options {
@@ -17,8 +19,49 @@ pub(super) struct Options {
     /// 
     /// use the following syntax: <forge>/<user>/<name>:<config>
     /// example: github.com/INRIA/spoon:Java
+    /// or for a repository already on disk: file:///<path>:<config>
     #[clap(short, long)]
     pub repository: Vec<RepoConfig>,
+
+    /// Directory where remote repositories are cloned
+    #[clap(long, default_value = hyper_ast_cvs_git::git::DEFAULT_REPOSITORIES_CACHE)]
+    pub repositories_cache: std::path::PathBuf,
+
+    /// Directory where processed commits are persisted,
+    /// they are restored from it on restart instead of being processed again
+    #[clap(long)]
+    pub store: Option<std::path::PathBuf>,
+
+    /// Maximum number of operations evaluated by a script request
+    #[clap(long, default_value_t = ScriptLimits::default().max_operations)]
+    pub script_max_operations: u64,
+
+    /// Maximum duration of a script request, in seconds
+    #[clap(long, default_value_t = ScriptLimits::default().max_duration.as_secs())]
+    pub script_max_duration: u64,
+
+    /// Maximum size of the results of a script request, in bytes
+    #[clap(long, default_value_t = ScriptLimits::default().max_result_size)]
+    pub script_max_result_size: usize,
+
+    /// Maximum depth of nested function calls in scripts
+    #[clap(long, default_value_t = ScriptLimits::default().max_call_depth)]
+    pub script_max_call_depth: usize,
+
+    /// Maximum number of values accumulated by scripts on subtrees kept to be reused across requests
+    #[clap(long, default_value_t = crate::ScriptCache::DEFAULT_CAPACITY)]
+    pub script_cache_capacity: usize,
+}
+
+impl Options {
+    pub(super) fn script_limits(&self) -> ScriptLimits {
+        ScriptLimits {
+            max_operations: self.script_max_operations,
+            max_duration: std::time::Duration::from_secs(self.script_max_duration),
+            max_result_size: self.script_max_result_size,
+            max_call_depth: self.script_max_call_depth,
+        }
+    }
 }
 
 pub(super) struct RepoConfig {
@@ -30,7 +73,7 @@ impl std::str::FromStr for RepoConfig {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (repo, config) = s.split_once(":").ok_or("")?;
+        let (repo, config) = s.rsplit_once(":").ok_or("")?;
         let repo = repo.parse()?;
         let config = config.parse()?;
 
@@ -0,0 +1,318 @@
+use std::path::PathBuf;
+
+use axum::{response::IntoResponse, Json};
+use hyper_ast::{
+    position::{compute_position, path_with_spaces},
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    types::{HyperAST, LabelStore, TypeStore},
+};
+use hyper_ast_cvs_git::{preprocessed::child_at_path, SimpleStores};
+use hyper_diff::{
+    actions::script_generator2::{self, ScriptGenerator, SimpleAction},
+    decompressed_tree_store::{
+        bfs_wrapper::SimpleBfsMapper, CompletePostOrder, DecompressedWithParent,
+        ShallowDecompressedTreeStore,
+    },
+    matchers::{mapping_store::MonoMappingStore, Mapper},
+    tree::tree_path::CompressedTreePath,
+};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::{no_space, MappingCache, SharedState};
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DiffParam {
+    user: String,
+    name: String,
+    before: String,
+    after: String,
+    /// restrict the diff to the subtree at this path, in both versions
+    path: Option<String>,
+}
+
+/// A range of code in one of the diffed versions
+#[derive(Serialize, Clone, Debug)]
+pub struct CodeRange {
+    file: String,
+    start: usize,
+    end: usize,
+    /// offsets from the diffed root, spaces included
+    path: Vec<Idx>,
+}
+
+/// An action of the edit script, with its positions resolved in source code
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Act {
+    /// `at` is in the `before` version
+    Delete { at: CodeRange },
+    /// `at` is in the `before` version, `to` is the node it is mapped to in the `after` version
+    Update {
+        at: CodeRange,
+        to: Option<CodeRange>,
+        new: String,
+    },
+    /// `from` is in the `before` version, `at` is in the `after` version
+    Move { from: CodeRange, at: CodeRange },
+    /// `from` is in the `before` version, `at` is in the `after` version
+    MovUpd {
+        from: CodeRange,
+        at: CodeRange,
+        new: String,
+    },
+    /// `at` is in the `after` version
+    Insert { at: CodeRange, r#type: String },
+}
+
+#[derive(Serialize)]
+pub struct DiffResult {
+    pub compute_time: f64,
+    before: String,
+    after: String,
+    actions: Vec<Act>,
+}
+
+impl IntoResponse for DiffResult {
+    fn into_response(self) -> axum::response::Response {
+        let mut resp = Json(&self).into_response();
+        let headers = resp.headers_mut();
+        headers.insert(
+            "Server-Timing",
+            format!("diff;desc=\"Compute Time\";dur={}", self.compute_time)
+                .parse()
+                .unwrap(),
+        );
+        resp
+    }
+}
+
+// set the type of offset used to index in children list
+type Idx = u16;
+type IdN = NodeIdentifier;
+type CDS<T> = CompletePostOrder<T, u32>;
+
+pub fn diff(state: SharedState, path: DiffParam) -> Result<DiffResult, String> {
+    let now = Instant::now();
+    let DiffParam {
+        user,
+        name,
+        before,
+        after,
+        path,
+    } = path;
+    let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
+    let repo = state
+        .get_config(repo_spec)
+        .ok_or_else(|| "missing config for repository".to_string())?;
+    let mut repo = repo.fetch();
+    log::warn!("done cloning {}", repo.spec);
+    let src_oid = state
+        .pre_process_with_limit(&mut repo, "", &before, 1)
+        .map_err(|e| e.to_string())?[0];
+    let dst_oid = state
+        .pre_process_with_limit(&mut repo, "", &after, 1)
+        .map_err(|e| e.to_string())?[0];
+    log::warn!(
+        "done construction of {src_oid} and {dst_oid} in {}",
+        repo.spec
+    );
+    let repositories = state.repositories.read().unwrap();
+    let src_tr = repositories
+        .get_commit(&repo.config, &src_oid)
+        .ok_or_else(|| format!("missing commit {src_oid}"))?
+        .ast_root;
+    let dst_tr = repositories
+        .get_commit(&repo.config, &dst_oid)
+        .ok_or_else(|| format!("missing commit {dst_oid}"))?
+        .ast_root;
+    let with_spaces_stores = &repositories.processor.main_stores;
+
+    let path = path.unwrap_or_default();
+    let segments = || path.split("/").filter(|x| !x.is_empty());
+    let src_tr = child_at_path(with_spaces_stores, src_tr, segments())
+        .ok_or_else(|| format!("{path} not found in {src_oid}"))?;
+    let dst_tr = child_at_path(with_spaces_stores, dst_tr, segments())
+        .ok_or_else(|| format!("{path} not found in {dst_oid}"))?;
+    // positions are computed from the diffed subtrees, they only include the name of the subtree itself
+    let mut prefix = PathBuf::from_iter(segments());
+    prefix.pop();
+
+    let actions = if src_tr == dst_tr {
+        vec![]
+    } else {
+        diff_subtrees(&state.mappings, with_spaces_stores, src_tr, dst_tr, &prefix)?
+    };
+    Ok(DiffResult {
+        compute_time: now.elapsed().as_secs_f64(),
+        before: src_oid.to_string(),
+        after: dst_oid.to_string(),
+        actions,
+    })
+}
+
+/// The edit script between `src_tr` and `dst_tr`, with its positions relative to `prefix`.
+///
+/// The mappings are looked up in `mappings`, and stored there when they are computed.
+fn diff_subtrees(
+    mappings: &MappingCache,
+    with_spaces_stores: &SimpleStores,
+    src_tr: IdN,
+    dst_tr: IdN,
+    prefix: &PathBuf,
+) -> Result<Vec<Act>, String> {
+    let stores = &no_space::as_nospaces(with_spaces_stores);
+    let node_store = stores.node_store();
+    // the script is only kept when the mappings are computed,
+    // otherwise it is generated again from the cached mappings
+    let mut computed = None;
+    let cached = mappings.entry((src_tr, dst_tr)).or_insert_with(|| {
+        let lazy = hyper_diff::algorithms::gumtree_lazy::diff(stores, &src_tr, &dst_tr);
+        computed = lazy.actions;
+        lazy.mapper.persist()
+    });
+    // SAFETY: the mappings were computed on the same stores
+    let mapping = unsafe { Mapper::<_, CDS<_>, CDS<_>, _>::unpersist(stores, &*cached) };
+    let actions = match computed {
+        Some(actions) => actions,
+        None => {
+            let dst_arena = SimpleBfsMapper::from(node_store, &mapping.dst_arena);
+            ScriptGenerator::_compute_actions(
+                node_store,
+                &mapping.src_arena,
+                &dst_arena,
+                &mapping.mappings,
+            )
+            .map_err(|e| format!("failed to generate the edit script: {e}"))?
+        }
+    };
+    // like hyper_diff::output::Resolver, updated nodes are also located in the `after` version
+    let mapped = |path: &CompressedTreePath<Idx>| {
+        let path: Vec<_> = path.iter().collect();
+        let src_arena = &mapping.src_arena;
+        let x = src_arena.child(node_store, &src_arena.root(), &path);
+        let y = mapping.mappings.get_dst(&x)?;
+        Some(mapping.dst_arena.path_rooted(&y))
+    };
+    let resolver = ActResolver {
+        stores: with_spaces_stores,
+        src_tr,
+        dst_tr,
+        prefix,
+        mapped,
+    };
+    Ok(actions.iter().map(|a| resolver.resolve(a)).collect())
+}
+
+struct ActResolver<'a, M> {
+    stores: &'a SimpleStores,
+    src_tr: IdN,
+    dst_tr: IdN,
+    prefix: &'a PathBuf,
+    /// the path without spaces in the `after` version of the node mapped with a node of the `before` version
+    mapped: M,
+}
+
+impl<'a, M> ActResolver<'a, M>
+where
+    M: Fn(&CompressedTreePath<Idx>) -> Option<Vec<Idx>>,
+{
+    fn resolve(
+        &self,
+        a: &SimpleAction<LabelIdentifier, CompressedTreePath<Idx>, IdN>,
+    ) -> Act {
+        let label = |l: &LabelIdentifier| self.stores.label_store.resolve(l).to_string();
+        match &a.action {
+            script_generator2::Act::Delete {} => Act::Delete {
+                at: self.range(self.src_tr, a.path.ori.iter()),
+            },
+            script_generator2::Act::Update { new } => Act::Update {
+                at: self.range(self.src_tr, a.path.ori.iter()),
+                to: (self.mapped)(&a.path.ori).map(|p| self.range(self.dst_tr, p.into_iter())),
+                new: label(new),
+            },
+            script_generator2::Act::Move { from } => Act::Move {
+                from: self.range(self.src_tr, from.ori.iter()),
+                at: self.range(self.dst_tr, a.path.ori.iter()),
+            },
+            script_generator2::Act::MovUpd { from, new } => Act::MovUpd {
+                from: self.range(self.src_tr, from.ori.iter()),
+                at: self.range(self.dst_tr, a.path.ori.iter()),
+                new: label(new),
+            },
+            script_generator2::Act::Insert { sub } => Act::Insert {
+                at: self.range(self.dst_tr, a.path.ori.iter()),
+                r#type: {
+                    let n = self.stores.node_store.resolve(*sub);
+                    self.stores.type_store.resolve_type(&n).to_string()
+                },
+            },
+        }
+    }
+
+    /// `no_spaces` is a path in the tree without spaces, as used to compute the diff
+    fn range(&self, root: IdN, mut no_spaces: impl Iterator<Item = Idx>) -> CodeRange {
+        let (path,) = path_with_spaces(root, &mut no_spaces, self.stores);
+        let (pos, _) = compute_position(root, &mut path.iter().copied(), self.stores);
+        let range = pos.range();
+        CodeRange {
+            file: self.prefix.join(pos.file()).to_string_lossy().to_string(),
+            start: range.start,
+            end: range.end,
+            path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(state: &crate::AppState, text: &[u8]) -> NodeIdentifier {
+        use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+        let mut repositories = state.repositories.write().unwrap();
+        let stores = &mut repositories.processor.main_stores;
+        let Ok(tree) = JavaTreeGen::<hyper_ast_cvs_git::TStore>::tree_sitter_parse(text) else {
+            panic!("syntax error in {}", String::from_utf8_lossy(text))
+        };
+        let mut md_cache = Default::default();
+        let mut tree_gen = JavaTreeGen::new(stores, &mut md_cache);
+        let file = tree_gen.generate_file(b"A.java", text, tree.walk());
+        file.local.compressed_node
+    }
+
+    #[test]
+    fn updates_are_located_in_both_versions() {
+        let state = crate::AppState::default();
+        let before = "class A {\n    int a = 1;\n}";
+        let after = "class A {\n    int a = 22;\n}";
+        let src_tr = parse(&state, before.as_bytes());
+        let dst_tr = parse(&state, after.as_bytes());
+        let repositories = state.repositories.read().unwrap();
+        let stores = &repositories.processor.main_stores;
+        let prefix = PathBuf::new();
+
+        let actions = diff_subtrees(&state.mappings, stores, src_tr, dst_tr, &prefix).unwrap();
+        let actions = serde_json::to_value(&actions).unwrap();
+        let update = actions
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|a| a["kind"] == "update")
+            .unwrap();
+        assert_eq!(update["new"], "22");
+        let start = before.find('1').unwrap();
+        assert_eq!(update["at"]["file"], "A.java");
+        assert_eq!(update["at"]["start"], start);
+        assert_eq!(update["at"]["end"], start + 1);
+        let start = after.find("22").unwrap();
+        assert_eq!(update["to"]["file"], "A.java");
+        assert_eq!(update["to"]["start"], start);
+        assert_eq!(update["to"]["end"], start + 2);
+
+        // the second script is generated from the cached mappings
+        assert_eq!(1, state.mappings.len());
+        let again = diff_subtrees(&state.mappings, stores, src_tr, dst_tr, &prefix).unwrap();
+        assert_eq!(actions, serde_json::to_value(&again).unwrap());
+    }
+}
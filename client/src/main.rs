@@ -6,20 +6,22 @@
 use std::{
     collections::HashMap,
     net::SocketAddr,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
 };
 
 use dashmap::DashMap;
 use hyper_ast_cvs_git::{
-    git::Forge, multi_preprocessed::PreProcessedRepositories, processing::ConfiguredRepoHandle,
+    git::{Forge, Oid, Repo},
+    multi_preprocessed::PreProcessedRepositories,
+    processing::{ConfiguredRepo2, ConfiguredRepoHandle, ConfiguredRepoHandle2},
 };
 use hyper_diff::{decompressed_tree_store::PersistedNode, matchers::mapping_store::VecStore};
 use tower_http::cors::CorsLayer;
 
 use crate::{
     app::{
-        commit_metadata_route, fetch_code_route, fetch_git_file, scripting_app, track_code_route,
-        view_code_route,
+        commit_metadata_route, diff_route, fetch_code_route, fetch_git_file, modules_route,
+        query_route, scripting_app, track_code_route, view_code_route,
     },
     examples::{example_app, kv_store_app},
 };
@@ -30,10 +32,13 @@ mod app;
 mod changes;
 mod cli;
 mod commit;
+mod diff;
 mod examples;
 mod fetch;
 mod file;
 mod matching;
+mod modules;
+mod querying;
 mod scripting;
 mod track;
 mod utils;
@@ -44,10 +49,14 @@ mod ws;
 pub struct AppState {
     db: DashMap<String, Bytes>,
     repositories: RwLock<PreProcessedRepositories>,
+    /// one lock per repository being processed, see [`AppState::pre_process_with_limit`]
+    processing: DashMap<Repo, Arc<Mutex<()>>>,
     // configs: RwLock<RepoConfigs>,
     mappings: MappingCache,
     mappings_alone: MappingAloneCache,
     partial_decomps: PartialDecompCache,
+    script_cache: ScriptCache,
+    script_limits: scripting::ScriptLimits,
     // Single shared doc
     doc: Arc<(
         RwLock<automerge::AutoCommit>,
@@ -66,9 +75,12 @@ impl Default for AppState {
         Self {
             db: Default::default(),
             repositories: Default::default(),
+            processing: Default::default(),
             mappings: Default::default(),
             mappings_alone: Default::default(),
             partial_decomps: Default::default(),
+            script_cache: Default::default(),
+            script_limits: Default::default(),
             doc: Arc::new((
                 RwLock::new(automerge::AutoCommit::new()),
                 tokio::sync::broadcast::channel(50),
@@ -79,6 +91,112 @@ impl Default for AppState {
     }
 }
 
+impl AppState {
+    pub(crate) fn get_config(&self, repo: Repo) -> Option<ConfiguredRepoHandle2> {
+        self.repositories.read().unwrap().get_config(repo)
+    }
+
+    /// Same as [`AppState::get_config`] then fetches the repository,
+    /// unknown repositories are registered with the config inferred from the files at the root of `commit`,
+    /// see [`RepoConfig::infer`](hyper_ast_cvs_git::processing::RepoConfig::infer).
+    pub(crate) fn get_or_infer_config(
+        &self,
+        repo: Repo,
+        commit: &str,
+    ) -> Result<ConfiguredRepo2, String> {
+        if let Some(config) = self.get_config(repo.clone()) {
+            return Ok(config.fetch());
+        }
+        let repository = repo.fetch();
+        let config = {
+            let tree = hyper_ast_cvs_git::git::retrieve_commit(&repository, commit)
+                .and_then(|commit| commit.tree())
+                .map_err(|e| format!("cannot infer the config of {}: {}", repo, e))?;
+            let names: Vec<String> = (tree.iter())
+                .filter_map(|x| x.name().map(str::to_owned))
+                .collect();
+            hyper_ast_cvs_git::processing::RepoConfig::infer(names.iter().map(String::as_str))
+        };
+        let mut repositories = self.repositories.write().unwrap();
+        // it could have been registered in between
+        let handle = match repositories.get_config(repo.clone()) {
+            Some(handle) => handle,
+            None => {
+                log::info!("{} is registered as {:?}", repo, config);
+                repositories.register_config(repo, config)
+            }
+        };
+        Ok(ConfiguredRepo2 {
+            spec: handle.spec,
+            repo: repository,
+            config: handle.config,
+        })
+    }
+
+    /// Processes commits without holding the write lock on all repositories while building them.
+    ///
+    /// The commits are listed with the read lock,
+    /// then each missing commit is built in a [`Shard`](hyper_ast_cvs_git::multi_preprocessed::Shard) of the repository without any lock on the shared stores,
+    /// the write lock is only taken to restore persisted data and to publish the built nodes.
+    /// Concurrent requests on the same repository wait for each other instead of building the same commits twice.
+    /// The shard only lives for the commits of a request, so its copy of the nodes is dropped once they are published.
+    pub(crate) fn pre_process_with_limit(
+        &self,
+        repository: &mut ConfiguredRepo2,
+        before: &str,
+        after: &str,
+        limit: usize,
+    ) -> Result<Vec<Oid>, String> {
+        self.pre_process_with_progress(repository, before, after, limit, |_, _| ())
+    }
+
+    /// Same as [`AppState::pre_process_with_limit`],
+    /// `on_built` is given the number of built commits and of commits to build after each one.
+    pub(crate) fn pre_process_with_progress(
+        &self,
+        repository: &mut ConfiguredRepo2,
+        before: &str,
+        after: &str,
+        limit: usize,
+        mut on_built: impl FnMut(usize, usize),
+    ) -> Result<Vec<Oid>, String> {
+        let building = self
+            .processing
+            .entry(repository.spec.clone())
+            .or_default()
+            .clone();
+        let _building = building.lock().unwrap();
+        let (commits, missing) = self
+            .repositories
+            .read()
+            .unwrap()
+            .commits_to_process(repository, before, after, limit)
+            .map_err(|e| e.to_string())?;
+        if missing.is_empty() {
+            return Ok(commits);
+        }
+        let mut shard = self.repositories.read().unwrap().shard(repository);
+        let total = missing.len();
+        for (i, oid) in missing.into_iter().enumerate() {
+            let restored = self
+                .repositories
+                .write()
+                .unwrap()
+                .prepare(&mut shard, repository, oid);
+            if !restored {
+                shard.process(&repository.repo, oid);
+                self.repositories
+                    .write()
+                    .unwrap()
+                    .publish(&mut shard, repository, oid)
+                    .map_err(|e| format!("failed to publish commit {}: {}", oid, e))?;
+            }
+            on_built(i + 1, total);
+        }
+        Ok(commits)
+    }
+}
+
 // #[derive(Default)]
 // struct RepoConfigs(HashMap<hyper_ast_cvs_git::git::Repo, hyper_ast_cvs_git::processing::RepoConfig2>);
 // impl RepoConfigs {
@@ -104,20 +222,32 @@ pub(crate) enum MappingStage {
 }
 
 type DS<T> = hyper_diff::decompressed_tree_store::lazy_post_order::LazyPostOrder<T, u32>;
-pub type PersistableMappings<I> =
-    hyper_diff::matchers::Mapping<DS<PersistedNode<I>>, DS<PersistedNode<I>>, VecStore<u32>>;
+pub type PersistableMappings<I> = hyper_diff::algorithms::gumtree_lazy::PersistableMappings<I>;
+/// mappings of the trees without spaces, between the roots of a diff, see [`diff::diff`]
 pub(crate) type MappingCache =
     DashMap<(NodeIdentifier, NodeIdentifier), PersistableMappings<NodeIdentifier>>;
+/// values accumulated by scripts on subtrees, shared by all the commits containing them
+pub(crate) type ScriptCache = scripting::MemoCache;
 type SharedState = Arc<AppState>;
 
 #[tokio::main]
 async fn main() {
     let opts = crate::cli::parse();
+    hyper_ast_cvs_git::git::set_repositories_cache(opts.repositories_cache.clone()).unwrap();
 
-    let shared_state = SharedState::default();
+    let shared_state = SharedState::new(AppState {
+        script_limits: opts.script_limits(),
+        script_cache: ScriptCache::new(opts.script_cache_capacity),
+        ..Default::default()
+    });
     {
         use hyper_ast_cvs_git::processing::RepoConfig;
         let mut repos = shared_state.repositories.write().unwrap();
+        if let Some(store) = &opts.store {
+            // eg. refuses a store written with other types
+            *repos = PreProcessedRepositories::with_persistence(store)
+                .unwrap_or_else(|e| panic!("failed to open the store {:?}: {}", store, e));
+        }
         repos.register_config(Forge::Github.repo("INRIA", "spoon"), RepoConfig::JavaMaven);
         repos.register_config(
             Forge::Github.repo("official-stockfish", "Stockfish"),
@@ -138,6 +268,9 @@ async fn main() {
         .merge(view_code_route(Arc::clone(&shared_state)))
         .merge(fetch_code_route(Arc::clone(&shared_state)))
         .merge(commit_metadata_route(Arc::clone(&shared_state)))
+        .merge(diff_route(Arc::clone(&shared_state)))
+        .merge(modules_route(Arc::clone(&shared_state)))
+        .merge(query_route(Arc::clone(&shared_state)))
         .merge(example_app())
         .layer(CorsLayer::permissive()) // WARN unwanted for deployment
         .with_state(Arc::clone(&shared_state));
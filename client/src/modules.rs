@@ -0,0 +1,108 @@
+use axum::{response::IntoResponse, Json};
+use hyper_ast_cvs_git::{
+    git::{retrieve_commit, Oid},
+    modules::{module_graph, reads_hyperast, ModuleGraph, ModuleGraphChanges},
+    processing::ConfiguredRepo2,
+};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::SharedState;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Param {
+    user: String,
+    name: String,
+    /// either a commit id or a tag
+    commit: String,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ModulesQuery {
+    /// the version the changes are computed from, defaults to the first parent of the commit
+    before: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ModulesResult {
+    pub compute_time: f64,
+    commit: String,
+    graph: ModuleGraph,
+    /// missing for root commits
+    before: Option<String>,
+    changes: Option<ModuleGraphChanges>,
+}
+
+impl IntoResponse for ModulesResult {
+    fn into_response(self) -> axum::response::Response {
+        let mut resp = Json(&self).into_response();
+        let headers = resp.headers_mut();
+        headers.insert(
+            "Server-Timing",
+            format!("modules;desc=\"Compute Time\";dur={}", self.compute_time)
+                .parse()
+                .unwrap(),
+        );
+        resp
+    }
+}
+
+pub fn modules(
+    state: SharedState,
+    path: Param,
+    query: ModulesQuery,
+) -> Result<ModulesResult, String> {
+    let now = Instant::now();
+    let Param { user, name, commit } = path;
+    let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
+    let mut repo = state.get_or_infer_config(repo_spec, &commit)?;
+    log::warn!("done cloning {}", repo.spec);
+    let (commit, before) = {
+        let commit = retrieve_commit(&repo.repo, &commit).map_err(|err| err.to_string())?;
+        let before = match &query.before {
+            Some(before) => Some(retrieve_commit(&repo.repo, before).map_err(|err| err.to_string())?),
+            None => commit.parents().next(),
+        };
+        (commit.id(), before.map(|x| x.id()))
+    };
+    let graph = graph_of(&state, &mut repo, commit)?;
+    let changes = match before {
+        Some(before) => Some(graph_of(&state, &mut repo, before)?.changes(&graph)),
+        None => None,
+    };
+    Ok(ModulesResult {
+        compute_time: now.elapsed().as_secs_f64(),
+        commit: commit.to_string(),
+        graph,
+        before: before.map(|x| x.to_string()),
+        changes,
+    })
+}
+
+/// Builds the commit if its build files are read from the HyperAST, then computes its module graph
+fn graph_of(
+    state: &SharedState,
+    repo: &mut ConfiguredRepo2,
+    commit: Oid,
+) -> Result<ModuleGraph, String> {
+    let kind = state
+        .repositories
+        .read()
+        .unwrap()
+        .get_kind(&repo.config)
+        .ok_or_else(|| format!("missing config for {}", repo.spec))?;
+    let root = if reads_hyperast(kind) {
+        let commit = state.pre_process_with_limit(repo, "", &commit.to_string(), 1)?[0];
+        let repositories = state.repositories.read().unwrap();
+        let root = repositories
+            .get_commit(&repo.config, &commit)
+            .ok_or_else(|| format!("missing commit {commit}"))?
+            .ast_root;
+        Some(root)
+    } else {
+        None
+    };
+    let repositories = state.repositories.read().unwrap();
+    let stores = &repositories.processor.main_stores;
+    module_graph(stores, &repo.repo, kind, commit, root).map_err(|err| err.to_string())
+}
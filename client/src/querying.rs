@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use axum::{response::IntoResponse, Json};
+use hyper_ast::{
+    store::{defaults::NodeIdentifier, SimpleStores},
+    types::{HyperType, TypeStore, TypedNodeId},
+};
+use hyper_ast_cvs_git::{
+    git::Oid,
+    processing::{ConfiguredRepo2, RepoConfig},
+    TStore,
+};
+use hyper_ast_gen_ts_tsquery::{
+    history::HistoryMatcher,
+    search::{ts_query, PreparedMatcher},
+    types::TStore as QStore,
+};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::SharedState;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct QueryParam {
+    user: String,
+    name: String,
+    commit: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct QueryContent {
+    /// a tree-sitter query
+    query: String,
+    /// number of commits to query, following first parents from `commit`
+    commits: usize,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct QueryContentBatch {
+    /// a tree-sitter query
+    query: String,
+    commits: Vec<BatchCommit>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BatchCommit {
+    user: String,
+    name: String,
+    commit: String,
+}
+
+/// A subtree matched by the query
+#[derive(Serialize, Clone, Debug)]
+pub struct Match {
+    /// identifies the matched subtree across commits
+    id: u64,
+    r#type: String,
+    /// number of occurrences of the subtree
+    count: usize,
+    /// labels of the captured nodes, null for captured nodes without label
+    captures: HashMap<String, Option<String>>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CommitMatches {
+    commit: String,
+    /// matches that are not in the previous commit
+    appeared: Vec<Match>,
+    /// matches of the previous commit that are not in this commit
+    disappeared: Vec<Match>,
+    /// matches both in this commit and the previous one
+    persisted: Vec<Match>,
+}
+
+#[derive(Serialize)]
+pub struct QueryResults {
+    pub prepare_time: f64,
+    pub compute_time: f64,
+    /// number of distinct subtrees on which the query was evaluated
+    evaluated: usize,
+    /// from the oldest commit to the newest,
+    /// matches of the oldest commit all appeared
+    results: Vec<CommitMatches>,
+}
+
+/// The matches of a commit of a batch, all of them appeared
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchMatches {
+    /// user/name of the repository
+    repo: String,
+    compute_time: f64,
+    /// number of distinct subtrees on which the query was evaluated
+    evaluated: usize,
+    #[serde(flatten)]
+    matches: CommitMatches,
+}
+
+#[derive(Serialize)]
+pub struct QueryBatchResults {
+    pub prepare_time: f64,
+    /// in the order of the batch
+    results: Vec<Result<BatchMatches, String>>,
+}
+
+impl IntoResponse for QueryBatchResults {
+    fn into_response(self) -> axum::response::Response {
+        Json(&self).into_response()
+    }
+}
+
+impl IntoResponse for QueryResults {
+    fn into_response(self) -> axum::response::Response {
+        let mut resp = Json(&self).into_response();
+        let headers = resp.headers_mut();
+        headers.insert(
+            "Server-Timing",
+            format!(
+                "prepare;desc=\"Prepare Time\";dur={}, query;desc=\"Compute Time\";dur={}",
+                self.prepare_time, self.compute_time
+            )
+            .parse()
+            .unwrap(),
+        );
+        resp
+    }
+}
+
+pub fn query_history(
+    state: SharedState,
+    path: QueryParam,
+    content: QueryContent,
+) -> Result<QueryResults, String> {
+    let now = Instant::now();
+    let QueryParam { user, name, commit } = path;
+    let QueryContent { query, commits } = content;
+    let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
+    let repo = state
+        .get_config(repo_spec)
+        .ok_or_else(|| "missing config for repository".to_string())?;
+    let kind = state.repositories.read().unwrap().get_kind(&repo.config);
+    // rejects invalid queries before building anything
+    let (query_store, query) = ts_query(query.as_bytes());
+    check_kind(kind, &query_store, query)?;
+    let mut repo = repo.fetch();
+    log::warn!("done cloning {}", repo.spec);
+    let commits = state
+        .pre_process_with_limit(&mut repo, "", &commit, commits)
+        .map_err(|e| e.to_string())?;
+    log::info!("done construction of {commits:?} in {}", repo.spec);
+    let prepare_time = now.elapsed().as_secs_f64();
+    let now = Instant::now();
+    let repositories = state.repositories.read().unwrap();
+    let versions = commits
+        .iter()
+        .rev()
+        .map(|oid| {
+            repositories
+                .get_commit(&repo.config, oid)
+                .map(|c| (*oid, c.ast_root))
+                .ok_or_else(|| format!("missing commit {oid}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let stores = &repositories.processor.main_stores;
+
+    let (evaluated, results) = query_kind(kind, stores, &query_store, query, versions)?;
+    Ok(QueryResults {
+        prepare_time,
+        compute_time: now.elapsed().as_secs_f64(),
+        evaluated,
+        results,
+    })
+}
+
+/// Evaluates the same query on each commit of the batch, in order.
+///
+/// Failing to find or build one of the commits only fails its own result,
+/// like queries that cannot be matched on the language of a repository.
+pub fn query_batch(
+    state: SharedState,
+    content: QueryContentBatch,
+) -> Result<QueryBatchResults, String> {
+    let QueryContentBatch { query, commits } = content;
+    let (query_store, query) = ts_query(query.as_bytes());
+    let mut prepare_time = 0.0;
+    let mut results = vec![];
+    // repositories are only fetched once, even if they appear multiple times in the batch
+    let mut fetched: HashMap<String, ConfiguredRepo2> = HashMap::new();
+    for BatchCommit { user, name, commit } in commits {
+        let now = Instant::now();
+        let key = format!("{}/{}", user, name);
+        if !fetched.contains_key(&key) {
+            let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
+            match state.get_or_infer_config(repo_spec, &commit) {
+                Ok(repo) => {
+                    log::warn!("done cloning {}", &repo.spec);
+                    fetched.insert(key.clone(), repo);
+                }
+                Err(e) => {
+                    results.push(Err(format!("{} {}: {}", key, commit, e)));
+                    continue;
+                }
+            }
+        }
+        let repo = fetched.get_mut(&key).unwrap();
+        let kind = state.repositories.read().unwrap().get_kind(&repo.config);
+        let prepared = check_kind(kind, &query_store, query)
+            .and_then(|()| state.pre_process_with_limit(repo, "", &commit, 1));
+        let oid = match prepared {
+            Ok(commits) => commits[0],
+            Err(e) => {
+                results.push(Err(format!("{} {}: {}", key, commit, e)));
+                continue;
+            }
+        };
+        prepare_time += now.elapsed().as_secs_f64();
+        let now = Instant::now();
+        let repositories = state.repositories.read().unwrap();
+        let root = match repositories.get_commit(&repo.config, &oid) {
+            Some(c) => c.ast_root,
+            None => {
+                results.push(Err(format!("{} {}: missing commit {}", key, commit, oid)));
+                continue;
+            }
+        };
+        let stores = &repositories.processor.main_stores;
+        let r = query_kind(kind, stores, &query_store, query, vec![(oid, root)]);
+        results.push(match r {
+            Ok((evaluated, mut matches)) => Ok(BatchMatches {
+                repo: key,
+                compute_time: now.elapsed().as_secs_f64(),
+                evaluated,
+                matches: matches.pop().expect("a result per version"),
+            }),
+            Err(e) => Err(format!("{} {}: {}", key, commit, e)),
+        });
+    }
+    Ok(QueryBatchResults {
+        prepare_time,
+        results,
+    })
+}
+
+/// Checks that `query` can be matched on the language of the repository
+fn check_kind(
+    kind: Option<RepoConfig>,
+    query_store: &SimpleStores<QStore>,
+    query: NodeIdentifier,
+) -> Result<(), String> {
+    match kind {
+        Some(RepoConfig::JavaMaven) => {
+            check::<hyper_ast_gen_ts_java::types::Type>(query_store, query)
+        }
+        Some(RepoConfig::CppMake) => check::<hyper_ast_gen_ts_cpp::types::Type>(query_store, query),
+        Some(RepoConfig::TsNpm) => check::<hyper_ast_gen_ts_ts::types::Type>(query_store, query),
+        Some(RepoConfig::RustCargo) => {
+            check::<hyper_ast_gen_ts_rust::types::Type>(query_store, query)
+        }
+        Some(RepoConfig::Python) => {
+            check::<hyper_ast_gen_ts_python::types::Type>(query_store, query)
+        }
+        kind => Err(format!(
+            "queries are matched on the language of the repository, \
+            they are not supported on {:?} repositories",
+            kind
+        )),
+    }
+}
+
+/// Same as [`query_versions`] with the types of the language of the repository,
+/// the query must have been accepted by [`check_kind`]
+fn query_kind(
+    kind: Option<RepoConfig>,
+    stores: &SimpleStores<TStore>,
+    query_store: &SimpleStores<QStore>,
+    query: NodeIdentifier,
+    versions: Vec<(Oid, NodeIdentifier)>,
+) -> Result<(usize, Vec<CommitMatches>), String> {
+    use hyper_ast_gen_ts_cpp::types::TIdN as Cpp;
+    use hyper_ast_gen_ts_java::types::TIdN as Java;
+    use hyper_ast_gen_ts_python::types::TIdN as Python;
+    use hyper_ast_gen_ts_rust::types::TIdN as Rust;
+    use hyper_ast_gen_ts_ts::types::TIdN as Ts;
+    match kind {
+        Some(RepoConfig::JavaMaven) => {
+            query_versions::<Java<_>>(stores, query_store, query, versions)
+        }
+        Some(RepoConfig::CppMake) => query_versions::<Cpp<_>>(stores, query_store, query, versions),
+        Some(RepoConfig::TsNpm) => query_versions::<Ts<_>>(stores, query_store, query, versions),
+        Some(RepoConfig::RustCargo) => {
+            query_versions::<Rust<_>>(stores, query_store, query, versions)
+        }
+        Some(RepoConfig::Python) => {
+            query_versions::<Python<_>>(stores, query_store, query, versions)
+        }
+        Some(RepoConfig::Any) | None => unreachable!("rejected by check_kind"),
+    }
+}
+
+/// Checks that `query` can be matched on the nodes of `Ty`
+fn check<Ty>(query_store: &SimpleStores<QStore>, query: NodeIdentifier) -> Result<(), String>
+where
+    Ty: for<'b> TryFrom<&'b str> + std::fmt::Debug + Copy,
+    for<'b> <Ty as TryFrom<&'b str>>::Error: std::fmt::Debug,
+{
+    PreparedMatcher::<_, Ty>::new(query_store, query)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Evaluates the query on each version,
+/// returns the number of evaluated subtrees and the matches of each version
+fn query_versions<TIdN>(
+    stores: &SimpleStores<TStore>,
+    query_store: &SimpleStores<QStore>,
+    query: NodeIdentifier,
+    versions: Vec<(Oid, NodeIdentifier)>,
+) -> Result<(usize, Vec<CommitMatches>), String>
+where
+    TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>,
+    TIdN::Ty: for<'b> TryFrom<&'b str> + std::fmt::Debug + Eq + Copy + HyperType,
+    for<'b> <TIdN::Ty as TryFrom<&'b str>>::Error: std::fmt::Debug,
+{
+    let matcher =
+        PreparedMatcher::<_, TIdN::Ty>::new(query_store, query).map_err(|e| e.to_string())?;
+    let mut history = HistoryMatcher::new(&matcher);
+    let res = history.query_history::<_, TIdN, _>(stores, versions);
+
+    let to_match = |(id, count): (NodeIdentifier, usize)| {
+        let n = stores.node_store.resolve(id);
+        let captures = history
+            .captures(&id)
+            .map(|x| {
+                x.iter()
+                    .map(|(k, v)| (k.clone(), v.clone().label()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Match {
+            id: unsafe { std::mem::transmute(id) },
+            r#type: stores.type_store.resolve_type(&n).to_string(),
+            count,
+            captures,
+        }
+    };
+    let results = res
+        .into_iter()
+        .map(|x| CommitMatches {
+            commit: x.version.to_string(),
+            appeared: x.appeared.into_iter().map(&to_match).collect(),
+            disappeared: x.disappeared.into_iter().map(&to_match).collect(),
+            persisted: x.persisted.into_iter().map(&to_match).collect(),
+        })
+        .collect();
+    Ok((history.evaluated_count(), results))
+}
@@ -1,8 +1,11 @@
 mod estimate;
 mod finalize;
 mod fs_container;
+mod generic;
+mod limits;
 mod max;
 mod mean;
+mod memo;
 mod min;
 mod named_container;
 mod quantile;
@@ -22,7 +25,14 @@ use rhai::{
     Array, Dynamic, Engine, Instant, Scope,
 };
 use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+pub use limits::{Cancellation, ScriptLimits};
+pub(crate) use memo::{MemoCache, MemoKey};
 
 #[derive(Deserialize, Clone)]
 pub struct ScriptingParam {
@@ -38,7 +48,22 @@ pub struct ScriptContentDepth {
     commits: usize,
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+/// A script evaluated on commits of possibly different repositories, see [`simple_batch`]
+#[derive(Deserialize, Clone)]
+pub struct ScriptContentBatch {
+    #[serde(flatten)]
+    inner: ScriptContent,
+    commits: Vec<BatchCommit>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct BatchCommit {
+    user: String,
+    name: String,
+    commit: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Hash)]
 pub struct ScriptContent {
     pub init: String,
     pub accumulate: String,
@@ -49,6 +74,8 @@ pub struct ScriptContent {
 pub enum ScriptingError {
     AtCompilation(String),
     AtEvaluation(String),
+    /// a limit of [`ScriptLimits`] was reached, or the request was cancelled
+    LimitExceeded(String),
     Other(String),
 }
 
@@ -60,6 +87,9 @@ pub struct ComputeResult {
 
 #[derive(Deserialize, Serialize)]
 pub struct ComputeResultIdentified {
+    /// user/name of the repository, only given for batches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
     pub commit: String,
     #[serde(flatten)]
     pub inner: ComputeResult,
@@ -87,17 +117,16 @@ pub fn simple(
     script: ScriptContent,
     state: SharedState,
     path: ScriptingParam,
+    cancelled: Arc<AtomicBool>,
 ) -> Result<Json<ComputeResult>, ScriptingError> {
     let now = Instant::now();
+    let budget = limits::Budget::new(state.script_limits, cancelled);
+    let script_hash = memo::script_hash(&script);
     let (commit, engine, init_script, accumulate_script, filter_script, mut repo) =
-        simple_prepare(path, script, &state)?;
-    let commits = state
-        .repositories
-        .write()
-        .unwrap()
-        .pre_process_with_limit(&mut repo, "", &commit, 2)
-        .unwrap();
+        simple_prepare(path, script, &state, &budget)?;
+    let commits = state.pre_process_with_limit(&mut repo, "", &commit, 2).unwrap();
     log::info!("done construction of {commits:?} in  {}", repo.spec);
+    budget.start();
 
     let commit_oid = &commits[0];
     simple_aux(
@@ -108,6 +137,8 @@ pub fn simple(
         &init_script,
         &filter_script,
         &accumulate_script,
+        script_hash,
+        &budget,
         now,
     )
     .map(|r| Json(r))
@@ -117,55 +148,68 @@ pub fn simple_depth(
     script: ScriptContentDepth,
     state: SharedState,
     path: ScriptingParam,
+    cancelled: Arc<AtomicBool>,
 ) -> Result<Json<ComputeResults>, ScriptingError> {
+    let mut prepare_time = 0.0;
+    let mut results = vec![];
+    simple_depth_stream(script, state, path, cancelled, |event| match event {
+        DepthEvent::Preparing { .. } => (),
+        DepthEvent::Prepared { prepare_time: t, .. } => prepare_time = t,
+        DepthEvent::Computed { result, .. } => results.push(result),
+    })?;
+    let r = ComputeResults {
+        prepare_time,
+        results,
+    };
+    Ok(Json(r))
+}
+
+/// What happens while evaluating a script on multiple commits, see [`simple_depth_stream`]
+#[derive(Serialize)]
+pub enum DepthEvent {
+    /// a missing commit was built
+    Preparing { built: usize, missing: usize },
+    /// every commit is built
+    Prepared { prepare_time: f64, commits: usize },
+    Computed {
+        done: usize,
+        result: Result<ComputeResultIdentified, String>,
+    },
+}
+
+/// Same as [`simple_depth`] but each result is given to `emit` as soon as it is computed.
+pub fn simple_depth_stream(
+    script: ScriptContentDepth,
+    state: SharedState,
+    path: ScriptingParam,
+    cancelled: Arc<AtomicBool>,
+    mut emit: impl FnMut(DepthEvent),
+) -> Result<(), ScriptingError> {
     let ScriptContentDepth {
         inner: script,
         commits,
     } = script;
     let now = Instant::now();
+    let budget = limits::Budget::new(state.script_limits, cancelled);
+    let script_hash = memo::script_hash(&script);
     let ScriptingParam { user, name, commit } = path.clone();
-    let mut engine = Engine::new();
-    engine.disable_symbol("/");
-    add_utils(&mut engine);
-    let init_script = engine.compile(script.init.clone()).map_err(|x| {
-        ScriptingError::AtCompilation(format!("Init: {}, {}", x, script.init.clone()))
-    })?;
-    let filter_script = engine.compile(script.filter.clone()).map_err(|x| {
-        ScriptingError::AtCompilation(format!("Filter: {}, {}", x, script.filter.clone()))
-    })?;
-    let accumulate_script = engine.compile(script.accumulate.clone()).map_err(|x| {
-        ScriptingError::AtCompilation(format!("Acc: {}, {}", x, script.accumulate.clone()))
-    })?;
+    let (engine, init_script, filter_script, accumulate_script) = compile(&script, &budget)?;
     let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
-    let repo = state
-        .repositories
-        .write()
-        .unwrap()
-        .get_config(repo_spec.clone());
-    let repo = match repo {
-        Some(repo) => repo,
-        None => {
-            let configs = &mut state.repositories.write().unwrap();
-            configs.register_config(
-                repo_spec.clone(),
-                hyper_ast_cvs_git::processing::RepoConfig::JavaMaven,
-            );
-            log::error!("missing config for {}", repo_spec);
-            configs.get_config(repo_spec.clone()).unwrap()
-        }
-    };
-    // .ok_or_else(|| ScriptingError::Other("missing config for repository".to_string()))?;
-    let mut repo = repo.fetch();
+    let mut repo = state
+        .get_or_infer_config(repo_spec, &commit)
+        .map_err(ScriptingError::Other)?;
     log::warn!("done cloning {}", &repo.spec);
     let commits = state
-        .repositories
-        .write()
-        .unwrap()
-        .pre_process_with_limit(&mut repo, "", &commit, commits)
-        .unwrap();
-    let prepare_time = now.elapsed().as_secs_f64();
-    let mut results = vec![];
-    for commit_oid in &commits {
+        .pre_process_with_progress(&mut repo, "", &commit, commits, |built, missing| {
+            emit(DepthEvent::Preparing { built, missing })
+        })
+        .map_err(ScriptingError::Other)?;
+    budget.start();
+    emit(DepthEvent::Prepared {
+        prepare_time: now.elapsed().as_secs_f64(),
+        commits: commits.len(),
+    });
+    for (i, commit_oid) in commits.iter().enumerate() {
         let now = Instant::now();
         let r = simple_aux(
             state.clone(),
@@ -175,28 +219,108 @@ pub fn simple_depth(
             &init_script,
             &filter_script,
             &accumulate_script,
+            script_hash,
+            &budget,
             now,
         );
-        match r {
-            Ok(r) => results.push(Ok(ComputeResultIdentified {
+        let result = match r {
+            Ok(r) => Ok(ComputeResultIdentified {
+                repo: None,
                 commit: commit_oid.to_string(),
                 inner: r,
-            })),
-            Err(ScriptingError::AtEvaluation(e)) => results.push(Err(e)),
+            }),
+            Err(ScriptingError::AtEvaluation(e)) => Err(e),
             Err(e) => return Err(e),
+        };
+        emit(DepthEvent::Computed { done: i + 1, result });
+    }
+    Ok(())
+}
+
+/// Evaluates the same script on each commit of the batch, in order.
+///
+/// Unlike [`simple_depth`], failing to find or build one of the commits
+/// only fails its own result, as for evaluation errors.
+/// Each commit gets its own budget of operations and duration, see [`limits::Budget::entry`].
+pub fn simple_batch(
+    script: ScriptContentBatch,
+    state: SharedState,
+    cancelled: Arc<AtomicBool>,
+) -> Result<Json<ComputeResults>, ScriptingError> {
+    let ScriptContentBatch {
+        inner: script,
+        commits,
+    } = script;
+    let budget = limits::Budget::new(state.script_limits, cancelled);
+    let script_hash = memo::script_hash(&script);
+    // fails early on compilation errors
+    compile(&script, &budget)?;
+    let mut prepare_time = 0.0;
+    let mut results = vec![];
+    // repositories are only fetched once, even if they appear multiple times in the batch
+    let mut fetched: HashMap<String, hyper_ast_cvs_git::processing::ConfiguredRepo2> =
+        HashMap::new();
+    for BatchCommit { user, name, commit } in commits {
+        let now = Instant::now();
+        let key = format!("{}/{}", user, name);
+        if !fetched.contains_key(&key) {
+            let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
+            match state.get_or_infer_config(repo_spec, &commit) {
+                Ok(repo) => {
+                    log::warn!("done cloning {}", &repo.spec);
+                    fetched.insert(key.clone(), repo);
+                }
+                Err(e) => {
+                    results.push(Err(format!("{} {}: {}", key, commit, e)));
+                    continue;
+                }
+            }
         }
+        let repo = fetched.get_mut(&key).unwrap();
+        let commit_oid = match state.pre_process_with_limit(repo, "", &commit, 1) {
+            Ok(commits) => commits[0],
+            Err(e) => {
+                results.push(Err(format!("{} {}: {}", key, commit, e)));
+                continue;
+            }
+        };
+        prepare_time += now.elapsed().as_secs_f64();
+        let now = Instant::now();
+        let budget = budget.entry();
+        let (engine, init_script, filter_script, accumulate_script) = compile(&script, &budget)?;
+        let r = simple_aux(
+            state.clone(),
+            repo,
+            &commit_oid,
+            &engine,
+            &init_script,
+            &filter_script,
+            &accumulate_script,
+            script_hash,
+            &budget,
+            now,
+        );
+        results.push(match r {
+            Ok(r) => Ok(ComputeResultIdentified {
+                repo: Some(key),
+                commit: commit_oid.to_string(),
+                inner: r,
+            }),
+            Err(ScriptingError::AtEvaluation(e)) => Err(e),
+            Err(e) => return Err(e),
+        });
     }
-    let r = ComputeResults {
+    Ok(Json(ComputeResults {
         prepare_time,
         results,
-    };
-    Ok(Json(r))
+    }))
 }
 
 fn simple_prepare(
     path: ScriptingParam,
     script: ScriptContent,
     state: &rhai::Shared<crate::AppState>,
+    budget: &limits::Budget,
 ) -> Result<
     (
         String,
@@ -209,23 +333,9 @@ fn simple_prepare(
     ScriptingError,
 > {
     let ScriptingParam { user, name, commit } = path.clone();
-    let mut engine = Engine::new();
-    engine.disable_symbol("/");
-    add_utils(&mut engine);
-    let init_script = engine.compile(script.init.clone()).map_err(|x| {
-        ScriptingError::AtCompilation(format!("Init: {}, {}", x, script.init.clone()))
-    })?;
-    let filter_script = engine.compile(script.filter.clone()).map_err(|x| {
-        ScriptingError::AtCompilation(format!("Filter: {}, {}", x, script.filter.clone()))
-    })?;
-    let accumulate_script = engine.compile(script.accumulate.clone()).map_err(|x| {
-        ScriptingError::AtCompilation(format!("Acc: {}, {}", x, script.accumulate.clone()))
-    })?;
+    let (engine, init_script, filter_script, accumulate_script) = compile(&script, budget)?;
     let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
     let repo = state
-        .repositories
-        .write()
-        .unwrap()
         .get_config(repo_spec)
         .ok_or_else(|| ScriptingError::Other("missing config for repository".to_string()))?;
     let repo = repo.fetch();
@@ -240,6 +350,26 @@ fn simple_prepare(
     ))
 }
 
+fn compile(
+    script: &ScriptContent,
+    budget: &limits::Budget,
+) -> Result<(Engine, rhai::AST, rhai::AST, rhai::AST), ScriptingError> {
+    let mut engine = Engine::new();
+    budget.apply(&mut engine);
+    engine.disable_symbol("/");
+    add_utils(&mut engine);
+    let init_script = engine.compile(script.init.clone()).map_err(|x| {
+        ScriptingError::AtCompilation(format!("Init: {}, {}", x, script.init.clone()))
+    })?;
+    let filter_script = engine.compile(script.filter.clone()).map_err(|x| {
+        ScriptingError::AtCompilation(format!("Filter: {}, {}", x, script.filter.clone()))
+    })?;
+    let accumulate_script = engine.compile(script.accumulate.clone()).map_err(|x| {
+        ScriptingError::AtCompilation(format!("Acc: {}, {}", x, script.accumulate.clone()))
+    })?;
+    Ok((engine, init_script, filter_script, accumulate_script))
+}
+
 fn simple_aux(
     state: rhai::Shared<crate::AppState>,
     repo: &mut hyper_ast_cvs_git::processing::ConfiguredRepo2,
@@ -248,6 +378,8 @@ fn simple_aux(
     init_script: &rhai::AST,
     filter_script: &rhai::AST,
     accumulate_script: &rhai::AST,
+    script_hash: u64,
+    budget: &limits::Budget,
     now: Instant,
 ) -> Result<ComputeResult, ScriptingError> {
     let repositories = state.repositories.read().unwrap();
@@ -256,6 +388,44 @@ fn simple_aux(
     let node_store = &repositories.processor.main_stores.node_store;
     // let size = node_store.resolve(src_tr).size();
     drop(repositories);
+    let (result, visited) = eval_tree(
+        state.clone(),
+        src_tr,
+        engine,
+        init_script,
+        filter_script,
+        accumulate_script,
+        script_hash,
+        budget,
+    )?;
+    log::debug!(
+        "filtered {} nodes of {}, {} values memoized",
+        visited,
+        commit_oid,
+        state.script_cache.len()
+    );
+    let compute_time = now.elapsed().as_secs_f64();
+    let result = result.finalize();
+    budget.consume_result(&result)?;
+    let r = ComputeResult {
+        compute_time,
+        result,
+    };
+    Ok(r)
+}
+
+/// Evaluates the scripts on the tree at `root`, reusing the values memoized in [`crate::ScriptCache`],
+/// along with the number of nodes the filter was evaluated on.
+fn eval_tree(
+    state: rhai::Shared<crate::AppState>,
+    root: NodeIdentifier,
+    engine: &Engine,
+    init_script: &rhai::AST,
+    filter_script: &rhai::AST,
+    accumulate_script: &rhai::AST,
+    script_hash: u64,
+    budget: &limits::Budget,
+) -> Result<(Dynamic, usize), ScriptingError> {
     macro_rules! ns {
         ($s:expr) => {
             $s.repositories
@@ -277,30 +447,38 @@ fn simple_aux(
         value: Option<Dynamic>,
         parent: usize,
         pending_cs: isize,
+        /// where to memoize the value once the subtree is accumulated
+        memo: Option<memo::MemoKey>,
     }
     let init: Dynamic = engine
         .eval_ast(&init_script)
-        .map_err(|x| ScriptingError::AtEvaluation(x.to_string()))?;
+        .map_err(limits::eval_error)?;
+    let queries = generic::QueryCache::default();
+    let metrics = generic::MetricCache::default();
     let mut stack: Vec<Acc> = vec![];
     stack.push(Acc {
-        sid: src_tr,
+        sid: root,
         value: Some(init),
         parent: 0,
         pending_cs: -1,
+        memo: None,
     });
     let mut acc_engine = Engine::new_raw();
+    budget.apply(&mut acc_engine);
     acc_engine.on_print(|text| println!("{text}"));
     let package = CorePackage::new();
     package.register_into_engine(&mut acc_engine);
     let package = BasicArrayPackage::new();
     package.register_into_engine(&mut acc_engine);
     let mut filter_engine = Engine::new_raw();
+    budget.apply(&mut filter_engine);
     filter_engine.on_print(|text| println!("{text}"));
     let package = CorePackage::new();
     package.register_into_engine(&mut filter_engine);
     let package = BasicArrayPackage::new();
     package.register_into_engine(&mut filter_engine);
     // let s = state.clone().read().unwrap();
+    let mut visited = 0;
     let result: Dynamic = loop {
         let Some(mut acc) = stack.pop() else {
         unreachable!()
@@ -309,6 +487,17 @@ fn simple_aux(
         let stack_len = stack.len();
 
         if acc.pending_cs < 0 {
+            // the subtree was already accumulated with the same initial value, eg. in a previous commit
+            acc.memo = memo::fingerprint(acc.value.as_ref().unwrap())
+                .map(|v| (script_hash, acc.sid, v));
+            if let Some(value) = acc.memo.as_ref().and_then(|k| state.script_cache.get(k)) {
+                acc.value = Some(value);
+                acc.pending_cs = 0;
+                acc.memo = None;
+            }
+        }
+        if acc.pending_cs < 0 {
+            visited += 1;
             let mut scope = Scope::new();
             scope.push("s", acc.value.clone().unwrap());
             filter_engine.disable_symbol("/");
@@ -342,15 +531,6 @@ fn simple_aux(
                 s == hyper_ast::types::Shared::TypeDeclaration
             });
             let s = state.clone();
-            filter_engine.register_fn("is_file", move || {
-                let stores = &stores!(s);
-                let node_store = &stores.node_store;
-                let type_store = &stores.type_store;
-                let n = node_store.resolve(current);
-                let t = type_store.resolve_type(&n);
-                t.is_file()
-            });
-            let s = state.clone();
             filter_engine.register_fn("children", move || {
                 let node_store = &ns!(s);
                 node_store
@@ -422,10 +602,17 @@ fn simple_aux(
                     x.contains(SemFlags::HoldMainFolder) || x.contains(SemFlags::HoldTestFolder)
                 })
             });
+            generic::add_node_utils(
+                &mut filter_engine,
+                state.clone(),
+                current,
+                queries.clone(),
+                metrics.clone(),
+            );
             add_utils(&mut filter_engine);
             let prepared: Dynamic = filter_engine
                 .eval_ast_with_scope(&mut scope, &filter_script)
-                .map_err(|x| ScriptingError::AtEvaluation(x.to_string()))?;
+                .map_err(limits::eval_error)?;
             acc.value = Some(scope.get_value("s").unwrap());
             if let Some(prepared) = prepared.try_cast::<Vec<Dynamic>>() {
                 stack.push(Acc {
@@ -439,11 +626,16 @@ fn simple_aux(
                         value: Some(it.next().unwrap()),
                         parent: stack_len,
                         pending_cs: -1,
+                        memo: None,
                     }
                 }));
             }
             continue;
         }
+        // leaves are not worth memoizing
+        if let (Some(key), true) = (acc.memo.take(), acc.pending_cs > 0) {
+            state.script_cache.insert(key, acc.value.clone().unwrap());
+        }
         if stack.is_empty() {
             assert_eq!(acc.parent, 0);
             break acc.value.unwrap();
@@ -488,15 +680,6 @@ fn simple_aux(
             t.is_directory()
         });
         let s = state.clone();
-        acc_engine.register_fn("is_file", move || {
-            let stores = &stores!(s);
-            let node_store = &stores.node_store;
-            let type_store = &stores.type_store;
-            let n = node_store.resolve(current);
-            let t = type_store.resolve_type(&n);
-            t.is_file()
-        });
-        let s = state.clone();
         acc_engine.register_fn("is_java_file", move || {
             let stores = &stores!(s);
             let node_store = &stores.node_store;
@@ -581,19 +764,20 @@ fn simple_aux(
                 todo!("need to choose a convenient path, try to exploit param overloading")
             },
         );
+        generic::add_node_utils(
+            &mut acc_engine,
+            state.clone(),
+            current,
+            queries.clone(),
+            metrics.clone(),
+        );
         add_utils(&mut acc_engine);
         acc_engine
             .eval_ast_with_scope(&mut scope, &accumulate_script)
-            .map_err(|x| ScriptingError::AtEvaluation(x.to_string()))?;
+            .map_err(limits::eval_error)?;
         stack[acc.parent].value = Some(scope.get_value("p").unwrap());
     };
-    let compute_time = now.elapsed().as_secs_f64();
-    let result = result.finalize();
-    let r = ComputeResult {
-        compute_time,
-        result,
-    };
-    Ok(r)
+    Ok((result, visited))
 }
 
 use self::{mean::Mean, min::Min, quantile::Quantile, stats::Stats};
@@ -715,3 +899,93 @@ fn add_utils(engine: &mut Engine) {
             },
         );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: (&str, &str, &str) = (
+        "#{ nodes: 0 }",
+        "children().map(|x| [x, #{ nodes: 0 }])",
+        "p.nodes += s.nodes + 1;",
+    );
+
+    fn class(literal: usize) -> String {
+        let methods: String = (0..20)
+            .map(|i| format!("void m{i}() {{ int a{i} = {i}; a{i} += {i}; }}\n"))
+            .collect();
+        format!("class A {{\n{methods}void f() {{ int a = {literal}; }}\n}}")
+    }
+
+    fn parse(state: &crate::AppState, text: &[u8]) -> NodeIdentifier {
+        use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+        let mut repositories = state.repositories.write().unwrap();
+        let stores = &mut repositories.processor.main_stores;
+        let Ok(tree) = JavaTreeGen::<hyper_ast_cvs_git::TStore>::tree_sitter_parse(text) else {
+            panic!("syntax error in {}", String::from_utf8_lossy(text))
+        };
+        let mut md_cache = Default::default();
+        let mut tree_gen = JavaTreeGen::new(stores, &mut md_cache);
+        let file = tree_gen.generate_file(b"A.java", text, tree.walk());
+        file.local.compressed_node
+    }
+
+    fn eval(state: &SharedState, root: NodeIdentifier) -> (i64, usize) {
+        let (init, filter, accumulate) = SCRIPT;
+        let script = ScriptContent {
+            init: init.to_string(),
+            filter: filter.to_string(),
+            accumulate: accumulate.to_string(),
+        };
+        let budget = limits::Budget::new(Default::default(), Default::default());
+        let (engine, init, filter, accumulate) = compile(&script, &budget).unwrap();
+        let hash = memo::script_hash(&script);
+        let (result, visited) = eval_tree(
+            state.clone(),
+            root,
+            &engine,
+            &init,
+            &filter,
+            &accumulate,
+            hash,
+            &budget,
+        )
+        .unwrap();
+        let nodes = result.cast::<rhai::Map>()["nodes"].as_int().unwrap();
+        (nodes, visited)
+    }
+
+    #[test]
+    fn only_changed_subtrees_are_visited_again() {
+        let state = SharedState::default();
+        let before = parse(&state, class(1).as_bytes());
+        let after = parse(&state, class(2).as_bytes());
+        assert_ne!(before, after);
+
+        let (nodes_before, visited_before) = eval(&state, before);
+        let (nodes_after, visited_after) = eval(&state, after);
+        assert_eq!(nodes_before, nodes_after);
+        // only the path to the changed literal and the leaves next to it are filtered again
+        assert!(0 < visited_after && visited_after * 4 < visited_before);
+        // the whole tree is memoized
+        assert_eq!((nodes_after, 0), eval(&state, after));
+    }
+
+    #[test]
+    fn least_recently_used_values_are_evicted() {
+        let state = crate::AppState::default();
+        let node = parse(&state, class(0).as_bytes());
+        let cache = MemoCache::new(4);
+        let key = |i: u64| (i, node, String::new());
+        for i in 0..4 {
+            cache.insert(key(i), Dynamic::from(i as i64));
+        }
+        // 0 becomes the most recently used
+        assert_eq!(Some(0), cache.get(&key(0)).and_then(|x| x.as_int().ok()));
+        cache.insert(key(4), Dynamic::from(4 as i64));
+        assert!(cache.len() <= 4);
+        assert!(cache.get(&key(0)).is_some());
+        assert!(cache.get(&key(1)).is_none());
+        assert!(cache.get(&key(4)).is_some());
+    }
+}
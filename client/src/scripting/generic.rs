@@ -0,0 +1,376 @@
+//! Accessors on nodes that do not depend on their language.
+//!
+//! They are dispatched through [`AnyType`], so the same metric scripts work on Java, C++, TS, ...
+//! Each accessor works on the current node, eg. `kind()`,
+//! or on a given node, eg. `kind(child_at(0))`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use hyper_ast::{
+    store::{defaults::NodeIdentifier, SimpleStores},
+    types::{AnyType, HyperType, LabelStore, Labeled, TypeStore, WithChildren},
+};
+use hyper_ast_cvs_git::TStore;
+use hyper_ast_gen_ts_tsquery::search::{ts_query, PreparedMatcher};
+use num::ToPrimitive;
+use rhai::{Dynamic, Engine, EvalAltResult};
+
+use crate::SharedState;
+
+type Matcher<Ty> =
+    PreparedMatcher<'static, SimpleStores<hyper_ast_gen_ts_tsquery::types::TStore>, Ty>;
+
+/// A query prepared for the types of a language
+enum Prepared {
+    Java(Matcher<hyper_ast_gen_ts_java::types::Type>),
+    Cpp(Matcher<hyper_ast_gen_ts_cpp::types::Type>),
+    Ts(Matcher<hyper_ast_gen_ts_ts::types::Type>),
+    Rust(Matcher<hyper_ast_gen_ts_rust::types::Type>),
+    Python(Matcher<hyper_ast_gen_ts_python::types::Type>),
+}
+
+/// Queries given to `matches` are prepared once per language during an evaluation.
+///
+/// A query that cannot be prepared for a language, eg. because it names node types of another language,
+/// is cached as `None` and never matches nodes of this language.
+#[derive(Default, Clone)]
+pub(super) struct QueryCache(Arc<Mutex<HashMap<(String, &'static str), Option<Arc<Prepared>>>>>);
+
+impl QueryCache {
+    fn get(&self, query: &str, lang: &'static str) -> Option<Arc<Prepared>> {
+        let mut cache = self.0.lock().unwrap();
+        cache
+            .entry((query.to_string(), lang))
+            .or_insert_with(|| {
+                Some(Arc::new(match lang {
+                    "java" => Prepared::Java(prepare(query)?),
+                    "cpp" => Prepared::Cpp(prepare(query)?),
+                    "ts" => Prepared::Ts(prepare(query)?),
+                    "rust" => Prepared::Rust(prepare(query)?),
+                    "python" => Prepared::Python(prepare(query)?),
+                    _ => return None,
+                }))
+            })
+            .clone()
+    }
+}
+
+/// Lengths and lines are memoized during an evaluation,
+/// as scripts usually ask for them on every node of a subtree.
+///
+/// Only nodes with children are memoized, leaves are measured directly.
+#[derive(Default, Clone)]
+pub(super) struct MetricCache(Arc<Mutex<HashMap<NodeIdentifier, TextMetrics>>>);
+
+#[derive(Clone, Copy, Default)]
+struct TextMetrics {
+    bytes: i64,
+    line_breaks: usize,
+    lines: i64,
+}
+
+pub(super) fn add_node_utils(
+    engine: &mut Engine,
+    state: SharedState,
+    current: NodeIdentifier,
+    queries: QueryCache,
+    metrics: MetricCache,
+) {
+    macro_rules! stores {
+        ($s:expr) => {
+            $s.repositories.read().unwrap().processor.main_stores
+        };
+    }
+    macro_rules! register {
+        ($name:literal, $f:expr) => {
+            let s = state.clone();
+            engine.register_fn($name, move || $f(&stores!(s), current));
+            let s = state.clone();
+            engine.register_fn($name, move |id: NodeIdentifier| $f(&stores!(s), id));
+        };
+    }
+    register!("kind", kind);
+    register!("lang", lang);
+    register!("label", label);
+    register!("is_file", is_file);
+
+    macro_rules! register_metric {
+        ($name:literal, $field:ident) => {
+            let (s, m) = (state.clone(), metrics.clone());
+            engine.register_fn($name, move || text_metrics(&stores!(s), &m, current).$field);
+            let (s, m) = (state.clone(), metrics.clone());
+            engine.register_fn($name, move |id: NodeIdentifier| {
+                text_metrics(&stores!(s), &m, id).$field
+            });
+        };
+    }
+    register_metric!("byte_len", bytes);
+    register_metric!("line_count", lines);
+
+    let s = state.clone();
+    engine.register_fn("child_at", move |i: i64| child_at(&stores!(s), current, i));
+    let s = state.clone();
+    engine.register_fn("child_at", move |id: NodeIdentifier, i: i64| {
+        child_at(&stores!(s), id, i)
+    });
+    let s = state.clone();
+    let q = queries.clone();
+    engine.register_fn("matches", move |query: String| {
+        matches(&stores!(s), &q, current, &query)
+    });
+    let s = state;
+    let q = queries;
+    engine.register_fn("matches", move |id: NodeIdentifier, query: String| {
+        matches(&stores!(s), &q, id, &query)
+    });
+}
+
+/// Short name of the language of a type, eg. "java", "cpp" or "ts",
+/// "directory" for directories and "blob" for opaque files, like in extension mappings.
+fn lang_name(t: &AnyType) -> &'static str {
+    if t.is_directory() {
+        return "directory";
+    }
+    let t = t.as_any();
+    if t.is::<hyper_ast_gen_ts_java::types::Type>() {
+        "java"
+    } else if t.is::<hyper_ast_gen_ts_cpp::types::Type>() {
+        "cpp"
+    } else if t.is::<hyper_ast_gen_ts_ts::types::Type>() {
+        "ts"
+    } else if t.is::<hyper_ast_gen_ts_rust::types::Type>() {
+        "rust"
+    } else if t.is::<hyper_ast_gen_ts_python::types::Type>() {
+        "python"
+    } else if t.is::<hyper_ast_gen_ts_xml::types::Type>() {
+        "xml"
+    } else if t.is::<hyper_ast::blob::types::Type>() {
+        "blob"
+    } else {
+        ""
+    }
+}
+
+fn kind(stores: &SimpleStores<TStore>, id: NodeIdentifier) -> String {
+    let n = stores.node_store.resolve(id);
+    stores.type_store.resolve_type(&n).to_string()
+}
+
+fn lang(stores: &SimpleStores<TStore>, id: NodeIdentifier) -> String {
+    let n = stores.node_store.resolve(id);
+    lang_name(&stores.type_store.resolve_type(&n)).to_string()
+}
+
+/// The file name for files and directories, the text of leaves like identifiers,
+/// empty for nodes without label.
+fn label(stores: &SimpleStores<TStore>, id: NodeIdentifier) -> String {
+    let n = stores.node_store.resolve(id);
+    n.try_get_label()
+        .map_or(String::new(), |l| stores.label_store.resolve(l).to_string())
+}
+
+fn is_file(stores: &SimpleStores<TStore>, id: NodeIdentifier) -> bool {
+    let n = stores.node_store.resolve(id);
+    stores.type_store.resolve_type(&n).is_file()
+}
+
+/// Length and lines of the text of a node.
+///
+/// Directories do not store their length, it is summed over their content like their lines.
+/// The content of opaque files is not kept, they count as zero lines.
+fn text_metrics(
+    stores: &SimpleStores<TStore>,
+    metrics: &MetricCache,
+    id: NodeIdentifier,
+) -> TextMetrics {
+    use hyper_ast::types::WithSerialization;
+    let n = stores.node_store.resolve(id);
+    let t = stores.type_store.resolve_type(&n);
+    let lines = |line_breaks: usize, content: i64| {
+        if t.is_directory() {
+            content
+        } else if matches!(lang_name(&t), "blob" | "") {
+            0
+        } else {
+            line_breaks as i64 + 1
+        }
+    };
+    let Some(cs) = n.children() else {
+        let (bytes, line_breaks) = match n.try_get_label() {
+            Some(l) => {
+                let l = stores.label_store.resolve(l);
+                (l.len() as i64, l.bytes().filter(|x| *x == b'\n').count())
+            }
+            None => (t.to_string().len() as i64, 0),
+        };
+        return TextMetrics {
+            bytes: n.try_bytes_len().map_or(bytes, |len| len as i64),
+            line_breaks,
+            lines: lines(line_breaks, 0),
+        };
+    };
+    if let Some(x) = metrics.0.lock().unwrap().get(&id) {
+        return *x;
+    }
+    let mut x = TextMetrics::default();
+    for c in cs.0.iter() {
+        let c = text_metrics(stores, metrics, *c);
+        x.bytes += c.bytes;
+        x.line_breaks += c.line_breaks;
+        x.lines += c.lines;
+    }
+    if let Some(len) = n.try_bytes_len() {
+        x.bytes = len as i64;
+    }
+    x.lines = lines(x.line_breaks, x.lines);
+    metrics.0.lock().unwrap().insert(id, x);
+    x
+}
+
+/// Returns `()` when there is no child at `i`.
+fn child_at(
+    stores: &SimpleStores<TStore>,
+    id: NodeIdentifier,
+    i: i64,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    let i = i
+        .to_usize()
+        .ok_or("child_at() should be given a positive offset")?;
+    let n = stores.node_store.resolve(id);
+    Ok(n.children()
+        .and_then(|cs| cs.0.get(i).copied())
+        .map_or(Dynamic::UNIT, Dynamic::from))
+}
+
+fn matches(
+    stores: &SimpleStores<TStore>,
+    queries: &QueryCache,
+    id: NodeIdentifier,
+    query: &str,
+) -> bool {
+    use hyper_ast_gen_ts_cpp::types::TIdN as Cpp;
+    use hyper_ast_gen_ts_java::types::TIdN as Java;
+    use hyper_ast_gen_ts_python::types::TIdN as Python;
+    use hyper_ast_gen_ts_rust::types::TIdN as Rust;
+    use hyper_ast_gen_ts_ts::types::TIdN as Ts;
+    let n = stores.node_store.resolve(id);
+    let lang = lang_name(&stores.type_store.resolve_type(&n));
+    let Some(query) = queries.get(query, lang) else {
+        return false;
+    };
+    match &*query {
+        Prepared::Java(m) => m.is_matching::<_, Java<NodeIdentifier>>(stores, id),
+        Prepared::Cpp(m) => m.is_matching::<_, Cpp<NodeIdentifier>>(stores, id),
+        Prepared::Ts(m) => m.is_matching::<_, Ts<NodeIdentifier>>(stores, id),
+        Prepared::Rust(m) => m.is_matching::<_, Rust<NodeIdentifier>>(stores, id),
+        Prepared::Python(m) => m.is_matching::<_, Python<NodeIdentifier>>(stores, id),
+    }
+}
+
+/// This is where queries are checked against a language, eg. for the node types they name.
+fn prepare<Ty>(query: &str) -> Option<Matcher<Ty>>
+where
+    Ty: for<'b> TryFrom<&'b str> + std::fmt::Debug + Copy,
+    for<'b> <Ty as TryFrom<&'b str>>::Error: std::fmt::Debug,
+{
+    let (query_store, query) = ts_query(query.as_bytes());
+    match PreparedMatcher::<_, Ty>::new(&query_store, query) {
+        Ok(matcher) => Some(matcher.detach()),
+        Err(e) => {
+            log::debug!(
+                "query cannot be used on {}: {}",
+                std::any::type_name::<Ty>(),
+                e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_java(state: &SharedState, text: &'static [u8]) -> NodeIdentifier {
+        use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+        let mut repositories = state.repositories.write().unwrap();
+        let stores = &mut repositories.processor.main_stores;
+        let Ok(tree) = JavaTreeGen::<TStore>::tree_sitter_parse(text) else {
+            panic!("syntax error in {}", String::from_utf8_lossy(text))
+        };
+        let mut md_cache = Default::default();
+        let mut tree_gen = JavaTreeGen::new(stores, &mut md_cache);
+        let file = tree_gen.generate_file(b"A.java", text, tree.walk());
+        file.local.compressed_node
+    }
+
+    fn parse_cpp(state: &SharedState, text: &'static [u8]) -> NodeIdentifier {
+        use hyper_ast_gen_ts_cpp::legion::CppTreeGen;
+        let mut repositories = state.repositories.write().unwrap();
+        let stores = &mut repositories.processor.main_stores;
+        let Ok(tree) = CppTreeGen::<TStore>::tree_sitter_parse(text) else {
+            panic!("syntax error in {}", String::from_utf8_lossy(text))
+        };
+        let mut md_cache = Default::default();
+        let mut tree_gen = CppTreeGen::new(stores, &mut md_cache);
+        let file = tree_gen.generate_file(b"a.cpp", text, tree.walk());
+        file.local.compressed_node
+    }
+
+    fn check(state: &SharedState, current: NodeIdentifier, script: &str) {
+        let mut engine = Engine::new();
+        add_node_utils(
+            &mut engine,
+            state.clone(),
+            current,
+            Default::default(),
+            Default::default(),
+        );
+        let r = engine.eval::<bool>(script);
+        assert!(r.as_ref().map_or(false, |x| *x), "{}: {:?}", script, r);
+    }
+
+    #[test]
+    fn java_nodes() {
+        let state = SharedState::default();
+        let text = "class A {\n    int f() { return 1; }\n}\n";
+        let root = parse_java(&state, text.as_bytes());
+        check(&state, root, r#"kind() == "program""#);
+        check(&state, root, r#"lang() == "java""#);
+        check(&state, root, r#"label() == "A.java" && is_file()"#);
+        check(&state, root, r#"kind(child_at(0)) == "class_declaration""#);
+        check(&state, root, r#"label(child_at(child_at(0), 2)) == "A""#);
+        check(&state, root, r#"type_of(child_at(42)) == "()""#);
+        check(&state, root, r#"matches(child_at(0), "(class_declaration)")"#);
+        check(&state, root, r#"!matches(child_at(0), "(method_declaration)")"#);
+        // function_definition is a C++ type
+        check(&state, root, r#"!matches(child_at(0), "(function_definition)")"#);
+        check(&state, root, &format!("byte_len() == {}", text.len()));
+        check(&state, root, "line_count() == 4");
+    }
+
+    #[test]
+    fn cpp_nodes() {
+        let state = SharedState::default();
+        let text = "int f() {\n    return 1;\n}\n";
+        let root = parse_cpp(&state, text.as_bytes());
+        check(&state, root, r#"kind() == "translation_unit""#);
+        check(&state, root, r#"lang() == "cpp""#);
+        check(&state, root, r#"label() == "a.cpp" && is_file()"#);
+        check(&state, root, r#"kind(child_at(0)) == "function_definition""#);
+        check(&state, root, r#"lang(child_at(0)) == "cpp""#);
+        check(&state, root, r#"label(child_at(0)) == """#);
+        check(
+            &state,
+            root,
+            r#"matches(child_at(0), "(function_definition declarator: (function_declarator))")"#,
+        );
+        // class_declaration is a Java type
+        check(&state, root, r#"!matches(child_at(0), "(class_declaration)")"#);
+        check(&state, root, &format!("byte_len() == {}", text.len()));
+        check(&state, root, "line_count() == 4");
+    }
+}
@@ -0,0 +1,259 @@
+//! Limits on the resources used by the scripts submitted to the server.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use rhai::{Dynamic, Engine, EvalAltResult};
+
+use super::ScriptingError;
+
+/// Limits applied to each request evaluating scripts
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptLimits {
+    /// operations summed over all the evaluations of a request
+    pub max_operations: u64,
+    pub max_duration: Duration,
+    /// in bytes, of the serialized result,
+    /// also bounds the size of strings, arrays and maps built by scripts
+    pub max_result_size: usize,
+    pub max_call_depth: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 100_000_000,
+            max_duration: Duration::from_secs(10),
+            max_result_size: 1 << 20,
+            max_call_depth: 32,
+        }
+    }
+}
+
+/// Set when the request is dropped, eg. when the client disconnects,
+/// evaluations in progress then stop at their next operation.
+#[derive(Default)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.0.clone()
+    }
+}
+
+impl Drop for Cancellation {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// What remains of the limits of a request, shared by all its engines
+#[derive(Clone)]
+pub(super) struct Budget(Arc<BudgetInner>);
+
+struct BudgetInner {
+    limits: ScriptLimits,
+    /// see [`Budget::start`]
+    start: Mutex<Instant>,
+    operations: AtomicU64,
+    /// shared by the entries of a request, see [`Budget::entry`]
+    result_size: Arc<AtomicUsize>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Budget {
+    pub(super) fn new(limits: ScriptLimits, cancelled: Arc<AtomicBool>) -> Self {
+        Self(Arc::new(BudgetInner {
+            limits,
+            start: Mutex::new(Instant::now()),
+            operations: AtomicU64::new(0),
+            result_size: Default::default(),
+            cancelled,
+        }))
+    }
+
+    /// A fresh budget of operations and duration for an entry of a request, eg. a commit of a batch,
+    /// the sizes of the results are still summed over the whole request.
+    pub(super) fn entry(&self) -> Self {
+        Self(Arc::new(BudgetInner {
+            limits: self.0.limits,
+            start: Mutex::new(Instant::now()),
+            operations: AtomicU64::new(0),
+            result_size: self.0.result_size.clone(),
+            cancelled: self.0.cancelled.clone(),
+        }))
+    }
+
+    /// Starts the clock, once the commits to evaluate are built,
+    /// so that only evaluations count in the duration of a request.
+    pub(super) fn start(&self) {
+        *self.0.start.lock().unwrap() = Instant::now();
+    }
+
+    pub(super) fn apply(&self, engine: &mut Engine) {
+        let limits = &self.0.limits;
+        engine.set_max_call_levels(limits.max_call_depth);
+        engine.set_max_string_size(limits.max_result_size);
+        engine.set_max_array_size(limits.max_result_size);
+        engine.set_max_map_size(limits.max_result_size);
+        let budget = self.clone();
+        engine.on_progress(move |_| budget.consume());
+    }
+
+    /// Called on each operation, returns the reason to terminate the evaluation
+    fn consume(&self) -> Option<Dynamic> {
+        let ops = self.0.operations.fetch_add(1, Ordering::Relaxed);
+        if self.0.cancelled.load(Ordering::Relaxed) {
+            Some("cancelled".into())
+        } else if ops >= self.0.limits.max_operations {
+            Some(format!("more than {} operations", self.0.limits.max_operations).into())
+        } else if ops % 1024 == 0
+            && self.0.start.lock().unwrap().elapsed() > self.0.limits.max_duration
+        {
+            Some(format!("took more than {:?}", self.0.limits.max_duration).into())
+        } else {
+            None
+        }
+    }
+
+    /// Sums the sizes of the results of a request, eg. one per commit
+    pub(super) fn consume_result(&self, result: &Dynamic) -> Result<(), ScriptingError> {
+        let size = serde_json::to_vec(result).map_or(0, |x| x.len());
+        let size = self.0.result_size.fetch_add(size, Ordering::Relaxed) + size;
+        if size > self.0.limits.max_result_size {
+            Err(ScriptingError::LimitExceeded(format!(
+                "results of {} bytes, more than {} bytes",
+                size, self.0.limits.max_result_size
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Distinguishes exceeded limits from the other errors of scripts
+pub(super) fn eval_error(e: Box<EvalAltResult>) -> ScriptingError {
+    let msg = e.to_string();
+    match *e {
+        EvalAltResult::ErrorTerminated(reason, _) => {
+            ScriptingError::LimitExceeded(reason.to_string())
+        }
+        EvalAltResult::ErrorTooManyOperations(_) => {
+            ScriptingError::LimitExceeded("too many operations".to_string())
+        }
+        EvalAltResult::ErrorStackOverflow(_) => {
+            ScriptingError::LimitExceeded("too many nested calls".to_string())
+        }
+        EvalAltResult::ErrorDataTooLarge(what, _) => {
+            ScriptingError::LimitExceeded(format!("{} is too large", what))
+        }
+        EvalAltResult::ErrorInFunctionCall(_, _, e, _) => match eval_error(e) {
+            ScriptingError::AtEvaluation(_) => ScriptingError::AtEvaluation(msg),
+            x => x,
+        },
+        _ => ScriptingError::AtEvaluation(msg),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a few operations, that cannot be evaluated at compilation
+    const SUM: &str = "let x = 0; for i in 0..10 { x += i; } x";
+
+    fn eval(budget: &Budget, script: &str) -> Result<Dynamic, ScriptingError> {
+        let mut engine = Engine::new();
+        budget.apply(&mut engine);
+        engine.eval::<Dynamic>(script).map_err(eval_error)
+    }
+
+    fn limits() -> ScriptLimits {
+        ScriptLimits {
+            max_operations: 1000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn operations_are_limited() {
+        let budget = Budget::new(limits(), Default::default());
+        assert!(eval(&budget, SUM).is_ok());
+        let Err(ScriptingError::LimitExceeded(reason)) = eval(&budget, "loop {}") else {
+            panic!("an endless loop should exceed the limit")
+        };
+        assert_eq!("more than 1000 operations", reason);
+        // operations are summed over the evaluations of a request
+        assert!(matches!(
+            eval(&budget, SUM),
+            Err(ScriptingError::LimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn limits_are_found_in_function_calls() {
+        let budget = Budget::new(limits(), Default::default());
+        assert!(matches!(
+            eval(&budget, "fn f() { loop {} } f()"),
+            Err(ScriptingError::LimitExceeded(_))
+        ));
+        let budget = Budget::new(limits(), Default::default());
+        assert!(matches!(
+            eval(&budget, "fn f() { x } f()"),
+            Err(ScriptingError::AtEvaluation(_))
+        ));
+    }
+
+    #[test]
+    fn cancelled_evaluations_stop() {
+        let cancellation = Cancellation::default();
+        let budget = Budget::new(limits(), cancellation.flag());
+        assert!(eval(&budget, SUM).is_ok());
+        drop(cancellation);
+        let Err(ScriptingError::LimitExceeded(reason)) = eval(&budget, SUM) else {
+            panic!("the evaluation should be cancelled")
+        };
+        assert_eq!("cancelled", reason);
+    }
+
+    #[test]
+    fn entries_have_their_own_operations() {
+        let cancellation = Cancellation::default();
+        let budget = Budget::new(limits(), cancellation.flag());
+        let entry = budget.entry();
+        assert!(matches!(
+            eval(&entry, "loop {}"),
+            Err(ScriptingError::LimitExceeded(_))
+        ));
+        let entry = budget.entry();
+        assert!(eval(&entry, SUM).is_ok());
+        // but they share the results and the cancellation of the request
+        let result = Dynamic::from("a".repeat(600_000));
+        assert!(entry.consume_result(&result).is_ok());
+        assert!(budget.entry().consume_result(&result).is_err());
+        drop(cancellation);
+        assert!(eval(&budget.entry(), SUM).is_err());
+    }
+
+    #[test]
+    fn duration_counts_from_the_start() {
+        let limits = ScriptLimits {
+            max_duration: Duration::from_millis(50),
+            ..Default::default()
+        };
+        let budget = Budget::new(limits, Default::default());
+        // eg. while building the commits
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(matches!(
+            eval(&budget, SUM),
+            Err(ScriptingError::LimitExceeded(_))
+        ));
+        budget.start();
+        assert!(eval(&budget, SUM).is_ok());
+    }
+}
@@ -0,0 +1,138 @@
+//! Memoization of the values accumulated on subtrees.
+//!
+//! The value of a node only depends on the scripts, on the subtree, and on the value given by its parent,
+//! thus it can be reused on every commit sharing the subtree.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt::Write,
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use dashmap::DashMap;
+use hyper_ast::store::defaults::NodeIdentifier;
+use rhai::{Array, Dynamic, Map};
+
+use super::ScriptContent;
+
+/// Identifies the value accumulated on a subtree, see [`MemoCache`]
+pub(crate) type MemoKey = (u64, NodeIdentifier, String);
+
+/// Memoized values, at most `capacity` of them, the least recently used are evicted first
+pub(crate) struct MemoCache {
+    values: DashMap<MemoKey, (Dynamic, AtomicU64)>,
+    capacity: usize,
+    /// incremented on each use of a value, to order them
+    clock: AtomicU64,
+}
+
+impl Default for MemoCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl MemoCache {
+    pub(crate) const DEFAULT_CAPACITY: usize = 1 << 20;
+
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            values: Default::default(),
+            capacity,
+            clock: Default::default(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub(crate) fn get(&self, key: &MemoKey) -> Option<Dynamic> {
+        let entry = self.values.get(key)?;
+        entry.1.store(self.tick(), Ordering::Relaxed);
+        Some(entry.0.clone())
+    }
+
+    pub(crate) fn insert(&self, key: MemoKey, value: Dynamic) {
+        self.values
+            .insert(key, (value, AtomicU64::new(self.tick())));
+        if self.values.len() > self.capacity {
+            self.evict();
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Evicts the least recently used quarter of the values,
+    /// so that the cost of finding them is shared by the following insertions.
+    fn evict(&self) {
+        let keep = self.capacity - self.capacity / 4;
+        let mut used: Vec<u64> = self
+            .values
+            .iter()
+            .map(|x| x.value().1.load(Ordering::Relaxed))
+            .collect();
+        if used.len() <= keep {
+            return;
+        }
+        let i = used.len() - keep - 1;
+        let threshold = *used.select_nth_unstable(i).1;
+        self.values
+            .retain(|_, (_, used)| used.load(Ordering::Relaxed) > threshold);
+    }
+}
+
+pub(super) fn script_hash(script: &ScriptContent) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    script.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A canonical representation of the value given to a node by its parent.
+///
+/// None if the value contains custom types, eg. Mean or Max, as they cannot be compared.
+pub(super) fn fingerprint(value: &Dynamic) -> Option<String> {
+    let mut out = String::new();
+    write_value(&mut out, value)?;
+    Some(out)
+}
+
+fn write_value(out: &mut String, value: &Dynamic) -> Option<()> {
+    if value.is_unit() {
+        out.push_str("()");
+    } else if let Ok(x) = value.as_bool() {
+        write!(out, "{}", x).ok()?;
+    } else if let Ok(x) = value.as_int() {
+        write!(out, "{}i", x).ok()?;
+    } else if let Ok(x) = value.as_float() {
+        write!(out, "{:?}f", x).ok()?;
+    } else if let Ok(x) = value.as_char() {
+        write!(out, "{:?}", x).ok()?;
+    } else if value.is_string() {
+        write!(out, "{:?}", value.clone().into_string().ok()?).ok()?;
+    } else if let Some(x) = value.read_lock::<NodeIdentifier>() {
+        write!(out, "{:?}", *x).ok()?;
+    } else if let Some(x) = value.read_lock::<Array>() {
+        out.push('[');
+        for x in x.iter() {
+            write_value(out, x)?;
+            out.push(',');
+        }
+        out.push(']');
+    } else if let Some(x) = value.read_lock::<Map>() {
+        // maps are ordered by key
+        out.push('{');
+        for (k, x) in x.iter() {
+            write!(out, "{:?}:", k.as_str()).ok()?;
+            write_value(out, x)?;
+            out.push(',');
+        }
+        out.push('}');
+    } else {
+        return None;
+    }
+    Some(())
+}
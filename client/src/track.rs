@@ -272,9 +272,6 @@ pub fn track_code(
     } = query;
     let repo_specifier = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
     let repo_handle = state
-        .repositories
-        .write()
-        .unwrap()
         .get_config(repo_specifier)
         .ok_or_else(|| TrackingError {
             compute_time: now.elapsed().as_secs_f64(),
@@ -296,9 +293,6 @@ pub fn track_code(
     while node_processed < MAX_NODES {
         commits_processed += 1;
         let commits = state
-            .repositories
-            .write()
-            .unwrap()
             .pre_process_with_limit(&mut repository, "", &commit, 2)
             .map_err(|e| TrackingError {
                 compute_time: now.elapsed().as_secs_f64(),
@@ -409,9 +403,6 @@ pub(crate) fn track_code_at_path(
     } = path;
     let repo_specifier = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
     let repository = state
-        .repositories
-        .write()
-        .unwrap()
         .get_config(repo_specifier)
         .ok_or_else(|| TrackingError {
             compute_time: now.elapsed().as_secs_f64(),
@@ -431,9 +422,6 @@ pub(crate) fn track_code_at_path(
     while node_processed < MAX_NODES {
         commits_processed += 1;
         let commits = state
-            .repositories
-            .write()
-            .unwrap()
             .pre_process_with_limit(&mut repository, "", &commit, 2)
             .map_err(|e| TrackingError {
                 compute_time: now.elapsed().as_secs_f64(),
@@ -445,9 +433,6 @@ pub(crate) fn track_code_at_path(
         let src_oid = commits[0];
         let dst_oid = if let Some(before) = &before {
             let commits = state
-                .repositories
-                .write()
-                .unwrap()
                 .pre_process_with_limit(&mut repository, "", before, 2)
                 .map_err(|e| TrackingError {
                     compute_time: now.elapsed().as_secs_f64(),
@@ -567,9 +552,6 @@ pub(crate) fn track_code_at_path_with_changes(
     let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
     let configs = state.clone();
     let repo_handle = state
-        .repositories
-        .write()
-        .unwrap()
         .get_config(repo_spec)
         .ok_or_else(|| TrackingError {
             compute_time: now.elapsed().as_secs_f64(),
@@ -588,9 +570,6 @@ pub(crate) fn track_code_at_path_with_changes(
     while node_processed < MAX_NODES {
         commits_processed += 1;
         let commits = state
-            .repositories
-            .write()
-            .unwrap()
             .pre_process_with_limit(&mut repository, "", &commit, 4)
             .map_err(|e| TrackingError {
                 compute_time: now.elapsed().as_secs_f64(),
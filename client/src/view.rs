@@ -88,17 +88,11 @@ pub fn view(state: SharedState, path: Parameters) -> Result<Json<ViewRes>, Strin
     dbg!(&path);
     let repo_spec = hyper_ast_cvs_git::git::Forge::Github.repo(user, name);
     let repo = state
-        .repositories
-        .write()
-        .unwrap()
         .get_config(repo_spec)
         .ok_or_else(|| "missing config for repository".to_string())?;
     let mut repo = repo.fetch();
     log::warn!("done cloning {}", repo.spec);
     let commits = state
-        .repositories
-        .write()
-        .unwrap()
         .pre_process_with_limit(&mut repo, "", &commit, 2)
         .map_err(|e| e.to_string())?;
     log::warn!(
@@ -0,0 +1,310 @@
+use hyper_ast::{
+    blob::types::Type,
+    filter::BloomSize,
+    hashed::{
+        self, ComputableNodeHashs, IndexingHashBuilder, MetaDataHashsBuilder, SyntaxNodeHashs,
+    },
+    store::{
+        defaults::{LabelIdentifier, NodeIdentifier},
+        nodes::legion::{compo, NodeStore},
+    },
+    tree_gen::SubTreeMetrics,
+    types::LabelStore,
+};
+
+use crate::{
+    processing::{caches, ObjectName},
+    Accumulator, DefaultMetrics, SimpleStores, PROPAGATE_ERROR_ON_BAD_CST_NODE,
+};
+
+/// The generators a file can be sent to by the any processor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize)]
+pub enum FileLang {
+    Java,
+    Cpp,
+    Ts,
+    Xml,
+    Rust,
+    Python,
+    /// kept as an opaque blob node, even if the file could be parsed
+    Blob,
+}
+
+/// Maps suffixes of file names to the generator that should handle them,
+/// the longest matching suffix wins, eg. ".d.ts" takes precedence over ".ts".
+///
+/// A suffix can also be a whole file name, eg. "pom.xml".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionMapping(Vec<(String, FileLang)>);
+
+impl Default for ExtensionMapping {
+    fn default() -> Self {
+        use FileLang::*;
+        let m = [
+            (".java", Java),
+            (".c", Cpp),
+            (".h", Cpp),
+            (".cc", Cpp),
+            (".cpp", Cpp),
+            (".cxx", Cpp),
+            (".hpp", Cpp),
+            (".ts", Ts),
+            (".tsx", Ts),
+            (".mts", Ts),
+            (".cts", Ts),
+            (".js", Ts),
+            (".jsx", Ts),
+            (".mjs", Ts),
+            (".cjs", Ts),
+            (".xml", Xml),
+            (".rs", Rust),
+            (".py", Python),
+        ];
+        Self(m.into_iter().map(|(s, l)| (s.to_owned(), l)).collect())
+    }
+}
+
+impl ExtensionMapping {
+    /// Without any suffix, ie. every file is a blob
+    pub fn empty() -> Self {
+        Self(vec![])
+    }
+
+    /// Adds or overrides the generator used for `suffix`
+    pub fn with(mut self, suffix: impl Into<String>, lang: FileLang) -> Self {
+        let suffix = suffix.into();
+        match self.0.iter_mut().find(|(s, _)| s == &suffix) {
+            Some(x) => x.1 = lang,
+            None => self.0.push((suffix, lang)),
+        }
+        self
+    }
+
+    pub fn resolve(&self, name: &ObjectName) -> FileLang {
+        self.0
+            .iter()
+            .filter(|(s, _)| name.as_bytes().ends_with(s.as_bytes()))
+            .max_by_key(|(s, _)| s.len())
+            .map_or(FileLang::Blob, |(_, l)| *l)
+    }
+}
+
+impl std::str::FromStr for ExtensionMapping {
+    type Err = String;
+
+    /// eg. ".vue=ts,.ino=cpp,.min.js=blob"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut r = Self::default();
+        for x in s.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let (suffix, lang) = x
+                .split_once('=')
+                .ok_or_else(|| format!("'{}' should be of the form suffix=lang", x))?;
+            let lang = match lang.trim() {
+                "java" => FileLang::Java,
+                "cpp" => FileLang::Cpp,
+                "ts" => FileLang::Ts,
+                "xml" => FileLang::Xml,
+                "rust" => FileLang::Rust,
+                "python" => FileLang::Python,
+                "blob" => FileLang::Blob,
+                x => return Err(format!("'{}' is not an available language", x)),
+            };
+            r = r.with(suffix.trim(), lang);
+        }
+        Ok(r)
+    }
+}
+
+/// Sends the file to the generator of `lang`,
+/// binary files, files with a disabled or unknown generator and files that fail to parse become blobs.
+pub(crate) fn handle_file(
+    stores: &mut SimpleStores,
+    caches: &mut caches::Any,
+    lang: FileLang,
+    name: &ObjectName,
+    text: &[u8],
+) -> (NodeIdentifier, DefaultMetrics) {
+    if std::str::from_utf8(text).is_err() {
+        return handle_blob_file(stores, name, text);
+    }
+    match parse_file(stores, caches, lang, name, text) {
+        Some(x) => x,
+        None => handle_blob_file(stores, name, text),
+    }
+}
+
+fn parse_file(
+    stores: &mut SimpleStores,
+    caches: &mut caches::Any,
+    lang: FileLang,
+    name: &ObjectName,
+    text: &[u8],
+) -> Option<(NodeIdentifier, DefaultMetrics)> {
+    let line_break = if text.contains(&b'\r') {
+        "\r\n".as_bytes().to_vec()
+    } else {
+        "\n".as_bytes().to_vec()
+    };
+    match lang {
+        #[cfg(feature = "java")]
+        FileLang::Java => {
+            use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+            let mut tree_gen = JavaTreeGen {
+                line_break,
+                stores,
+                md_cache: &mut caches.java_md_cache,
+            };
+            let x = crate::java::handle_java_file(&mut tree_gen, name, text).ok()?;
+            Some((x.local.compressed_node, x.local.metrics))
+        }
+        #[cfg(feature = "cpp")]
+        FileLang::Cpp => {
+            use hyper_ast_gen_ts_cpp::legion::CppTreeGen;
+            let mut tree_gen = CppTreeGen {
+                line_break,
+                stores,
+                md_cache: &mut caches.cpp_md_cache,
+            };
+            let x = crate::cpp::handle_cpp_file(&mut tree_gen, name, text).ok()?;
+            Some((x.local.compressed_node, x.local.metrics))
+        }
+        #[cfg(feature = "ts")]
+        FileLang::Ts => {
+            use hyper_ast_gen_ts_ts::legion::TsTreeGen;
+            let mut tree_gen = TsTreeGen {
+                line_break,
+                stores,
+                md_cache: &mut caches.ts_md_cache,
+            };
+            let x = crate::ts::handle_ts_file(&mut tree_gen, name, text).ok()?;
+            Some((x.local.compressed_node, x.local.metrics))
+        }
+        #[cfg(feature = "maven")]
+        FileLang::Xml => {
+            use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
+            let tree = match XmlTreeGen::<crate::TStore>::tree_sitter_parse(text) {
+                Ok(tree) => tree,
+                Err(tree) => {
+                    log::warn!("bad CST");
+                    log::debug!("{:?}", name.try_str());
+                    if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                        return None;
+                    }
+                    tree
+                }
+            };
+            let mut tree_gen = XmlTreeGen { line_break, stores };
+            let x = tree_gen.generate_file(name.as_bytes(), text, tree.walk());
+            Some((x.local.compressed_node, x.local.metrics))
+        }
+        #[cfg(feature = "rust")]
+        FileLang::Rust => {
+            use hyper_ast_gen_ts_rust::legion::RustTreeGen;
+            let mut tree_gen = RustTreeGen {
+                line_break,
+                stores,
+                md_cache: &mut caches.rust_md_cache,
+            };
+            let x = crate::rust::handle_rust_file(&mut tree_gen, name, text).ok()?;
+            Some((x.local.compressed_node, x.local.metrics))
+        }
+        #[cfg(feature = "python")]
+        FileLang::Python => {
+            use hyper_ast_gen_ts_python::legion::PythonTreeGen;
+            let mut tree_gen = PythonTreeGen {
+                line_break,
+                stores,
+                md_cache: &mut caches.python_md_cache,
+            };
+            let x = crate::python::handle_python_file(&mut tree_gen, name, text).ok()?;
+            Some((x.local.compressed_node, x.local.metrics))
+        }
+        _ => None,
+    }
+}
+
+/// An opaque leaf labeled with the name of the file.
+///
+/// The content is only hashed, so that two versions of a file are not deduplicated.
+pub(crate) fn handle_blob_file(
+    stores: &mut SimpleStores,
+    name: &ObjectName,
+    text: &[u8],
+) -> (NodeIdentifier, DefaultMetrics) {
+    let name = String::from_utf8_lossy(name.as_bytes()).into_owned();
+    let content = SyntaxNodeHashs::<u32>::prepare(text);
+    let hashs = SyntaxNodeHashs {
+        structt: content,
+        label: content,
+        syntax: content,
+    };
+    let hbuilder = hashed::Builder::new(hashs, &Type::Blob, &name, 1);
+    let hashable = hbuilder.most_discriminating();
+    let hashs = hbuilder.build();
+    let label = stores.label_store.get_or_insert(name);
+    let eq = |x: hyper_ast::store::nodes::legion::EntryRef| {
+        x.get_component::<Type>() == Ok(&Type::Blob)
+            && x.get_component::<LabelIdentifier>() == Ok(&label)
+            && x.get_component::<SyntaxNodeHashs<u32>>() == Ok(&hashs)
+    };
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    let node_id = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        let vacant = insertion.vacant();
+        NodeStore::insert_after_prepare(
+            vacant,
+            (
+                Type::Blob,
+                label,
+                hashs,
+                compo::BytesLen(text.len() as u32),
+                BloomSize::None,
+            ),
+        )
+    };
+    let metrics = SubTreeMetrics {
+        size: 1,
+        height: 1,
+        hashs,
+        size_no_spaces: 1,
+    };
+    (node_id, metrics)
+}
+
+pub struct AnyModuleAcc {
+    pub(crate) name: String,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) metrics: DefaultMetrics,
+}
+
+impl From<String> for AnyModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl AnyModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for AnyModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, DefaultMetrics));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1);
+    }
+}
+
+impl Accumulator for AnyModuleAcc {
+    type Unlabeled = (NodeIdentifier, DefaultMetrics);
+}
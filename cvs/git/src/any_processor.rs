@@ -0,0 +1,413 @@
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+use git2::{Oid, Repository};
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{
+        defaults::NodeIdentifier,
+        nodes::legion::{compo, compo::CS, NodeStore},
+    },
+    tree_gen::SubTreeMetrics,
+    types::LabelStore,
+};
+use hyper_ast_gen_ts_xml::{legion::eq_node, types::Type};
+
+use crate::{
+    any::AnyModuleAcc,
+    git::BasicGitObject,
+    preprocessed::RepositoryProcessor,
+    processing::{erased::ParametrizedCommitProc2, CacheHolding, ObjectName},
+    DefaultMetrics, Processor, SimpleStores,
+};
+
+/// Explores the whole repository without assuming a build system,
+/// each file is sent to a generator depending on its name, see [`crate::any::ExtensionMapping`].
+pub struct AnyProcessor<'a, 'b, 'c, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<(Oid, Vec<BasicGitObject>, Acc)>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<AnyProc>,
+}
+
+impl<'a, 'b, 'c, Acc: From<String>> AnyProcessor<'a, 'b, 'c, Acc> {
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+        parameter: Parameter,
+    ) -> Self {
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<AnyProcessorHolder>();
+        let handle =
+            <AnyProc as crate::processing::erased::CommitProcExt>::register_param(h, parameter);
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c> Processor<AnyModuleAcc> for AnyProcessor<'a, 'b, 'c, AnyModuleAcc> {
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                let full_node =
+                    self.prepro
+                        .handle_any_blob(oid, &name, self.repository, self.handle);
+                let name = self.prepro.intern_object_name(&name);
+                let acc = &mut self.stack.last_mut().unwrap().2;
+                assert!(!acc.children_names.contains(&name));
+                hyper_ast::tree_gen::Accumulator::push(acc, (name, full_node));
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: AnyModuleAcc) -> Option<(NodeIdentifier, DefaultMetrics)> {
+        let name = acc.name.clone();
+        let key = (oid, name.as_bytes().into());
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<AnyProcessorHolder>()
+            .with_parameters_mut(self.handle.0)
+            .get_caches_mut()
+            .dir_map
+            .insert(key, full_node.clone());
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            hyper_ast::tree_gen::Accumulator::push(w, (name, full_node));
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, AnyModuleAcc)> {
+        &mut self.stack
+    }
+}
+
+impl<'a, 'b, 'c> AnyProcessor<'a, 'b, 'c, AnyModuleAcc> {
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_encoded_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").1.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree);
+                self.stack
+                    .push((oid, prepared, AnyModuleAcc::new(name.try_into().unwrap())));
+            }
+            return;
+        }
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<AnyProcessorHolder>()
+            .with_parameters_mut(self.handle.0)
+            .get_caches_mut()
+            .dir_map
+            .get(&(oid, name.clone()))
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let name = self.prepro.intern_object_name(&name);
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(!w.children_names.contains(&name));
+            hyper_ast::tree_gen::Accumulator::push(w, (name, full_node));
+            return;
+        }
+        log::debug!("tree {:?}", name.try_str());
+        let tree = self.repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        self.stack.push((
+            oid,
+            prepared,
+            AnyModuleAcc::new(String::from_utf8_lossy(name.as_bytes()).into_owned()),
+        ));
+    }
+}
+
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+pub(crate) fn make(
+    acc: AnyModuleAcc,
+    stores: &mut SimpleStores,
+) -> (NodeIdentifier, DefaultMetrics) {
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &Type::Directory, &acc.name, size_no_spaces);
+    let hashable = hbuilder.most_discriminating();
+    let label = stores.label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&Type::Directory, Some(&label), &acc.children);
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    let hashs = hbuilder.build();
+    let node_id = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        log::info!("make dir {} {}", &acc.name, acc.children.len());
+        let vacant = insertion.vacant();
+        assert_eq!(acc.children_names.len(), acc.children.len());
+        if acc.children.is_empty() {
+            NodeStore::insert_after_prepare(
+                vacant,
+                (Type::Directory, label, hashs, BloomSize::None),
+            )
+        } else {
+            NodeStore::insert_after_prepare(
+                vacant,
+                (
+                    Type::Directory,
+                    label,
+                    hashs,
+                    compo::Size(size),
+                    compo::Height(height),
+                    compo::SizeNoSpaces(size_no_spaces),
+                    CS(acc.children_names.into_boxed_slice()),
+                    CS(acc.children.into_boxed_slice()),
+                    BloomSize::Much,
+                ),
+            )
+        }
+    };
+
+    let metrics = SubTreeMetrics {
+        size,
+        height,
+        hashs,
+        size_no_spaces,
+    };
+    (node_id, metrics)
+}
+
+impl RepositoryProcessor {
+    /// Files are cached by oid and name, as the name selects the generator
+    pub(crate) fn handle_any_blob(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<AnyProc>,
+    ) -> (NodeIdentifier, DefaultMetrics) {
+        let proc = self
+            .processing_systems
+            .mut_or_default::<AnyProcessorHolder>()
+            .with_parameters_mut(parameters.0);
+        if let Some(already) = proc.cache.object_map.get(&(oid, name.clone())) {
+            return already.clone();
+        }
+        let lang = proc.parameter.mapping.resolve(name);
+        log::info!("blob {:?} {:?} as {:?}", name.try_str(), oid, lang);
+        let blob = repository.find_blob(oid).unwrap();
+        let text = blob.content();
+        let caches = &mut proc.cache;
+        let full_node = crate::any::handle_file(&mut self.main_stores, caches, lang, name, text);
+        caches
+            .object_map
+            .insert((oid, name.clone()), full_node.clone());
+        full_node
+    }
+}
+
+/// The mapping from file names to generators, see [`crate::any::ExtensionMapping`]
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct Parameter {
+    pub mapping: crate::any::ExtensionMapping,
+}
+
+// # Any
+/// Unlike other processors, it can hold multiple configurations, one per mapping.
+#[derive(Default)]
+pub(crate) struct AnyProcessorHolder(Vec<AnyProc>);
+pub(crate) struct AnyProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Any,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for AnyProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = self.0.len();
+                self.0.push(AnyProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+struct PreparedAnyCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+    parameter: Parameter,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedAnyCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedAnyCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = AnyProcessor::<AnyModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+            self.parameter.clone(),
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<AnyProcessorHolder>();
+        let handle = <AnyProc as crate::processing::erased::CommitProcExt>::register_param(
+            h,
+            self.parameter,
+        );
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.0)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+impl AnyProc {
+    pub(crate) fn parameter(&self) -> &Parameter {
+        &self.parameter
+    }
+}
+impl crate::processing::erased::CommitProc for AnyProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!("see reason in the java proc")
+    }
+
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedAnyCommitProc {
+            repository,
+            commit_builder,
+            parameter: self.parameter.clone(),
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for AnyProc {
+    type Holder = AnyProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for AnyProcessorHolder {
+    type Proc = AnyProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        &mut self.0[parameters.0]
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        &self.0[parameters.0]
+    }
+}
+impl CacheHolding<crate::processing::caches::Any> for AnyProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Any {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Any {
+        &self.cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::any::{ExtensionMapping, FileLang};
+
+    #[test]
+    fn extension_mapping() {
+        let mapping = ExtensionMapping::default();
+        assert_eq!(mapping.resolve(&b"Main.java".into()), FileLang::Java);
+        assert_eq!(mapping.resolve(&b"pom.xml".into()), FileLang::Xml);
+        assert_eq!(mapping.resolve(&b"index.d.ts".into()), FileLang::Ts);
+        assert_eq!(mapping.resolve(&b"README.md".into()), FileLang::Blob);
+        assert_eq!(mapping.resolve(&b"Makefile".into()), FileLang::Blob);
+
+        let mapping: ExtensionMapping = ".vue=ts, .min.js=blob, Makefile=blob, .h=cpp"
+            .parse()
+            .unwrap();
+        assert_eq!(mapping.resolve(&b"App.vue".into()), FileLang::Ts);
+        assert_eq!(mapping.resolve(&b"app.js".into()), FileLang::Ts);
+        assert_eq!(mapping.resolve(&b"app.min.js".into()), FileLang::Blob);
+        assert!(".vue=php".parse::<ExtensionMapping>().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use std::path::{Path, PathBuf};
+
+use enumset::EnumSet;
+use hyper_ast::store::defaults::{LabelIdentifier, NodeIdentifier};
+use hyper_ast_gen_ts_rust::legion as rust_tree_gen;
+
+use crate::{processing::ObjectName, Accumulator, DefaultMetrics, ParseErr, SimpleStores};
+
+/// Adds the Cargo.toml to the HyperAST and extracts the structure of a crate or a workspace from it.
+///
+/// There is no toml grammar, so the manifest is stored as a blob.
+/// An ill-formed manifest is still stored but describes nothing.
+pub(crate) fn handle_cargo_toml_file(
+    stores: &mut SimpleStores,
+    name: &ObjectName,
+    text: &[u8],
+) -> Result<CargoToml, ParseErr> {
+    let (compressed_node, metrics) = crate::any::handle_blob_file(stores, name, text);
+    let text = std::str::from_utf8(text)?;
+    let manifest: toml::Value = text.parse().unwrap_or_else(|e| {
+        log::warn!("bad manifest {:?}: {}", name.try_str(), e);
+        toml::Value::Table(Default::default())
+    });
+    let strings = |v: Option<&toml::Value>| -> Vec<String> {
+        v.and_then(|x| x.as_array())
+            .map(|x| {
+                x.iter()
+                    .filter_map(|x| x.as_str())
+                    .map(|x| x.trim_start_matches("./").trim_end_matches('/').to_owned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    // directory containing the file of each target, eg. [[bin]] path = "cli/main.rs"
+    let target_dirs = |key: &str| -> Vec<String> {
+        let targets = match manifest.get(key) {
+            Some(toml::Value::Array(x)) => x.iter().collect(),
+            Some(x @ toml::Value::Table(_)) => vec![x],
+            _ => vec![],
+        };
+        targets
+            .into_iter()
+            .filter_map(|x| x.get("path")?.as_str())
+            .filter_map(|x| Path::new(x.trim_start_matches("./")).parent())
+            .filter(|x| x.components().next().is_some())
+            .map(|x| x.to_string_lossy().into_owned())
+            .collect()
+    };
+    let package = manifest
+        .get("package")
+        .and_then(|x| x.get("name"))
+        .and_then(|x| x.as_str())
+        .map(|x| x.to_owned());
+    let workspace = manifest.get("workspace");
+    let mut source_dirs = vec![];
+    let mut test_source_dirs = vec![];
+    if package.is_some() {
+        source_dirs.push("src".to_owned());
+        source_dirs.extend(target_dirs("lib"));
+        source_dirs.extend(target_dirs("bin"));
+        test_source_dirs.push("tests".to_owned());
+        test_source_dirs.push("benches".to_owned());
+        test_source_dirs.push("examples".to_owned());
+        test_source_dirs.extend(target_dirs("test"));
+        test_source_dirs.extend(target_dirs("bench"));
+        test_source_dirs.extend(target_dirs("example"));
+        source_dirs.sort();
+        source_dirs.dedup();
+        test_source_dirs.sort();
+        test_source_dirs.dedup();
+    }
+    Ok(CargoToml {
+        compressed_node,
+        metrics,
+        package,
+        is_workspace: workspace.is_some(),
+        members: strings(workspace.and_then(|x| x.get("members"))),
+        source_dirs,
+        test_source_dirs,
+    })
+}
+
+/// What matters in a Cargo.toml to find the rust code of a crate or a workspace
+#[derive(Debug, Clone)]
+pub struct CargoToml {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    /// name of the package if the manifest describes a crate
+    pub package: Option<String>,
+    pub is_workspace: bool,
+    /// paths to the member crates of the workspace, they may contain globs, eg. crates/*
+    members: Vec<String>,
+    source_dirs: Vec<String>,
+    test_source_dirs: Vec<String>,
+}
+
+/// Stored on the directories of crates and workspaces
+#[derive(enumset::EnumSetType, Debug)]
+pub enum SemFlags {
+    IsCrate,
+    IsWorkspace,
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+    /// name of the package if the directory is a crate
+    pub(crate) package: Option<String>,
+    pub(crate) is_workspace: bool,
+}
+
+impl MD {
+    pub fn is_crate(&self) -> bool {
+        self.package.is_some()
+    }
+}
+
+impl CargoModuleAcc {
+    pub(crate) fn status(&self) -> EnumSet<SemFlags> {
+        let mut status = EnumSet::new();
+        if self.package.is_some() {
+            status |= SemFlags::IsCrate;
+        }
+        if self.is_workspace {
+            status |= SemFlags::IsWorkspace;
+        }
+        status
+    }
+}
+
+pub struct CargoModuleAcc {
+    pub(crate) name: String,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) metrics: DefaultMetrics,
+    pub(crate) package: Option<String>,
+    pub(crate) is_workspace: bool,
+    pub(crate) sub_modules: Option<Vec<PathBuf>>,
+    pub(crate) main_dirs: Option<Vec<PathBuf>>,
+    pub(crate) test_dirs: Option<Vec<PathBuf>>,
+}
+
+impl From<String> for CargoModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl CargoModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            package: None,
+            is_workspace: false,
+            sub_modules: None,
+            main_dirs: None,
+            test_dirs: None,
+        }
+    }
+    pub(crate) fn with_content(
+        name: String,
+        sub_modules: Vec<PathBuf>,
+        main_dirs: Vec<PathBuf>,
+        test_dirs: Vec<PathBuf>,
+    ) -> Self {
+        let non_empty = |x: Vec<PathBuf>| if x.is_empty() { None } else { Some(x) };
+        Self {
+            sub_modules: non_empty(sub_modules),
+            main_dirs: non_empty(main_dirs),
+            test_dirs: non_empty(test_dirs),
+            ..Self::new(name)
+        }
+    }
+}
+
+impl CargoModuleAcc {
+    /// The manifest is a child, it also tells where to look for member crates and sources
+    pub(crate) fn push_manifest(&mut self, name: LabelIdentifier, manifest: CargoToml) {
+        assert!(!self.children_names.contains(&name));
+        self.children.push(manifest.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(manifest.metrics);
+        let paths = |x: Vec<String>| Some(x.into_iter().map(PathBuf::from).collect());
+        self.package = manifest.package;
+        self.is_workspace = manifest.is_workspace;
+        self.sub_modules = paths(manifest.members);
+        self.main_dirs = paths(manifest.source_dirs);
+        self.test_dirs = paths(manifest.test_source_dirs);
+    }
+    pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rust_tree_gen::Local,
+        skiped_ana: bool,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.metrics);
+    }
+    pub(crate) fn push_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rust_tree_gen::Local,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.metrics);
+    }
+    pub(crate) fn push_test_source_directory(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rust_tree_gen::Local,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.metrics);
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for CargoModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+}
+
+impl Accumulator for CargoModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
@@ -0,0 +1,710 @@
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+use git2::{Oid, Repository};
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{
+        defaults::NodeIdentifier,
+        nodes::legion::{compo, compo::CS, NodeStore},
+    },
+    tree_gen::SubTreeMetrics,
+    types::LabelStore,
+};
+use hyper_ast_gen_ts_rust::{legion::eq_node, types::Type};
+
+use crate::{
+    cargo::{CargoModuleAcc, MD},
+    git::BasicGitObject,
+    preprocessed::RepositoryProcessor,
+    processing::{erased::ParametrizedCommitProc2, CacheHolding, InFiles, ObjectName},
+    Processor, SimpleStores,
+};
+
+/// RMS: Resursive Module Search, also look for crates that are not workspace members
+/// FFWD: Fast ForWarD to rust directories without looking at cargo stuff
+pub struct CargoProcessor<'a, 'b, 'c, const RMS: bool, const FFWD: bool, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<(Oid, Vec<BasicGitObject>, Acc)>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<CargoProc>,
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool, Acc: From<String>>
+    CargoProcessor<'a, 'b, 'c, RMS, FFWD, Acc>
+{
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        mut dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self {
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<CargoProcessorHolder>();
+        let handle =
+            <CargoProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree, &mut dir_path);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<CargoModuleAcc>
+    for CargoProcessor<'a, 'b, 'c, RMS, FFWD, CargoModuleAcc>
+{
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if FFWD {
+                    return;
+                }
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                if crate::processing::file_sys::CargoToml::matches(&name) {
+                    let r = self.prepro.help_handle_cargo_toml(
+                        oid,
+                        &mut self.stack.last_mut().unwrap().2,
+                        &name,
+                        &self.repository,
+                        self.handle.into(),
+                    );
+                    if let Err(e) = r {
+                        log::warn!("ignoring manifest {:?}: {:?}", name.try_str(), e);
+                    }
+                } else if crate::processing::file_sys::Rust::matches(&name) {
+                    self.prepro
+                        .help_handle_rust_file2(
+                            oid,
+                            &mut self.stack.last_mut().unwrap().2,
+                            &name,
+                            self.repository,
+                        )
+                        .unwrap();
+                } else {
+                    log::debug!("not rust source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: CargoModuleAcc) -> Option<(NodeIdentifier, MD)> {
+        let name = acc.name.clone();
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<CargoProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(oid, full_node.clone());
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            w.push_submodule(name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, CargoModuleAcc)> {
+        &mut self.stack
+    }
+}
+
+impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
+    CargoProcessor<'a, 'b, 'c, RMS, FFWD, CargoModuleAcc>
+{
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_encoded_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").1.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                self.stack
+                    .push((oid, prepared, CargoModuleAcc::new(name.try_into().unwrap())));
+            }
+            return;
+        }
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<CargoProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .get(&oid)
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().2;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.children_names.contains(&name));
+            w.push_submodule(name, full_node);
+            return;
+        }
+        log::debug!("cargo tree {:?}", name.try_str());
+        let parent_acc = &mut self.stack.last_mut().unwrap().2;
+        if FFWD {
+            let (name, (full_node, _)) = self.prepro.help_handle_rust_folder(
+                &self.repository,
+                &mut self.dir_path,
+                oid,
+                &name,
+            );
+            assert!(!parent_acc.children_names.contains(&name));
+            parent_acc.push_source_directory(name, full_node);
+            return;
+        }
+        let helper = CargoModuleHelper::from((parent_acc, &name));
+        if helper.source_directories.0 || helper.test_source_directories.0 {
+            // handle as source dir
+            let (name, (full_node, _)) =
+                self.prepro
+                    .help_handle_rust_folder(&self.repository, self.dir_path, oid, &name);
+            let parent_acc = &mut self.stack.last_mut().unwrap().2;
+            assert!(!parent_acc.children_names.contains(&name));
+            if helper.source_directories.0 {
+                parent_acc.push_source_directory(name, full_node);
+            } else {
+                parent_acc.push_test_source_directory(name, full_node);
+            }
+            return;
+        }
+        if helper.submodules.0
+            || !helper.submodules.1.is_empty()
+            || !helper.source_directories.1.is_empty()
+            || !helper.test_source_directories.1.is_empty()
+        {
+            // a member crate, or search further inside for members and sources
+            let tree = self.repository.find_tree(oid).unwrap();
+            let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+            self.stack.push((oid, prepared, helper.into()));
+        } else if RMS {
+            // anyway try to find crates, eg. path dependencies or crates outside of a workspace
+            let tree = self.repository.find_tree(oid).unwrap();
+            let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+            self.stack.push((oid, prepared, helper.into()));
+        }
+    }
+}
+
+pub(crate) fn make(acc: CargoModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &Type::Directory, &acc.name, size_no_spaces);
+    let hashable = hbuilder.most_discriminating();
+    let label = stores.label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&Type::Directory, Some(&label), &acc.children);
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    let hashs = hbuilder.build();
+    let status = acc.status();
+    let node_id = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        log::info!("make cargo dir {} {}", &acc.name, acc.children.len());
+        let vacant = insertion.vacant();
+        assert_eq!(acc.children_names.len(), acc.children.len());
+        let mut dyn_builder = hyper_ast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+        dyn_builder.add(Type::Directory);
+        dyn_builder.add(hashs.clone());
+        dyn_builder.add(label);
+        dyn_builder.add(BloomSize::Much);
+        dyn_builder.add(compo::Size(size));
+        dyn_builder.add(compo::SizeNoSpaces(size_no_spaces));
+        dyn_builder.add(compo::Height(height));
+        dyn_builder.add(CS(acc.children_names.into_boxed_slice()));
+        dyn_builder.add(CS(acc.children.into_boxed_slice()));
+        if !status.is_empty() {
+            // like the maven modules, so that crates can be found from the HyperAST alone
+            dyn_builder.add(status);
+        }
+        NodeStore::insert_built_after_prepare(vacant, dyn_builder.build())
+    };
+
+    let metrics = SubTreeMetrics {
+        size,
+        height,
+        hashs,
+        size_no_spaces,
+    };
+    let md = MD {
+        metrics,
+        package: acc.package,
+        is_workspace: acc.is_workspace,
+    };
+    (node_id, md)
+}
+
+impl RepositoryProcessor {
+    fn help_handle_cargo_toml(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut CargoModuleAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<CargoTomlProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::CargoToml>()
+            .handle(oid, repository, name, parameters, |_, n, t| {
+                crate::cargo::handle_cargo_toml_file(&mut self.main_stores, n, t)
+            })?;
+        let name = self.intern_object_name(name);
+        parent_acc.push_manifest(name, x);
+        Ok(())
+    }
+}
+
+struct CargoModuleHelper {
+    name: String,
+    submodules: (bool, Vec<PathBuf>),
+    source_directories: (bool, Vec<PathBuf>),
+    test_source_directories: (bool, Vec<PathBuf>),
+}
+
+impl From<(&mut CargoModuleAcc, &ObjectName)> for CargoModuleHelper {
+    fn from((parent_acc, name): (&mut CargoModuleAcc, &ObjectName)) -> Self {
+        let process = |mut v: &mut Option<Vec<PathBuf>>| {
+            let mut v = drain_filter_strip(&mut v, name.as_bytes());
+            let c = v.extract_if(|x| x.components().next().is_none()).count();
+            (c > 0, v)
+        };
+        Self {
+            name: name.try_into().unwrap(),
+            submodules: process(&mut parent_acc.sub_modules),
+            source_directories: process(&mut parent_acc.main_dirs),
+            test_source_directories: process(&mut parent_acc.test_dirs),
+        }
+    }
+}
+
+impl From<CargoModuleHelper> for CargoModuleAcc {
+    fn from(helper: CargoModuleHelper) -> Self {
+        CargoModuleAcc::with_content(
+            helper.name,
+            helper.submodules.1,
+            helper.source_directories.1,
+            helper.test_source_directories.1,
+        )
+    }
+}
+
+/// Removes the paths starting with `name` and returns them without their first component.
+///
+/// Workspace members can also start with a glob, eg. crates/*,
+/// such paths are kept as they also match the following siblings.
+fn drain_filter_strip(v: &mut Option<Vec<PathBuf>>, name: &[u8]) -> Vec<PathBuf> {
+    let mut new_sub_modules = vec![];
+    let name = std::str::from_utf8(&name).unwrap();
+    if let Some(sub_modules) = v {
+        sub_modules
+            .extract_if(|x| x.starts_with(name))
+            .for_each(|x| {
+                let x = x.strip_prefix(name).unwrap().to_owned();
+                new_sub_modules.push(x);
+            });
+        for x in sub_modules.iter() {
+            let mut components = x.components();
+            let Some(first) = components.next() else {
+                continue;
+            };
+            let first = first.as_os_str().to_string_lossy();
+            if first.contains('*') && glob_match(&first, name) {
+                new_sub_modules.push(components.as_path().to_owned());
+            }
+        }
+    }
+    new_sub_modules
+}
+
+/// `*` in `pattern` matches any sequence of characters
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == name;
+    };
+    let Some(name) = name.strip_prefix(prefix) else {
+        return false;
+    };
+    name.char_indices()
+        .map(|(i, _)| i)
+        .chain([name.len()])
+        .any(|i| glob_match(rest, &name[i..]))
+}
+
+/// sometimes order of files/dirs can be important, similarly to order of statement
+/// exploration order for example
+pub(crate) fn prepare_dir_exploration(
+    tree: git2::Tree,
+    dir_path: &mut Peekable<Components>,
+) -> Vec<BasicGitObject> {
+    let mut children_objects: Vec<BasicGitObject> = tree
+        .iter()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect();
+    if dir_path.peek().is_none() {
+        let p = children_objects.iter().position(|x| match x {
+            BasicGitObject::Blob(_, n) => crate::processing::file_sys::CargoToml::matches(n),
+            _ => false,
+        });
+        if let Some(p) = p {
+            children_objects.swap(0, p); // priority to config file processing
+            children_objects.reverse(); // we use it like a stack
+        }
+    }
+    children_objects
+}
+
+// # Cargo.toml
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter;
+impl From<crate::processing::erased::ParametrizedCommitProcessor2Handle<CargoProc>>
+    for crate::processing::erased::ParametrizedCommitProcessor2Handle<CargoTomlProc>
+{
+    fn from(
+        value: crate::processing::erased::ParametrizedCommitProcessor2Handle<CargoProc>,
+    ) -> Self {
+        crate::processing::erased::ParametrizedCommitProcessor2Handle(
+            value.0,
+            std::marker::PhantomData,
+        )
+    }
+}
+struct CargoTomlProcessorHolder(Option<CargoTomlProc>);
+impl Default for CargoTomlProcessorHolder {
+    fn default() -> Self {
+        Self(Some(CargoTomlProc(Parameter, Default::default())))
+    }
+}
+struct CargoTomlProc(Parameter, crate::processing::caches::CargoToml);
+impl crate::processing::erased::Parametrized for CargoTomlProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self.0.iter().position(|x| &x.0 == &t).unwrap_or_else(|| {
+            let l = 0;
+            self.0 = Some(CargoTomlProc(t, Default::default()));
+            l
+        });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+// TODO should not have to impl this trait
+impl crate::processing::erased::CommitProc for CargoTomlProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!()
+    }
+
+    fn prepare_processing(
+        &self,
+        repository: &git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        unimplemented!()
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        unimplemented!()
+    }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        unimplemented!()
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for CargoTomlProc {
+    type Holder = CargoTomlProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for CargoTomlProcessorHolder {
+    type Proc = CargoTomlProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::CargoToml> for CargoTomlProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::CargoToml {
+        &mut self.1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::CargoToml {
+        &self.1
+    }
+}
+impl CacheHolding<crate::processing::caches::CargoToml> for CargoTomlProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::CargoToml {
+        &mut self.0.as_mut().unwrap().1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::CargoToml {
+        &self.0.as_ref().unwrap().1
+    }
+}
+
+// # Cargo
+#[derive(Default)]
+pub(crate) struct CargoProcessorHolder(Option<CargoProc>);
+pub(crate) struct CargoProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Cargo,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for CargoProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                self.0 = Some(CargoProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+struct PreparedCargoCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedCargoCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedCargoCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = CargoProcessor::<true, false, CargoModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<CargoProcessorHolder>();
+        let handle =
+            <CargoProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.0)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+impl crate::processing::erased::CommitProc for CargoProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!("see reason in the java proc")
+    }
+
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedCargoCommitProc {
+            repository,
+            commit_builder,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for CargoProc {
+    type Holder = CargoProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for CargoProcessorHolder {
+    type Proc = CargoProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Cargo> for CargoProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Cargo {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Cargo {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::Cargo> for CargoProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Cargo {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Cargo {
+        &self.0.as_ref().unwrap().cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_members() {
+        assert!(glob_match("*", "foo"));
+        assert!(glob_match("hyper_*", "hyper_ast"));
+        assert!(!glob_match("hyper_*", "client"));
+        assert!(glob_match("*_ast", "hyper_ast"));
+
+        let mut members = Some(vec![
+            PathBuf::from("client"),
+            PathBuf::from("crates/*"),
+            PathBuf::from("crates/foo/bar"),
+        ]);
+        let mut under_crates = drain_filter_strip(&mut members, b"crates");
+        under_crates.sort();
+        assert_eq!(
+            under_crates,
+            vec![PathBuf::from("*"), PathBuf::from("foo/bar")]
+        );
+        assert_eq!(members, Some(vec![PathBuf::from("client")]));
+
+        let mut under_crates = Some(under_crates);
+        let foo = drain_filter_strip(&mut under_crates, b"foo");
+        assert_eq!(foo, vec![PathBuf::from("bar"), PathBuf::from("")]);
+        // the glob still applies to the other crates
+        let baz = drain_filter_strip(&mut under_crates, b"baz");
+        assert_eq!(baz, vec![PathBuf::from("")]);
+    }
+
+    #[test]
+    fn workspace_manifest() {
+        let text = br#"
+[workspace]
+members = ["./client", "crates/*/"]
+
+[package]
+name = "root"
+
+[[bin]]
+name = "cli"
+path = "cli/main.rs"
+"#;
+        let mut stores = SimpleStores::default();
+        let name = b"Cargo.toml".into();
+        let manifest = crate::cargo::handle_cargo_toml_file(&mut stores, &name, text).unwrap();
+        assert_eq!(manifest.package.as_deref(), Some("root"));
+        assert!(manifest.is_workspace);
+        let mut acc = CargoModuleAcc::new("".to_string());
+        let name = stores.label_store.get_or_insert("Cargo.toml");
+        acc.push_manifest(name, manifest);
+        assert_eq!(acc.children.len(), 1);
+        assert_eq!(
+            acc.status(),
+            crate::cargo::SemFlags::IsCrate | crate::cargo::SemFlags::IsWorkspace
+        );
+        assert_eq!(
+            acc.sub_modules,
+            Some(vec![PathBuf::from("client"), PathBuf::from("crates/*")])
+        );
+        assert_eq!(
+            acc.main_dirs,
+            Some(vec![PathBuf::from("cli"), PathBuf::from("src")])
+        );
+    }
+
+    #[test]
+    fn ill_formed_manifest_is_kept() {
+        let mut stores = SimpleStores::default();
+        let name = b"Cargo.toml".into();
+        let manifest =
+            crate::cargo::handle_cargo_toml_file(&mut stores, &name, b"[package").unwrap();
+        assert_eq!(manifest.package, None);
+        assert!(!manifest.is_workspace);
+        let n = stores.node_store.resolve(manifest.compressed_node);
+        assert_eq!(
+            n.get_component::<hyper_ast::blob::types::Type>(),
+            Ok(&hyper_ast::blob::types::Type::Blob)
+        );
+    }
+}
@@ -207,6 +207,10 @@ impl crate::processing::erased::CommitProc for CppProc {
     fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
         self.commits.get(&commit_oid)
     }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
 }
 
 impl crate::processing::erased::CommitProcExt for CppProc {
@@ -3,8 +3,8 @@ use std::{
     path::{Path, PathBuf},
 };
 
-pub use git2::Oid;
-use git2::{RemoteCallbacks, Repository, Revwalk, TreeEntry};
+pub use git2::{Oid, Repository};
+use git2::{RemoteCallbacks, Revwalk, TreeEntry};
 use hyper_ast::position::Position;
 
 use crate::processing::ObjectName;
@@ -151,6 +151,10 @@ where
 pub enum Forge {
     Github,
     Gitlab,
+    /// A repository already on disk, opened in place without any network access.
+    /// For such repositories, [`Repo::user`] is the directory containing the repository
+    /// and [`Repo::name`] is the name of the repository directory.
+    Local,
 }
 
 impl std::str::FromStr for Forge {
@@ -170,6 +174,7 @@ impl Forge {
         match self {
             Forge::Github => "https://github.com/",
             Forge::Gitlab => "https://gitlab.com/",
+            Forge::Local => "file://",
         }
     }
     pub fn repo(self, user: impl Into<String>, name: impl Into<String>) -> Repo {
@@ -181,6 +186,19 @@ impl Forge {
             name,
         }
     }
+    /// Designate the repository at `path`, that should already exist on disk.
+    ///
+    /// `path` can either be a working tree, containing a `.git` directory, or a bare repository.
+    pub fn local(path: impl AsRef<Path>) -> Repo {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .map_or(String::new(), |x| x.to_string_lossy().to_string());
+        let user = path
+            .parent()
+            .map_or(String::new(), |x| x.to_string_lossy().to_string());
+        Self::Local.repo(user, name)
+    }
 }
 
 // TODO use `&'static str`s to derive with Copy
@@ -191,14 +209,48 @@ pub struct Repo {
     pub name: String,
 }
 
+/// Default directory where remote repositories are cloned.
+pub const DEFAULT_REPOSITORIES_CACHE: &str = "/tmp/hyperastgitresources/repo/";
+
+static REPOSITORIES_CACHE: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
+/// Set the directory where remote repositories are cloned and then fetched,
+/// can only be set once and before any repository is fetched.
+///
+/// # Errors
+///
+/// Gives back `path` if the cache directory was already set or used.
+pub fn set_repositories_cache(path: impl Into<PathBuf>) -> Result<(), PathBuf> {
+    REPOSITORIES_CACHE.set(path.into())
+}
+
+/// Directory where remote repositories are cloned,
+/// see [`set_repositories_cache`] and [`DEFAULT_REPOSITORIES_CACHE`].
+pub fn repositories_cache() -> &'static Path {
+    REPOSITORIES_CACHE.get_or_init(|| DEFAULT_REPOSITORIES_CACHE.into())
+}
+
 impl Repo {
     pub fn url(&self) -> String {
         format!("{}{}/{}", self.forge.url(), self.user, self.name)
     }
+    /// Path of the repository on disk, once fetched
+    pub fn path(&self) -> PathBuf {
+        match self.forge {
+            Forge::Local => Path::new(&self.user).join(&self.name),
+            _ => {
+                let mut path = repositories_cache().to_path_buf();
+                path.push(&self.user);
+                path.push(&self.name);
+                path
+            }
+        }
+    }
     pub fn fetch(&self) -> Repository {
-        let url = self.url();
-        let path = format!("{}", "/home/clahoche/tmp/hyperastgitresources/repo/");
-        fetch_repository(url, path)
+        match self.forge {
+            Forge::Local => open_local_repository(self.path()),
+            _ => fetch_repository(self.url(), repositories_cache()),
+        }
     }
 }
 
@@ -212,6 +264,12 @@ impl std::str::FromStr for Repo {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix(Forge::Local.url()) {
+            if !path.starts_with("/") {
+                return Err(format!("{} should be an absolute path to a local repository", path))
+            }
+            return Ok(Forge::local(path.trim_end_matches("/")))
+        }
         let (forge, repo) = s.split_once("/").ok_or("give a valid repository address without 'https://' and '.git'")?;
         let (user, name) = repo.split_once("/").ok_or("give a valid repository address without 'https://' and '.git'")?;
         let forge = forge.parse()?;
@@ -224,10 +282,19 @@ impl std::str::FromStr for Repo {
     }
 }
 
+/// Open a repository already on disk, without fetching anything.
+pub fn open_local_repository(path: impl AsRef<Path>) -> Repository {
+    let path = path.as_ref();
+    log::info!("open: {:?}", path);
+    match Repository::open(path) {
+        Ok(repo) => repo,
+        Err(e) => panic!("failed to open {:?}: {}", path, e),
+    }
+}
+
 pub fn fetch_github_repository(repo_name: &str) -> Repository {
     let url = format!("{}{}", "https://github.com/", repo_name);
-    let path = format!("{}", "/tmp/hyperastgitresources/repo/");
-    fetch_repository(url, path)
+    fetch_repository(url, repositories_cache())
 }
 
 /// avoid mixing providers
@@ -507,3 +574,24 @@ fn own(r: &[u8]) -> Result<String, git2::Error> {
     let r = r.map_err(|x| git2::Error::from_str(&x.to_string()));
     r.map(|s| s.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_repo_spec() {
+        let repo: Repo = "file:///srv/git/project/".parse().unwrap();
+        assert_eq!(repo, Forge::Local.repo("/srv/git", "project"));
+        assert_eq!(repo.url(), "file:///srv/git/project");
+        assert_eq!(repo.path(), PathBuf::from("/srv/git/project"));
+        assert!("file://relative/project".parse::<Repo>().is_err());
+    }
+
+    #[test]
+    fn remote_repo_spec() {
+        let repo: Repo = "github.com/INRIA/spoon".parse().unwrap();
+        assert_eq!(repo, Forge::Github.repo("INRIA", "spoon"));
+        assert_eq!(repo.path(), repositories_cache().join("INRIA/spoon"));
+    }
+}
@@ -109,6 +109,9 @@ impl JavaAcc {
             } else {
                 self.skiped_ana = true;
             }
+        } else if skiped_ana {
+            // eg. restored by crate::persist, which does not persist analyses
+            self.skiped_ana = true;
         }
     }
 }
@@ -129,6 +132,9 @@ impl hyper_ast::tree_gen::Accumulator for JavaAcc {
             } else {
                 self.skiped_ana = true;
             }
+        } else if skiped_ana {
+            // eg. restored by crate::persist, which does not persist analyses
+            self.skiped_ana = true;
         }
     }
 }
@@ -3,12 +3,28 @@
 #![feature(trait_upcasting)]
 #![feature(os_str_bytes)]
 pub mod allrefs;
+pub mod any;
 pub mod cpp;
 pub mod git;
 pub mod java;
 pub mod make;
 pub mod maven;
-
+#[cfg(feature = "maven")]
+pub mod modules;
+#[cfg(feature = "cargo")]
+pub mod cargo;
+#[cfg(feature = "rust")]
+pub mod rust;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "npm")]
+pub mod npm;
+#[cfg(feature = "ts")]
+pub mod ts;
+
+pub mod any_processor;
+#[cfg(feature = "cargo")]
+pub mod cargo_processor;
 #[cfg(feature = "cpp")]
 pub mod cpp_processor;
 #[cfg(feature = "java")]
@@ -17,8 +33,15 @@ pub mod java_processor;
 pub mod make_processor;
 #[cfg(feature = "maven")]
 pub mod maven_processor;
+#[cfg(feature = "rust")]
+pub mod rust_processor;
+#[cfg(feature = "python")]
+pub mod python_processor;
+#[cfg(feature = "npm")]
+pub mod npm_processor;
 pub mod multi_preprocessed;
 pub mod no_space;
+pub mod persist;
 /// for now only tested on maven repositories with a pom in root.
 pub mod preprocessed;
 pub mod processing;
@@ -107,6 +130,7 @@ mod type_store {
     use std::{fmt::Display, hash::Hash};
 
     use hyper_ast::{
+        blob,
         store::{defaults::NodeIdentifier, nodes::legion::HashedNodeRef},
         types::{AnyType, HyperType, LangRef, LangWrapper, Shared, TypeIndex, TypeStore, Typed},
     };
@@ -114,6 +138,12 @@ mod type_store {
     use hyper_ast_gen_ts_cpp::types::CppEnabledTypeStore;
     #[cfg(feature = "java")]
     use hyper_ast_gen_ts_java::types::JavaEnabledTypeStore;
+    #[cfg(feature = "rust")]
+    use hyper_ast_gen_ts_rust::types::RustEnabledTypeStore;
+    #[cfg(feature = "python")]
+    use hyper_ast_gen_ts_python::types::PythonEnabledTypeStore;
+    #[cfg(feature = "ts")]
+    use hyper_ast_gen_ts_ts::types::TsEnabledTypeStore;
     #[cfg(feature = "maven")]
     use hyper_ast_gen_ts_xml::types::XmlEnabledTypeStore;
 
@@ -124,6 +154,9 @@ mod type_store {
         Maven = 0,
         Java = 1,
         Cpp = 2,
+        Rust = 3,
+        Python = 4,
+        Ts = 5,
     }
 
     impl Default for TStore {
@@ -156,7 +189,11 @@ mod type_store {
             on_multi!(n, [
                     hyper_ast_gen_ts_java,
                     hyper_ast_gen_ts_cpp,
-                    hyper_ast_gen_ts_xml
+                    hyper_ast_gen_ts_rust,
+                    hyper_ast_gen_ts_python,
+                    hyper_ast_gen_ts_ts,
+                    hyper_ast_gen_ts_xml,
+                    blob
                 ], 
                 (t, u) => u::types::as_any(t),
                 {
@@ -173,7 +210,11 @@ mod type_store {
             on_multi!(n, [
                     hyper_ast_gen_ts_java,
                     hyper_ast_gen_ts_cpp,
-                    hyper_ast_gen_ts_xml
+                    hyper_ast_gen_ts_rust,
+                    hyper_ast_gen_ts_python,
+                    hyper_ast_gen_ts_ts,
+                    hyper_ast_gen_ts_xml,
+                    blob
                 ], 
                 (_t, u) => From::<&'static (dyn LangRef<AnyType>)>::from(&u::types::Lang),
                 {
@@ -189,7 +230,11 @@ mod type_store {
             on_multi!(n, [
                     hyper_ast_gen_ts_java,
                     hyper_ast_gen_ts_cpp,
-                    hyper_ast_gen_ts_xml
+                    hyper_ast_gen_ts_rust,
+                    hyper_ast_gen_ts_python,
+                    hyper_ast_gen_ts_ts,
+                    hyper_ast_gen_ts_xml,
+                    blob
                 ], 
                 (t, u) => {
                     let ty = <u::types::Lang as hyper_ast::types::Lang<_>>::to_u16(*t);
@@ -487,11 +532,147 @@ mod type_store {
         }
     }
 
+    impl<'a> TypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        type Ty = hyper_ast_gen_ts_rust::types::Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+
+        fn resolve_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Ty {
+            todo!()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            todo!()
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Marshaled {
+            todo!()
+        }
+    }
+    impl<'a>
+        RustEnabledTypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_rust::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        const LANG: u16 = 0;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            hyper_ast_gen_ts_rust::types::Type::resolve(t)
+        }
+
+        fn resolve(&self, t: Self::Ty) -> hyper_ast_gen_ts_rust::types::Type {
+            todo!()
+        }
+    }
+
+    impl<'a> TypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        type Ty = hyper_ast_gen_ts_python::types::Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+
+        fn resolve_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Ty {
+            todo!()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            todo!()
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Marshaled {
+            todo!()
+        }
+    }
+    impl<'a>
+        PythonEnabledTypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_python::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        const LANG: u16 = 0;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            hyper_ast_gen_ts_python::types::Type::resolve(t)
+        }
+
+        fn resolve(&self, t: Self::Ty) -> hyper_ast_gen_ts_python::types::Type {
+            todo!()
+        }
+    }
+
+    impl<'a> TypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_ts::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        type Ty = hyper_ast_gen_ts_ts::types::Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+
+        fn resolve_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_ts::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Ty {
+            todo!()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_ts::types::TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            todo!()
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(
+            &self,
+            n: &HashedNodeRef<'a, hyper_ast_gen_ts_ts::types::TIdN<NodeIdentifier>>,
+        ) -> Self::Marshaled {
+            todo!()
+        }
+    }
+    impl<'a>
+        TsEnabledTypeStore<HashedNodeRef<'a, hyper_ast_gen_ts_ts::types::TIdN<NodeIdentifier>>>
+        for TStore
+    {
+        const LANG: u16 = 0;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            hyper_ast_gen_ts_ts::types::Type::resolve(t)
+        }
+
+        fn resolve(&self, t: Self::Ty) -> hyper_ast_gen_ts_ts::types::Type {
+            todo!()
+        }
+    }
+
     #[derive(Clone, Copy, Debug)]
     pub enum MultiType {
         Java(hyper_ast_gen_ts_java::types::Type),
         Cpp(hyper_ast_gen_ts_cpp::types::Type),
+        Rust(hyper_ast_gen_ts_rust::types::Type),
+        Python(hyper_ast_gen_ts_python::types::Type),
+        Ts(hyper_ast_gen_ts_ts::types::Type),
         Xml(hyper_ast_gen_ts_xml::types::Type),
+        Blob(hyper_ast::blob::types::Type),
     }
 
     macro_rules! on_multi {
@@ -499,14 +680,22 @@ mod type_store {
             match $on {
                 MultiType::Java($with) => $body,
                 MultiType::Cpp($with) => $body,
+                MultiType::Rust($with) => $body,
+                MultiType::Python($with) => $body,
+                MultiType::Ts($with) => $body,
                 MultiType::Xml($with) => $body,
+                MultiType::Blob($with) => $body,
             }
         };
         ($on1:ident, $on2:ident, ($with1:ident,$with2:ident) => $body:expr, _ => $default:expr) => {
             match ($on1, $on2) {
                 (MultiType::Java($with1), MultiType::Java($with2)) => $body,
                 (MultiType::Cpp($with1), MultiType::Cpp($with2)) => $body,
+                (MultiType::Rust($with1), MultiType::Rust($with2)) => $body,
+                (MultiType::Python($with1), MultiType::Python($with2)) => $body,
+                (MultiType::Ts($with1), MultiType::Ts($with2)) => $body,
                 (MultiType::Xml($with1), MultiType::Xml($with2)) => $body,
+                (MultiType::Blob($with1), MultiType::Blob($with2)) => $body,
                 _ => $default,
             }
         };
@@ -1,9 +1,11 @@
 use std::{
+    collections::HashMap,
     fmt::{self, Debug},
     ops::AddAssign,
-    path::PathBuf,
+    path::{Component, Path, PathBuf},
 };
 
+use enumset::EnumSet;
 use hyper_ast::{
     hashed::SyntaxNodeHashs,
     position::{StructuralPosition, TreePath},
@@ -11,59 +13,834 @@ use hyper_ast::{
     tree_gen::SubTreeMetrics,
 };
 use hyper_ast_gen_ts_cpp::legion as cpp_tree_gen;
-use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
 
-use crate::{
-    processing::ObjectName, Accumulator, DefaultMetrics, SimpleStores, TStore,
-    PROPAGATE_ERROR_ON_BAD_CST_NODE,
-};
+use crate::{processing::ObjectName, Accumulator, DefaultMetrics, ParseErr, SimpleStores};
 
-pub(crate) fn handle_makefile_file<'a>(
-    tree_gen: &mut XmlTreeGen<'a, TStore>,
+/// Extracts the structure of a C/C++ module from its Makefile or CMakeLists.txt.
+///
+/// There is no Make or CMake grammar, so the build file is added to the HyperAST as a blob,
+/// see [`read_build_file`] for its content.
+pub(crate) fn handle_makefile_file(
+    stores: &mut SimpleStores,
     name: &ObjectName,
-    text: &'a [u8],
-) -> Result<MakeFile, ()> {
-    let tree = match XmlTreeGen::<TStore>::tree_sitter_parse(b"<proj></proj>") {
-        Ok(tree) => tree,
-        Err(tree) => {
-            log::warn!("bad CST");
-            log::debug!("{:?}", name.try_str());
-            log::debug!("{}", tree.root_node().to_sexp());
-            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
-                return Err(());
-            } else {
-                tree
-            }
-        }
+    text: &[u8],
+) -> Result<MakeFile, ParseErr> {
+    let (compressed_node, metrics) = crate::any::handle_blob_file(stores, name, text);
+    let build = read_build_file(name, text)?;
+    Ok(MakeFile {
+        compressed_node,
+        metrics,
+        build,
+    })
+}
+
+/// Reads a Makefile or a CMakeLists.txt with a lightweight extractor
+/// that only understands the most common constructs.
+pub(crate) fn read_build_file(name: &ObjectName, text: &[u8]) -> Result<BuildFile, ParseErr> {
+    // build files are not always UTF-8, eg. a Latin-1 author name in a comment
+    let text = String::from_utf8_lossy(text);
+    let text = text.as_ref();
+    let (build_system, targets, include_dirs, submodules) = if name.as_bytes() == b"CMakeLists.txt"
+    {
+        let cmake = cmake::extract(text);
+        (
+            BuildSystem::CMake,
+            cmake.targets,
+            cmake.include_dirs,
+            cmake.submodules,
+        )
+    } else {
+        let make = makefile::extract(text);
+        (
+            BuildSystem::Make,
+            make.targets,
+            make.include_dirs,
+            make.submodules,
+        )
     };
-    let x = tree_gen
-        .generate_file(name.as_bytes(), b"<proj></proj>", tree.walk())
-        .local;
-    // TODO extract submodules, dependencies and directories. maybe even more ie. artefact id, ...
-    let x = MakeFile {
-        compressed_node: x.compressed_node,
-        metrics: x.metrics,
-        submodules: vec![],
-        source_dirs: vec![".".to_owned()],
-        test_source_dirs: vec!["../tests".to_owned()],
+    let normalize = |x: Vec<String>| -> Vec<String> {
+        let mut x: Vec<_> = x.iter().filter_map(|x| normalize_path(x)).collect();
+        x.sort();
+        x.dedup();
+        x
     };
-    Ok(x)
+    let include_dirs = normalize(include_dirs);
+    let submodules = normalize(submodules);
+    // directories holding the sources and headers, the module directory itself is always explored
+    let dirs = targets
+        .iter()
+        .flat_map(|x| x.sources.iter())
+        .filter_map(|x| source_dir(x))
+        .chain(targets.iter().flat_map(|x| x.include_dirs.iter().cloned()))
+        .chain(include_dirs.iter().cloned());
+    let (test_source_dirs, source_dirs) = normalize(dirs.collect())
+        .into_iter()
+        .partition(|x| is_test_dir(x));
+    Ok(BuildFile {
+        build_system,
+        targets,
+        include_dirs,
+        submodules,
+        source_dirs,
+        test_source_dirs,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildSystem {
+    Make,
+    CMake,
 }
 
+/// A build file added to the HyperAST, see [`handle_makefile_file`]
 #[derive(Debug, Clone)]
 pub struct MakeFile {
-    pub compressed_node: NodeIdentifier,
-    pub metrics: DefaultMetrics,
+    pub(crate) compressed_node: NodeIdentifier,
+    pub(crate) metrics: DefaultMetrics,
+    pub build: BuildFile,
+}
+
+/// What matters in a Makefile or a CMakeLists.txt to find the code of a module
+#[derive(Debug, Clone)]
+pub struct BuildFile {
+    pub build_system: BuildSystem,
+    pub targets: Vec<Target>,
+    /// directories given to the compiler to find headers, relative to the module
+    pub include_dirs: Vec<String>,
+    /// directories of the nested modules, eg. add_subdirectory(lib) or $(MAKE) -C lib
     submodules: Vec<String>,
     source_dirs: Vec<String>,
     test_source_dirs: Vec<String>,
 }
 
+impl BuildFile {
+    pub fn submodules(&self) -> &[String] {
+        &self.submodules
+    }
+    pub fn source_dirs(&self) -> &[String] {
+        &self.source_dirs
+    }
+    pub fn test_source_dirs(&self) -> &[String] {
+        &self.test_source_dirs
+    }
+}
+
+/// A library or an executable built by the module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub name: String,
+    pub kind: TargetKind,
+    /// paths to the sources as written in the build file, they may contain globs
+    pub sources: Vec<String>,
+    pub include_dirs: Vec<String>,
+    /// libraries linked with the target, eg. target_link_libraries(app foo) or -lfoo
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Library,
+    Executable,
+}
+
+const CPP_EXTENSIONS: &[&str] = &[
+    "c", "cc", "cpp", "cxx", "c++", "h", "hh", "hpp", "hxx", "h++", "inl", "ipp",
+];
+
+fn is_source(word: &str) -> bool {
+    Path::new(word)
+        .extension()
+        .and_then(|x| x.to_str())
+        .map_or(false, |x| CPP_EXTENSIONS.contains(&x))
+}
+
+/// The directory of a source, without the components containing globs, eg. src for src/**/*.c
+fn source_dir(source: &str) -> Option<String> {
+    let dir = Path::new(source).parent()?;
+    let dir: PathBuf = dir
+        .components()
+        .take_while(|x| !x.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+        .collect();
+    Some(dir.to_string_lossy().into_owned())
+}
+
+fn is_test_dir(dir: &str) -> bool {
+    Path::new(dir).components().any(|x| {
+        matches!(
+            x.as_os_str().to_str(),
+            Some("test" | "tests" | "unittest" | "unittests" | "testing")
+        )
+    })
+}
+
+/// Makes a path of a build file relative to the directory of the module.
+///
+/// Paths going out of the module, absolute ones and ones using unknown variables are ignored,
+/// modules and source directories are only searched inside the module for now.
+/// The module directory itself, ie. `.`, is also ignored as it is always explored.
+fn normalize_path(path: &str) -> Option<String> {
+    let path = path.trim().trim_start_matches("./").trim_end_matches('/');
+    if path.is_empty() || path == "." {
+        return None;
+    }
+    let unsupported = path.contains('$')
+        || !Path::new(path)
+            .components()
+            .all(|x| matches!(x, Component::Normal(_) | Component::CurDir));
+    if unsupported {
+        log::debug!("ignoring the path {:?} of a build file", path);
+        return None;
+    }
+    let path: PathBuf = Path::new(path)
+        .components()
+        .filter(|x| matches!(x, Component::Normal(_)))
+        .collect();
+    Some(path.to_string_lossy().into_owned())
+}
+
+/// The information extracted from a build file, before normalization of the paths
+#[derive(Default)]
+struct Extracted {
+    targets: Vec<Target>,
+    include_dirs: Vec<String>,
+    submodules: Vec<String>,
+}
+
+/// Expands `$(VAR)` and `${VAR}` in `text`, unknown variables are kept as is.
+///
+/// `function` is called for `$(name args)` references,
+/// it receives the name and the expanded arguments and returns None if unsupported.
+fn expand(
+    text: &str,
+    vars: &HashMap<String, String>,
+    function: &dyn Fn(&str, &str) -> Option<String>,
+    depth: usize,
+) -> String {
+    if depth > 16 || !text.contains('$') {
+        return text.to_owned();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let close = match rest[1..].chars().next() {
+            Some('(') => ')',
+            Some('{') => '}',
+            Some('$') => {
+                out.push('$');
+                rest = &rest[2..];
+                continue;
+            }
+            _ => {
+                out.push('$');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let open = if close == ')' { '(' } else { '{' };
+        // find the matching parenthesis
+        let mut level = 0;
+        let end = rest[1..].char_indices().find_map(|(j, c)| {
+            if c == open {
+                level += 1;
+            } else if c == close {
+                level -= 1;
+                if level == 0 {
+                    return Some(j + 1);
+                }
+            }
+            None
+        });
+        let Some(end) = end else {
+            out.push_str(rest);
+            return out;
+        };
+        let inner = &rest[2..end];
+        let whole = &rest[..=end];
+        rest = &rest[end + 1..];
+        let inner = expand(inner, vars, function, depth + 1);
+        if let Some((name, args)) = inner.split_once(char::is_whitespace) {
+            match function(name, args.trim()) {
+                Some(x) => out.push_str(&x),
+                None => out.push_str(whole),
+            }
+        } else if let Some((name, subst)) = inner.split_once(':') {
+            // substitution reference, eg. $(SRCS:.c=.o)
+            match (vars.get(name), subst.split_once('=')) {
+                (Some(value), Some((from, to))) => {
+                    let value = expand(value, vars, function, depth + 1);
+                    let words = value
+                        .split_whitespace()
+                        .map(|x| match x.strip_suffix(from) {
+                            Some(x) => format!("{}{}", x, to),
+                            None => x.to_owned(),
+                        });
+                    out.push_str(&words.collect::<Vec<_>>().join(" "));
+                }
+                _ => out.push_str(whole),
+            }
+        } else if let Some(value) = vars.get(inner.as_str()) {
+            out.push_str(&expand(value, vars, function, depth + 1));
+        } else {
+            out.push_str(whole);
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A lightweight extractor of Makefiles
+mod makefile {
+    use super::*;
+
+    pub(super) fn extract(text: &str) -> Extracted {
+        let mut vars: HashMap<String, String> = HashMap::new();
+        let mut rules: Vec<(String, String)> = vec![];
+        let mut recipes: Vec<String> = vec![];
+        for line in logical_lines(text) {
+            if line.starts_with('\t') {
+                recipes.push(line);
+                continue;
+            }
+            let line = strip_comment(&line);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((name, op, value)) = assignment(line) {
+                let value = value.trim().to_owned();
+                match op {
+                    "+=" => {
+                        let v = vars.entry(name).or_default();
+                        if !v.is_empty() {
+                            v.push(' ');
+                        }
+                        v.push_str(&value);
+                    }
+                    "?=" => {
+                        vars.entry(name).or_insert(value);
+                    }
+                    "!=" => (),
+                    _ => {
+                        vars.insert(name, value);
+                    }
+                }
+            } else if let Some((targets, prerequisites)) = line.split_once(':') {
+                let prerequisites = prerequisites.trim_start_matches(':');
+                let prerequisites = prerequisites.split(';').next().unwrap_or_default();
+                rules.push((targets.trim().to_owned(), prerequisites.to_owned()));
+            }
+        }
+        let function = |name: &str, args: &str| -> Option<String> {
+            let (first, second) = args.split_once(',').unwrap_or((args, ""));
+            let words = |x: &str, f: &dyn Fn(&str) -> String| {
+                x.split_whitespace().map(f).collect::<Vec<_>>().join(" ")
+            };
+            match name {
+                "wildcard" | "sort" | "strip" => Some(args.to_owned()),
+                "addprefix" => Some(words(second, &|x| format!("{}{}", first, x))),
+                "addsuffix" => Some(words(second, &|x| format!("{}{}", x, first))),
+                _ => Some(String::new()),
+            }
+        };
+        let expand = |x: &str| expand(x, &vars, &function, 0);
+
+        let mut extracted = Extracted::default();
+        let mut libraries: Vec<String> = vec![];
+        for value in vars
+            .values()
+            .map(|x| expand(x))
+            .chain(recipes.iter().map(|x| expand(x)))
+        {
+            extracted.include_dirs.extend(flags(&value, "-I"));
+            libraries.extend(flags(&value, "-l"));
+        }
+        libraries.sort();
+        libraries.dedup();
+        for (name, value) in &vars {
+            if name.to_uppercase().contains("SUBDIR") {
+                extracted
+                    .submodules
+                    .extend(expand(value).split_whitespace().map(|x| x.to_owned()));
+            }
+        }
+        for recipe in &recipes {
+            extracted.submodules.extend(sub_makes(&expand(recipe)));
+        }
+        let rules: Vec<(String, String)> = rules
+            .iter()
+            .map(|(targets, prerequisites)| (expand(targets), expand(prerequisites)))
+            .collect();
+        // sources listed in variables, eg. SRCS = $(wildcard src/*.c)
+        let mut listed: Vec<String> = vars
+            .values()
+            .flat_map(|x| {
+                expand(x)
+                    .split_whitespace()
+                    .filter(|x| is_source(x))
+                    .map(|x| x.to_owned())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        listed.sort();
+        listed.dedup();
+        // the sources of the explicit rules, eg. app: main.o util.o
+        for (targets, prerequisites) in &rules {
+            let sources: Vec<String> = prerequisites
+                .split_whitespace()
+                .filter_map(|x| {
+                    if is_source(x) {
+                        Some(x.to_owned())
+                    } else if is_object(x) {
+                        Some(object_source(x, &rules, &listed))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            for target in targets.split_whitespace() {
+                if target.starts_with('.')
+                    || target.contains('%')
+                    || is_source(target)
+                    || is_object(target)
+                {
+                    continue;
+                }
+                if sources.is_empty() {
+                    continue;
+                }
+                let kind = if target.ends_with(".a")
+                    || target.contains(".so")
+                    || target.ends_with(".dylib")
+                {
+                    TargetKind::Library
+                } else {
+                    TargetKind::Executable
+                };
+                extracted.targets.push(Target {
+                    name: target.to_owned(),
+                    kind,
+                    sources: sources.clone(),
+                    include_dirs: vec![],
+                    dependencies: vec![],
+                });
+            }
+        }
+        // sources only listed in variables and built with a pattern rule
+        let listed: Vec<String> = listed
+            .into_iter()
+            .filter(|x| !extracted.targets.iter().any(|t| t.sources.contains(x)))
+            .collect();
+        if !listed.is_empty() {
+            extracted.targets.push(Target {
+                name: String::new(),
+                kind: TargetKind::Executable,
+                sources: listed,
+                include_dirs: vec![],
+                dependencies: vec![],
+            });
+        }
+        // the flags are usually shared by all the targets of a Makefile
+        for target in &mut extracted.targets {
+            target.dependencies = libraries.clone();
+        }
+        extracted
+    }
+
+    fn is_object(word: &str) -> bool {
+        Path::new(word).extension().map_or(false, |x| x == "o")
+    }
+
+    /// The source an object file is compiled from, looked up in order in
+    /// - an explicit rule, eg. main.o: main.cpp main.h
+    /// - a pattern rule, eg. obj/%.o: src/%.cpp
+    /// - the listed sources with the same stem, eg. SRCS = main.cpp
+    ///
+    /// and otherwise the C source of the builtin rule of make, ie. %.o: %.c
+    fn object_source(object: &str, rules: &[(String, String)], listed: &[String]) -> String {
+        let prerequisite_source = |prerequisites: &str| {
+            prerequisites
+                .split_whitespace()
+                .find(|x| is_source(x))
+                .map(|x| x.to_owned())
+        };
+        let explicit = rules.iter().find_map(|(targets, prerequisites)| {
+            targets
+                .split_whitespace()
+                .any(|x| x == object)
+                .then(|| prerequisite_source(prerequisites))
+                .flatten()
+        });
+        if let Some(source) = explicit {
+            return source;
+        }
+        let pattern = rules.iter().find_map(|(targets, prerequisites)| {
+            let stem = targets.split_whitespace().find_map(|x| {
+                let (prefix, suffix) = x.split_once('%')?;
+                object.strip_prefix(prefix)?.strip_suffix(suffix)
+            })?;
+            let source = prerequisite_source(prerequisites)?;
+            source.contains('%').then(|| source.replacen('%', stem, 1))
+        });
+        if let Some(source) = pattern {
+            return source;
+        }
+        let stem = Path::new(object).with_extension("");
+        listed
+            .iter()
+            .find(|x| Path::new(x).with_extension("") == stem)
+            .cloned()
+            .unwrap_or_else(|| stem.with_extension("c").to_string_lossy().into_owned())
+    }
+
+    /// Joins the lines continued with a backslash
+    fn logical_lines(text: &str) -> Vec<String> {
+        let mut lines = vec![];
+        let mut current = String::new();
+        for line in text.lines() {
+            match line.strip_suffix('\\') {
+                Some(x) => {
+                    current.push_str(x);
+                    current.push(' ');
+                }
+                None => {
+                    current.push_str(line);
+                    lines.push(std::mem::take(&mut current));
+                }
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+        lines
+    }
+
+    fn strip_comment(line: &str) -> &str {
+        let mut escaped = false;
+        for (i, c) in line.char_indices() {
+            match c {
+                '#' if !escaped => return &line[..i],
+                '\\' => escaped = !escaped,
+                _ => escaped = false,
+            }
+        }
+        line
+    }
+
+    /// Parses `NAME op value` where op is one of `=`, `:=`, `::=`, `+=`, `?=` or `!=`
+    fn assignment(line: &str) -> Option<(String, &'static str, &str)> {
+        let line = line
+            .strip_prefix("override ")
+            .or_else(|| line.strip_prefix("export "))
+            .unwrap_or(line);
+        let eq = line.find('=')?;
+        let (lhs, value) = (&line[..eq], &line[eq + 1..]);
+        let (name, op) = [
+            ("::", "::="),
+            (":", ":="),
+            ("+", "+="),
+            ("?", "?="),
+            ("!", "!="),
+        ]
+        .into_iter()
+        .find_map(|(suffix, op)| Some((lhs.strip_suffix(suffix)?, op)))
+        .unwrap_or((lhs, "="));
+        let name = name.trim();
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ':') {
+            return None;
+        }
+        Some((name.to_owned(), op, value))
+    }
+
+    /// The directories given to `-C` or `cd` before running make in a recipe
+    fn sub_makes(recipe: &str) -> Vec<String> {
+        let mut dirs = vec![];
+        let words: Vec<&str> = recipe
+            .split(|c: char| c.is_whitespace() || c == ';')
+            .filter(|x| !x.is_empty())
+            .collect();
+        let is_make = |x: &str| x == "make" || x == "$(MAKE)" || x == "${MAKE}";
+        if !words.iter().any(|x| is_make(x)) {
+            return dirs;
+        }
+        for (i, w) in words.iter().enumerate() {
+            match *w {
+                "-C" | "cd" => dirs.extend(words.get(i + 1).map(|x| x.to_string())),
+                w if w.starts_with("-C") && w.len() > 2 => dirs.push(w[2..].to_owned()),
+                w if w.starts_with("--directory=") => {
+                    dirs.push(w["--directory=".len()..].to_owned())
+                }
+                _ => (),
+            }
+        }
+        dirs
+    }
+
+    /// The values of a flag, eg. the directories of -I or the libraries of -l
+    fn flags(value: &str, flag: &str) -> Vec<String> {
+        let words: Vec<&str> = value.split_whitespace().collect();
+        let mut values = vec![];
+        for (i, w) in words.iter().enumerate() {
+            if *w == flag {
+                values.extend(words.get(i + 1).map(|x| x.to_string()));
+            } else if let Some(x) = w.strip_prefix(flag) {
+                values.push(x.to_owned());
+            }
+        }
+        values
+    }
+}
+
+/// A lightweight extractor of CMakeLists.txt
+mod cmake {
+    use super::*;
+
+    pub(super) fn extract(text: &str) -> Extracted {
+        let mut vars: HashMap<String, String> = HashMap::new();
+        for x in ["CMAKE_CURRENT_SOURCE_DIR", "CMAKE_CURRENT_LIST_DIR"] {
+            vars.insert(x.to_owned(), ".".to_owned());
+        }
+        let function = |_: &str, _: &str| None;
+        let mut extracted = Extracted::default();
+        for (command, args) in commands(text) {
+            let args: Vec<String> = args
+                .iter()
+                .flat_map(|x| {
+                    let x = expand(x, &vars, &function, 0);
+                    // an unquoted variable holding a list is split on ;
+                    x.split(';').map(|x| x.to_owned()).collect::<Vec<_>>()
+                })
+                .filter(|x| !x.is_empty())
+                .collect();
+            let Some(first) = args.first() else {
+                continue;
+            };
+            match command.as_str() {
+                "set" => {
+                    let values = args[1..]
+                        .iter()
+                        .take_while(|x| *x != "CACHE" && *x != "PARENT_SCOPE");
+                    let values: Vec<_> = values.map(|x| x.as_str()).collect();
+                    vars.insert(first.clone(), values.join(";"));
+                }
+                "list" if first == "APPEND" && args.len() > 2 => {
+                    let v = vars.entry(args[1].clone()).or_default();
+                    for x in &args[2..] {
+                        if !v.is_empty() {
+                            v.push(';');
+                        }
+                        v.push_str(x);
+                    }
+                }
+                "file" if (first == "GLOB" || first == "GLOB_RECURSE") && args.len() > 2 => {
+                    let patterns = args[2..].iter().filter(|x| {
+                        !matches!(
+                            x.as_str(),
+                            "LIST_DIRECTORIES"
+                                | "RELATIVE"
+                                | "CONFIGURE_DEPENDS"
+                                | "true"
+                                | "false"
+                        )
+                    });
+                    let patterns: Vec<_> = patterns.map(|x| x.as_str()).collect();
+                    vars.insert(args[1].clone(), patterns.join(";"));
+                }
+                "add_subdirectory" => extracted.submodules.push(first.clone()),
+                "include_directories" => {
+                    extracted.include_dirs.extend(paths(&args));
+                }
+                "add_library" | "add_executable" => {
+                    if args.iter().any(|x| x == "IMPORTED" || x == "ALIAS") {
+                        continue;
+                    }
+                    let kind = if command == "add_library" {
+                        TargetKind::Library
+                    } else {
+                        TargetKind::Executable
+                    };
+                    extracted.targets.push(Target {
+                        name: first.clone(),
+                        kind,
+                        sources: paths(&args[1..]),
+                        include_dirs: vec![],
+                        dependencies: vec![],
+                    });
+                }
+                "target_sources" | "target_include_directories" | "target_link_libraries" => {
+                    let Some(target) = extracted.targets.iter_mut().find(|x| &x.name == first)
+                    else {
+                        log::debug!("{} of an unknown target {}", command, first);
+                        continue;
+                    };
+                    let args = paths(&args[1..]).into_iter();
+                    match command.as_str() {
+                        "target_sources" => target.sources.extend(args),
+                        "target_include_directories" => target.include_dirs.extend(args),
+                        _ => {
+                            target.dependencies.extend(args.filter(|x| {
+                                !matches!(x.as_str(), "debug" | "optimized" | "general")
+                            }))
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        extracted
+    }
+
+    /// The arguments that are paths, ie. without keywords and generator expressions
+    fn paths(args: &[String]) -> Vec<String> {
+        args.iter()
+            .filter_map(|x| match x.strip_prefix("$<BUILD_INTERFACE:") {
+                Some(x) => x.strip_suffix('>'),
+                None => Some(x.as_str()),
+            })
+            .filter(|x| !x.starts_with("$<"))
+            .filter(|x| !x.chars().all(|c| c.is_ascii_uppercase() || c == '_'))
+            .map(|x| x.to_owned())
+            .collect()
+    }
+
+    /// Splits the file in commands, with their lowercase name and their arguments
+    pub(super) fn commands(text: &str) -> Vec<(String, Vec<String>)> {
+        let mut commands = vec![];
+        let mut chars = text.char_indices().peekable();
+        let mut name = String::new();
+        // spaces are allowed between the name and the arguments, eg. add_library (foo a.c)
+        let mut ended = false;
+        while let Some((_, c)) = chars.next() {
+            match c {
+                '#' => {
+                    skip_comment(text, &mut chars);
+                    name.clear();
+                }
+                c if c.is_alphanumeric() || c == '_' => {
+                    if ended {
+                        name.clear();
+                        ended = false;
+                    }
+                    name.push(c)
+                }
+                '(' if !name.is_empty() => {
+                    let args = arguments(text, &mut chars);
+                    commands.push((name.to_lowercase(), args));
+                    name.clear();
+                }
+                ' ' | '\t' => ended = true,
+                _ => name.clear(),
+            }
+        }
+        commands
+    }
+
+    type Chars<'a> = std::iter::Peekable<std::str::CharIndices<'a>>;
+
+    /// Skips a line comment or a bracket comment like #[[ ... ]]
+    fn skip_comment(text: &str, chars: &mut Chars) {
+        if let Some((i, '[')) = chars.peek().copied() {
+            if let Some(close) = bracket_close(&text[i..]) {
+                if let Some(end) = text[i..].find(&close) {
+                    let end = i + end + close.len();
+                    while chars.next_if(|(j, _)| *j < end).is_some() {}
+                    return;
+                }
+            }
+        }
+        while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+    }
+
+    /// The closing of a bracket argument or comment starting `text`, eg. ]==] for [==[
+    fn bracket_close(text: &str) -> Option<String> {
+        let rest = text.strip_prefix('[')?;
+        let level = rest.chars().take_while(|c| *c == '=').count();
+        rest[level..]
+            .starts_with('[')
+            .then(|| format!("]{}]", "=".repeat(level)))
+    }
+
+    fn arguments(text: &str, chars: &mut Chars) -> Vec<String> {
+        let mut args = vec![];
+        let mut current = String::new();
+        let mut level = 0;
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    let mut escaped = false;
+                    for (_, c) in chars.by_ref() {
+                        match c {
+                            '"' if !escaped => break,
+                            '\\' if !escaped => escaped = true,
+                            c => {
+                                current.push(c);
+                                escaped = false;
+                            }
+                        }
+                    }
+                }
+                '[' if bracket_close(&text[i..]).is_some() => {
+                    let close = bracket_close(&text[i..]).unwrap();
+                    let open_len = close.len();
+                    let content = &text[i + open_len..];
+                    let len = content.find(&close).unwrap_or(content.len());
+                    current.push_str(&content[..len]);
+                    let end = i + open_len + len + close.len();
+                    while chars.next_if(|(j, _)| *j < end).is_some() {}
+                }
+                '#' => skip_comment(text, chars),
+                '(' => {
+                    level += 1;
+                    current.push(c);
+                }
+                ')' if level == 0 => break,
+                ')' => {
+                    level -= 1;
+                    current.push(c);
+                }
+                c if c.is_whitespace() => {
+                    if !current.is_empty() {
+                        args.push(std::mem::take(&mut current));
+                    }
+                }
+                c => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            args.push(current);
+        }
+        args
+    }
+}
+
+/// Stored on the directories of make and cmake modules
+#[derive(enumset::EnumSetType, Debug)]
+pub enum SemFlags {
+    IsMakeModule,
+    IsCMakeModule,
+    /// the build files give directories to search for headers
+    HoldIncludeDirs,
+}
+
 #[derive(Debug, Clone)]
 pub struct MD {
     pub(crate) metrics: DefaultMetrics,
     #[allow(unused)] // TODO needed for scalable module level reference analysis
     pub(crate) ana: MakePartialAnalysis,
+    /// the build system if the directory holds a Makefile or a CMakeLists.txt
+    pub(crate) build_system: Option<BuildSystem>,
+    /// directories searched for headers, relative to the module
+    pub(crate) include_dirs: Vec<PathBuf>,
+}
+
+impl MD {
+    pub fn is_module(&self) -> bool {
+        self.build_system.is_some()
+    }
+    pub fn include_dirs(&self) -> &[PathBuf] {
+        &self.include_dirs
+    }
 }
 
 pub struct MakeModuleAcc {
@@ -72,6 +849,8 @@ pub struct MakeModuleAcc {
     pub(crate) children: Vec<NodeIdentifier>,
     pub(crate) metrics: DefaultMetrics,
     pub(crate) ana: MakePartialAnalysis,
+    pub(crate) build_system: Option<BuildSystem>,
+    pub(crate) include_dirs: Vec<PathBuf>,
     pub(crate) sub_modules: Option<Vec<PathBuf>>,
     pub(crate) main_dirs: Option<Vec<PathBuf>>,
     pub(crate) test_dirs: Option<Vec<PathBuf>>,
@@ -86,6 +865,8 @@ impl From<String> for MakeModuleAcc {
             // simple: BasicAccumulator::new(kind),
             metrics: Default::default(),
             ana: MakePartialAnalysis::new(),
+            build_system: None,
+            include_dirs: vec![],
             sub_modules: None,
             main_dirs: None,
             test_dirs: None,
@@ -102,6 +883,8 @@ impl MakeModuleAcc {
             // simple: BasicAccumulator::new(kind),
             metrics: Default::default(),
             ana: MakePartialAnalysis::new(),
+            build_system: None,
+            include_dirs: vec![],
             sub_modules: None,
             main_dirs: None,
             test_dirs: None,
@@ -120,6 +903,8 @@ impl MakeModuleAcc {
             // simple: BasicAccumulator::new(kind),
             metrics: Default::default(),
             ana: MakePartialAnalysis::new(),
+            build_system: None,
+            include_dirs: vec![],
             sub_modules: if sub_modules.is_empty() {
                 None
             } else {
@@ -140,21 +925,48 @@ impl MakeModuleAcc {
 }
 
 impl MakeModuleAcc {
-    pub(crate) fn push_makefile(&mut self, name: LabelIdentifier, full_node: MakeFile) {
-        self.children.push(full_node.compressed_node);
+    pub(crate) fn status(&self) -> EnumSet<SemFlags> {
+        let mut status = EnumSet::new();
+        match self.build_system {
+            Some(BuildSystem::Make) => status |= SemFlags::IsMakeModule,
+            Some(BuildSystem::CMake) => status |= SemFlags::IsCMakeModule,
+            None => (),
+        }
+        if !self.include_dirs.is_empty() {
+            status |= SemFlags::HoldIncludeDirs;
+        }
+        status
+    }
+}
+
+impl MakeModuleAcc {
+    /// The build file is a child, it also tells where to look for modules and sources.
+    ///
+    /// A directory can hold both a Makefile and a CMakeLists.txt,
+    /// and a parent module can already have declared nested directories, so paths are merged.
+    pub(crate) fn push_makefile(&mut self, name: LabelIdentifier, makefile: MakeFile) {
+        assert!(!self.children_names.contains(&name));
+        self.children.push(makefile.compressed_node);
         self.children_names.push(name);
-        self.main_dirs = Some(full_node.source_dirs.iter().map(|x| x.into()).collect());
-        self.test_dirs = Some(
-            full_node
-                .test_source_dirs
-                .iter()
-                .map(|x| x.into())
-                .collect(),
-        );
-        self.sub_modules = Some(full_node.submodules.iter().map(|x| x.into()).collect());
-        self.metrics.acc(full_node.metrics);
-        // TODO
-        // full_node.2.acc(&Type::Directory, &mut self.ana);
+        self.metrics.acc(makefile.metrics);
+        let merge = |acc: &mut Option<Vec<PathBuf>>, x: Vec<String>| {
+            let acc = acc.get_or_insert_with(Vec::new);
+            for x in x.into_iter().map(PathBuf::from) {
+                if !acc.contains(&x) {
+                    acc.push(x);
+                }
+            }
+        };
+        let build = makefile.build;
+        self.build_system = self.build_system.or(Some(build.build_system));
+        for x in build.include_dirs.into_iter().map(PathBuf::from) {
+            if !self.include_dirs.contains(&x) {
+                self.include_dirs.push(x);
+            }
+        }
+        merge(&mut self.sub_modules, build.submodules);
+        merge(&mut self.main_dirs, build.source_dirs);
+        merge(&mut self.test_dirs, build.test_source_dirs);
     }
     pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
         self.children.push(full_node.0);
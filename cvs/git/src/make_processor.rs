@@ -14,8 +14,7 @@ use hyper_ast::{
     tree_gen::SubTreeMetrics,
     types::LabelStore,
 };
-use hyper_ast_gen_ts_cpp::types::Type;
-use hyper_ast_gen_ts_java::legion_with_refs::{eq_node, hash32};
+use hyper_ast_gen_ts_cpp::{legion::eq_node, types::Type};
 
 use crate::{
     git::{BasicGitObject, NamedObject, ObjectType, TypedObject},
@@ -88,7 +87,6 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<MakeModuleAcc>
                         return;
                     }
                 }
-                // check if module or src/main/java or src/test/java
                 if let Some(already) = self
                     .prepro
                     .processing_systems
@@ -106,26 +104,23 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<MakeModuleAcc>
                     w.push_submodule(name, full_node);
                     return;
                 }
-                // TODO use Make pom.xml to find source_dir  and tests_dir ie. ignore resources, maybe also tests
                 // TODO maybe at some point try to handle Make modules and source dirs that reference parent directory in their path
                 log::debug!("mm tree {:?}", name.try_str());
 
                 let parent_acc = &mut self.stack.last_mut().unwrap().2;
-                if true {
-                    // TODO also try to handle nested Makefiles
-                    let (name, (full_node, _)) = self.prepro.help_handle_cpp_folder(
-                        &self.repository,
-                        &mut self.dir_path,
-                        oid,
-                        &name,
-                    );
-                    assert!(!parent_acc.children_names.contains(&name));
-                    parent_acc.push_source_directory(name, full_node);
-                    return;
-                }
-
                 let helper = MakeModuleHelper::from((parent_acc, &name));
-                if helper.source_directories.0 || helper.test_source_directories.0 {
+                // TODO check it we can use more info from context and prepare analysis more specifically
+                if helper.submodules.0
+                    || !helper.submodules.1.is_empty()
+                    || !helper.source_directories.1.is_empty()
+                    || !helper.test_source_directories.1.is_empty()
+                {
+                    // a module declared by the build file, eg. add_subdirectory(lib),
+                    // or search further inside for modules and sources
+                    let tree = self.repository.find_tree(oid).unwrap();
+                    let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
+                    self.stack.push((oid, prepared, helper.into()));
+                } else if helper.source_directories.0 || helper.test_source_directories.0 {
                     // handle as source dir
                     let (name, (full_node, _)) = self.prepro.help_handle_cpp_folder(
                         &self.repository,
@@ -141,28 +136,23 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<MakeModuleAcc>
                         // test_source_folders.0
                         parent_acc.push_test_source_directory(name, full_node);
                     }
-                }
-                // TODO check it we can use more info from context and prepare analysis more specifically
-                if helper.submodules.0
-                    || !helper.submodules.1.is_empty()
-                    || !helper.source_directories.1.is_empty()
-                    || !helper.test_source_directories.1.is_empty()
-                {
-                    let tree = self.repository.find_tree(oid).unwrap();
-                    let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
-                    if helper.submodules.0 {
-                        // handle as Make module
-                        self.stack.push((oid, prepared, helper.into()));
-                    } else {
-                        // search further inside
-                        self.stack.push((oid, prepared, helper.into()));
-                    };
-                } else if RMS && !(helper.source_directories.0 || helper.test_source_directories.0)
-                {
+                } else if RMS {
                     let tree = self.repository.find_tree(oid).unwrap();
-                    // anyway try to find Make modules, but maybe can do better
+                    // anyway explore it, to find nested Make modules and the sources of
+                    // the build files that were not understood
                     let prepared = prepare_dir_exploration(tree, &mut self.dir_path);
                     self.stack.push((oid, prepared, helper.into()));
+                } else {
+                    // TODO also try to handle nested Makefiles
+                    let (name, (full_node, _)) = self.prepro.help_handle_cpp_folder(
+                        &self.repository,
+                        &mut self.dir_path,
+                        oid,
+                        &name,
+                    );
+                    let parent_acc = &mut self.stack.last_mut().unwrap().2;
+                    assert!(!parent_acc.children_names.contains(&name));
+                    parent_acc.push_source_directory(name, full_node);
                 }
             }
             BasicGitObject::Blob(oid, name) => {
@@ -173,15 +163,16 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool> Processor<MakeModuleAcc>
                     return;
                 }
                 if crate::processing::file_sys::MakeFile::matches(&name) {
-                    self.prepro
-                        .help_handle_makefile(
-                            oid,
-                            &mut self.stack.last_mut().unwrap().2,
-                            name,
-                            &self.repository,
-                            self.handle.into(),
-                        )
-                        .unwrap();
+                    let r = self.prepro.help_handle_makefile(
+                        oid,
+                        &mut self.stack.last_mut().unwrap().2,
+                        &name,
+                        &self.repository,
+                        self.handle.into(),
+                    );
+                    if let Err(e) = r {
+                        log::warn!("ignoring build file {:?}: {:?}", name.try_str(), e);
+                    }
                 } else if crate::processing::file_sys::Cpp::matches(&name) {
                     self.prepro
                         .help_handle_cpp_file2(
@@ -244,13 +235,12 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
     }
 }
 
-pub(crate) fn make(mut acc: MakeModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
-    let dir_hash: u32 = hash32(&Type::Directory); // FIXME should be MakeDirectory ?
+pub(crate) fn make(acc: MakeModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
     let hashs = acc.metrics.hashs;
     let size = acc.metrics.size + 1;
     let height = acc.metrics.height + 1;
     let size_no_spaces = acc.metrics.size_no_spaces + 1;
-    let hbuilder = hashed::Builder::new(hashs, &dir_hash, &acc.name, size_no_spaces);
+    let hbuilder = hashed::Builder::new(hashs, &Type::Directory, &acc.name, size_no_spaces);
     let hashable = hbuilder.most_discriminating();
     let label = stores.label_store.get_or_insert(acc.name.clone());
 
@@ -279,26 +269,28 @@ pub(crate) fn make(mut acc: MakeModuleAcc, stores: &mut SimpleStores) -> (NodeId
     };
     let insertion = stores.node_store.prepare_insertion(&hashable, eq);
     let hashs = hbuilder.build();
+    let status = acc.status();
     let node_id = if let Some(id) = insertion.occupied_id() {
         id
     } else {
         log::info!("make mm {} {}", &acc.name, acc.children.len());
         let vacant = insertion.vacant();
         assert_eq!(acc.children_names.len(), acc.children.len());
-        NodeStore::insert_after_prepare(
-            vacant,
-            (
-                Type::Directory,
-                label,
-                hashs,
-                compo::Size(size),
-                compo::Height(height),
-                compo::SizeNoSpaces(size_no_spaces),
-                CS(acc.children_names.into_boxed_slice()), // TODO extract dir names
-                CS(acc.children.into_boxed_slice()),
-                BloomSize::Much,
-            ),
-        )
+        let mut dyn_builder = hyper_ast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+        dyn_builder.add(Type::Directory);
+        dyn_builder.add(hashs.clone());
+        dyn_builder.add(label);
+        dyn_builder.add(BloomSize::Much);
+        dyn_builder.add(compo::Size(size));
+        dyn_builder.add(compo::SizeNoSpaces(size_no_spaces));
+        dyn_builder.add(compo::Height(height));
+        dyn_builder.add(CS(acc.children_names.into_boxed_slice()));
+        dyn_builder.add(CS(acc.children.into_boxed_slice()));
+        if !status.is_empty() {
+            // so that the build system and the include dirs are still known from the HyperAST alone
+            dyn_builder.add(status);
+        }
+        NodeStore::insert_built_after_prepare(vacant, dyn_builder.build())
     };
 
     let metrics = SubTreeMetrics {
@@ -308,36 +300,32 @@ pub(crate) fn make(mut acc: MakeModuleAcc, stores: &mut SimpleStores) -> (NodeId
         size_no_spaces,
     };
 
-    let full_node = (node_id.clone(), MD { metrics, ana });
+    let md = MD {
+        metrics,
+        ana,
+        build_system: acc.build_system,
+        include_dirs: acc.include_dirs,
+    };
+    let full_node = (node_id.clone(), md);
     full_node
 }
 
-use hyper_ast_gen_ts_xml::legion::XmlTreeGen;
 impl RepositoryProcessor {
     fn help_handle_makefile(
         &mut self,
         oid: Oid,
         parent_acc: &mut MakeModuleAcc,
-        name: ObjectName,
+        name: &ObjectName,
         repository: &Repository,
         parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<MakefileProc>,
     ) -> Result<(), crate::ParseErr> {
         let x = self
             .processing_systems
             .caching_blob_handler::<crate::processing::file_sys::MakeFile>()
-            .handle(oid, repository, &name, parameters, |c, n, t| {
-                crate::make::handle_makefile_file(
-                    &mut XmlTreeGen {
-                        line_break: "\n".as_bytes().to_vec(),
-                        stores: &mut self.main_stores,
-                    },
-                    n,
-                    t,
-                )
-                .map_err(|_| crate::ParseErr::IllFormed)
+            .handle(oid, repository, name, parameters, |_, n, t| {
+                crate::make::handle_makefile_file(&mut self.main_stores, n, t)
             })?;
-        let name = self.intern_object_name(&name);
-        assert!(!parent_acc.children_names.contains(&name));
+        let name = self.intern_object_name(name);
         parent_acc.push_makefile(name, x);
         Ok(())
     }
@@ -400,12 +388,13 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
         It::Item: NamedObject + TypedObject,
     {
         let mut children_objects: Vec<_> = tree.collect();
-        let p = children_objects.iter().position(|x| match x.r#type() {
+        // priority to config files processing, there can be a Makefile and a CMakeLists.txt
+        let is_config = |x: &It::Item| match x.r#type() {
             ObjectType::File => crate::processing::file_sys::MakeFile::matches(x.name()),
             ObjectType::Dir => false,
-        });
-        if let Some(p) = p {
-            children_objects.swap(0, p); // priority to config file processing
+        };
+        if children_objects.iter().any(is_config) {
+            children_objects.sort_by_key(|x| !is_config(x));
             children_objects.reverse(); // we use it like a stack
         }
         children_objects
@@ -424,12 +413,13 @@ pub(crate) fn prepare_dir_exploration(
         .filter_map(|x| x.ok())
         .collect();
     if dir_path.peek().is_none() {
-        let p = children_objects.iter().position(|x| match x {
+        // priority to config files processing, there can be a Makefile and a CMakeLists.txt
+        let is_config = |x: &BasicGitObject| match x {
             BasicGitObject::Blob(_, n) => crate::processing::file_sys::MakeFile::matches(n),
             _ => false,
-        });
-        if let Some(p) = p {
-            children_objects.swap(0, p); // priority to config file processing
+        };
+        if children_objects.iter().any(is_config) {
+            children_objects.sort_by_key(|x| !is_config(x));
             children_objects.reverse(); // we use it like a stack
         }
     }
@@ -511,6 +501,10 @@ impl crate::processing::erased::CommitProc for MakefileProc {
     fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
         unimplemented!()
     }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        unimplemented!()
+    }
 }
 
 impl crate::processing::erased::CommitProcExt for MakefileProc {
@@ -643,6 +637,10 @@ impl crate::processing::erased::CommitProc for MakeProc {
     fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
         self.commits.get(&commit_oid)
     }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
 }
 
 impl crate::processing::erased::CommitProcExt for MakeProc {
@@ -683,3 +681,151 @@ impl CacheHolding<crate::processing::caches::Make> for MakeProcessorHolder {
         &self.0.as_ref().unwrap().cache
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::make::{handle_makefile_file, BuildSystem, TargetKind};
+
+    #[test]
+    fn makefile() {
+        let text = br#"
+# a comment
+CC ?= gcc
+SRC_DIR = src
+SRCS = $(wildcard $(SRC_DIR)/*.c) \
+       lib/util.c
+OBJS = $(SRCS:.c=.o)
+CFLAGS += -Iinclude -I third_party/json
+SUBDIRS = plugins
+LDLIBS = -lm -lpthread
+
+app: $(OBJS)
+	$(CC) $(CFLAGS) -o $@ $^
+
+test: tests/test_main.o
+	cd tests && $(MAKE)
+	$(MAKE) -C docs all
+
+.PHONY: test
+"#;
+        let mut stores = SimpleStores::default();
+        let makefile = handle_makefile_file(&mut stores, &b"Makefile".into(), text)
+            .unwrap()
+            .build;
+        assert_eq!(makefile.build_system, BuildSystem::Make);
+        assert_eq!(makefile.include_dirs, vec!["include", "third_party/json"]);
+        assert_eq!(makefile.submodules(), ["docs", "plugins", "tests"]);
+        let app = &makefile.targets[0];
+        assert_eq!(app.name, "app");
+        assert_eq!(app.kind, TargetKind::Executable);
+        assert_eq!(app.sources, vec!["src/*.c", "lib/util.c"]);
+        assert_eq!(app.dependencies, vec!["m", "pthread"]);
+        assert_eq!(
+            makefile.source_dirs(),
+            ["include", "lib", "src", "third_party/json"]
+        );
+        assert_eq!(makefile.test_source_dirs(), ["tests"]);
+        let test = &makefile.targets[1];
+        assert_eq!(test.name, "test");
+        assert_eq!(test.sources, vec!["tests/test_main.c"]);
+        assert_eq!(makefile.targets.len(), 2);
+    }
+
+    #[test]
+    fn makefile_objects() {
+        let text = b"
+# \xe9crit par Ren\xe9
+OBJS = obj/main.o obj/parser.o version.o
+
+lib/libdemo.a: $(OBJS)
+\tar rcs $@ $^
+
+obj/%.o: src/%.cpp include/demo.hpp
+\t$(CXX) -Iinclude -c $< -o $@
+
+version.o: gen/version.cc
+";
+        let mut stores = SimpleStores::default();
+        let makefile = handle_makefile_file(&mut stores, &b"Makefile".into(), text)
+            .unwrap()
+            .build;
+        assert_eq!(makefile.targets.len(), 1);
+        let lib = &makefile.targets[0];
+        assert_eq!(lib.kind, TargetKind::Library);
+        assert_eq!(
+            lib.sources,
+            vec!["src/main.cpp", "src/parser.cpp", "gen/version.cc"]
+        );
+        assert_eq!(makefile.source_dirs(), ["gen", "include", "src"]);
+    }
+
+    #[test]
+    fn cmake() {
+        let text = br#"
+cmake_minimum_required(VERSION 3.10)
+project(Demo CXX)
+#[[ a bracket comment
+add_library(ignored ignored.cpp)
+]]
+set(SOURCES src/a.cpp "src/detail/b.cpp") # a comment
+file(GLOB_RECURSE HEADERS CONFIGURE_DEPENDS include/*.hpp)
+add_library (demo STATIC ${SOURCES} ${HEADERS})
+target_include_directories(demo
+    PUBLIC $<BUILD_INTERFACE:${CMAKE_CURRENT_SOURCE_DIR}/include> $<INSTALL_INTERFACE:include>
+    PRIVATE src)
+add_executable(demo_tests test/main.cpp)
+target_sources(demo_tests PRIVATE test/util.cpp)
+target_link_libraries(demo_tests PRIVATE demo debug GTest::gtest)
+include_directories(${PROJECT_SOURCE_DIR}/generated third_party)
+add_subdirectory(examples)
+if(BUILD_TESTING)
+  add_subdirectory(test)
+endif()
+"#;
+        let mut stores = SimpleStores::default();
+        let name = b"CMakeLists.txt".into();
+        let cmake_file = handle_makefile_file(&mut stores, &name, text).unwrap();
+        let cmake = &cmake_file.build;
+        assert_eq!(cmake.build_system, BuildSystem::CMake);
+        assert_eq!(cmake.targets.len(), 2);
+        let demo = &cmake.targets[0];
+        assert_eq!(demo.name, "demo");
+        assert_eq!(demo.kind, TargetKind::Library);
+        assert_eq!(
+            demo.sources,
+            vec!["src/a.cpp", "src/detail/b.cpp", "include/*.hpp"]
+        );
+        assert_eq!(demo.include_dirs, vec!["./include", "src"]);
+        let tests = &cmake.targets[1];
+        assert_eq!(tests.kind, TargetKind::Executable);
+        assert_eq!(tests.sources, vec!["test/main.cpp", "test/util.cpp"]);
+        assert_eq!(tests.dependencies, vec!["demo", "GTest::gtest"]);
+        // the unknown variable is ignored
+        assert_eq!(cmake.include_dirs, vec!["third_party"]);
+        assert_eq!(cmake.submodules(), ["examples", "test"]);
+        assert_eq!(
+            cmake.source_dirs(),
+            ["include", "src", "src/detail", "third_party"]
+        );
+        assert_eq!(cmake.test_source_dirs(), ["test"]);
+
+        let mut acc = MakeModuleAcc::new("".to_string());
+        let label = stores.label_store.get_or_insert("CMakeLists.txt");
+        acc.push_makefile(label, cmake_file);
+        assert_eq!(acc.build_system, Some(BuildSystem::CMake));
+        // the build file is kept as a child
+        assert_eq!(acc.children_names, vec![label]);
+        assert_eq!(
+            acc.status(),
+            crate::make::SemFlags::IsCMakeModule | crate::make::SemFlags::HoldIncludeDirs
+        );
+        let helper = MakeModuleHelper::from((&mut acc, &b"src".into()));
+        assert!(helper.source_directories.0);
+        assert_eq!(helper.source_directories.1, vec![PathBuf::from("detail")]);
+        // also a declared module, its own build file tells where its sources are
+        let helper = MakeModuleHelper::from((&mut acc, &b"test".into()));
+        assert!(helper.submodules.0);
+        assert!(helper.test_source_directories.0);
+    }
+}
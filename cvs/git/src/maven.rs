@@ -41,26 +41,267 @@ pub(crate) fn handle_pom_file<'a>(
     let x = tree_gen
         .generate_file(name.as_bytes(), text, tree.walk())
         .local;
-    // TODO extract submodules, dependencies and directories. maybe even more ie. artefact id, ...
-    let x = POM {
-        compressed_node: x.compressed_node,
-        metrics: x.metrics,
-        submodules: vec![],
-        source_dirs: vec!["src/main/java".to_owned()],
-        test_source_dirs: vec!["src/test/java".to_owned()],
+    Ok(read_pom(&*tree_gen.stores, x.compressed_node, x.metrics))
+}
+
+/// Reads the model of a `pom.xml` already in the HyperAST, `compressed_node` being the file
+pub(crate) fn read_pom(
+    stores: &SimpleStores,
+    compressed_node: NodeIdentifier,
+    metrics: DefaultMetrics,
+) -> POM {
+    let project = XmlElement::root(stores, compressed_node).filter(|x| x.name() == Some("project"));
+    let Some(project) = project else {
+        log::warn!("no <project> in a pom.xml");
+        return POM {
+            compressed_node,
+            metrics,
+            coordinates: Default::default(),
+            parent: None,
+            dependencies: vec![],
+            submodules: vec![],
+            profiles: vec![],
+            source_dirs: vec![DEFAULT_SOURCE_DIR.to_owned()],
+            test_source_dirs: vec![DEFAULT_TEST_SOURCE_DIR.to_owned()],
+        };
     };
-    Ok(x)
+    let build = project.element("build");
+    let source_dir = |name, default: &str| {
+        let dir = build.and_then(|x| x.child_text(name));
+        let dir = dir.as_deref().unwrap_or(default);
+        normalize_path(dir).into_iter().collect()
+    };
+    let modules = |x: XmlElement<'_>| -> Vec<String> {
+        x.elements_named("modules")
+            .flat_map(|x| x.elements_named("module"))
+            .filter_map(|x| normalize_path(&x.text()))
+            .collect()
+    };
+    let profiles: Vec<Profile> = project
+        .elements_named("profiles")
+        .flat_map(|x| x.elements_named("profile"))
+        .map(|x| Profile {
+            id: x.child_text("id"),
+            active_by_default: x
+                .element("activation")
+                .and_then(|x| x.child_text("activeByDefault"))
+                .map_or(false, |x| x == "true"),
+            submodules: modules(x),
+        })
+        .collect();
+    // the modules of the other profiles are only built when the profile is activated
+    let mut submodules: Vec<String> = modules(project)
+        .into_iter()
+        .chain(
+            profiles
+                .iter()
+                .filter(|x| x.active_by_default)
+                .flat_map(|x| x.submodules.iter().cloned()),
+        )
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    submodules.retain(|x| seen.insert(x.clone()));
+    let dependencies = project
+        .elements_named("dependencies")
+        .flat_map(|x| x.elements_named("dependency"))
+        .map(|x| Dependency {
+            coordinates: Coordinates::read(x),
+            scope: x.child_text("scope"),
+            optional: x.child_text("optional").map_or(false, |x| x == "true"),
+        })
+        .collect();
+    POM {
+        compressed_node,
+        metrics,
+        coordinates: Coordinates::read(project),
+        parent: project.element("parent").map(Coordinates::read),
+        dependencies,
+        submodules,
+        profiles,
+        source_dirs: source_dir("sourceDirectory", DEFAULT_SOURCE_DIR),
+        test_source_dirs: source_dir("testSourceDirectory", DEFAULT_TEST_SOURCE_DIR),
+    }
 }
 
+const DEFAULT_SOURCE_DIR: &str = "src/main/java";
+const DEFAULT_TEST_SOURCE_DIR: &str = "src/test/java";
+
+/// Makes a path of a pom.xml relative to the directory of the module.
+///
+/// Paths going out of the module, absolute ones and ones using other properties are ignored,
+/// maven modules and source directories are only searched inside the module for now.
+fn normalize_path(path: &str) -> Option<String> {
+    let mut path = path.trim();
+    for prefix in ["${project.basedir}", "${basedir}"] {
+        if let Some(x) = path.strip_prefix(prefix) {
+            path = x.trim_start_matches('/');
+        }
+    }
+    let path = path.trim_start_matches("./").trim_end_matches('/');
+    let components = std::path::Path::new(path).components();
+    let unsupported = path.is_empty()
+        || path.contains("${")
+        || !components
+            .into_iter()
+            .all(|x| matches!(x, std::path::Component::Normal(_)));
+    if unsupported {
+        log::warn!("ignoring the path {:?} of a pom.xml", path);
+        return None;
+    }
+    Some(path.to_owned())
+}
+
+/// The model of a `pom.xml`, read from its XML HyperAST
 #[derive(Debug, Clone)]
 pub struct POM {
     pub compressed_node: NodeIdentifier,
     pub metrics: DefaultMetrics,
+    pub coordinates: Coordinates,
+    /// coordinates of the `<parent>`, the missing group id and version are inherited from it
+    pub parent: Option<Coordinates>,
+    pub dependencies: Vec<Dependency>,
+    /// paths to the declared `<modules>`, relative to the module,
+    /// including the ones of the profiles active by default
     submodules: Vec<String>,
+    profiles: Vec<Profile>,
     source_dirs: Vec<String>,
     test_source_dirs: Vec<String>,
 }
 
+impl POM {
+    pub fn submodules(&self) -> &[String] {
+        &self.submodules
+    }
+    pub fn profiles(&self) -> &[Profile] {
+        &self.profiles
+    }
+    pub fn source_dirs(&self) -> &[String] {
+        &self.source_dirs
+    }
+    pub fn test_source_dirs(&self) -> &[String] {
+        &self.test_source_dirs
+    }
+}
+
+/// A `<profile>` of a pom.xml, only its `<modules>` are read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub id: Option<String>,
+    /// `<activation><activeByDefault>`
+    pub active_by_default: bool,
+    submodules: Vec<String>,
+}
+
+impl Profile {
+    pub fn submodules(&self) -> &[String] {
+        &self.submodules
+    }
+}
+
+/// The `<groupId>`, `<artifactId>` and `<version>` of an artifact,
+/// properties like `${project.version}` are kept as is
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coordinates {
+    pub group_id: Option<String>,
+    pub artifact_id: Option<String>,
+    pub version: Option<String>,
+}
+
+impl Coordinates {
+    fn read(x: XmlElement<'_>) -> Self {
+        Self {
+            group_id: x.child_text("groupId"),
+            artifact_id: x.child_text("artifactId"),
+            version: x.child_text("version"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub coordinates: Coordinates,
+    pub scope: Option<String>,
+    pub optional: bool,
+}
+
+/// An element of an XML file stored in the HyperAST
+#[derive(Clone, Copy)]
+struct XmlElement<'a> {
+    stores: &'a SimpleStores,
+    id: NodeIdentifier,
+}
+
+impl<'a> XmlElement<'a> {
+    /// The root element of the file `root`
+    fn root(stores: &'a SimpleStores, root: NodeIdentifier) -> Option<Self> {
+        Self { stores, id: root }
+            .children_of_type(Type::Element)
+            .next()
+            .map(|id| Self { stores, id })
+    }
+
+    fn children_of_type(&self, t: Type) -> impl Iterator<Item = NodeIdentifier> + 'a {
+        let stores = self.stores;
+        let b = stores
+            .node_store
+            .try_resolve_typed::<XmlIdN>(&self.id)
+            .unwrap()
+            .0;
+        let cs: Vec<_> = b
+            .children()
+            .map(|x| x.iter_children().cloned().collect())
+            .unwrap_or_default();
+        cs.into_iter().filter(move |x| {
+            stores
+                .node_store
+                .try_resolve_typed::<XmlIdN>(x)
+                .map_or(false, |x| x.0.get_type() == t)
+        })
+    }
+
+    fn label(&self, id: NodeIdentifier) -> Option<&'a str> {
+        let b = self.stores.node_store.try_resolve_typed::<XmlIdN>(&id)?.0;
+        let l = b.try_get_label()?;
+        Some(self.stores.label_store.resolve(l))
+    }
+
+    fn name(&self) -> Option<&'a str> {
+        let tag = Self {
+            stores: self.stores,
+            id: self
+                .children_of_type(Type::STag)
+                .next()
+                .or_else(|| self.children_of_type(Type::EmptyElemTag).next())?,
+        };
+        let name = tag.children_of_type(Type::Name).next()?;
+        self.label(name)
+    }
+
+    /// The trimmed text directly contained in the element
+    fn text(&self) -> String {
+        let text: String = self
+            .children_of_type(Type::CharData)
+            .filter_map(|x| self.label(x))
+            .collect();
+        text.trim().to_owned()
+    }
+
+    fn elements_named(&self, name: &'a str) -> impl Iterator<Item = Self> + 'a {
+        let stores = self.stores;
+        self.children_of_type(Type::Element)
+            .map(move |id| Self { stores, id })
+            .filter(move |x| x.name() == Some(name))
+    }
+
+    fn element(&self, name: &'a str) -> Option<Self> {
+        self.elements_named(name).next()
+    }
+
+    fn child_text(&self, name: &'a str) -> Option<String> {
+        Some(self.element(name)?.text()).filter(|x| !x.is_empty())
+    }
+}
+
 pub struct IterMavenModules2<'a> {
     stores: &'a SimpleStores,
     parents: Vec<NodeIdentifier>,
@@ -300,7 +541,19 @@ impl MavenModuleAcc {
                 .map(|x| x.into())
                 .collect(),
         );
-        self.sub_modules = Some(full_node.submodules.iter().map(|x| x.into()).collect());
+        // eg. ./core is matched against the directories like core
+        self.sub_modules = Some(
+            full_node
+                .submodules
+                .iter()
+                .map(|x| {
+                    std::path::Path::new(x)
+                        .components()
+                        .filter(|x| x != &std::path::Component::CurDir)
+                        .collect()
+                })
+                .collect(),
+        );
         self.metrics.acc(full_node.metrics);
         // TODO
         // full_node.2.acc(&Type::Directory, &mut self.ana);
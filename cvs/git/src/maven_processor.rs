@@ -197,9 +197,11 @@ impl<'a, 'b, 'c, const RMS: bool, const FFWD: bool>
                 // test_source_folders.0
                 parent_acc.push_test_source_directory(name, full_node);
             }
+            // the whole directory is already indexed as java code,
+            // eg. a test source directory inside a source directory
+            return;
         }
-        // check if module or src/main/java or src/test/java
-        // TODO use maven pom.xml to find source_dir  and tests_dir ie. ignore resources, maybe also tests
+        // check if module or one of the source directories declared in the pom.xml
         // TODO maybe at some point try to handle maven modules and source dirs that reference parent directory in their path
 
         // TODO check it we can use more info from context and prepare analysis more specifically
@@ -558,6 +560,10 @@ impl crate::processing::erased::CommitProc for PomProc {
     fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
         unimplemented!()
     }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        unimplemented!()
+    }
 }
 
 impl crate::processing::erased::CommitProcExt for PomProc {
@@ -706,6 +712,10 @@ impl crate::processing::erased::CommitProc for MavenProc {
     fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
         self.commits.get(&commit_oid)
     }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
 }
 
 impl crate::processing::erased::CommitProcExt for MavenProc {
@@ -746,3 +756,96 @@ impl CacheHolding<crate::processing::caches::Maven> for MavenProcessorHolder {
         &self.0.as_ref().unwrap().cache
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pom_model() {
+        let text = br#"<?xml version="1.0" encoding="UTF-8"?>
+<project>
+  <parent>
+    <groupId>org.example</groupId>
+    <artifactId>parent</artifactId>
+    <version>1.0</version>
+  </parent>
+  <artifactId>app</artifactId>
+  <modules>
+    <module>core</module>
+    <module>./libs/util/</module>
+    <module>../outside</module>
+  </modules>
+  <dependencies>
+    <dependency>
+      <groupId>junit</groupId>
+      <artifactId>junit</artifactId>
+      <version>4.13</version>
+      <scope>test</scope>
+    </dependency>
+  </dependencies>
+  <build>
+    <sourceDirectory>${project.basedir}/src</sourceDirectory>
+  </build>
+  <profiles>
+    <profile>
+      <id>default</id>
+      <activation>
+        <activeByDefault>true</activeByDefault>
+      </activation>
+      <modules>
+        <module>core</module>
+        <module>bench</module>
+      </modules>
+    </profile>
+    <profile>
+      <id>extras</id>
+      <modules>
+        <module>extra</module>
+      </modules>
+    </profile>
+  </profiles>
+</project>
+"#;
+        let mut stores = SimpleStores::default();
+        let mut tree_gen = XmlTreeGen {
+            line_break: "\n".as_bytes().to_vec(),
+            stores: &mut stores,
+        };
+        let pom = crate::maven::handle_pom_file(&mut tree_gen, &b"pom.xml".into(), text).unwrap();
+        assert_eq!(pom.coordinates.group_id, None);
+        assert_eq!(pom.coordinates.artifact_id.as_deref(), Some("app"));
+        let parent = pom.parent.as_ref().unwrap();
+        assert_eq!(parent.group_id.as_deref(), Some("org.example"));
+        assert_eq!(parent.version.as_deref(), Some("1.0"));
+        assert_eq!(pom.dependencies.len(), 1);
+        assert_eq!(
+            pom.dependencies[0].coordinates.artifact_id.as_deref(),
+            Some("junit")
+        );
+        assert_eq!(pom.dependencies[0].scope.as_deref(), Some("test"));
+        assert_eq!(pom.submodules(), ["core", "libs/util", "bench"]);
+        let profiles = pom.profiles();
+        assert_eq!(profiles.len(), 2);
+        assert!(profiles[0].active_by_default);
+        assert_eq!(profiles[1].id.as_deref(), Some("extras"));
+        assert!(!profiles[1].active_by_default);
+        assert_eq!(profiles[1].submodules(), ["extra"]);
+        assert_eq!(pom.source_dirs(), ["src"]);
+        assert_eq!(pom.test_source_dirs(), ["src/test/java"]);
+
+        let mut acc = MavenModuleAcc::new("".to_string());
+        let name = stores.label_store.get_or_insert("pom.xml");
+        acc.push_pom(name, pom);
+        let helper = MavenModuleHelper::from((&mut acc, &b"libs".into()));
+        assert!(!helper.submodules.0);
+        assert_eq!(helper.submodules.1, vec![PathBuf::from("util")]);
+        // src is a source directory, it contains the default test directory
+        let helper = MavenModuleHelper::from((&mut acc, &b"src".into()));
+        assert!(helper.source_directories.0);
+        assert_eq!(
+            helper.test_source_directories.1,
+            vec![PathBuf::from("test/java")]
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! The module graph of a commit: the modules declared by the build files of a repository,
+//! with their source roots and their dependencies, see [`module_graph`].
+//!
+//! Build files are read with the same extractors as the processors, from the built HyperAST when it keeps them,
+//! so the graph matches the modules and source roots that the processors follow.
+//! How the graph changed between two commits is given by [`ModuleGraph::changes`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use git2::{ObjectType, Oid, Repository, Tree};
+use hyper_ast::{
+    position::{StructuralPosition, TreePath},
+    store::defaults::NodeIdentifier,
+};
+
+use crate::{
+    make,
+    maven::{self, IterMavenModules, POM},
+    persist,
+    preprocessed::child_by_name,
+    processing::{file_sys, InFiles, ObjectName, RepoConfig},
+    SimpleStores,
+};
+
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildSystem {
+    Maven,
+    Make,
+    CMake,
+}
+
+impl From<make::BuildSystem> for BuildSystem {
+    fn from(value: make::BuildSystem) -> Self {
+        match value {
+            make::BuildSystem::Make => Self::Make,
+            make::BuildSystem::CMake => Self::CMake,
+        }
+    }
+}
+
+/// A module, paths are relative to the root of the repository
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Module {
+    /// the directory of the module, empty for the root
+    pub path: String,
+    pub build_system: BuildSystem,
+    /// eg. groupId:artifactId for maven, or the names of the targets for make and cmake
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub source_roots: Vec<String>,
+    pub test_roots: Vec<String>,
+    pub include_dirs: Vec<String>,
+    /// directories of the modules of the repository this module depends on
+    pub dependencies: Vec<String>,
+    /// artefacts from outside of the repository, eg. junit:junit:4.13 or pthread
+    pub external: Vec<String>,
+}
+
+#[derive(serde::Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleGraph {
+    /// sorted by path
+    pub modules: Vec<Module>,
+}
+
+/// How the module graph changed between two commits
+#[derive(serde::Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleGraphChanges {
+    pub added: Vec<Module>,
+    pub removed: Vec<Module>,
+    pub changed: Vec<ModuleChanges>,
+}
+
+/// The differences of a module present in both commits, the unchanged parts are left empty
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ModuleChanges {
+    pub path: String,
+    pub build_system: BuildSystem,
+    pub name: Option<(Option<String>, Option<String>)>,
+    pub version: Option<(Option<String>, Option<String>)>,
+    pub added_source_roots: Vec<String>,
+    pub removed_source_roots: Vec<String>,
+    pub added_dependencies: Vec<String>,
+    pub removed_dependencies: Vec<String>,
+    pub added_external: Vec<String>,
+    pub removed_external: Vec<String>,
+}
+
+/// Computes the module graph of a commit, `root` being its HyperAST as built by the processor of `kind`.
+///
+/// Only the modules declared from the root are followed, eg. with `<modules>` or `add_subdirectory`,
+/// so that the build files of test fixtures are not taken as modules.
+/// Maven modules are read from the HyperAST,
+/// the build files of make and cmake modules are only kept as blobs so they are read from `repository`,
+/// thus `root` is only needed when [`reads_hyperast`].
+/// When a directory holds both a Makefile and a CMakeLists.txt only the latter is considered.
+pub fn module_graph(
+    stores: &SimpleStores,
+    repository: &Repository,
+    kind: RepoConfig,
+    commit: Oid,
+    root: Option<NodeIdentifier>,
+) -> Result<ModuleGraph, git2::Error> {
+    let mut declared = vec![];
+    match (kind, root) {
+        (RepoConfig::JavaMaven, Some(root)) => collect_maven(stores, root, &mut declared),
+        (RepoConfig::JavaMaven, None) => {
+            return Err(git2::Error::from_str(
+                "maven modules are read from the HyperAST of the commit",
+            ))
+        }
+        (RepoConfig::CppMake, _) => {
+            let tree = repository.find_commit(commit)?.tree()?;
+            collect_make(repository, &tree, "", &mut declared)?;
+        }
+        _ => log::warn!("no module graph for {:?} repositories", kind),
+    }
+    Ok(link(declared))
+}
+
+/// Whether [`module_graph`] reads the build files of `kind` from the HyperAST,
+/// ie. whether the commit must be built before computing its module graph
+pub fn reads_hyperast(kind: RepoConfig) -> bool {
+    kind == RepoConfig::JavaMaven
+}
+
+/// A module as declared by its build file, before resolution of its dependencies
+enum Declared {
+    Maven(String, POM),
+    Make(String, make::BuildFile),
+}
+
+fn collect_maven(stores: &SimpleStores, root: NodeIdentifier, declared: &mut Vec<Declared>) {
+    let mut declared_paths: BTreeSet<String> = std::iter::once(String::new()).collect();
+    // in pre-order, so modules are found after the ones declaring them
+    for module in IterMavenModules::new(stores, StructuralPosition::new(root), root) {
+        let position = module.make_position(stores);
+        let path = (position.file().components())
+            .map(|x| x.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if !declared_paths.contains(&path) {
+            log::debug!("{} is not a declared maven module", path);
+            continue;
+        }
+        let Some(id) = module.node().copied() else {
+            continue;
+        };
+        let Some(pom) = child_by_name(stores, id, "pom.xml") else {
+            continue;
+        };
+        let pom = maven::read_pom(stores, pom, persist::metrics(stores, pom));
+        // eg. ./core or core/ declare the module in core
+        declared_paths.extend(pom.submodules().iter().map(|x| normalize(&join(&path, x))));
+        declared.push(Declared::Maven(path, pom));
+    }
+}
+
+fn collect_make(
+    repository: &Repository,
+    tree: &Tree,
+    path: &str,
+    declared: &mut Vec<Declared>,
+) -> Result<(), git2::Error> {
+    let mut build_file: Option<make::BuildFile> = None;
+    for entry in tree.iter() {
+        let name = ObjectName::from(entry.name_bytes());
+        if entry.kind() != Some(ObjectType::Blob) || !file_sys::MakeFile::matches(&name) {
+            continue;
+        }
+        let blob = repository.find_blob(entry.id())?;
+        match make::read_build_file(&name, blob.content()) {
+            Ok(x) => {
+                let replace = build_file.as_ref().map_or(true, |current| {
+                    current.build_system == make::BuildSystem::Make
+                });
+                if replace {
+                    build_file = Some(x);
+                }
+            }
+            Err(e) => log::warn!("ignoring {:?} in {}: {:?}", name.try_str(), path, e),
+        }
+    }
+    let Some(build_file) = build_file else {
+        return Ok(());
+    };
+    for submodule in build_file.submodules() {
+        let submodule = normalize(submodule);
+        let Ok(entry) = tree.get_path(Path::new(&submodule)) else {
+            log::debug!("missing module {} declared in {}", submodule, path);
+            continue;
+        };
+        if entry.kind() == Some(ObjectType::Tree) {
+            let subtree = repository.find_tree(entry.id())?;
+            collect_make(repository, &subtree, &join(path, &submodule), declared)?;
+        }
+    }
+    declared.push(Declared::Make(path.to_owned(), build_file));
+    Ok(())
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}/{}", path, name)
+    }
+}
+
+/// Removes the `.` and empty components of a relative path, and resolves its `..` components
+fn normalize(path: &str) -> String {
+    let mut components: Vec<&str> = vec![];
+    for x in path.split('/') {
+        match x {
+            "" | "." => (),
+            ".." => {
+                components.pop();
+            }
+            x => components.push(x),
+        }
+    }
+    components.join("/")
+}
+
+fn sorted(x: impl IntoIterator<Item = String>) -> Vec<String> {
+    x.into_iter().collect::<BTreeSet<_>>().into_iter().collect()
+}
+
+/// Resolves the dependencies between the declared modules
+fn link(declared: Vec<Declared>) -> ModuleGraph {
+    // maven modules by groupId:artifactId, the groupId can be inherited from the parent
+    let mut artifacts: BTreeMap<String, String> = BTreeMap::new();
+    // make and cmake modules by target, eg. foo for add_library(foo ...) or libfoo.a
+    let mut targets: BTreeMap<String, String> = BTreeMap::new();
+    for x in &declared {
+        match x {
+            Declared::Maven(path, pom) => {
+                if let Some(id) = artifact(pom) {
+                    artifacts.insert(id, path.clone());
+                }
+            }
+            Declared::Make(path, build_file) => {
+                for target in &build_file.targets {
+                    let name = target.name.as_str();
+                    if name.is_empty() {
+                        continue;
+                    }
+                    targets.insert(name.to_owned(), path.clone());
+                    // libfoo.a or libfoo.so is linked with -lfoo
+                    let lib = name.strip_prefix("lib").and_then(|x| x.split_once('.'));
+                    if let Some((lib, _)) = lib.filter(|(x, _)| !x.is_empty()) {
+                        targets.insert(lib.to_owned(), path.clone());
+                    }
+                }
+            }
+        }
+    }
+    let mut modules: Vec<Module> = declared
+        .into_iter()
+        .map(|x| match x {
+            Declared::Maven(path, pom) => {
+                let mut dependencies = vec![];
+                let mut external = vec![];
+                for d in &pom.dependencies {
+                    let c = &d.coordinates;
+                    let group = c.group_id.as_deref().unwrap_or_default();
+                    let artifact = c.artifact_id.as_deref().unwrap_or_default();
+                    match artifacts.get(&format!("{}:{}", group, artifact)) {
+                        Some(p) if p != &path => dependencies.push(p.clone()),
+                        Some(_) => (),
+                        None => external.push(match &c.version {
+                            Some(v) => format!("{}:{}:{}", group, artifact, v),
+                            None => format!("{}:{}", group, artifact),
+                        }),
+                    }
+                }
+                let roots = |x: &[String]| sorted(x.iter().map(|x| join(&path, x)));
+                Module {
+                    name: artifact(&pom),
+                    version: (pom.coordinates.version.clone())
+                        .or_else(|| pom.parent.as_ref()?.version.clone()),
+                    source_roots: roots(pom.source_dirs()),
+                    test_roots: roots(pom.test_source_dirs()),
+                    include_dirs: vec![],
+                    dependencies: sorted(dependencies),
+                    external: sorted(external),
+                    build_system: BuildSystem::Maven,
+                    path,
+                }
+            }
+            Declared::Make(path, build_file) => {
+                let mut dependencies = vec![];
+                let mut external = vec![];
+                for d in build_file.targets.iter().flat_map(|x| &x.dependencies) {
+                    match targets.get(d) {
+                        Some(p) if p != &path => dependencies.push(p.clone()),
+                        Some(_) => (),
+                        None => external.push(d.clone()),
+                    }
+                }
+                let names: Vec<&str> = (build_file.targets.iter())
+                    .map(|x| x.name.as_str())
+                    .filter(|x| !x.is_empty())
+                    .collect();
+                let roots = |x: &[String]| sorted(x.iter().map(|x| join(&path, x)));
+                Module {
+                    name: Some(names.join(" ")).filter(|x| !x.is_empty()),
+                    version: None,
+                    source_roots: roots(build_file.source_dirs()),
+                    test_roots: roots(build_file.test_source_dirs()),
+                    include_dirs: roots(&build_file.include_dirs),
+                    dependencies: sorted(dependencies),
+                    external: sorted(external),
+                    build_system: build_file.build_system.into(),
+                    path,
+                }
+            }
+        })
+        .collect();
+    modules.sort_by(|a, b| (&a.path, a.build_system).cmp(&(&b.path, b.build_system)));
+    ModuleGraph { modules }
+}
+
+/// The groupId:artifactId of a pom, the groupId can be inherited from the parent
+fn artifact(pom: &POM) -> Option<String> {
+    let artifact = pom.coordinates.artifact_id.as_ref()?;
+    let group =
+        (pom.coordinates.group_id.as_ref()).or_else(|| pom.parent.as_ref()?.group_id.as_ref())?;
+    Some(format!("{}:{}", group, artifact))
+}
+
+impl ModuleGraph {
+    pub fn get(&self, path: &str, build_system: BuildSystem) -> Option<&Module> {
+        self.modules
+            .iter()
+            .find(|x| x.path == path && x.build_system == build_system)
+    }
+
+    /// How the graph changed from `self` to `after`
+    pub fn changes(&self, after: &ModuleGraph) -> ModuleGraphChanges {
+        let mut changes = ModuleGraphChanges::default();
+        for m in &self.modules {
+            if after.get(&m.path, m.build_system).is_none() {
+                changes.removed.push(m.clone());
+            }
+        }
+        for m in &after.modules {
+            let Some(before) = self.get(&m.path, m.build_system) else {
+                changes.added.push(m.clone());
+                continue;
+            };
+            let diff = |before: &[String], after: &[String]| {
+                let added = after.iter().filter(|x| !before.contains(x)).cloned();
+                let removed = before.iter().filter(|x| !after.contains(x)).cloned();
+                (added.collect::<Vec<_>>(), removed.collect::<Vec<_>>())
+            };
+            let pair = |before: &Option<String>, after: &Option<String>| {
+                (before != after).then(|| (before.clone(), after.clone()))
+            };
+            let (added_source_roots, removed_source_roots) =
+                diff(&before.source_roots, &m.source_roots);
+            let (added_dependencies, removed_dependencies) =
+                diff(&before.dependencies, &m.dependencies);
+            let (added_external, removed_external) = diff(&before.external, &m.external);
+            let c = ModuleChanges {
+                path: m.path.clone(),
+                build_system: m.build_system,
+                name: pair(&before.name, &m.name),
+                version: pair(&before.version, &m.version),
+                added_source_roots,
+                removed_source_roots,
+                added_dependencies,
+                removed_dependencies,
+                added_external,
+                removed_external,
+            };
+            if !c.is_empty() {
+                changes.changed.push(c);
+            }
+        }
+        changes
+    }
+}
+
+impl ModuleChanges {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.version.is_none()
+            && self.added_source_roots.is_empty()
+            && self.removed_source_roots.is_empty()
+            && self.added_dependencies.is_empty()
+            && self.removed_dependencies.is_empty()
+            && self.added_external.is_empty()
+            && self.removed_external.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_preprocessed::PreProcessedRepositories;
+
+    fn commit(repository: &Repository, files: &[(&str, &str)]) -> Oid {
+        // builds the nested trees bottom up, files are given by their full path
+        fn tree(repository: &Repository, files: &[(&str, &str)]) -> Oid {
+            let mut builder = repository.treebuilder(None).unwrap();
+            let mut dirs: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+            for (path, text) in files {
+                match path.split_once('/') {
+                    Some((dir, rest)) => dirs.entry(dir).or_default().push((rest, text)),
+                    None => {
+                        let blob = repository.blob(text.as_bytes()).unwrap();
+                        builder.insert(path, blob, 0o100644).unwrap();
+                    }
+                }
+            }
+            for (dir, files) in dirs {
+                let oid = tree(repository, &files);
+                builder.insert(dir, oid, 0o040000).unwrap();
+            }
+            builder.write().unwrap()
+        }
+        let tree = repository.find_tree(tree(repository, files)).unwrap();
+        let signature = git2::Signature::now("a", "a@example.com").unwrap();
+        repository
+            .commit(None, &signature, &signature, "", &tree, &[])
+            .unwrap()
+    }
+
+    const ROOT_POM: &str = r#"<project>
+  <groupId>org.example</groupId>
+  <artifactId>root</artifactId>
+  <version>1.0</version>
+  <modules>
+    <module>core</module>
+    <module>app</module>
+  </modules>
+</project>"#;
+
+    const CORE_POM: &str = r#"<project>
+  <parent>
+    <groupId>org.example</groupId>
+    <artifactId>root</artifactId>
+    <version>1.0</version>
+  </parent>
+  <artifactId>core</artifactId>
+</project>"#;
+
+    /// Builds the commit like the server would, then computes its module graph
+    fn build_graph(
+        repositories: &mut PreProcessedRepositories,
+        repository: &Repository,
+        kind: RepoConfig,
+        commit: Oid,
+    ) -> ModuleGraph {
+        let repo = crate::git::Forge::Github.repo("example", "modules");
+        let config = repositories.register_config(repo, kind).config;
+        let processor = &mut repositories.processor;
+        let root = reads_hyperast(kind)
+            .then(|| processor.process_commit_with(repository, &config, commit));
+        module_graph(&processor.main_stores, repository, kind, commit, root).unwrap()
+    }
+
+    fn temp_repository(name: &str) -> (std::path::PathBuf, Repository) {
+        let dir = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repository = Repository::init_bare(&dir).unwrap();
+        (dir, repository)
+    }
+
+    #[cfg(feature = "maven_java")]
+    #[test]
+    fn maven_modules() {
+        let (dir, repository) = temp_repository("maven_modules");
+        let mut repositories = PreProcessedRepositories::default();
+        let app_pom = r#"<project>
+  <parent><groupId>org.example</groupId><artifactId>root</artifactId></parent>
+  <artifactId>app</artifactId>
+  <build><sourceDirectory>src</sourceDirectory></build>
+  <dependencies>
+    <dependency><groupId>org.example</groupId><artifactId>core</artifactId></dependency>
+    <dependency><groupId>junit</groupId><artifactId>junit</artifactId><version>4.13</version></dependency>
+  </dependencies>
+</project>"#;
+        let legacy_pom = r#"<project>
+  <groupId>org.example</groupId>
+  <artifactId>legacy</artifactId>
+</project>"#;
+        let fixture_pom = r#"<project>
+  <groupId>org.example</groupId>
+  <artifactId>fixture</artifactId>
+</project>"#;
+        // module paths are normalized
+        let root_pom = ROOT_POM.replace(
+            "<module>app</module>",
+            "<module>./app/</module>\n    <module>legacy/</module>",
+        );
+        let before = commit(
+            &repository,
+            &[
+                ("pom.xml", &root_pom),
+                ("core/pom.xml", CORE_POM),
+                ("app/pom.xml", app_pom),
+                ("legacy/pom.xml", legacy_pom),
+                ("it/fixture/pom.xml", fixture_pom),
+            ],
+        );
+        let graph = build_graph(&mut repositories, &repository, RepoConfig::JavaMaven, before);
+        let paths: Vec<_> = graph.modules.iter().map(|x| x.path.as_str()).collect();
+        // the fixture is not declared by any module
+        assert_eq!(paths, ["", "app", "core", "legacy"]);
+        let app = graph.get("app", BuildSystem::Maven).unwrap();
+        assert_eq!(app.name.as_deref(), Some("org.example:app"));
+        assert_eq!(app.source_roots, ["app/src"]);
+        assert_eq!(app.test_roots, ["app/src/test/java"]);
+        assert_eq!(app.dependencies, ["core"]);
+        assert_eq!(app.external, ["junit:junit:4.13"]);
+        let core = graph.get("core", BuildSystem::Maven).unwrap();
+        assert_eq!(core.version.as_deref(), Some("1.0"));
+
+        // legacy is still there but it is not a module anymore
+        let app_pom = app_pom.replace("junit", "hamcrest");
+        let after = commit(
+            &repository,
+            &[
+                ("pom.xml", ROOT_POM),
+                ("core/pom.xml", CORE_POM),
+                ("app/pom.xml", &app_pom),
+                ("legacy/pom.xml", legacy_pom),
+                ("it/fixture/pom.xml", fixture_pom),
+            ],
+        );
+        let changes = graph.changes(&build_graph(
+            &mut repositories,
+            &repository,
+            RepoConfig::JavaMaven,
+            after,
+        ));
+        assert!(changes.added.is_empty());
+        assert_eq!(changes.removed.len(), 1);
+        assert_eq!(changes.removed[0].path, "legacy");
+        assert_eq!(changes.changed.len(), 1);
+        assert_eq!(changes.changed[0].path, "app");
+        assert_eq!(
+            changes.changed[0].added_external,
+            ["hamcrest:hamcrest:4.13"]
+        );
+        assert_eq!(changes.changed[0].removed_external, ["junit:junit:4.13"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "make_cpp")]
+    #[test]
+    fn cmake_modules() {
+        let (dir, repository) = temp_repository("cmake_modules");
+        let mut repositories = PreProcessedRepositories::default();
+        let cmake = "add_library(native src/a.cpp)\ntarget_link_libraries(native PRIVATE pthread)";
+        let commit = commit(
+            &repository,
+            &[
+                ("CMakeLists.txt", "project(Demo)\nadd_subdirectory(native)"),
+                ("native/CMakeLists.txt", cmake),
+                ("native/Makefile", "all:\n\tcmake ."),
+                ("native/src/a.cpp", "int a() { return 0; }"),
+                ("tests/fixture/CMakeLists.txt", "add_library(fixture f.cpp)"),
+            ],
+        );
+        let graph = build_graph(&mut repositories, &repository, RepoConfig::CppMake, commit);
+        let paths: Vec<_> = graph.modules.iter().map(|x| x.path.as_str()).collect();
+        assert_eq!(paths, ["", "native"]);
+        // the CMakeLists.txt is considered instead of the Makefile
+        let native = graph.get("native", BuildSystem::CMake).unwrap();
+        assert_eq!(native.name.as_deref(), Some("native"));
+        assert_eq!(native.source_roots, ["native/src"]);
+        assert_eq!(native.external, ["pthread"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -4,12 +4,16 @@ use std::{
 };
 
 use git2::Repository;
-use hyper_ast::store::nodes::DefaultNodeIdentifier as NodeIdentifier;
+use hyper_ast::store::nodes::{
+    legion::persist::{Codecs, PersistError, Transfer},
+    DefaultNodeIdentifier as NodeIdentifier,
+};
 
 use crate::{
     git::{all_commits_between, Repo},
     maven::MavenModuleAcc,
     maven_processor::make,
+    persist::PersistedStore,
     preprocessed::{CommitProcessor, RepositoryProcessor},
     processing::{
         erased::ParametrizedCommitProcessorHandle, file_sys, BuildSystem, CacheHolding,
@@ -27,6 +31,8 @@ pub struct PreProcessedRepositories {
     pub processor: RepositoryProcessor,
     // pub processing_ordered_commits: HashMap<String,Vec<git2::Oid>>,
     configs: HashMap<Repo, ParametrizedCommitProcessorHandle>,
+    kinds: HashMap<ParametrizedCommitProcessorHandle, RepoConfig>,
+    persistence: Option<PersistedStore>,
 }
 
 #[derive(Default)]
@@ -34,6 +40,8 @@ pub struct CommitsPerSys {
     pub maven: HashMap<git2::Oid, Commit>,
     pub make: HashMap<git2::Oid, Commit>,
     pub npm: HashMap<git2::Oid, Commit>,
+    pub cargo: HashMap<git2::Oid, Commit>,
+    pub python: HashMap<git2::Oid, Commit>,
     pub any: HashMap<git2::Oid, Commit>,
 }
 
@@ -51,6 +59,8 @@ impl CommitsPerSys {
             RepoConfig::JavaMaven => &self.maven,
             RepoConfig::CppMake => &self.make,
             RepoConfig::TsNpm => &self.npm,
+            RepoConfig::RustCargo => &self.cargo,
+            RepoConfig::Python => &self.python,
             RepoConfig::Any => &self.any,
         }
     }
@@ -118,6 +128,17 @@ impl PreProcessedRepositories {
     //     // }
     // }
 
+    /// Persist processed commits in `dir`,
+    /// commits already persisted there are restored instead of being processed again.
+    pub fn with_persistence(
+        dir: &std::path::Path,
+    ) -> Result<Self, hyper_ast::store::nodes::legion::persist::PersistError> {
+        let mut r = Self::default();
+        let persistence = PersistedStore::open(dir, &mut r.processor.main_stores)?;
+        r.persistence = Some(persistence);
+        Ok(r)
+    }
+
     pub fn purge_caches(&mut self) {
         self.processor.purge_caches()
     }
@@ -137,36 +158,40 @@ impl PreProcessedRepositories {
     }
 
     pub fn register_config(&mut self, repo: Repo, config: RepoConfig) -> ConfiguredRepoHandle2 {
-        use crate::processing::erased::Parametrized;
-        let r = match config {
-            RepoConfig::JavaMaven => {
-                let h = self
-                    .processor
-                    .processing_systems
-                    .mut_or_default::<crate::maven_processor::MavenProcessorHolder>();
-                ConfiguredRepoHandle2 {
-                    spec: repo,
-                    config: h.register_param(crate::maven_processor::Parameter),
-                }
-            }
-            RepoConfig::CppMake => {
-                let h = self
-                    .processor
-                    .processing_systems
-                    .mut_or_default::<crate::make_processor::MakeProcessorHolder>();
-                ConfiguredRepoHandle2 {
-                    spec: repo,
-                    config: h.register_param(crate::make_processor::Parameter),
-                }
-            }
-            _ => todo!(),
+        if config == RepoConfig::Any {
+            return self.register_any_config(repo, Default::default());
+        }
+        let r = ConfiguredRepoHandle2 {
+            spec: repo,
+            config: register(&mut self.processor, config),
         };
+        self.configs.insert(r.spec.clone(), r.config);
+        self.kinds.insert(r.config, config);
+        r
+    }
 
+    /// Same as [`Self::register_config`] with [`RepoConfig::Any`],
+    /// but files are sent to generators following `mapping`
+    pub fn register_any_config(
+        &mut self,
+        repo: Repo,
+        mapping: crate::any::ExtensionMapping,
+    ) -> ConfiguredRepoHandle2 {
+        let r = ConfiguredRepoHandle2 {
+            spec: repo,
+            config: register_any(&mut self.processor, mapping),
+        };
         self.configs.insert(r.spec.clone(), r.config);
+        self.kinds.insert(r.config, RepoConfig::Any);
         r
     }
 
-    pub fn get_config(&mut self, repo: Repo) -> Option<ConfiguredRepoHandle2> {
+    /// The kind of repository processed with `config`
+    pub fn get_kind(&self, config: &ParametrizedCommitProcessorHandle) -> Option<RepoConfig> {
+        self.kinds.get(config).copied()
+    }
+
+    pub fn get_config(&self, repo: Repo) -> Option<ConfiguredRepoHandle2> {
         // let proc = self
         //     .processor.processing_systems
         //     .by_id(&repository.config.0)
@@ -187,8 +212,218 @@ impl PreProcessedRepositories {
         // dir_path: &str,
         limit: usize,
     ) -> Result<Vec<git2::Oid>, git2::Error> {
+        let rw = all_commits_between(&repository.repo, before, after)?;
+        rw.take(limit)
+            .map(|oid| {
+                let oid = oid?;
+                self.ensure_processed(repository, oid);
+                Ok(oid)
+            })
+            .collect()
+    }
+
+    /// The commits between `before` and `after` (at most `limit`),
+    /// along with the ones among them that are not yet in memory.
+    ///
+    /// Only needs a shared access, so that a server can plan the processing of a repository
+    /// without blocking the readers of the others, then build each missing commit,
+    /// with [`Self::ensure_processed`] or in a [`Shard`].
+    pub fn commits_to_process(
+        &self,
+        repository: &ConfiguredRepo2,
+        before: &str,
+        after: &str,
+        limit: usize,
+    ) -> Result<(Vec<git2::Oid>, Vec<git2::Oid>), git2::Error> {
+        let commits = all_commits_between(&repository.repo, before, after)?
+            .take(limit)
+            .collect::<Result<Vec<_>, _>>()?;
+        let missing = commits
+            .iter()
+            .filter(|oid| !self.is_processed(&repository.config, oid))
+            .copied()
+            .collect();
+        Ok((commits, missing))
+    }
+
+    /// Tells if the commit is already in memory, restored or processed.
+    pub fn is_processed(
+        &self,
+        config: &ParametrizedCommitProcessorHandle,
+        commit_oid: &git2::Oid,
+    ) -> bool {
+        self.get_commit(config, commit_oid).is_some()
+    }
+
+    /// Restores or processes a single commit, does nothing if it is already in memory.
+    pub fn ensure_processed(&mut self, repository: &ConfiguredRepo2, oid: git2::Oid) {
+        if self.is_processed(&repository.config, &oid) {
+            return;
+        }
+        let Self {
+            processor,
+            kinds,
+            persistence,
+            ..
+        } = self;
+        let (Some(persistence), Some(&kind)) = (persistence, kinds.get(&repository.config)) else {
+            processor.process_commit(repository, oid);
+            return;
+        };
+        match persistence.restore(&mut processor.main_stores, kind, oid) {
+            Ok(Some(commit)) => {
+                processor
+                    .processing_systems
+                    .by_id_mut(&repository.config.0)
+                    .unwrap()
+                    .get_mut(repository.config.1)
+                    .insert_commit(oid, commit);
+                return;
+            }
+            Ok(None) => (),
+            Err(e) => log::error!("failed to restore commit {}: {}", oid, e),
+        }
+        if let Err(e) = persistence.restore_caches(processor, &repository.repo, kind, oid) {
+            log::error!("failed to restore caches for commit {}: {}", oid, e);
+        }
+        processor.process_commit(repository, oid);
+        let commit = processor
+            .processing_systems
+            .by_id(&repository.config.0)
+            .unwrap()
+            .get(repository.config.1)
+            .get_commit(oid)
+            .cloned();
+        if let Some(commit) = commit {
+            let stores = &processor.main_stores;
+            if let Err(e) = persistence.persist(stores, processor, &Some, kind, oid, &commit) {
+                log::error!("failed to persist commit {}: {}", oid, e);
+            }
+        }
+    }
+
+    /// A shard to build the commits of `repository` apart from the shared stores, see [`Shard`].
+    pub fn shard(&self, repository: &ConfiguredRepo2) -> Shard {
+        use crate::processing::erased::ParametrizedCommitProc2;
+        let kind = self
+            .get_kind(&repository.config)
+            .expect("the config of the repository should be registered");
+        let mut processor = RepositoryProcessor::default();
+        let config = match kind {
+            RepoConfig::Any => {
+                let mapping = self
+                    .processor
+                    .processing_systems
+                    .get::<crate::any_processor::AnyProcessorHolder>()
+                    .unwrap()
+                    .with_parameters(repository.config.1)
+                    .parameter()
+                    .mapping
+                    .clone();
+                register_any(&mut processor, mapping)
+            }
+            kind => register(&mut processor, kind),
+        };
+        Shard {
+            kind,
+            config,
+            processor,
+            codecs: crate::persist::codecs(),
+            restored: Default::default(),
+            published: Default::default(),
+        }
+    }
+
+    /// Restores a persisted commit like [`Self::ensure_processed`],
+    /// otherwise restores the persisted cache entries of the commit in `shard`,
+    /// then returns false as it remains to [`Shard::process`] and [`Self::publish`] it.
+    pub fn prepare(
+        &mut self,
+        shard: &mut Shard,
+        repository: &ConfiguredRepo2,
+        oid: git2::Oid,
+    ) -> bool {
+        if self.is_processed(&repository.config, &oid) {
+            return true;
+        }
+        let Some(persistence) = &mut self.persistence else {
+            return false;
+        };
+        let processor = &mut self.processor;
+        match persistence.restore(&mut processor.main_stores, shard.kind, oid) {
+            Ok(Some(commit)) => {
+                processor
+                    .processing_systems
+                    .by_id_mut(&repository.config.0)
+                    .unwrap()
+                    .get_mut(repository.config.1)
+                    .insert_commit(oid, commit);
+                return true;
+            }
+            Ok(None) => (),
+            Err(e) => log::error!("failed to restore commit {}: {}", oid, e),
+        }
+        let (main, kind) = (&mut processor.main_stores, shard.kind);
+        if let Err(e) =
+            persistence.restore_caches_in_shard(main, shard, &repository.repo, kind, oid)
+        {
+            log::error!("failed to restore caches for commit {}: {}", oid, e);
+        }
+        false
+    }
+
+    /// Copies a commit built in `shard` to the shared stores, then persists it if enabled.
+    ///
+    /// Only the nodes that are not yet published are copied.
+    pub fn publish(
+        &mut self,
+        shard: &mut Shard,
+        repository: &ConfiguredRepo2,
+        oid: git2::Oid,
+    ) -> Result<(), PersistError> {
+        let Shard {
+            kind,
+            config,
+            processor: built,
+            codecs,
+            published,
+            ..
+        } = shard;
+        let Some(commit) = built
+            .processing_systems
+            .by_id(&config.0)
+            .unwrap()
+            .get(config.1)
+            .get_commit(oid)
+        else {
+            panic!("commit {} should be processed in the shard", oid)
+        };
+        let main = &mut self.processor.main_stores;
+        let ast_root = published.copy(
+            codecs,
+            (
+                &built.main_stores.node_store,
+                &built.main_stores.label_store,
+            ),
+            (&mut main.node_store, &mut main.label_store),
+            commit.ast_root,
+        )?;
+        let commit = Commit {
+            ast_root,
+            ..commit.clone()
+        };
         self.processor
-            .pre_process_with_limit(repository, before, after, limit)
+            .processing_systems
+            .by_id_mut(&repository.config.0)
+            .unwrap()
+            .get_mut(repository.config.1)
+            .insert_commit(oid, commit.clone());
+        if let Some(persistence) = &mut self.persistence {
+            let stores = &self.processor.main_stores;
+            let ids = |id| published.get(id);
+            persistence.persist(stores, built, &ids, *kind, oid, &commit)?;
+        }
+        Ok(())
     }
 
     pub fn pre_process_with_config2(
@@ -297,3 +532,79 @@ impl PreProcessedRepositories {
         make(acc, stores)
     }
 }
+
+/// Builds the commits of a single repository in its own stores,
+/// so that the shared stores of [`PreProcessedRepositories`] are only locked while publishing them.
+///
+/// A commit is first prepared with [`PreProcessedRepositories::prepare`], built with [`Shard::process`],
+/// then copied to the shared stores with [`PreProcessedRepositories::publish`].
+/// The shard keeps its nodes and the caches of its processor, thus following commits are built incrementally,
+/// at the cost of a second copy of the nodes of the repository, it should be dropped once its commits are published.
+pub struct Shard {
+    kind: RepoConfig,
+    config: ParametrizedCommitProcessorHandle,
+    pub(crate) processor: RepositoryProcessor,
+    codecs: Codecs,
+    /// nodes of the shared stores to their copy in the shard, eg. restored cache entries
+    pub(crate) restored: Transfer,
+    /// nodes of the shard to their copy in the shared stores
+    pub(crate) published: Transfer,
+}
+
+impl Shard {
+    /// Builds a commit of `repository` in the shard, without accessing the shared stores.
+    pub fn process(&mut self, repository: &Repository, oid: git2::Oid) {
+        if self.is_processed(&oid) {
+            return;
+        }
+        self.processor
+            .process_commit_with(repository, &self.config, oid);
+    }
+
+    fn is_processed(&self, commit_oid: &git2::Oid) -> bool {
+        self.processor
+            .processing_systems
+            .by_id(&self.config.0)
+            .unwrap()
+            .get(self.config.1)
+            .get_commit(*commit_oid)
+            .is_some()
+    }
+}
+
+fn register(
+    processor: &mut RepositoryProcessor,
+    config: RepoConfig,
+) -> ParametrizedCommitProcessorHandle {
+    use crate::processing::erased::Parametrized;
+    let systems = &mut processor.processing_systems;
+    match config {
+        RepoConfig::JavaMaven => systems
+            .mut_or_default::<crate::maven_processor::MavenProcessorHolder>()
+            .register_param(crate::maven_processor::Parameter),
+        RepoConfig::CppMake => systems
+            .mut_or_default::<crate::make_processor::MakeProcessorHolder>()
+            .register_param(crate::make_processor::Parameter),
+        RepoConfig::RustCargo => systems
+            .mut_or_default::<crate::cargo_processor::CargoProcessorHolder>()
+            .register_param(crate::cargo_processor::Parameter),
+        RepoConfig::Python => systems
+            .mut_or_default::<crate::python_processor::PythonProcessorHolder>()
+            .register_param(crate::python_processor::Parameter),
+        RepoConfig::TsNpm => systems
+            .mut_or_default::<crate::npm_processor::NpmProcessorHolder>()
+            .register_param(crate::npm_processor::Parameter),
+        RepoConfig::Any => register_any(processor, Default::default()),
+    }
+}
+
+fn register_any(
+    processor: &mut RepositoryProcessor,
+    mapping: crate::any::ExtensionMapping,
+) -> ParametrizedCommitProcessorHandle {
+    use crate::processing::erased::Parametrized;
+    processor
+        .processing_systems
+        .mut_or_default::<crate::any_processor::AnyProcessorHolder>()
+        .register_param(crate::any_processor::Parameter { mapping })
+}
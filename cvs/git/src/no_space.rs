@@ -422,6 +422,27 @@ impl<'a> types::Typed for NoSpaceWrapper<'a, MIdN<NodeIdentifier>> {
             let t = *t as u16;
             let t = <hyper_ast_gen_ts_cpp::types::Cpp as hyper_ast::types::Lang<_>>::make(t);
             MultiType::Cpp(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_rust::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_rust::types::Rust as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Rust(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_python::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_python::types::Python as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Python(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_ts::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_ts::types::Ts as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Ts(*t)
         } else if let Ok(t) = self
             .inner
             .get_component::<hyper_ast_gen_ts_xml::types::Type>()
@@ -429,6 +450,8 @@ impl<'a> types::Typed for NoSpaceWrapper<'a, MIdN<NodeIdentifier>> {
             let t = *t as u16;
             let t = <hyper_ast_gen_ts_xml::types::Xml as hyper_ast::types::Lang<_>>::make(t);
             MultiType::Xml(*t)
+        } else if let Ok(t) = self.inner.get_component::<hyper_ast::blob::types::Type>() {
+            MultiType::Blob(*t)
         } else {
             panic!()
         }
@@ -453,6 +476,27 @@ impl<'a> types::Typed for NoSpaceWrapper<'a, NodeIdentifier> {
             let t = *t as u16;
             let t = <hyper_ast_gen_ts_cpp::types::Cpp as hyper_ast::types::Lang<_>>::make(t);
             MultiType::Cpp(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_rust::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_rust::types::Rust as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Rust(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_python::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_python::types::Python as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Python(*t)
+        } else if let Ok(t) = self
+            .inner
+            .get_component::<hyper_ast_gen_ts_ts::types::Type>()
+        {
+            let t = *t as u16;
+            let t = <hyper_ast_gen_ts_ts::types::Ts as hyper_ast::types::Lang<_>>::make(t);
+            MultiType::Ts(*t)
         } else if let Ok(t) = self
             .inner
             .get_component::<hyper_ast_gen_ts_xml::types::Type>()
@@ -460,6 +504,8 @@ impl<'a> types::Typed for NoSpaceWrapper<'a, NodeIdentifier> {
             let t = *t as u16;
             let t = <hyper_ast_gen_ts_xml::types::Xml as hyper_ast::types::Lang<_>>::make(t);
             MultiType::Xml(*t)
+        } else if let Ok(t) = self.inner.get_component::<hyper_ast::blob::types::Type>() {
+            MultiType::Blob(*t)
         } else {
             panic!()
         }
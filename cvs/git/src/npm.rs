@@ -0,0 +1,155 @@
+use enumset::EnumSet;
+use hyper_ast::store::defaults::{LabelIdentifier, NodeIdentifier};
+use hyper_ast_gen_ts_ts::legion as ts_tree_gen;
+
+use crate::{processing::ObjectName, Accumulator, DefaultMetrics, ParseErr, SimpleStores};
+
+/// Adds the package.json to the HyperAST and extracts the name of the package and its workspaces from it.
+///
+/// The json is not parsed with tree-sitter, so the file is stored as a blob.
+/// An ill-formed file is still stored but describes nothing.
+pub(crate) fn handle_package_json_file(
+    stores: &mut SimpleStores,
+    name: &ObjectName,
+    text: &[u8],
+) -> Result<PackageJson, ParseErr> {
+    let (compressed_node, metrics) = crate::any::handle_blob_file(stores, name, text);
+    let text = std::str::from_utf8(text)?;
+    let manifest: serde_json::Value = serde_json::from_str(text).unwrap_or_else(|e| {
+        log::warn!("bad package.json {:?}: {}", name.try_str(), e);
+        serde_json::Value::Null
+    });
+    let package = manifest
+        .get("name")
+        .and_then(|x| x.as_str())
+        .map(|x| x.to_owned());
+    // npm and yarn use a list of globs, yarn also accepts { "packages": [...] }
+    let workspaces = manifest.get("workspaces").and_then(|x| match x {
+        serde_json::Value::Array(_) => Some(x),
+        x => x.get("packages"),
+    });
+    let workspaces = workspaces
+        .and_then(|x| x.as_array())
+        .map(|x| {
+            x.iter()
+                .filter_map(|x| x.as_str())
+                .map(|x| x.trim_start_matches("./").trim_end_matches('/').to_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(PackageJson {
+        compressed_node,
+        metrics,
+        package,
+        workspaces,
+    })
+}
+
+/// What matters in a package.json to find the packages of a repository
+#[derive(Debug, Clone)]
+pub struct PackageJson {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    pub package: Option<String>,
+    /// paths to the packages of the workspace, they may contain globs, eg. packages/*
+    pub workspaces: Vec<String>,
+}
+
+/// Stored on the directories of packages and workspaces
+#[derive(enumset::EnumSetType, Debug)]
+pub enum SemFlags {
+    IsPackage,
+    IsWorkspace,
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+    /// name of the package if the directory contains a package.json
+    pub(crate) package: Option<String>,
+    pub(crate) is_workspace: bool,
+}
+
+impl MD {
+    pub fn is_package(&self) -> bool {
+        self.package.is_some()
+    }
+}
+
+pub struct NpmModuleAcc {
+    pub(crate) name: String,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) metrics: DefaultMetrics,
+    pub(crate) package: Option<String>,
+    pub(crate) workspaces: Vec<String>,
+}
+
+impl From<String> for NpmModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl NpmModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            package: None,
+            workspaces: Default::default(),
+        }
+    }
+}
+
+impl NpmModuleAcc {
+    /// The package.json is a child, it also marks the directory as a package
+    pub(crate) fn push_package_json(&mut self, name: LabelIdentifier, package_json: PackageJson) {
+        assert!(!self.children_names.contains(&name));
+        self.children.push(package_json.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(package_json.metrics);
+        self.package = package_json.package;
+        self.workspaces = package_json.workspaces;
+    }
+    pub(crate) fn status(&self) -> EnumSet<SemFlags> {
+        let mut status = EnumSet::new();
+        if self.package.is_some() {
+            status |= SemFlags::IsPackage;
+        }
+        if !self.workspaces.is_empty() {
+            status |= SemFlags::IsWorkspace;
+        }
+        status
+    }
+    pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: ts_tree_gen::Local,
+        skiped_ana: bool,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.metrics);
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for NpmModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+}
+
+impl Accumulator for NpmModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
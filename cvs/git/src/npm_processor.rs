@@ -0,0 +1,594 @@
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+use git2::{Oid, Repository};
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{
+        defaults::NodeIdentifier,
+        nodes::legion::{compo, compo::CS, NodeStore},
+    },
+    tree_gen::SubTreeMetrics,
+    types::LabelStore,
+};
+use hyper_ast_gen_ts_ts::{
+    legion::{self as ts_gen, eq_node},
+    types::Type,
+};
+
+use crate::{
+    git::BasicGitObject,
+    npm::{NpmModuleAcc, MD},
+    preprocessed::{IsSkippedAna, RepositoryProcessor},
+    processing::{erased::ParametrizedCommitProc2, CacheHolding, InFiles, ObjectName},
+    Processor, SimpleStores,
+};
+
+/// Explores the whole repository except node_modules/ directories,
+/// directories containing a package.json are marked as packages.
+pub struct NpmProcessor<'a, 'b, 'c, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<(Oid, Vec<BasicGitObject>, Acc)>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<NpmProc>,
+}
+
+impl<'a, 'b, 'c, Acc: From<String>> NpmProcessor<'a, 'b, 'c, Acc> {
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self {
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>();
+        let handle =
+            <NpmProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c> Processor<NpmModuleAcc> for NpmProcessor<'a, 'b, 'c, NpmModuleAcc> {
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                let acc = &mut self.stack.last_mut().unwrap().2;
+                if crate::processing::file_sys::PackageJson::matches(&name) {
+                    let r = self.prepro.help_handle_package_json(
+                        oid,
+                        acc,
+                        &name,
+                        &self.repository,
+                        self.handle.into(),
+                    );
+                    if let Err(e) = r {
+                        log::warn!("ignoring package.json {:?}: {:?}", name.try_str(), e);
+                    }
+                } else if crate::processing::file_sys::Ts::matches(&name) {
+                    self.prepro
+                        .help_handle_ts_file(oid, acc, &name, self.repository, self.handle)
+                        .unwrap();
+                } else {
+                    log::debug!("not ts or js source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: NpmModuleAcc) -> Option<(NodeIdentifier, MD)> {
+        let name = acc.name.clone();
+        let key = (oid, name.as_bytes().into());
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>()
+            .get_caches_mut()
+            .dir_map
+            .insert(key, full_node.clone());
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            w.push_submodule(name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, NpmModuleAcc)> {
+        &mut self.stack
+    }
+}
+
+impl<'a, 'b, 'c> NpmProcessor<'a, 'b, 'c, NpmModuleAcc> {
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_encoded_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").1.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree);
+                self.stack
+                    .push((oid, prepared, NpmModuleAcc::new(name.try_into().unwrap())));
+            }
+            return;
+        }
+        if name.as_bytes() == b"node_modules" {
+            // installed dependencies, not part of the repository even if commited
+            return;
+        }
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>()
+            .get_caches_mut()
+            .dir_map
+            .get(&(oid, name.clone()))
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().2;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.children_names.contains(&name));
+            w.push_submodule(name, full_node);
+            return;
+        }
+        log::debug!("npm tree {:?}", name.try_str());
+        let tree = self.repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        self.stack
+            .push((oid, prepared, NpmModuleAcc::new(name.try_into().unwrap())));
+    }
+}
+
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+pub(crate) fn make(acc: NpmModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &Type::Directory, &acc.name, size_no_spaces);
+    let hashable = hbuilder.most_discriminating();
+    let label = stores.label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&Type::Directory, Some(&label), &acc.children);
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    let hashs = hbuilder.build();
+    let status = acc.status();
+    let node_id = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        log::info!("make npm dir {} {}", &acc.name, acc.children.len());
+        let vacant = insertion.vacant();
+        assert_eq!(acc.children_names.len(), acc.children.len());
+        if acc.children.is_empty() {
+            NodeStore::insert_after_prepare(
+                vacant,
+                (Type::Directory, label, hashs, BloomSize::None),
+            )
+        } else {
+            let mut dyn_builder =
+                hyper_ast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+            dyn_builder.add(Type::Directory);
+            dyn_builder.add(hashs.clone());
+            dyn_builder.add(label);
+            dyn_builder.add(BloomSize::Much);
+            dyn_builder.add(compo::Size(size));
+            dyn_builder.add(compo::SizeNoSpaces(size_no_spaces));
+            dyn_builder.add(compo::Height(height));
+            dyn_builder.add(CS(acc.children_names.into_boxed_slice()));
+            dyn_builder.add(CS(acc.children.into_boxed_slice()));
+            if !status.is_empty() {
+                dyn_builder.add(status);
+            }
+            NodeStore::insert_built_after_prepare(vacant, dyn_builder.build())
+        }
+    };
+
+    let metrics = SubTreeMetrics {
+        size,
+        height,
+        hashs,
+        size_no_spaces,
+    };
+    let md = MD {
+        metrics,
+        is_workspace: !acc.workspaces.is_empty(),
+        package: acc.package,
+    };
+    (node_id, md)
+}
+
+impl RepositoryProcessor {
+    fn help_handle_package_json(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut NpmModuleAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<PackageJsonProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::PackageJson>()
+            .handle(oid, repository, name, parameters, |_, n, t| {
+                crate::npm::handle_package_json_file(&mut self.main_stores, n, t)
+            })?;
+        let name = self.intern_object_name(name);
+        parent_acc.push_package_json(name, x);
+        Ok(())
+    }
+
+    fn handle_ts_blob(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<NpmProc>,
+    ) -> Result<(ts_gen::Local, IsSkippedAna), crate::ParseErr> {
+        self.processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::Ts>()
+            .handle2(oid, repository, &name, parameters, |c, n, t| {
+                let line_break = if t.contains(&b'\r') {
+                    "\r\n".as_bytes().to_vec()
+                } else {
+                    "\n".as_bytes().to_vec()
+                };
+                crate::ts::handle_ts_file(
+                    &mut ts_gen::TsTreeGen {
+                        line_break,
+                        stores: &mut self.main_stores,
+                        md_cache: &mut c
+                            .mut_or_default::<NpmProcessorHolder>()
+                            .get_caches_mut()
+                            .md_cache,
+                    },
+                    n,
+                    t,
+                )
+                .map_err(|_| crate::ParseErr::IllFormed)
+                .map(|x| (x.local.clone(), false))
+            })
+    }
+
+    pub(crate) fn help_handle_ts_file(
+        &mut self,
+        oid: Oid,
+        parent: &mut NpmModuleAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<NpmProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let (full_node, skiped_ana) = self.handle_ts_blob(oid, name, repository, parameters)?;
+        let name = self.intern_object_name(name);
+        assert!(!parent.children_names.contains(&name));
+
+        parent.push_source_file(name, full_node, skiped_ana);
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter;
+
+// # package.json
+
+impl From<crate::processing::erased::ParametrizedCommitProcessor2Handle<NpmProc>>
+    for crate::processing::erased::ParametrizedCommitProcessor2Handle<PackageJsonProc>
+{
+    fn from(value: crate::processing::erased::ParametrizedCommitProcessor2Handle<NpmProc>) -> Self {
+        crate::processing::erased::ParametrizedCommitProcessor2Handle(
+            value.0,
+            std::marker::PhantomData,
+        )
+    }
+}
+struct PackageJsonProcessorHolder(Option<PackageJsonProc>);
+impl Default for PackageJsonProcessorHolder {
+    fn default() -> Self {
+        Self(Some(PackageJsonProc(Parameter, Default::default())))
+    }
+}
+struct PackageJsonProc(Parameter, crate::processing::caches::PackageJson);
+impl crate::processing::erased::Parametrized for PackageJsonProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self.0.iter().position(|x| &x.0 == &t).unwrap_or_else(|| {
+            let l = 0;
+            self.0 = Some(PackageJsonProc(t, Default::default()));
+            l
+        });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+// TODO should not have to impl this trait
+impl crate::processing::erased::CommitProc for PackageJsonProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!()
+    }
+
+    fn prepare_processing(
+        &self,
+        repository: &git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        unimplemented!()
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        unimplemented!()
+    }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        unimplemented!()
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for PackageJsonProc {
+    type Holder = PackageJsonProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for PackageJsonProcessorHolder {
+    type Proc = PackageJsonProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::PackageJson> for PackageJsonProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::PackageJson {
+        &mut self.1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::PackageJson {
+        &self.1
+    }
+}
+impl CacheHolding<crate::processing::caches::PackageJson> for PackageJsonProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::PackageJson {
+        &mut self.0.as_mut().unwrap().1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::PackageJson {
+        &self.0.as_ref().unwrap().1
+    }
+}
+
+// # Npm
+#[derive(Default)]
+pub(crate) struct NpmProcessorHolder(Option<NpmProc>);
+pub(crate) struct NpmProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Ts,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for NpmProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                self.0 = Some(NpmProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+struct PreparedNpmCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedNpmCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedNpmCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = NpmProcessor::<NpmModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<NpmProcessorHolder>();
+        let handle =
+            <NpmProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.0)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+impl crate::processing::erased::CommitProc for NpmProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!("see reason in the java proc")
+    }
+
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedNpmCommitProc {
+            repository,
+            commit_builder,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for NpmProc {
+    type Holder = NpmProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for NpmProcessorHolder {
+    type Proc = NpmProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Ts> for NpmProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Ts {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Ts {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::Ts> for NpmProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Ts {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Ts {
+        &self.0.as_ref().unwrap().cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SimpleStores;
+
+    #[test]
+    fn package_json_workspaces() {
+        let mut stores = SimpleStores::default();
+        let name = b"package.json".into();
+        let text = br#"{
+  "name": "root",
+  "private": true,
+  "workspaces": ["packages/*", "./tools/cli/"]
+}"#;
+        let package = crate::npm::handle_package_json_file(&mut stores, &name, text).unwrap();
+        assert_eq!(package.package.as_deref(), Some("root"));
+        assert_eq!(package.workspaces, vec!["packages/*", "tools/cli"]);
+
+        let text = br#"{
+  "name": "@scope/lib",
+  "workspaces": { "packages": ["libs/*"] }
+}"#;
+        let package = crate::npm::handle_package_json_file(&mut stores, &name, text).unwrap();
+        assert_eq!(package.package.as_deref(), Some("@scope/lib"));
+        assert_eq!(package.workspaces, vec!["libs/*"]);
+
+        let text = br#"{ "version": "1.0.0" }"#;
+        let package = crate::npm::handle_package_json_file(&mut stores, &name, text).unwrap();
+        assert_eq!(package.package, None);
+        assert!(package.workspaces.is_empty());
+    }
+
+    #[test]
+    fn package_json_is_a_child() {
+        use hyper_ast::types::LabelStore;
+        let mut stores = SimpleStores::default();
+        let name = b"package.json".into();
+        let text = br#"{ "name": "lib" }"#;
+        let package = crate::npm::handle_package_json_file(&mut stores, &name, text).unwrap();
+        let mut acc = crate::npm::NpmModuleAcc::new("".to_string());
+        let name = stores.label_store.get_or_insert("package.json");
+        acc.push_package_json(name, package);
+        assert_eq!(acc.children.len(), 1);
+        assert_eq!(
+            acc.status(),
+            enumset::EnumSet::only(crate::npm::SemFlags::IsPackage)
+        );
+    }
+}
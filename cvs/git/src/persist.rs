@@ -0,0 +1,759 @@
+//! Persistence of preprocessed commits on disk, to restart without processing them again.
+//!
+//! A store directory contains:
+//! - `labels`, the label store,
+//! - `nodes.seg`, the node store as an append-only segment,
+//! - `commits`, the processed commits, their roots refer to offsets in the segment,
+//! - `caches`, entries of the caches of processors, they also refer to offsets in the segment.
+//!
+//! See [`hyper_ast::store::nodes::legion::persist`] for the formats of the first two.
+//!
+//! Nodes are loaded on demand: restoring a commit loads its tree,
+//! and before processing a new commit the cache entries of the unchanged git objects of this commit are restored,
+//! so that only changed files are parsed again.
+//! Cache entries are only persisted for the Java and Maven processors.
+//! After a restart, the C++, Rust, Python and TypeScript processors thus parse again every file
+//! of the commits that are not persisted, only their persisted commits are restored as is.
+//!
+//! The reference analysis of Java entries is not persisted either,
+//! restored entries are marked as skipping it, so the directories built on top of them
+//! get a [`hyper_ast::filter::BloomSize::Much`] filter instead of one missing their references.
+//! Generators rebuild the metadata of the restored nodes they build again, eg. shared tokens.
+
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use git2::Oid;
+use hyper_ast::{
+    hashed::SyntaxNodeHashs,
+    store::{
+        defaults::NodeIdentifier,
+        nodes::legion::persist::{Codecs, LabelsFile, PersistError, Segment},
+    },
+};
+
+use crate::{
+    multi_preprocessed::Shard, preprocessed::RepositoryProcessor, processing::RepoConfig, Commit,
+    SimpleStores,
+};
+
+/// Loads the node at an offset of the segment in the stores of a processor
+type Load<'a> = dyn FnMut(&mut Segment, &Codecs, u64, &mut SimpleStores) -> Result<NodeIdentifier, PersistError>
+    + 'a;
+
+pub struct PersistedStore {
+    codecs: Codecs,
+    labels: LabelsFile,
+    nodes: Segment,
+    commits_file: File,
+    commits: HashMap<(RepoConfig, Oid), PersistedCommit>,
+    caches_file: File,
+    /// offsets of the nodes of persisted cache entries, names are empty for caches keyed by oid only
+    caches: HashMap<(CacheKind, Oid, Vec<u8>), u64>,
+}
+
+struct PersistedCommit {
+    parents: Vec<Oid>,
+    tree_oid: Oid,
+    /// offset of the root in the segment
+    root: u64,
+    processing_time: u128,
+    memory_used: isize,
+}
+
+/// Caches of processors that are persisted
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum CacheKind {
+    /// directories and modules of the maven processor, by tree oid
+    Maven,
+    /// directories and files of the java processor, by oid and name
+    Java,
+}
+
+impl PersistedStore {
+    /// Opens or creates the store in `dir`, loading its labels in `stores`.
+    ///
+    /// `stores` should be empty, nodes are loaded lazily when restoring commits.
+    pub fn open(dir: &Path, stores: &mut SimpleStores) -> Result<Self, PersistError> {
+        std::fs::create_dir_all(dir)?;
+        let codecs = codecs();
+        let labels = LabelsFile::open(&dir.join("labels"), &mut stores.label_store)?;
+        let nodes = Segment::open(&dir.join("nodes.seg"), &codecs)?;
+        let commits_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(dir.join("commits"))?;
+        let commits = read_commits(&commits_file, nodes.end())?;
+        log::info!("{} persisted commits in {:?}", commits.len(), dir);
+        let caches_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(dir.join("caches"))?;
+        let caches = read_caches(&caches_file, nodes.end())?;
+        Ok(Self {
+            codecs,
+            labels,
+            nodes,
+            commits_file,
+            commits,
+            caches_file,
+            caches,
+        })
+    }
+
+    /// Loads the nodes of a persisted commit in `stores`,
+    /// returns None if the commit was not persisted.
+    ///
+    /// The commit should then be given to its processor, see [`crate::processing::erased::CommitProc::insert_commit`].
+    pub fn restore(
+        &mut self,
+        stores: &mut SimpleStores,
+        kind: RepoConfig,
+        commit_oid: Oid,
+    ) -> Result<Option<Commit>, PersistError> {
+        let Some(c) = self.commits.get(&(kind, commit_oid)) else {
+            return Ok(None);
+        };
+        let ast_root = self
+            .nodes
+            .load(&mut stores.node_store, &self.codecs, c.root)?;
+        Ok(Some(Commit {
+            parents: c.parents.clone(),
+            processing_time: c.processing_time,
+            memory_used: c.memory_used.into(),
+            ast_root,
+            tree_oid: c.tree_oid,
+        }))
+    }
+
+    /// Restores the persisted cache entries of the git objects of a commit about to be processed.
+    ///
+    /// The tree of the commit is walked without descending in restored objects,
+    /// thus only the nodes of unchanged subtrees are loaded.
+    pub fn restore_caches(
+        &mut self,
+        processor: &mut RepositoryProcessor,
+        repository: &git2::Repository,
+        kind: RepoConfig,
+        commit_oid: Oid,
+    ) -> Result<(), PersistError> {
+        let mut load = |nodes: &mut Segment, codecs: &Codecs, offset, stores: &mut SimpleStores| {
+            nodes.load(&mut stores.node_store, codecs, offset)
+        };
+        self.restore_caches_with(processor, &mut load, repository, kind, commit_oid)
+    }
+
+    /// Same as [`Self::restore_caches`] for a commit about to be processed in `shard`.
+    ///
+    /// Nodes are loaded in `main`, the stores of this persisted store, then copied to the shard.
+    pub fn restore_caches_in_shard(
+        &mut self,
+        main: &mut SimpleStores,
+        shard: &mut Shard,
+        repository: &git2::Repository,
+        kind: RepoConfig,
+        commit_oid: Oid,
+    ) -> Result<(), PersistError> {
+        let Shard {
+            processor,
+            restored,
+            published,
+            ..
+        } = shard;
+        let mut load = |nodes: &mut Segment, codecs: &Codecs, offset, stores: &mut SimpleStores| {
+            let id = nodes.load(&mut main.node_store, codecs, offset)?;
+            let copy = restored.copy(
+                codecs,
+                (&main.node_store, &main.label_store),
+                (&mut stores.node_store, &mut stores.label_store),
+                id,
+            )?;
+            published.insert(copy, id);
+            Ok(copy)
+        };
+        self.restore_caches_with(processor, &mut load, repository, kind, commit_oid)
+    }
+
+    fn restore_caches_with(
+        &mut self,
+        processor: &mut RepositoryProcessor,
+        load: &mut Load,
+        repository: &git2::Repository,
+        kind: RepoConfig,
+        commit_oid: Oid,
+    ) -> Result<(), PersistError> {
+        // other processors do not persist their caches, see the module documentation
+        if kind != RepoConfig::JavaMaven || self.caches.is_empty() {
+            return Ok(());
+        }
+        let tree = repository
+            .find_commit(commit_oid)
+            .and_then(|c| c.tree())
+            .map_err(git_error)?;
+        let mut restored = 0;
+        if self.restore_entry(processor, load, tree.id(), b"")? {
+            restored += 1;
+        } else {
+            let mut stack = vec![tree];
+            while let Some(tree) = stack.pop() {
+                for entry in tree.iter() {
+                    if self.restore_entry(processor, load, entry.id(), entry.name_bytes())? {
+                        restored += 1;
+                    } else if entry.kind() == Some(git2::ObjectType::Tree) {
+                        stack.push(repository.find_tree(entry.id()).map_err(git_error)?);
+                    }
+                }
+            }
+        }
+        log::info!("{} cache entries restored for {}", restored, commit_oid);
+        Ok(())
+    }
+
+    /// Returns true if the object is in the caches of `processor`, restoring it if needed.
+    #[allow(unused_variables, unused_mut)]
+    fn restore_entry(
+        &mut self,
+        processor: &mut RepositoryProcessor,
+        load: &mut Load,
+        oid: Oid,
+        name: &[u8],
+    ) -> Result<bool, PersistError> {
+        let mut found = false;
+        #[cfg(all(feature = "maven", feature = "java"))]
+        {
+            use crate::java_processor::{JavaProc, JavaProcessorHolder};
+            use crate::maven_processor::{MavenProc, MavenProcessorHolder};
+            use crate::processing::{
+                erased::{CommitProcExt, ParametrizedCommitProc2},
+                CacheHolding,
+            };
+            use hyper_ast::cyclomatic::Mcc;
+            use hyper_ast_gen_ts_java::types::Type;
+
+            let h = processor
+                .processing_systems
+                .mut_or_default::<MavenProcessorHolder>();
+            let handle = MavenProc::register_param(h, crate::maven_processor::Parameter);
+            let cache = &h.with_parameters(handle.0).get_caches().object_map;
+            found = cache.contains_key(&oid);
+            if !found {
+                if let Some(&offset) = self.caches.get(&(CacheKind::Maven, oid, vec![])) {
+                    let stores = &mut processor.main_stores;
+                    let id = load(&mut self.nodes, &self.codecs, offset, stores)?;
+                    let status = stores
+                        .node_store
+                        .resolve(id)
+                        .get_component::<enumset::EnumSet<crate::maven::SemFlags>>()
+                        .map_or(Default::default(), |x| *x);
+                    let md = crate::maven::MD {
+                        metrics: metrics(stores, id),
+                        ana: crate::maven::MavenPartialAnalysis::new(),
+                        status,
+                    };
+                    let h = processor
+                        .processing_systems
+                        .mut_or_default::<MavenProcessorHolder>();
+                    h.with_parameters_mut(handle.0)
+                        .get_caches_mut()
+                        .object_map
+                        .insert(oid, (id, md));
+                    found = true;
+                }
+            }
+
+            let key = (oid, crate::processing::ObjectName::from(name));
+            let h = processor
+                .processing_systems
+                .mut_or_default::<JavaProcessorHolder>();
+            let handle = JavaProc::register_param(h, crate::java_processor::Parameter);
+            if h.with_parameters(handle.0)
+                .get_caches()
+                .object_map
+                .contains_key(&key)
+            {
+                found = true;
+            } else if let Some(&offset) = self.caches.get(&(CacheKind::Java, oid, name.to_vec())) {
+                let stores = &mut processor.main_stores;
+                let id = load(&mut self.nodes, &self.codecs, offset, stores)?;
+                let mcc = stores
+                    .node_store
+                    .resolve(id)
+                    .get_component::<Mcc>()
+                    .map_or(Mcc::new(&Type::Directory), |x| x.clone());
+                let local = hyper_ast_gen_ts_java::legion_with_refs::Local {
+                    compressed_node: id,
+                    metrics: metrics(stores, id),
+                    ana: None,
+                    mcc,
+                };
+                // the analysis is not persisted, thus it is skipped in the parents of the entry
+                let h = processor
+                    .processing_systems
+                    .mut_or_default::<JavaProcessorHolder>();
+                h.with_parameters_mut(handle.0)
+                    .get_caches_mut()
+                    .object_map
+                    .insert(key, (local, true));
+                found = true;
+            }
+        }
+        Ok(found)
+    }
+
+    /// Appends a freshly processed commit to the store, with the new entries of the caches of `processor`.
+    ///
+    /// The nodes of the commit are in `stores`,
+    /// `ids` gives the node in `stores` of a node in the caches, see [`Shard`] for processors with their own stores.
+    pub fn persist(
+        &mut self,
+        stores: &SimpleStores,
+        processor: &RepositoryProcessor,
+        ids: &dyn Fn(NodeIdentifier) -> Option<NodeIdentifier>,
+        kind: RepoConfig,
+        commit_oid: Oid,
+        commit: &Commit,
+    ) -> Result<(), PersistError> {
+        if self.commits.contains_key(&(kind, commit_oid)) {
+            return Ok(());
+        }
+        // labels, then nodes, then caches and the commit, so that persisted data only refer to persisted data
+        self.labels.persist(&stores.label_store)?;
+        let root = self
+            .nodes
+            .persist(&stores.node_store, &self.codecs, commit.ast_root)?;
+        self.persist_caches(processor, ids)?;
+        let c = PersistedCommit {
+            parents: commit.parents.clone(),
+            tree_oid: commit.tree_oid,
+            root,
+            processing_time: commit.processing_time,
+            memory_used: (&commit.memory_used).into(),
+        };
+        let mut file = self.commits_file.try_clone()?;
+        file.seek(SeekFrom::End(0))?;
+        let mut writer = BufWriter::new(file);
+        write_commit(&mut writer, kind, commit_oid, &c)?;
+        writer.flush()?;
+        writer.get_ref().sync_data()?;
+        self.commits.insert((kind, commit_oid), c);
+        Ok(())
+    }
+
+    /// Appends the cache entries that refer to persisted nodes and are not persisted yet
+    #[allow(unused_mut)]
+    #[allow(unused_variables)]
+    fn persist_caches(
+        &mut self,
+        processor: &RepositoryProcessor,
+        ids: &dyn Fn(NodeIdentifier) -> Option<NodeIdentifier>,
+    ) -> Result<(), PersistError> {
+        let mut new = vec![];
+        #[cfg(all(feature = "maven", feature = "java"))]
+        {
+            use crate::processing::CacheHolding;
+            let mut add = |kind, oid: Oid, name: &[u8], id| {
+                let key = (kind, oid, name.to_vec());
+                if self.caches.contains_key(&key) {
+                    return;
+                }
+                if let Some(offset) = ids(id).and_then(|id| self.nodes.offset(id)) {
+                    new.push((key, offset));
+                }
+            };
+            let systems = &processor.processing_systems;
+            if let Some(h) = systems.get::<crate::maven_processor::MavenProcessorHolder>() {
+                for (oid, (id, _)) in &h.get_caches().object_map {
+                    add(CacheKind::Maven, *oid, b"", *id);
+                }
+            }
+            if let Some(h) = systems.get::<crate::java_processor::JavaProcessorHolder>() {
+                for ((oid, name), (local, _)) in &h.get_caches().object_map {
+                    add(
+                        CacheKind::Java,
+                        *oid,
+                        name.as_bytes(),
+                        local.compressed_node,
+                    );
+                }
+            }
+        }
+        if new.is_empty() {
+            return Ok(());
+        }
+        let mut file = self.caches_file.try_clone()?;
+        file.seek(SeekFrom::End(0))?;
+        let mut writer = BufWriter::new(file);
+        for ((kind, oid, name), offset) in &new {
+            write_cache_entry(&mut writer, *kind, *oid, name, *offset)?;
+        }
+        writer.flush()?;
+        writer.get_ref().sync_data()?;
+        self.caches.extend(new);
+        Ok(())
+    }
+}
+
+fn git_error(e: git2::Error) -> PersistError {
+    PersistError::Io(io::Error::new(io::ErrorKind::Other, e))
+}
+
+/// Metrics of a persisted node, as computed by the processor that built it
+pub(crate) fn metrics(stores: &SimpleStores, id: NodeIdentifier) -> crate::DefaultMetrics {
+    use hyper_ast::types::WithStats;
+    let node = stores.node_store.resolve(id);
+    crate::DefaultMetrics {
+        hashs: node
+            .get_component::<SyntaxNodeHashs<u32>>()
+            .map_or(Default::default(), |x| x.clone()),
+        size: node.size() as u32,
+        height: node.height() as u32,
+        size_no_spaces: node.size_no_spaces() as u32,
+    }
+}
+
+/// Codecs of all the components produced by the processors of this crate
+///
+/// Type enums and flags are persisted as raw values,
+/// so their codecs are named after their variants and a store written with other variants is refused.
+pub(crate) fn codecs() -> Codecs {
+    let mut codecs = Codecs::new();
+    // SAFETY: type enums are repr(u16) without explicit discriminants and end with ERROR, flags are bit sets
+    unsafe {
+        #[cfg(feature = "java")]
+        {
+            use hyper_ast_gen_ts_java::types::Type;
+            codecs.register_type_enum("java::Type", Type::ERROR);
+        }
+        #[cfg(feature = "cpp")]
+        {
+            use hyper_ast_gen_ts_cpp::types::Type;
+            codecs.register_type_enum("cpp::Type", Type::ERROR);
+        }
+        #[cfg(feature = "rust")]
+        {
+            use hyper_ast_gen_ts_rust::types::Type;
+            codecs.register_type_enum("rust::Type", Type::ERROR);
+        }
+        #[cfg(feature = "python")]
+        {
+            use hyper_ast_gen_ts_python::types::Type;
+            codecs.register_type_enum("python::Type", Type::ERROR);
+        }
+        #[cfg(feature = "ts")]
+        {
+            use hyper_ast_gen_ts_ts::types::Type;
+            codecs.register_type_enum("ts::Type", Type::ERROR);
+        }
+        #[cfg(feature = "maven")]
+        {
+            use hyper_ast_gen_ts_xml::types::Type;
+            codecs.register_type_enum("xml::Type", Type::ERROR);
+        }
+        codecs.register_type_enum("blob::Type", hyper_ast::blob::types::Type::Blob);
+        register_flags::<crate::maven::SemFlags>(&mut codecs, "maven::SemFlags");
+        register_flags::<crate::make::SemFlags>(&mut codecs, "make::SemFlags");
+        #[cfg(feature = "cargo")]
+        register_flags::<crate::cargo::SemFlags>(&mut codecs, "cargo::SemFlags");
+        #[cfg(feature = "python")]
+        register_flags::<crate::python::SemFlags>(&mut codecs, "python::SemFlags");
+        #[cfg(feature = "npm")]
+        register_flags::<crate::npm::SemFlags>(&mut codecs, "npm::SemFlags");
+    }
+    codecs
+}
+
+/// # Safety
+/// the representation of `EnumSet<T>` must be a plain bit set
+unsafe fn register_flags<T: enumset::EnumSetType + std::fmt::Debug + 'static>(
+    codecs: &mut Codecs,
+    name: &str,
+) where
+    enumset::EnumSet<T>: Send + Sync,
+{
+    let variants = enumset::EnumSet::<T>::all()
+        .iter()
+        .map(|x| format!("{:?}", x));
+    codecs.register_pod_with_layout::<enumset::EnumSet<T>>(name, variants);
+}
+
+fn config_tag(kind: RepoConfig) -> u8 {
+    match kind {
+        RepoConfig::CppMake => 0,
+        RepoConfig::JavaMaven => 1,
+        RepoConfig::TsNpm => 2,
+        RepoConfig::Any => 3,
+        RepoConfig::RustCargo => 4,
+        RepoConfig::Python => 5,
+    }
+}
+
+fn config_from_tag(tag: u8) -> Option<RepoConfig> {
+    Some(match tag {
+        0 => RepoConfig::CppMake,
+        1 => RepoConfig::JavaMaven,
+        2 => RepoConfig::TsNpm,
+        3 => RepoConfig::Any,
+        4 => RepoConfig::RustCargo,
+        5 => RepoConfig::Python,
+        _ => return None,
+    })
+}
+
+/// `[u8 config][oid][tree oid][u64 root][u128 processing time][i64 memory used][u16 count][parents oids]`
+fn write_commit(
+    w: &mut impl Write,
+    kind: RepoConfig,
+    commit_oid: Oid,
+    c: &PersistedCommit,
+) -> io::Result<()> {
+    w.write_all(&[config_tag(kind)])?;
+    w.write_all(commit_oid.as_bytes())?;
+    w.write_all(c.tree_oid.as_bytes())?;
+    w.write_all(&c.root.to_le_bytes())?;
+    w.write_all(&c.processing_time.to_le_bytes())?;
+    w.write_all(&(c.memory_used as i64).to_le_bytes())?;
+    w.write_all(&(c.parents.len() as u16).to_le_bytes())?;
+    for p in &c.parents {
+        w.write_all(p.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_commit(r: &mut impl Read) -> io::Result<(RepoConfig, Oid, PersistedCommit)> {
+    fn read<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+        let mut x = [0u8; N];
+        r.read_exact(&mut x)?;
+        Ok(x)
+    }
+    fn oid(r: &mut impl Read) -> io::Result<Oid> {
+        Oid::from_bytes(&read::<20>(r)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    let [tag] = read::<1>(r)?;
+    let kind = config_from_tag(tag)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unknown repository config"))?;
+    let commit_oid = oid(r)?;
+    let tree_oid = oid(r)?;
+    let root = u64::from_le_bytes(read(r)?);
+    let processing_time = u128::from_le_bytes(read(r)?);
+    let memory_used = i64::from_le_bytes(read(r)?) as isize;
+    let count = u16::from_le_bytes(read(r)?);
+    let parents = (0..count).map(|_| oid(r)).collect::<io::Result<_>>()?;
+    Ok((
+        kind,
+        commit_oid,
+        PersistedCommit {
+            parents,
+            tree_oid,
+            root,
+            processing_time,
+            memory_used,
+        },
+    ))
+}
+
+fn read_commits(
+    file: &File,
+    segment_end: u64,
+) -> Result<HashMap<(RepoConfig, Oid), PersistedCommit>, PersistError> {
+    let len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut commits = HashMap::default();
+    let mut end = 0;
+    loop {
+        match read_commit(&mut reader) {
+            Ok((kind, oid, c)) => {
+                end = reader.stream_position()?;
+                if c.root >= segment_end {
+                    log::warn!("the root of persisted commit {} is missing", oid);
+                    continue;
+                }
+                commits.insert((kind, oid), c);
+            }
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if end < len {
+        log::warn!(
+            "truncating partially written commits from {} to {}",
+            len,
+            end
+        );
+        file.set_len(end)?;
+    }
+    Ok(commits)
+}
+
+/// `[u8 kind][oid][u16 len][name][u64 offset]`
+fn write_cache_entry(
+    w: &mut impl Write,
+    kind: CacheKind,
+    oid: Oid,
+    name: &[u8],
+    offset: u64,
+) -> io::Result<()> {
+    let tag = match kind {
+        CacheKind::Maven => 0u8,
+        CacheKind::Java => 1,
+    };
+    w.write_all(&[tag])?;
+    w.write_all(oid.as_bytes())?;
+    w.write_all(&(name.len() as u16).to_le_bytes())?;
+    w.write_all(name)?;
+    w.write_all(&offset.to_le_bytes())?;
+    Ok(())
+}
+
+fn read_cache_entry(r: &mut impl Read) -> io::Result<((CacheKind, Oid, Vec<u8>), u64)> {
+    let mut tag = [0u8];
+    r.read_exact(&mut tag)?;
+    let kind = match tag[0] {
+        0 => CacheKind::Maven,
+        1 => CacheKind::Java,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "unknown cache")),
+    };
+    let mut oid = [0u8; 20];
+    r.read_exact(&mut oid)?;
+    let oid = Oid::from_bytes(&oid).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut len = [0u8; 2];
+    r.read_exact(&mut len)?;
+    let mut name = vec![0u8; u16::from_le_bytes(len) as usize];
+    r.read_exact(&mut name)?;
+    let mut offset = [0u8; 8];
+    r.read_exact(&mut offset)?;
+    Ok(((kind, oid, name), u64::from_le_bytes(offset)))
+}
+
+fn read_caches(
+    file: &File,
+    segment_end: u64,
+) -> Result<HashMap<(CacheKind, Oid, Vec<u8>), u64>, PersistError> {
+    let len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut caches = HashMap::default();
+    let mut end = 0;
+    loop {
+        match read_cache_entry(&mut reader) {
+            Ok((key, offset)) => {
+                end = reader.stream_position()?;
+                if offset < segment_end {
+                    caches.insert(key, offset);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if end < len {
+        log::warn!(
+            "truncating partially written cache entries from {} to {}",
+            len,
+            end
+        );
+        file.set_len(end)?;
+    }
+    Ok(caches)
+}
+
+#[cfg(all(test, feature = "maven_java"))]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{multi_preprocessed::PreProcessedRepositories, processing::ConfiguredRepo2};
+
+    fn commit(repository: &git2::Repository, files: &[(&str, &str)]) -> Oid {
+        // builds the nested trees bottom up, files are given by their full path
+        fn tree(repository: &git2::Repository, files: &[(&str, &str)]) -> Oid {
+            let mut builder = repository.treebuilder(None).unwrap();
+            let mut dirs: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+            for (path, text) in files {
+                match path.split_once('/') {
+                    Some((dir, rest)) => dirs.entry(dir).or_default().push((rest, text)),
+                    None => {
+                        let blob = repository.blob(text.as_bytes()).unwrap();
+                        builder.insert(path, blob, 0o100644).unwrap();
+                    }
+                }
+            }
+            for (dir, files) in dirs {
+                let oid = tree(repository, &files);
+                builder.insert(dir, oid, 0o040000).unwrap();
+            }
+            builder.write().unwrap()
+        }
+        let tree = repository.find_tree(tree(repository, files)).unwrap();
+        let signature = git2::Signature::now("a", "a@example.com").unwrap();
+        repository
+            .commit(None, &signature, &signature, "", &tree, &[])
+            .unwrap()
+    }
+
+    /// Processes a commit like a freshly started server would,
+    /// returns the size, height and syntax hash of its root.
+    fn process(store: Option<&Path>, repository: &Path, oid: Oid) -> (u32, u32, u32) {
+        let mut repositories = match store {
+            Some(store) => PreProcessedRepositories::with_persistence(store).unwrap(),
+            None => PreProcessedRepositories::default(),
+        };
+        let spec = crate::git::Forge::Github.repo("example", "persist");
+        let config = repositories
+            .register_config(spec.clone(), RepoConfig::JavaMaven)
+            .config;
+        let repository = ConfiguredRepo2 {
+            spec,
+            repo: git2::Repository::open(repository).unwrap(),
+            config,
+        };
+        repositories.ensure_processed(&repository, oid);
+        let root = repositories.get_commit(&config, &oid).unwrap().ast_root;
+        let m = metrics(&repositories.processor.main_stores, root);
+        (m.size, m.height, m.hashs.syntax)
+    }
+
+    #[test]
+    fn warm_restart() {
+        let dir =
+            std::env::temp_dir().join(format!("hyperast_warm_restart_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (path, store) = (dir.join("repo"), dir.join("store"));
+        let repository = git2::Repository::init_bare(&path).unwrap();
+        let pom = "<project>\n  <artifactId>app</artifactId>\n</project>\n";
+        let a = "package app;\n\nclass A {\n    int f() {\n        return 1;\n    }\n}\n";
+        let b = "package app;\n\nclass B {\n}\n";
+        let n = commit(
+            &repository,
+            &[
+                ("pom.xml", pom),
+                ("src/main/java/app/A.java", a),
+                ("src/main/java/app/B.java", b),
+            ],
+        );
+        // B now shares most of its tokens with the unchanged A
+        let b = "package app;\n\nclass B {\n    int g() {\n        return 1;\n    }\n}\n";
+        let n1 = commit(
+            &repository,
+            &[
+                ("pom.xml", pom),
+                ("src/main/java/app/A.java", a),
+                ("src/main/java/app/B.java", b),
+            ],
+        );
+
+        let built = process(Some(&store), &path, n);
+        // restarts, restoring the persisted commit
+        assert_eq!(built, process(Some(&store), &path, n));
+        // restarts, then builds the next commit on top of the restored caches
+        let warm = process(Some(&store), &path, n1);
+        assert_eq!(process(None, &path, n1), warm);
+        // the next commit was persisted in turn
+        assert_eq!(warm, process(Some(&store), &path, n1));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
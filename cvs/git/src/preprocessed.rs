@@ -221,20 +221,39 @@ impl RepositoryProcessor {
             .take(limit)
             .map(|oid| {
                 let oid = oid.unwrap();
-                let builder = crate::preprocessed::CommitBuilder::start(&repository.repo, oid);
-                let commit_processor = self
-                    .processing_systems
-                    .by_id_mut(&repository.config.0)
-                    .unwrap()
-                    .get_mut(repository.config.1);
-                let _id = commit_processor
-                    .prepare_processing(&repository.repo, builder)
-                    .process(self);
+                self.process_commit(repository, oid);
                 oid
             })
             .collect();
         Ok(r)
     }
+
+    /// Process a single commit with the configuration of `repository`
+    pub fn process_commit(
+        &mut self,
+        repository: &ConfiguredRepo2,
+        commit_oid: git2::Oid,
+    ) -> NodeIdentifier {
+        self.process_commit_with(&repository.repo, &repository.config, commit_oid)
+    }
+
+    /// Process a single commit of `repository` with `config`, a configuration registered in this processor
+    pub fn process_commit_with(
+        &mut self,
+        repository: &Repository,
+        config: &crate::processing::erased::ParametrizedCommitProcessorHandle,
+        commit_oid: git2::Oid,
+    ) -> NodeIdentifier {
+        let builder = crate::preprocessed::CommitBuilder::start(repository, commit_oid);
+        let commit_processor = self
+            .processing_systems
+            .by_id_mut(&config.0)
+            .unwrap()
+            .get_mut(config.1);
+        commit_processor
+            .prepare_processing(repository, builder)
+            .process(self)
+    }
 }
 #[cfg(feature = "maven_java")]
 impl PreProcessedRepository {
@@ -711,21 +730,24 @@ impl CommitProcessor<file_sys::Any> for RepositoryProcessor {
     type Module = (NodeIdentifier, DefaultMetrics);
     fn handle_module<'a, 'b, const RMS: bool>(
         &mut self,
-        _repository: &'a Repository,
-        _dir_path: &'b mut Peekable<Components<'b>>,
-        _name: &[u8],
-        _oid: git2::Oid,
+        repository: &'a Repository,
+        dir_path: &'b mut Peekable<Components<'b>>,
+        name: &[u8],
+        oid: git2::Oid,
     ) -> Self::Module {
-        todo!("still not sure how to dispatch")
+        crate::any_processor::AnyProcessor::<crate::any::AnyModuleAcc>::new(
+            repository,
+            self,
+            dir_path,
+            name,
+            oid,
+            Default::default(),
+        )
+        .process()
     }
 }
 
-/// plan to work on all languges of the family of typesript ie. ts, js, tsx, jsx
-/// - [ ] ts
-/// - [ ] js
-/// - [ ] tsx
-/// - [ ] jsx
-/// - [ ] d.ts
+/// works on all languges of the family of typesript ie. ts, js, tsx, jsx, d.ts
 /// - [ ] various transpiler configs
 ///   - [ ] babel
 ///   - [ ] ts
@@ -734,17 +756,16 @@ impl CommitProcessor<file_sys::Npm> for RepositoryProcessor {
     type Module = (NodeIdentifier, DefaultMetrics);
     fn handle_module<'a, 'b, const RMS: bool>(
         &mut self,
-        _repository: &'a Repository,
-        _dir_path: &'b mut Peekable<Components<'b>>,
-        _name: &[u8],
-        _oid: git2::Oid,
+        repository: &'a Repository,
+        dir_path: &'b mut Peekable<Components<'b>>,
+        name: &[u8],
+        oid: git2::Oid,
     ) -> Self::Module {
-        todo!("need to implement NpmProcessor")
-        // let root_full_node = NpmProcessor::<RMS, FFWD, NpmModuleAcc>::new(repository, self, dir_path, name, oid)
-        //     .process();
-        // // self.object_map_make
-        // //     .insert(commit_oid, root_full_node.clone());
-        // root_full_node.0
+        let root_full_node = crate::npm_processor::NpmProcessor::<crate::npm::NpmModuleAcc>::new(
+            repository, self, dir_path, name, oid,
+        )
+        .process();
+        (root_full_node.0, root_full_node.1.metrics)
     }
 }
 
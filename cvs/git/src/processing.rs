@@ -8,6 +8,8 @@ pub enum BuildSystem {
     Maven,
     Make,
     Npm,
+    Cargo,
+    Python,
     None,
 }
 
@@ -16,12 +18,16 @@ enum Language {
     Cpp,
     Ts,
     Xml,
+    Rust,
+    Python,
 }
 
 pub enum ProcessingConfig<P> {
     JavaMaven { limit: usize, dir_path: P },
     CppMake { limit: usize, dir_path: P },
     TsNpm { limit: usize, dir_path: P },
+    RustCargo { limit: usize, dir_path: P },
+    Python { limit: usize, dir_path: P },
     Any { limit: usize, dir_path: P },
 }
 
@@ -32,9 +38,36 @@ pub enum RepoConfig {
     CppMake,
     JavaMaven,
     TsNpm,
+    RustCargo,
+    Python,
     Any,
 }
 
+impl RepoConfig {
+    /// Guesses the config of a repository from the names of the files at its root,
+    /// eg. a `pom.xml` for a Java project built with Maven.
+    ///
+    /// Without any known build file, files are handled according to their extension, see [`RepoConfig::Any`].
+    pub fn infer<'a>(root_files: impl IntoIterator<Item = &'a str>) -> Self {
+        // the first build file wins, eg. a Maven project with a Makefile for its native parts
+        const BUILD_FILES: &[(&str, RepoConfig)] = &[
+            ("pom.xml", RepoConfig::JavaMaven),
+            ("Cargo.toml", RepoConfig::RustCargo),
+            ("package.json", RepoConfig::TsNpm),
+            ("Makefile", RepoConfig::CppMake),
+            ("makefile", RepoConfig::CppMake),
+            ("GNUmakefile", RepoConfig::CppMake),
+            ("pyproject.toml", RepoConfig::Python),
+            ("setup.py", RepoConfig::Python),
+        ];
+        let root_files: Vec<&str> = root_files.into_iter().collect();
+        BUILD_FILES
+            .iter()
+            .find(|(name, _)| root_files.contains(name))
+            .map_or(RepoConfig::Any, |(_, config)| *config)
+    }
+}
+
 impl std::str::FromStr for RepoConfig {
     type Err = String;
 
@@ -48,6 +81,10 @@ impl std::str::FromStr for RepoConfig {
             "javascript" => Self::TsNpm,
             "Ts" => Self::TsNpm,
             "ts" => Self::TsNpm,
+            "Rust" => Self::RustCargo,
+            "rust" => Self::RustCargo,
+            "Python" => Self::Python,
+            "python" => Self::Python,
             "any" => Self::Any,
             x => return Err(format!("'{}' is not anvailable config", x))
         })
@@ -65,8 +102,22 @@ impl From<&RepoConfig> for ProcessingConfig<&'static str> {
                 limit: 3,
                 dir_path: "",
             },
-            RepoConfig::TsNpm => todo!(),
-            RepoConfig::Any => todo!(),
+            RepoConfig::RustCargo => Self::RustCargo {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::Python => Self::Python {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::TsNpm => Self::TsNpm {
+                limit: 3,
+                dir_path: "",
+            },
+            RepoConfig::Any => Self::Any {
+                limit: 3,
+                dir_path: "",
+            },
         }
     }
 }
@@ -341,6 +392,146 @@ pub(crate) mod caches {
         }
     }
 
+    #[derive(Default)]
+    pub struct Rust {
+        pub(crate) md_cache: hyper_ast_gen_ts_rust::legion::MDCache,
+        pub object_map: NamedMap<(hyper_ast_gen_ts_rust::legion::Local, IsSkippedAna)>,
+    }
+
+    impl super::ObjectMapper for Rust {
+        type K = (git2::Oid, ObjectName);
+
+        type V = (hyper_ast_gen_ts_rust::legion::Local, IsSkippedAna);
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Cargo {
+        pub object_map: OidMap<(NodeIdentifier, crate::cargo::MD)>,
+    }
+
+    #[derive(Default)]
+    pub struct CargoToml {
+        pub object_map: OidMap<crate::cargo::CargoToml>,
+    }
+
+    impl super::ObjectMapper for CargoToml {
+        type K = git2::Oid;
+
+        type V = crate::cargo::CargoToml;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Python {
+        pub(crate) md_cache: hyper_ast_gen_ts_python::legion::MDCache,
+        pub object_map: NamedMap<(hyper_ast_gen_ts_python::legion::Local, IsSkippedAna)>,
+        /// directories, with their package markers
+        pub dir_map: NamedMap<(NodeIdentifier, crate::python::MD)>,
+    }
+
+    impl super::ObjectMapper for Python {
+        type K = (git2::Oid, ObjectName);
+
+        type V = (hyper_ast_gen_ts_python::legion::Local, IsSkippedAna);
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[derive(Default)]
+    pub struct PyProject {
+        pub object_map: OidMap<crate::python::PyProject>,
+    }
+
+    impl super::ObjectMapper for PyProject {
+        type K = git2::Oid;
+
+        type V = crate::python::PyProject;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Ts {
+        pub(crate) md_cache: hyper_ast_gen_ts_ts::legion::MDCache,
+        pub object_map: NamedMap<(hyper_ast_gen_ts_ts::legion::Local, IsSkippedAna)>,
+        /// directories, with their package.json if any
+        pub dir_map: NamedMap<(NodeIdentifier, crate::npm::MD)>,
+    }
+
+    impl super::ObjectMapper for Ts {
+        type K = (git2::Oid, ObjectName);
+
+        type V = (hyper_ast_gen_ts_ts::legion::Local, IsSkippedAna);
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    #[derive(Default)]
+    pub struct PackageJson {
+        pub object_map: OidMap<crate::npm::PackageJson>,
+    }
+
+    impl super::ObjectMapper for PackageJson {
+        type K = git2::Oid;
+
+        type V = crate::npm::PackageJson;
+
+        fn get(&self, key: &Self::K) -> Option<&Self::V> {
+            self.object_map.get(key)
+        }
+
+        fn insert(&mut self, key: Self::K, value: Self::V) -> Option<Self::V> {
+            self.object_map.insert(key, value)
+        }
+    }
+
+    /// Caches of the any processor,
+    /// it holds the metadata caches of each generator it can dispatch to
+    #[derive(Default)]
+    pub struct Any {
+        pub(crate) java_md_cache: hyper_ast_gen_ts_java::legion_with_refs::MDCache,
+        pub(crate) cpp_md_cache: hyper_ast_gen_ts_cpp::legion::MDCache,
+        pub(crate) ts_md_cache: hyper_ast_gen_ts_ts::legion::MDCache,
+        pub(crate) rust_md_cache: hyper_ast_gen_ts_rust::legion::MDCache,
+        pub(crate) python_md_cache: hyper_ast_gen_ts_python::legion::MDCache,
+        /// files, the name matters as it selects the generator
+        pub object_map: NamedMap<(NodeIdentifier, crate::DefaultMetrics)>,
+        pub dir_map: NamedMap<(NodeIdentifier, crate::DefaultMetrics)>,
+    }
+
     // // any
     // pub object_map_any: OidMap<(NodeIdentifier, DefaultMetrics)>,
     // // maven
@@ -382,6 +573,10 @@ pub mod file_sys {
     /// The default file system, directories and files
     pub struct Any;
 
+    impl CachesHolding for Any {
+        type Caches = super::caches::Any;
+    }
+
     /// The maven scheme https://maven.apache.org/guides/introduction/introduction-to-the-standard-directory-layout.html ,
     /// made of nested maven modules.
     /// Each maven module has a config file (often a pom.xml),
@@ -444,7 +639,10 @@ pub mod file_sys {
 
     impl super::InFiles for MakeFile {
         fn matches(name: &ObjectName) -> bool {
-            name.0.eq(b"Makefile")
+            matches!(
+                &name.0[..],
+                b"Makefile" | b"makefile" | b"GNUmakefile" | b"CMakeLists.txt"
+            )
         }
     }
 
@@ -471,6 +669,103 @@ pub mod file_sys {
     /// or is a collection of packages that contains a packages/ directory where each package is located
     #[cfg(feature = "npm")]
     pub struct Npm;
+
+    #[cfg(feature = "npm")]
+    pub struct PackageJson;
+
+    impl CachesHolding for PackageJson {
+        type Caches = super::caches::PackageJson;
+    }
+
+    impl super::InFiles for PackageJson {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"package.json")
+        }
+    }
+
+    /// typescript and javascript files, with or without jsx
+    #[cfg(feature = "ts")]
+    pub struct Ts;
+
+    impl CachesHolding for Ts {
+        type Caches = super::caches::Ts;
+    }
+
+    impl super::InFiles for Ts {
+        fn matches(name: &ObjectName) -> bool {
+            [".ts", ".tsx", ".mts", ".cts", ".js", ".jsx", ".mjs", ".cjs"]
+                .iter()
+                .any(|x| name.0.ends_with(x.as_bytes()))
+        }
+    }
+
+    /// The cargo scheme https://doc.rust-lang.org/cargo/guide/project-layout.html ,
+    /// made of crates, each with a Cargo.toml,
+    /// a src/ directory that contains production code,
+    /// and tests/, benches/ and examples/ directories.
+    /// A workspace is a Cargo.toml that lists member crates, often located in a crates/ directory.
+    #[cfg(feature = "cargo")]
+    pub struct Cargo;
+
+    impl CachesHolding for Cargo {
+        type Caches = super::caches::Cargo;
+    }
+
+    #[cfg(feature = "cargo")]
+    pub struct CargoToml;
+
+    impl CachesHolding for CargoToml {
+        type Caches = super::caches::CargoToml;
+    }
+
+    impl super::InFiles for CargoToml {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"Cargo.toml")
+        }
+    }
+
+    #[cfg(feature = "rust")]
+    pub struct Rust;
+
+    impl CachesHolding for Rust {
+        type Caches = super::caches::Rust;
+    }
+
+    impl super::InFiles for Rust {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".rs")
+        }
+    }
+
+    /// The python scheme https://docs.python.org/3/reference/import.html#packages ,
+    /// python files can be anywhere,
+    /// a directory with an __init__.py is a regular package,
+    /// a directory with a pyproject.toml or a setup.py is the root of a distribution package.
+    #[cfg(feature = "python")]
+    pub struct Python;
+
+    impl CachesHolding for Python {
+        type Caches = super::caches::Python;
+    }
+
+    impl super::InFiles for Python {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.ends_with(b".py")
+        }
+    }
+
+    #[cfg(feature = "python")]
+    pub struct PyProject;
+
+    impl CachesHolding for PyProject {
+        type Caches = super::caches::PyProject;
+    }
+
+    impl super::InFiles for PyProject {
+        fn matches(name: &ObjectName) -> bool {
+            name.0.eq(b"pyproject.toml")
+        }
+    }
 }
 
 impl crate::preprocessed::RepositoryProcessor {
@@ -503,3 +798,21 @@ make_multi! {
     Npm [Ts, Js] Xml => crate::make::Md,
     None => crate::make::Md,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RepoConfig;
+
+    #[test]
+    fn infer_from_build_files() {
+        let infer = |x: &[&str]| RepoConfig::infer(x.iter().copied());
+        assert_eq!(infer(&["README.md", "pom.xml", "src"]), RepoConfig::JavaMaven);
+        assert_eq!(infer(&["Makefile", "src"]), RepoConfig::CppMake);
+        assert_eq!(infer(&["Cargo.toml", "Cargo.lock"]), RepoConfig::RustCargo);
+        assert_eq!(infer(&["package.json", "tsconfig.json"]), RepoConfig::TsNpm);
+        assert_eq!(infer(&["pyproject.toml"]), RepoConfig::Python);
+        // the build of the main language comes first
+        assert_eq!(infer(&["Makefile", "pom.xml"]), RepoConfig::JavaMaven);
+        assert_eq!(infer(&["README.md", "docs"]), RepoConfig::Any);
+    }
+}
@@ -8,11 +8,11 @@ pub trait Parametrized: ParametrizedCommitProc {
     // each identical (structurally) parameter should identify exactly one processor
     fn register_param(&mut self, t: Self::T) -> ParametrizedCommitProcessorHandle;
 }
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ConfigParametersHandle(pub usize);
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ParametrizedCommitProcessorHandle(pub CommitProcessorHandle, pub ConfigParametersHandle);
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct CommitProcessorHandle(std::any::TypeId);
 #[derive(Debug)]
 pub struct ParametrizedCommitProcessor2Handle<T: CommitProcExt>(
@@ -69,6 +69,9 @@ pub trait CommitProc {
     ) -> hyper_ast::store::defaults::NodeIdentifier;
 
     fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit>;
+
+    /// Makes a commit processed elsewhere, eg. restored from disk, available through [`CommitProc::get_commit`]
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit);
 }
 pub trait PreparedCommitProc {
     fn process(
@@ -156,6 +159,10 @@ fn t() {
         fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
             todo!()
         }
+
+        fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+            todo!()
+        }
     }
     impl ParametrizedCommitProc for P0 {
         fn get_mut(&mut self, parameters: ConfigParametersHandle) -> &mut dyn CommitProc {
@@ -346,6 +353,10 @@ mod spreaded {
             fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
                 todo!()
             }
+
+            fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+                todo!()
+            }
         }
         impl CommitProcExt for P {
             type Holder = P0;
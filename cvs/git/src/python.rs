@@ -0,0 +1,174 @@
+use crate::{
+    processing::ObjectName, Accumulator, DefaultMetrics, ParseErr, SimpleStores, TStore,
+    PROPAGATE_ERROR_ON_BAD_CST_NODE,
+};
+
+use enumset::EnumSet;
+use hyper_ast::store::defaults::{LabelIdentifier, NodeIdentifier};
+
+use hyper_ast_gen_ts_python::legion as python_tree_gen;
+
+pub(crate) fn handle_python_file<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut python_tree_gen::PythonTreeGen<'stores, 'cache, TStore>,
+    name: &ObjectName,
+    text: &'b [u8],
+) -> Result<python_tree_gen::FNode, ()> {
+    let tree = match python_tree_gen::PythonTreeGen::<TStore>::tree_sitter_parse(text) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    Ok(tree_gen.generate_file(name.as_bytes(), text, tree.walk()))
+}
+
+/// Adds the pyproject.toml to the HyperAST and extracts the name of the project from it,
+/// either from the standard [project] table or from the [tool.poetry] one.
+///
+/// There is no toml grammar, so the file is stored as a blob.
+/// An ill-formed file is still stored and still marks a project.
+pub(crate) fn handle_pyproject_file(
+    stores: &mut SimpleStores,
+    name: &ObjectName,
+    text: &[u8],
+) -> Result<PyProject, ParseErr> {
+    let (compressed_node, metrics) = crate::any::handle_blob_file(stores, name, text);
+    let text = std::str::from_utf8(text)?;
+    let manifest: toml::Value = text.parse().unwrap_or_else(|e| {
+        log::warn!("bad pyproject {:?}: {}", name.try_str(), e);
+        toml::Value::Table(Default::default())
+    });
+    let name = manifest
+        .get("project")
+        .or_else(|| manifest.get("tool").and_then(|x| x.get("poetry")))
+        .and_then(|x| x.get("name"))
+        .and_then(|x| x.as_str())
+        .map(|x| x.to_owned());
+    Ok(PyProject {
+        compressed_node,
+        metrics,
+        name,
+    })
+}
+
+/// What matters in a pyproject.toml
+#[derive(Debug, Clone)]
+pub struct PyProject {
+    pub compressed_node: NodeIdentifier,
+    pub metrics: DefaultMetrics,
+    /// name of the distribution package
+    pub name: Option<String>,
+}
+
+/// Stored on the directories of packages and projects
+#[derive(enumset::EnumSetType, Debug)]
+pub enum SemFlags {
+    IsPackage,
+    IsProject,
+}
+
+#[derive(Debug, Clone)]
+pub struct MD {
+    pub(crate) metrics: DefaultMetrics,
+    /// the directory contains an __init__.py
+    pub(crate) is_package: bool,
+    /// the directory contains a pyproject.toml or a setup.py
+    pub(crate) is_project: bool,
+    /// name given in the pyproject.toml
+    pub(crate) project_name: Option<String>,
+}
+
+impl MD {
+    /// packages and projects delimit python modules,
+    /// other directories are namespace packages or just hold files
+    pub fn is_module_boundary(&self) -> bool {
+        self.is_package || self.is_project
+    }
+}
+
+pub struct PythonModuleAcc {
+    pub(crate) name: String,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) metrics: DefaultMetrics,
+    pub(crate) is_package: bool,
+    pub(crate) is_project: bool,
+    pub(crate) project_name: Option<String>,
+}
+
+impl From<String> for PythonModuleAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl PythonModuleAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+            is_package: false,
+            is_project: false,
+            project_name: None,
+        }
+    }
+}
+
+impl PythonModuleAcc {
+    /// The pyproject.toml is a child, it also marks the directory as a project
+    pub(crate) fn push_pyproject(&mut self, name: LabelIdentifier, pyproject: PyProject) {
+        assert!(!self.children_names.contains(&name));
+        self.children.push(pyproject.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(pyproject.metrics);
+        self.is_project = true;
+        self.project_name = pyproject.name;
+    }
+    pub(crate) fn status(&self) -> EnumSet<SemFlags> {
+        let mut status = EnumSet::new();
+        if self.is_package {
+            status |= SemFlags::IsPackage;
+        }
+        if self.is_project {
+            status |= SemFlags::IsProject;
+        }
+        status
+    }
+    pub fn push_submodule(&mut self, name: LabelIdentifier, full_node: (NodeIdentifier, MD)) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+    pub(crate) fn push_source_file(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: python_tree_gen::Local,
+        skiped_ana: bool,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.metrics);
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for PythonModuleAcc {
+    type Node = (LabelIdentifier, (NodeIdentifier, MD));
+    fn push(&mut self, (name, full_node): Self::Node) {
+        self.children.push(full_node.0);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.1.metrics);
+    }
+}
+
+impl Accumulator for PythonModuleAcc {
+    type Unlabeled = (NodeIdentifier, MD);
+}
@@ -0,0 +1,602 @@
+use std::{
+    iter::Peekable,
+    path::{Components, PathBuf},
+};
+
+use git2::{Oid, Repository};
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder},
+    store::{
+        defaults::NodeIdentifier,
+        nodes::legion::{compo, compo::CS, NodeStore},
+    },
+    tree_gen::SubTreeMetrics,
+    types::LabelStore,
+};
+use hyper_ast_gen_ts_python::{
+    legion::{self as python_gen, eq_node},
+    types::Type,
+};
+
+use crate::{
+    git::BasicGitObject,
+    preprocessed::{IsSkippedAna, RepositoryProcessor},
+    processing::{erased::ParametrizedCommitProc2, CacheHolding, InFiles, ObjectName},
+    python::{PythonModuleAcc, MD},
+    Processor, SimpleStores,
+};
+
+/// Explores the whole repository,
+/// directories containing an __init__.py, a pyproject.toml or a setup.py are marked as module boundaries.
+pub struct PythonProcessor<'a, 'b, 'c, Acc> {
+    prepro: &'b mut RepositoryProcessor,
+    repository: &'a Repository,
+    stack: Vec<(Oid, Vec<BasicGitObject>, Acc)>,
+    dir_path: &'c mut Peekable<Components<'c>>,
+    handle: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+}
+
+impl<'a, 'b, 'c, Acc: From<String>> PythonProcessor<'a, 'b, 'c, Acc> {
+    pub fn new(
+        repository: &'a Repository,
+        prepro: &'b mut RepositoryProcessor,
+        dir_path: &'c mut Peekable<Components<'c>>,
+        name: &[u8],
+        oid: git2::Oid,
+    ) -> Self {
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>();
+        let handle =
+            <PythonProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = std::str::from_utf8(&name).unwrap().to_string();
+        let stack = vec![(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            handle,
+        }
+    }
+}
+
+impl<'a, 'b, 'c> Processor<PythonModuleAcc> for PythonProcessor<'a, 'b, 'c, PythonModuleAcc> {
+    fn pre(&mut self, current_dir: BasicGitObject) {
+        match current_dir {
+            BasicGitObject::Tree(oid, name) => {
+                self.handle_tree_cached(name, oid);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if self.dir_path.peek().is_some() {
+                    return;
+                }
+                let acc = &mut self.stack.last_mut().unwrap().2;
+                if crate::processing::file_sys::PyProject::matches(&name) {
+                    let r = self.prepro.help_handle_pyproject(
+                        oid,
+                        acc,
+                        &name,
+                        &self.repository,
+                        self.handle.into(),
+                    );
+                    if let Err(e) = r {
+                        log::warn!("ignoring pyproject {:?}: {:?}", name.try_str(), e);
+                        // still a project
+                        acc.is_project = true;
+                    }
+                } else if crate::processing::file_sys::Python::matches(&name) {
+                    match name.as_bytes() {
+                        b"__init__.py" => acc.is_package = true,
+                        b"setup.py" => acc.is_project = true,
+                        _ => (),
+                    }
+                    self.prepro
+                        .help_handle_python_file(oid, acc, &name, self.repository, self.handle)
+                        .unwrap();
+                } else {
+                    log::debug!("not python source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: PythonModuleAcc) -> Option<(NodeIdentifier, MD)> {
+        let name = acc.name.clone();
+        let key = (oid, name.as_bytes().into());
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>()
+            .get_caches_mut()
+            .dir_map
+            .insert(key, full_node.clone());
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some(full_node)
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            w.push_submodule(name, full_node);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, PythonModuleAcc)> {
+        &mut self.stack
+    }
+}
+
+impl<'a, 'b, 'c> PythonProcessor<'a, 'b, 'c, PythonModuleAcc> {
+    fn handle_tree_cached(&mut self, name: ObjectName, oid: Oid) {
+        if let Some(s) = self.dir_path.peek() {
+            if name
+                .as_bytes()
+                .eq(std::ffi::OsStr::as_encoded_bytes(s.as_os_str()))
+            {
+                self.dir_path.next();
+                self.stack.last_mut().expect("never empty").1.clear();
+                let tree = self.repository.find_tree(oid).unwrap();
+                let prepared = prepare_dir_exploration(tree);
+                self.stack.push((
+                    oid,
+                    prepared,
+                    PythonModuleAcc::new(name.try_into().unwrap()),
+                ));
+            }
+            return;
+        }
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>()
+            .get_caches_mut()
+            .dir_map
+            .get(&(oid, name.clone()))
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().2;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.children_names.contains(&name));
+            w.push_submodule(name, full_node);
+            return;
+        }
+        log::debug!("python tree {:?}", name.try_str());
+        let tree = self.repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        self.stack.push((
+            oid,
+            prepared,
+            PythonModuleAcc::new(name.try_into().unwrap()),
+        ));
+    }
+}
+
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+pub(crate) fn make(acc: PythonModuleAcc, stores: &mut SimpleStores) -> (NodeIdentifier, MD) {
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &Type::Directory, &acc.name, size_no_spaces);
+    let hashable = hbuilder.most_discriminating();
+    let label = stores.label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&Type::Directory, Some(&label), &acc.children);
+    let insertion = stores.node_store.prepare_insertion(&hashable, eq);
+    let hashs = hbuilder.build();
+    let status = acc.status();
+    let node_id = if let Some(id) = insertion.occupied_id() {
+        id
+    } else {
+        log::info!("make python dir {} {}", &acc.name, acc.children.len());
+        let vacant = insertion.vacant();
+        assert_eq!(acc.children_names.len(), acc.children.len());
+        if acc.children.is_empty() {
+            NodeStore::insert_after_prepare(
+                vacant,
+                (Type::Directory, label, hashs, BloomSize::None),
+            )
+        } else {
+            let mut dyn_builder =
+                hyper_ast::store::nodes::legion::dyn_builder::EntityBuilder::new();
+            dyn_builder.add(Type::Directory);
+            dyn_builder.add(hashs.clone());
+            dyn_builder.add(label);
+            dyn_builder.add(BloomSize::Much);
+            dyn_builder.add(compo::Size(size));
+            dyn_builder.add(compo::SizeNoSpaces(size_no_spaces));
+            dyn_builder.add(compo::Height(height));
+            dyn_builder.add(CS(acc.children_names.into_boxed_slice()));
+            dyn_builder.add(CS(acc.children.into_boxed_slice()));
+            if !status.is_empty() {
+                dyn_builder.add(status);
+            }
+            NodeStore::insert_built_after_prepare(vacant, dyn_builder.build())
+        }
+    };
+
+    let metrics = SubTreeMetrics {
+        size,
+        height,
+        hashs,
+        size_no_spaces,
+    };
+    let md = MD {
+        metrics,
+        is_package: acc.is_package,
+        is_project: acc.is_project,
+        project_name: acc.project_name,
+    };
+    (node_id, md)
+}
+
+impl RepositoryProcessor {
+    fn help_handle_pyproject(
+        &mut self,
+        oid: Oid,
+        parent_acc: &mut PythonModuleAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<PyProjectProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let x = self
+            .processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::PyProject>()
+            .handle(oid, repository, name, parameters, |_, n, t| {
+                crate::python::handle_pyproject_file(&mut self.main_stores, n, t)
+            })?;
+        let name = self.intern_object_name(name);
+        parent_acc.push_pyproject(name, x);
+        Ok(())
+    }
+
+    fn handle_python_blob(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> Result<(python_gen::Local, IsSkippedAna), crate::ParseErr> {
+        self.processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::Python>()
+            .handle2(oid, repository, &name, parameters, |c, n, t| {
+                let line_break = if t.contains(&b'\r') {
+                    "\r\n".as_bytes().to_vec()
+                } else {
+                    "\n".as_bytes().to_vec()
+                };
+                crate::python::handle_python_file(
+                    &mut python_gen::PythonTreeGen {
+                        line_break,
+                        stores: &mut self.main_stores,
+                        md_cache: &mut c
+                            .mut_or_default::<PythonProcessorHolder>()
+                            .get_caches_mut()
+                            .md_cache,
+                    },
+                    n,
+                    t,
+                )
+                .map_err(|_| crate::ParseErr::IllFormed)
+                .map(|x| (x.local.clone(), false))
+            })
+    }
+
+    pub(crate) fn help_handle_python_file(
+        &mut self,
+        oid: Oid,
+        parent: &mut PythonModuleAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let (full_node, skiped_ana) = self.handle_python_blob(oid, name, repository, parameters)?;
+        let name = self.intern_object_name(name);
+        assert!(!parent.children_names.contains(&name));
+
+        parent.push_source_file(name, full_node, skiped_ana);
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter;
+
+// # pyproject.toml
+
+impl From<crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>>
+    for crate::processing::erased::ParametrizedCommitProcessor2Handle<PyProjectProc>
+{
+    fn from(
+        value: crate::processing::erased::ParametrizedCommitProcessor2Handle<PythonProc>,
+    ) -> Self {
+        crate::processing::erased::ParametrizedCommitProcessor2Handle(
+            value.0,
+            std::marker::PhantomData,
+        )
+    }
+}
+struct PyProjectProcessorHolder(Option<PyProjectProc>);
+impl Default for PyProjectProcessorHolder {
+    fn default() -> Self {
+        Self(Some(PyProjectProc(Parameter, Default::default())))
+    }
+}
+struct PyProjectProc(Parameter, crate::processing::caches::PyProject);
+impl crate::processing::erased::Parametrized for PyProjectProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self.0.iter().position(|x| &x.0 == &t).unwrap_or_else(|| {
+            let l = 0;
+            self.0 = Some(PyProjectProc(t, Default::default()));
+            l
+        });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+// TODO should not have to impl this trait
+impl crate::processing::erased::CommitProc for PyProjectProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!()
+    }
+
+    fn prepare_processing(
+        &self,
+        repository: &git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        unimplemented!()
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        unimplemented!()
+    }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        unimplemented!()
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for PyProjectProc {
+    type Holder = PyProjectProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for PyProjectProcessorHolder {
+    type Proc = PyProjectProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::PyProject> for PyProjectProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::PyProject {
+        &mut self.1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::PyProject {
+        &self.1
+    }
+}
+impl CacheHolding<crate::processing::caches::PyProject> for PyProjectProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::PyProject {
+        &mut self.0.as_mut().unwrap().1
+    }
+    fn get_caches(&self) -> &crate::processing::caches::PyProject {
+        &self.0.as_ref().unwrap().1
+    }
+}
+
+// # Python
+#[derive(Default)]
+pub(crate) struct PythonProcessorHolder(Option<PythonProc>);
+pub(crate) struct PythonProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Python,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for PythonProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0;
+                self.0 = Some(PythonProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+struct PreparedPythonCommitProc<'repo> {
+    repository: &'repo git2::Repository,
+    commit_builder: crate::preprocessed::CommitBuilder,
+}
+impl<'repo> crate::processing::erased::PreparedCommitProc for PreparedPythonCommitProc<'repo> {
+    fn process(
+        self: Box<PreparedPythonCommitProc<'repo>>,
+        prepro: &mut RepositoryProcessor,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        let dir_path = PathBuf::from("");
+        let mut dir_path = dir_path.components().peekable();
+        let name = b"";
+        let root_full_node = PythonProcessor::<PythonModuleAcc>::new(
+            self.repository,
+            prepro,
+            &mut dir_path,
+            name,
+            self.commit_builder.tree_oid(),
+        )
+        .process();
+        let h = prepro
+            .processing_systems
+            .mut_or_default::<PythonProcessorHolder>();
+        let handle =
+            <PythonProc as crate::processing::erased::CommitProcExt>::register_param(h, Parameter);
+        let commit_oid = self.commit_builder.commit_oid();
+        let commit = self.commit_builder.finish(root_full_node.0);
+        h.with_parameters_mut(handle.0)
+            .commits
+            .insert(commit_oid, commit);
+        root_full_node.0
+    }
+}
+impl crate::processing::erased::CommitProc for PythonProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        unimplemented!("see reason in the java proc")
+    }
+
+    fn prepare_processing<'repo>(
+        &self,
+        repository: &'repo git2::Repository,
+        commit_builder: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc + 'repo> {
+        Box::new(PreparedPythonCommitProc {
+            repository,
+            commit_builder,
+        })
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for PythonProc {
+    type Holder = PythonProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for PythonProcessorHolder {
+    type Proc = PythonProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Python> for PythonProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Python {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Python {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::Python> for PythonProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Python {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Python {
+        &self.0.as_ref().unwrap().cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SimpleStores;
+
+    #[test]
+    fn pyproject_name() {
+        let mut stores = SimpleStores::default();
+        let name = b"pyproject.toml".into();
+        let text = br#"
+[build-system]
+requires = ["setuptools"]
+
+[project]
+name = "hyperast"
+"#;
+        let pyproject = crate::python::handle_pyproject_file(&mut stores, &name, text).unwrap();
+        assert_eq!(pyproject.name.as_deref(), Some("hyperast"));
+
+        let text = br#"
+[tool.poetry]
+name = "poetry-project"
+"#;
+        let pyproject = crate::python::handle_pyproject_file(&mut stores, &name, text).unwrap();
+        assert_eq!(pyproject.name.as_deref(), Some("poetry-project"));
+    }
+
+    #[test]
+    fn pyproject_is_a_child() {
+        use hyper_ast::types::LabelStore;
+        let mut stores = SimpleStores::default();
+        let name = b"pyproject.toml".into();
+        let pyproject =
+            crate::python::handle_pyproject_file(&mut stores, &name, b"[project").unwrap();
+        assert_eq!(pyproject.name, None);
+        let mut acc = crate::python::PythonModuleAcc::new("".to_string());
+        let name = stores.label_store.get_or_insert("pyproject.toml");
+        acc.push_pyproject(name, pyproject);
+        assert_eq!(acc.children.len(), 1);
+        assert_eq!(
+            acc.status(),
+            enumset::EnumSet::only(crate::python::SemFlags::IsProject)
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    preprocessed::IsSkippedAna, processing::ObjectName, Accumulator, TStore,
+    PROPAGATE_ERROR_ON_BAD_CST_NODE,
+};
+
+use hyper_ast::{
+    hashed::SyntaxNodeHashs,
+    store::defaults::{LabelIdentifier, NodeIdentifier},
+    tree_gen::SubTreeMetrics,
+};
+
+use hyper_ast_gen_ts_rust::legion as rust_tree_gen;
+
+pub(crate) fn handle_rust_file<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut rust_tree_gen::RustTreeGen<'stores, 'cache, TStore>,
+    name: &ObjectName,
+    text: &'b [u8],
+) -> Result<rust_tree_gen::FNode, ()> {
+    let tree = match rust_tree_gen::RustTreeGen::<TStore>::tree_sitter_parse(text) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    Ok(tree_gen.generate_file(name.as_bytes(), text, tree.walk()))
+}
+
+pub struct RustAcc {
+    pub(crate) name: String,
+    pub(crate) children: Vec<NodeIdentifier>,
+    pub(crate) children_names: Vec<LabelIdentifier>,
+    pub(crate) metrics: SubTreeMetrics<SyntaxNodeHashs<u32>>,
+}
+
+impl RustAcc {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            children_names: Default::default(),
+            children: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+}
+
+impl From<String> for RustAcc {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl RustAcc {
+    pub(crate) fn push(
+        &mut self,
+        name: LabelIdentifier,
+        full_node: rust_tree_gen::Local,
+        skiped_ana: bool,
+    ) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.metrics);
+    }
+}
+
+impl hyper_ast::tree_gen::Accumulator for RustAcc {
+    type Node = (LabelIdentifier, (rust_tree_gen::Local, IsSkippedAna));
+    fn push(&mut self, (name, (full_node, skiped_ana)): Self::Node) {
+        self.children.push(full_node.compressed_node);
+        self.children_names.push(name);
+        self.metrics.acc(full_node.metrics);
+    }
+}
+
+impl Accumulator for RustAcc {
+    type Unlabeled = (rust_tree_gen::Local, IsSkippedAna);
+}
@@ -0,0 +1,444 @@
+use std::{iter::Peekable, path::Components};
+
+use git2::{Oid, Repository};
+use hyper_ast::{
+    hashed::SyntaxNodeHashs,
+    store::{
+        defaults::{LabelIdentifier, NodeIdentifier},
+        nodes::legion::PendingInsert,
+    },
+    types::LabelStore,
+};
+use hyper_ast_gen_ts_rust::{
+    legion::{self as rust_gen, eq_node},
+    types::Type,
+};
+use tuples::CombinConcat;
+
+use crate::{
+    cargo::CargoModuleAcc,
+    git::BasicGitObject,
+    preprocessed::{IsSkippedAna, RepositoryProcessor},
+    processing::{erased::CommitProcExt, CacheHolding, InFiles, ObjectName},
+    rust::RustAcc,
+    Processor, SimpleStores,
+};
+
+pub(crate) fn prepare_dir_exploration(tree: git2::Tree) -> Vec<BasicGitObject> {
+    tree.iter()
+        .rev()
+        .map(TryInto::try_into)
+        .filter_map(|x| x.ok())
+        .collect()
+}
+
+pub struct RustProcessor<'repo, 'prepro, 'd, 'c, Acc> {
+    repository: &'repo Repository,
+    prepro: &'prepro mut RepositoryProcessor,
+    stack: Vec<(Oid, Vec<BasicGitObject>, Acc)>,
+    pub dir_path: &'d mut Peekable<Components<'c>>,
+    parameters: &'d crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+}
+
+impl<'repo, 'b, 'd, 'c, Acc: From<String>> RustProcessor<'repo, 'b, 'd, 'c, Acc> {
+    pub(crate) fn new(
+        repository: &'repo Repository,
+        prepro: &'b mut RepositoryProcessor,
+        dir_path: &'d mut Peekable<Components<'c>>,
+        name: &ObjectName,
+        oid: git2::Oid,
+        parameters: &'d crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+    ) -> Self {
+        let tree = repository.find_tree(oid).unwrap();
+        let prepared = prepare_dir_exploration(tree);
+        let name = name.try_into().unwrap();
+        let stack = vec![(oid, prepared, Acc::from(name))];
+        Self {
+            stack,
+            repository,
+            prepro,
+            dir_path,
+            parameters,
+        }
+    }
+}
+
+impl<'repo, 'b, 'd, 'c> Processor<RustAcc> for RustProcessor<'repo, 'b, 'd, 'c, RustAcc> {
+    fn pre(&mut self, current_object: BasicGitObject) {
+        match current_object {
+            BasicGitObject::Tree(oid, name) => {
+                self.handle_tree_cached(oid, name);
+            }
+            BasicGitObject::Blob(oid, name) => {
+                if crate::processing::file_sys::Rust::matches(&name) {
+                    self.prepro
+                        .help_handle_rust_file(
+                            oid,
+                            &mut self.stack.last_mut().unwrap().2,
+                            &name,
+                            self.repository,
+                            *self.parameters,
+                        )
+                        .unwrap();
+                } else {
+                    log::debug!("not rust source file {:?}", name.try_str());
+                }
+            }
+        }
+    }
+    fn post(&mut self, oid: Oid, acc: RustAcc) -> Option<(rust_gen::Local, IsSkippedAna)> {
+        let skiped_ana = true;
+        let name = acc.name.clone();
+        let key = (oid, name.as_bytes().into());
+        let full_node = make(acc, self.prepro.main_stores_mut());
+        self.prepro
+            .processing_systems
+            .mut_or_default::<RustProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .insert(key, (full_node.clone(), skiped_ana));
+        let name = self.prepro.main_stores.label_store.get_or_insert(name);
+        if self.stack.is_empty() {
+            Some((full_node, skiped_ana))
+        } else {
+            let w = &mut self.stack.last_mut().unwrap().2;
+            assert!(
+                !w.children_names.contains(&name),
+                "{:?} {:?}",
+                w.children_names,
+                name
+            );
+            w.push(name, full_node.clone(), skiped_ana);
+            None
+        }
+    }
+
+    fn stack(&mut self) -> &mut Vec<(Oid, Vec<BasicGitObject>, RustAcc)> {
+        &mut self.stack
+    }
+}
+
+impl<'repo, 'prepro, 'd, 'c> RustProcessor<'repo, 'prepro, 'd, 'c, RustAcc> {
+    fn handle_tree_cached(&mut self, oid: Oid, name: ObjectName) {
+        if let Some(already) = self
+            .prepro
+            .processing_systems
+            .mut_or_default::<RustProcessorHolder>()
+            .get_caches_mut()
+            .object_map
+            .get(&(oid, name.clone()))
+        {
+            // reinit already computed node for post order
+            let full_node = already.clone();
+            let w = &mut self.stack.last_mut().unwrap().2;
+            let name = self.prepro.intern_object_name(&name);
+            assert!(!w.children_names.contains(&name));
+            hyper_ast::tree_gen::Accumulator::push(w, (name, full_node));
+        } else {
+            log::info!("tree {:?}", name.try_str());
+            let tree = self.repository.find_tree(oid).unwrap();
+            let prepared: Vec<BasicGitObject> = prepare_dir_exploration(tree);
+            self.stack
+                .push((oid, prepared, RustAcc::new(name.try_into().unwrap())));
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Parameter;
+#[derive(Default)]
+pub(crate) struct RustProcessorHolder(Option<RustProc>);
+pub(crate) struct RustProc {
+    parameter: Parameter,
+    cache: crate::processing::caches::Rust,
+    commits: std::collections::HashMap<git2::Oid, crate::Commit>,
+}
+impl crate::processing::erased::Parametrized for RustProcessorHolder {
+    type T = Parameter;
+    fn register_param(
+        &mut self,
+        t: Self::T,
+    ) -> crate::processing::erased::ParametrizedCommitProcessorHandle {
+        let l = self
+            .0
+            .iter()
+            .position(|x| &x.parameter == &t)
+            .unwrap_or_else(|| {
+                let l = 0; //self.0.len();
+                self.0 = Some(RustProc {
+                    parameter: t,
+                    cache: Default::default(),
+                    commits: Default::default(),
+                });
+                l
+            });
+        use crate::processing::erased::ConfigParametersHandle;
+        use crate::processing::erased::ParametrizedCommitProc;
+        use crate::processing::erased::ParametrizedCommitProcessorHandle;
+        ParametrizedCommitProcessorHandle(self.erased_handle(), ConfigParametersHandle(l))
+    }
+}
+impl crate::processing::erased::CommitProc for RustProc {
+    fn process_root_tree(
+        &mut self,
+        repository: &git2::Repository,
+        tree_oid: &git2::Oid,
+    ) -> hyper_ast::store::defaults::NodeIdentifier {
+        todo!()
+    }
+
+    fn prepare_processing(
+        &self,
+        repository: &git2::Repository,
+        tree_oid: crate::preprocessed::CommitBuilder,
+    ) -> Box<dyn crate::processing::erased::PreparedCommitProc> {
+        todo!()
+    }
+
+    fn get_commit(&self, commit_oid: git2::Oid) -> Option<&crate::Commit> {
+        self.commits.get(&commit_oid)
+    }
+
+    fn insert_commit(&mut self, commit_oid: git2::Oid, commit: crate::Commit) {
+        self.commits.insert(commit_oid, commit);
+    }
+}
+
+impl crate::processing::erased::CommitProcExt for RustProc {
+    type Holder = RustProcessorHolder;
+}
+impl crate::processing::erased::ParametrizedCommitProc2 for RustProcessorHolder {
+    type Proc = RustProc;
+
+    fn with_parameters_mut(
+        &mut self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &mut Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_mut().unwrap()
+    }
+
+    fn with_parameters(
+        &self,
+        parameters: crate::processing::erased::ConfigParametersHandle,
+    ) -> &Self::Proc {
+        assert_eq!(0, parameters.0);
+        self.0.as_ref().unwrap()
+    }
+}
+impl CacheHolding<crate::processing::caches::Rust> for RustProc {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Rust {
+        &mut self.cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Rust {
+        &self.cache
+    }
+}
+impl CacheHolding<crate::processing::caches::Rust> for RustProcessorHolder {
+    fn get_caches_mut(&mut self) -> &mut crate::processing::caches::Rust {
+        &mut self.0.as_mut().unwrap().cache
+    }
+    fn get_caches(&self) -> &crate::processing::caches::Rust {
+        &self.0.as_ref().unwrap().cache
+    }
+}
+
+#[cfg(feature = "rust")]
+impl RepositoryProcessor {
+    fn handle_rust_blob(
+        &mut self,
+        oid: Oid,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+    ) -> Result<(rust_gen::Local, IsSkippedAna), crate::ParseErr> {
+        self.processing_systems
+            .caching_blob_handler::<crate::processing::file_sys::Rust>()
+            .handle2(oid, repository, &name, parameters, |c, n, t| {
+                let line_break = if t.contains(&b'\r') {
+                    "\r\n".as_bytes().to_vec()
+                } else {
+                    "\n".as_bytes().to_vec()
+                };
+                crate::rust::handle_rust_file(
+                    &mut rust_gen::RustTreeGen {
+                        line_break,
+                        stores: &mut self.main_stores,
+                        md_cache: &mut c
+                            .mut_or_default::<RustProcessorHolder>()
+                            .get_caches_mut()
+                            .md_cache,
+                    },
+                    n,
+                    t,
+                )
+                .map_err(|_| crate::ParseErr::IllFormed)
+                .map(|x| (x.local.clone(), false))
+            })
+    }
+
+    pub(crate) fn help_handle_rust_file(
+        &mut self,
+        oid: Oid,
+        parent: &mut RustAcc,
+        name: &ObjectName,
+        repository: &Repository,
+        parameters: crate::processing::erased::ParametrizedCommitProcessor2Handle<RustProc>,
+    ) -> Result<(), crate::ParseErr> {
+        let (full_node, skiped_ana) = self.handle_rust_blob(oid, name, repository, parameters)?;
+        let name = self.intern_object_name(name);
+        assert!(!parent.children_names.contains(&name));
+
+        parent.push(name, full_node, skiped_ana);
+        Ok(())
+    }
+
+    /// for rust files directly in a crate or a workspace, eg. build.rs
+    pub(crate) fn help_handle_rust_file2(
+        &mut self,
+        oid: Oid,
+        parent: &mut CargoModuleAcc,
+        name: &ObjectName,
+        repository: &Repository,
+    ) -> Result<(), crate::ParseErr> {
+        // might be the first rust file encountered
+        let h = self
+            .processing_systems
+            .mut_or_default::<RustProcessorHolder>();
+        let parameters = RustProc::register_param(h, Parameter);
+        let (full_node, skiped_ana) = self.handle_rust_blob(oid, name, repository, parameters)?;
+        let name = self.intern_object_name(name);
+        assert!(!parent.children_names.contains(&name));
+
+        parent.push_source_file(name, full_node, skiped_ana);
+        Ok(())
+    }
+
+    pub(crate) fn handle_rust_directory<'b, 'd: 'b>(
+        &mut self,
+        repository: &Repository,
+        dir_path: &'b mut Peekable<Components<'d>>,
+        name: &ObjectName,
+        oid: git2::Oid,
+    ) -> (rust_gen::Local, IsSkippedAna) {
+        let h = self
+            .processing_systems
+            .mut_or_default::<RustProcessorHolder>();
+
+        let handle = RustProc::register_param(h, Parameter);
+        RustProcessor::<RustAcc>::new(repository, self, dir_path, name, oid, &handle).process()
+    }
+
+    pub(crate) fn help_handle_rust_folder<'a, 'b, 'c, 'd: 'c>(
+        &'a mut self,
+        repository: &'b Repository,
+        dir_path: &'c mut Peekable<Components<'d>>,
+        oid: Oid,
+        name: &ObjectName,
+    ) -> <RustAcc as hyper_ast::tree_gen::Accumulator>::Node {
+        let full_node = self.handle_rust_directory(repository, dir_path, name, oid);
+        let name = self.intern_object_name(name);
+        (name, full_node)
+    }
+}
+
+fn make(acc: RustAcc, stores: &mut SimpleStores) -> rust_gen::Local {
+    use hyper_ast::{
+        hashed::{self, IndexingHashBuilder, MetaDataHashsBuilder},
+        tree_gen::SubTreeMetrics,
+    };
+    let node_store = &mut stores.node_store;
+    let label_store = &mut stores.label_store;
+
+    let hashs = acc.metrics.hashs;
+    let size = acc.metrics.size + 1;
+    let height = acc.metrics.height + 1;
+    let size_no_spaces = acc.metrics.size_no_spaces + 1;
+    let hbuilder = hashed::Builder::new(hashs, &Type::Directory, &acc.name, size_no_spaces);
+    let hashable = &hbuilder.most_discriminating();
+    let label_id = label_store.get_or_insert(acc.name.clone());
+
+    let eq = eq_node(&Type::Directory, Some(&label_id), &acc.children);
+
+    let insertion = node_store.prepare_insertion(&hashable, eq);
+
+    let compute_md = || {
+        let hashs = hbuilder.build();
+
+        SubTreeMetrics {
+            size,
+            height,
+            size_no_spaces,
+            hashs,
+        }
+    };
+
+    if let Some(id) = insertion.occupied_id() {
+        let metrics = compute_md();
+        return rust_gen::Local {
+            compressed_node: id,
+            metrics,
+        };
+    }
+
+    let metrics = compute_md();
+    let hashs = hbuilder.build();
+    let node_id = compress(
+        insertion,
+        label_id,
+        acc.children,
+        acc.children_names,
+        size,
+        height,
+        size_no_spaces,
+        hashs,
+    );
+
+    rust_gen::Local {
+        compressed_node: node_id,
+        metrics,
+    }
+}
+
+fn compress(
+    insertion: PendingInsert,
+    label_id: LabelIdentifier,
+    children: Vec<NodeIdentifier>,
+    children_names: Vec<LabelIdentifier>,
+    size: u32,
+    height: u32,
+    size_no_spaces: u32,
+    hashs: SyntaxNodeHashs<u32>,
+) -> NodeIdentifier {
+    use hyper_ast::{
+        filter::BloomSize,
+        store::nodes::legion::{compo, compo::CS, NodeStore},
+    };
+    let vacant = insertion.vacant();
+    macro_rules! insert {
+        ( $c0:expr, $($c:expr),* $(,)? ) => {{
+            let c = $c0;
+            $(
+                let c = c.concat($c);
+            )*
+            NodeStore::insert_after_prepare(vacant, c)
+        }};
+    }
+    match children.len() {
+        0 => insert!((Type::Directory, label_id, hashs, BloomSize::None),),
+        _ => {
+            assert_eq!(children_names.len(), children.len());
+            let c = (
+                Type::Directory,
+                label_id,
+                compo::Size(size),
+                compo::Height(height),
+                compo::SizeNoSpaces(size_no_spaces),
+                hashs,
+                CS(children_names.into_boxed_slice()),
+                CS(children.into_boxed_slice()),
+            );
+            insert!(c, (BloomSize::Much,))
+        }
+    }
+}
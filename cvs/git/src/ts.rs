@@ -0,0 +1,37 @@
+use crate::{processing::ObjectName, TStore, PROPAGATE_ERROR_ON_BAD_CST_NODE};
+
+use hyper_ast_gen_ts_ts::legion as ts_tree_gen;
+
+/// .ts files are parsed with the typescript grammar,
+/// .tsx and javascript files with the tsx grammar.
+pub(crate) fn handle_ts_file<'stores, 'cache, 'b: 'stores>(
+    tree_gen: &mut ts_tree_gen::TsTreeGen<'stores, 'cache, TStore>,
+    name: &ObjectName,
+    text: &'b [u8],
+) -> Result<ts_tree_gen::FNode, ()> {
+    let parse: fn(&[u8]) -> Result<tree_sitter::Tree, tree_sitter::Tree> = if is_typescript(name) {
+        ts_tree_gen::TsTreeGen::<TStore>::tree_sitter_parse
+    } else {
+        ts_tree_gen::TsTreeGen::<TStore>::tree_sitter_parse_tsx
+    };
+    let tree = match parse(text) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            log::warn!("bad CST");
+            log::debug!("{:?}", name.try_str());
+            log::debug!("{}", tree.root_node().to_sexp());
+            if PROPAGATE_ERROR_ON_BAD_CST_NODE {
+                return Err(());
+            } else {
+                tree
+            }
+        }
+    };
+    Ok(tree_gen.generate_file(name.as_bytes(), text, tree.walk()))
+}
+
+/// eg. index.ts, index.d.ts, index.mts, but not index.tsx
+fn is_typescript(name: &ObjectName) -> bool {
+    let name = name.as_bytes();
+    name.ends_with(b".ts") || name.ends_with(b".mts") || name.ends_with(b".cts")
+}
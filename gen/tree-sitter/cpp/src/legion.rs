@@ -94,6 +94,8 @@ pub struct Acc {
     ana: Option<PartialAnalysis>,
     padding_start: usize,
     indentation: Spaces,
+    /// the fields of the children, by index
+    roles: Vec<(usize, LabelIdentifier)>,
 }
 
 pub type FNode = FullNode<BasicGlobalData, Local>;
@@ -185,6 +187,10 @@ impl<'a> hyper_ast::tree_gen::parser::TreeCursor<'a, TNode<'a>> for TTreeCursor<
         // self.0.goto_next_sibling_internal()
         // // ts_internal::TreeCursor::as_internal(&mut self.0).goto_next_sibling_internal()
     }
+
+    fn role(&self) -> Option<&'static str> {
+        self.0.field_name()
+    }
 }
 
 impl<'store, 'cache, TS: CppEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIdentifier>>>>
@@ -227,6 +233,7 @@ impl<'store, 'cache, TS: CppEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIden
             ana,
             padding_start: 0,
             indentation: indent,
+            roles: vec![],
         }
     }
     fn pre_skippable(
@@ -292,6 +299,7 @@ impl<'store, 'cache, TS: CppEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIden
                 children: vec![],
             },
             no_space: vec![],
+            roles: vec![],
         }
     }
 
@@ -323,6 +331,11 @@ impl<'store, 'cache, TS: CppEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIden
         };
         self.make(global, acc, label)
     }
+
+    fn role(&mut self, parent: &mut <Self as TreeGen>::Acc, role: &'static str) {
+        let role = self.stores.label_store.get_or_insert(role);
+        parent.roles.push((parent.simple.children.len(), role));
+    }
 }
 
 impl<'store, 'cache, TS: CppEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIdentifier>>>>
@@ -548,11 +561,13 @@ impl<'stores, 'cache, TS: CppEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeId
             let hashs = hbuilder.build();
             let bytes_len = compo::BytesLen((acc.end_byte - acc.start_byte).try_into().unwrap());
             let base = (interned_kind, hashs, bytes_len);
+            let roles = compo::Roles::new(acc.simple.children.len(), acc.roles);
             let compressed_node = compress(
                 label_id,
                 &ana,
                 acc.simple,
                 acc.no_space,
+                roles,
                 // bytes_len,
                 size,
                 height,
@@ -588,6 +603,7 @@ fn compress<T: 'static + std::marker::Send + std::marker::Sync>(
     _ana: &Option<PartialAnalysis>,
     simple: BasicAccumulator<Type, NodeIdentifier>,
     no_space: Vec<NodeIdentifier>,
+    roles: Option<compo::Roles<LabelIdentifier>>,
     // bytes_len: compo::BytesLen,
     size: u32,
     height: u32,
@@ -626,11 +642,16 @@ fn compress<T: 'static + std::marker::Send + std::marker::Sync>(
                     let a = simple.children.into_boxed_slice();
                     let c = c.concat((compo::Size(size), compo::SizeNoSpaces(size_no_spaces), compo::Height(height), ));
                     let c = c.concat((CS(a),));
-                    if x == no_space.len() {
-                        insert!(c,)
-                    } else {
-                        let b = no_space.into_boxed_slice();
-                        insert!(c, (NoSpacesCS(b),))
+                    match (x == no_space.len(), roles) {
+                        (true, None) => insert!(c,),
+                        (true, Some(roles)) => insert!(c, (roles,)),
+                        (false, roles) => {
+                            let b = no_space.into_boxed_slice();
+                            match roles {
+                                None => insert!(c, (NoSpacesCS(b),)),
+                                Some(roles) => insert!(c, (NoSpacesCS(b), roles)),
+                            }
+                        }
                     }
                 }
             }}
@@ -147,6 +147,8 @@ pub struct Acc {
     mcc: Mcc,
     padding_start: usize,
     indentation: Spaces,
+    /// the fields of the children, by index
+    roles: Vec<(usize, LabelIdentifier)>,
 }
 
 impl Accumulator for Acc {
@@ -188,6 +190,10 @@ impl<'a> hyper_ast::tree_gen::parser::TreeCursor<'a, TNode<'a>> for TTreeCursor<
     fn goto_next_sibling(&mut self) -> bool {
         self.0.goto_next_sibling()
     }
+
+    fn role(&self) -> Option<&'static str> {
+        self.0.field_name()
+    }
 }
 
 /// Implements [ZippedTreeGen] to offer a visitor for Java generation
@@ -233,6 +239,7 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
             mcc,
             padding_start: 0,
             indentation: indent,
+            roles: vec![],
         }
     }
 
@@ -267,6 +274,7 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
                 children: vec![],
             },
             no_space: vec![],
+            roles: vec![],
         }
     }
 
@@ -298,6 +306,11 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
         };
         self.make(global, acc, label)
     }
+
+    fn role(&mut self, parent: &mut <Self as TreeGen>::Acc, role: &'static str) {
+        let role = self.stores.label_store.get_or_insert(role);
+        parent.roles.push((parent.simple.children.len(), role));
+    }
 }
 
 pub fn tree_sitter_parse(text: &[u8]) -> Result<tree_sitter::Tree, tree_sitter::Tree> {
@@ -543,8 +556,11 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
 
         let insertion = node_store.prepare_insertion(&hashable, eq);
 
-        let local = if let Some(compressed_node) = insertion.occupied_id() {
-            let md = self.md_cache.get(&compressed_node).unwrap();
+        // the metadata of a stored node can be missing, eg. if it was loaded from disk
+        let cached = insertion
+            .occupied_id()
+            .and_then(|id| self.md_cache.get(&id).map(|md| (id, md)));
+        let local = if let Some((compressed_node, md)) = cached {
             let ana = md.ana.clone();
             let metrics = md.metrics;
             let mcc = md.mcc.clone();
@@ -566,7 +582,9 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
             let hashs = hbuilder.build();
             let bytes_len = compo::BytesLen((acc.end_byte - acc.start_byte).try_into().unwrap());
             let mcc = acc.mcc;
-            let compressed_node = if false {
+            let compressed_node = if let Some(compressed_node) = insertion.occupied_id() {
+                compressed_node
+            } else if false {
                 let base = (interned_kind, hashs, bytes_len);
                 compress(
                     label_id,
@@ -661,6 +679,9 @@ impl<'stores, 'cache, TS: JavaEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeI
                         dyn_builder.add(BloomSize::None);
                     }
                     x => {
+                        if let Some(roles) = compo::Roles::new(x, acc.roles) {
+                            dyn_builder.add(roles);
+                        }
                         let a = acc.simple.children.into_boxed_slice();
                         dyn_builder.add(compo::Size(size));
                         dyn_builder.add(compo::SizeNoSpaces(size_no_spaces));
@@ -1050,6 +1071,7 @@ where
                     children: vec![],
                 },
                 no_space: vec![],
+                roles: vec![],
             }
         };
         for c in cs {
@@ -1112,8 +1134,10 @@ where
 
             let insertion = node_store.prepare_insertion(&hashable, eq);
 
-            let local = if let Some(id) = insertion.occupied_id() {
-                let md = self.md_cache.get(&id).unwrap();
+            let cached = insertion
+                .occupied_id()
+                .and_then(|id| self.md_cache.get(&id).map(|md| (id, md)));
+            let local = if let Some((id, md)) = cached {
                 let ana = md.ana.clone();
                 let metrics = md.metrics;
                 let mcc = md.mcc.clone();
@@ -1131,18 +1155,22 @@ where
 
                 let mcc = Mcc::new(&acc.simple.kind);
 
-                let compressed_node = compress(
-                    label_id,
-                    &ana,
-                    acc.simple,
-                    acc.no_space,
-                    size,
-                    height,
-                    size_no_spaces,
-                    insertion,
-                    mcc.clone(),
-                    base,
-                );
+                let compressed_node = if let Some(id) = insertion.occupied_id() {
+                    id
+                } else {
+                    compress(
+                        label_id,
+                        &ana,
+                        acc.simple,
+                        acc.no_space,
+                        size,
+                        height,
+                        size_no_spaces,
+                        insertion,
+                        mcc.clone(),
+                        base,
+                    )
+                };
 
                 let metrics = SubTreeMetrics {
                     size,
@@ -636,6 +636,14 @@ impl Display for Type {
     }
 }
 
+impl TryFrom<&str> for Type {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Type::from_str(value).ok_or(())
+    }
+}
+
 const COUNT: u16 = 286 + 1 + 2;
 #[repr(u16)]
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
@@ -0,0 +1,2 @@
+mod simple;
+mod types;
@@ -0,0 +1,94 @@
+use hyper_ast::{
+    store::defaults::NodeIdentifier,
+    types::{LabelStore as _, Labeled, Typed, WithChildren},
+};
+
+use crate::types::{TIdN, TStore, Type};
+
+type PythonTreeGen<'store, 'cache> = crate::legion::PythonTreeGen<'store, 'cache, TStore>;
+type SimpleStores = hyper_ast::store::SimpleStores<TStore>;
+
+/// the first child of `id` of type `t`
+fn child_of_type(stores: &SimpleStores, id: NodeIdentifier, t: Type) -> Option<NodeIdentifier> {
+    let n = stores.node_store.resolve(id);
+    (0..n.child_count())
+        .filter_map(|i| n.child(&i))
+        .find(|x| {
+            stores
+                .node_store
+                .try_resolve_typed::<TIdN<_>>(x)
+                .map_or(false, |(x, _)| x.get_type() == t)
+        })
+}
+
+fn label(stores: &SimpleStores, id: NodeIdentifier) -> &str {
+    let n = stores.node_store.resolve(id);
+    stores.label_store.resolve(n.get_label_unchecked())
+}
+
+#[test]
+pub(crate) fn python_simple_test() {
+    let text = {
+        let source_code1 = r#"
+def main():
+    print("a {} a".format(42))
+"#;
+        source_code1.as_bytes()
+    };
+    let tree = match PythonTreeGen::tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    assert!(!tree.root_node().has_error());
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = PythonTreeGen {
+        line_break: "\n".as_bytes().to_vec(),
+        stores: &mut stores,
+        md_cache: &mut md_cache,
+    };
+    let x = tree_gen.generate_file(b"main.py", text, tree.walk()).local;
+    // the serialized tree gives back the source code
+    assert_eq!(
+        std::str::from_utf8(text).unwrap(),
+        hyper_ast::nodes::TextSerializer::new(&stores, x.compressed_node).to_string()
+    );
+    let n = stores
+        .node_store
+        .try_resolve_typed::<TIdN<_>>(&x.compressed_node)
+        .unwrap()
+        .0;
+    assert_eq!(n.get_type(), Type::Module);
+    assert_eq!(label(&stores, x.compressed_node), "main.py");
+
+    let function =
+        child_of_type(&stores, x.compressed_node, Type::FunctionDefinition).unwrap();
+    let name = child_of_type(&stores, function, Type::Identifier).unwrap();
+    assert_eq!(label(&stores, name), "main");
+    let body = child_of_type(&stores, function, Type::Block).unwrap();
+    let statement = child_of_type(&stores, body, Type::ExpressionStatement).unwrap();
+    assert!(child_of_type(&stores, statement, Type::Call).is_some());
+}
+
+#[test]
+pub(crate) fn python_dedup_test() {
+    let text = r#"a = f(1)
+a = f(1)
+"#
+    .as_bytes();
+    let tree = match PythonTreeGen::tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = PythonTreeGen {
+        line_break: "\n".as_bytes().to_vec(),
+        stores: &mut stores,
+        md_cache: &mut md_cache,
+    };
+    let x = tree_gen.generate_file(b"", text, tree.walk()).local;
+    let n = stores.node_store.resolve(x.compressed_node);
+    // both statements are the same subtree, separated by spaces
+    assert_eq!(n.child(&0).unwrap(), n.child(&2).unwrap());
+}
@@ -0,0 +1,1746 @@
+use std::fmt::Display;
+
+use hyper_ast::{
+    store::defaults::NodeIdentifier,
+    tree_gen::parser::NodeWithU16TypeId,
+    types::{AnyType, HyperType, LangRef, NodeId, TypeStore, TypeTrait, TypedNodeId},
+};
+
+#[cfg(feature = "legion")]
+mod legion_impls {
+    use super::*;
+
+    use crate::TNode;
+
+    impl<'a> TNode<'a> {
+        pub fn obtain_type<T>(&self, _: &mut impl PythonEnabledTypeStore<T>) -> Type {
+            let t = self.kind_id();
+            Type::from_u16(t)
+        }
+    }
+
+    use hyper_ast::{store::nodes::legion::HashedNodeRef, types::TypeIndex};
+
+    impl<'a> TypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        type Ty = Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Ty {
+            n.get_component::<Type>().unwrap().clone()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<Type>)>::from(&Lang)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Lang),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+    impl<'a> PythonEnabledTypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        const LANG: TypeInternalSize = Self::Python as u16;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            // T((u16::MAX - l as u16) | t)
+            todo!()
+        }
+        fn intern(&self, t: Type) -> Self::Ty {
+            t
+        }
+
+        fn resolve(&self, t: Self::Ty) -> Type {
+            t
+        }
+    }
+    impl<'a> TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TStore {
+        type Ty = AnyType;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Ty {
+            From::<&'static (dyn HyperType)>::from(LangRef::<Type>::make(
+                &Lang,
+                *n.get_component::<Type>().unwrap() as u16,
+            ))
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, NodeIdentifier>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<AnyType>)>::from(&Lang)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Lang),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+}
+
+pub trait PythonEnabledTypeStore<T>: TypeStore<T> {
+    const LANG: u16;
+    fn intern(&self, t: Type) -> Self::Ty {
+        let t = t as u16;
+        Self::_intern(Self::LANG, t)
+    }
+    fn _intern(l: u16, t: u16) -> Self::Ty;
+    fn resolve(&self, t: Self::Ty) -> Type;
+}
+
+impl Type {
+    pub fn resolve(t: u16) -> Self {
+        assert!(t < COUNT);
+        unsafe { std::mem::transmute(t) }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TIdN<IdN>(IdN);
+
+impl<IdN: Clone + Eq + NodeId> NodeId for TIdN<IdN> {
+    type IdN = IdN;
+
+    fn as_id(&self) -> &Self::IdN {
+        &self.0
+    }
+
+    unsafe fn from_id(id: Self::IdN) -> Self {
+        Self(id)
+    }
+
+    unsafe fn from_ref_id(id: &Self::IdN) -> &Self {
+        todo!()
+    }
+}
+
+impl<IdN: Clone + Eq + NodeId> TypedNodeId for TIdN<IdN> {
+    type Ty = Type;
+}
+
+#[repr(u8)]
+pub enum TStore {
+    Python = 0,
+}
+
+impl Default for TStore {
+    fn default() -> Self {
+        Self::Python
+    }
+}
+
+type TypeInternalSize = u16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct T(TypeInternalSize);
+
+pub struct Lang;
+pub type Python = Lang;
+
+pub fn as_any(t: &Type) -> AnyType {
+    let t = <Python as hyper_ast::types::Lang<Type>>::to_u16(*t);
+    let t = <Python as hyper_ast::types::Lang<Type>>::make(t);
+    let t: &'static dyn HyperType = t;
+    t.into()
+}
+
+impl LangRef<AnyType> for Python {
+    fn make(&self, t: u16) -> &'static AnyType {
+        panic!()
+        // &From::<&'static dyn HyperType>::from(&S_T_L[t as usize])
+    }
+    fn to_u16(&self, t: AnyType) -> u16 {
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Python>()
+    }
+}
+
+impl LangRef<Type> for Python {
+    fn make(&self, t: u16) -> &'static Type {
+        &S_T_L[t as usize]
+    }
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Python>()
+    }
+}
+
+impl hyper_ast::types::Lang<Type> for Python {
+    fn make(t: u16) -> &'static Type {
+        Lang.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Lang.to_u16(t)
+    }
+}
+
+impl HyperType for Type {
+    fn generic_eq(&self, other: &dyn HyperType) -> bool
+    where
+        Self: 'static + PartialEq + Sized,
+    {
+        // Do a type-safe casting. If the types are different,
+        // return false, otherwise test the values for equality.
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn is_directory(&self) -> bool {
+        self == &Type::Directory
+    }
+
+    fn is_file(&self) -> bool {
+        self == &Type::Module
+    }
+
+    fn is_spaces(&self) -> bool {
+        self == &Type::Spaces
+    }
+
+    fn is_syntax(&self) -> bool {
+        matches!(
+            self,
+            Type::LParen
+                | Type::RParen
+                | Type::LBrace
+                | Type::RBrace
+                | Type::LBracket
+                | Type::RBracket
+                | Type::SemiColon
+                | Type::Colon
+                | Type::Comma
+                | Type::Dot
+                | Type::DashGt
+                | Type::Ellipsis
+        )
+    }
+
+    fn as_shared(&self) -> hyper_ast::types::Shared {
+        use hyper_ast::types::Shared;
+        match self {
+            Type::ClassDefinition => Shared::TypeDeclaration,
+            Type::Comment => Shared::Comment,
+            Type::Identifier => Shared::Identifier,
+            Type::DottedName => Shared::Identifier,
+            _ => Shared::Other,
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_static(&self) -> &'static dyn HyperType {
+        let t = <Python as hyper_ast::types::Lang<Type>>::to_u16(*self);
+        let t = <Python as hyper_ast::types::Lang<Type>>::make(t);
+        t
+    }
+
+    fn get_lang(&self) -> hyper_ast::types::LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        From::<&'static (dyn LangRef<Self>)>::from(&Lang)
+    }
+}
+impl TypeTrait for Type {
+    type Lang = Python;
+
+    fn is_fork(&self) -> bool {
+        todo!()
+    }
+
+    fn is_literal(&self) -> bool {
+        todo!()
+    }
+
+    fn is_primitive(&self) -> bool {
+        todo!()
+    }
+
+    fn is_type_declaration(&self) -> bool {
+        todo!()
+    }
+
+    fn is_identifier(&self) -> bool {
+        todo!()
+    }
+
+    fn is_instance_ref(&self) -> bool {
+        todo!()
+    }
+
+    fn is_type_body(&self) -> bool {
+        todo!()
+    }
+
+    fn is_value_member(&self) -> bool {
+        todo!()
+    }
+
+    fn is_executable_member(&self) -> bool {
+        todo!()
+    }
+
+    fn is_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_declarative_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_structural_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_block_related(&self) -> bool {
+        todo!()
+    }
+
+    fn is_simple_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_local_declare(&self) -> bool {
+        todo!()
+    }
+
+    fn is_parameter(&self) -> bool {
+        todo!()
+    }
+
+    fn is_parameter_list(&self) -> bool {
+        todo!()
+    }
+
+    fn is_argument_list(&self) -> bool {
+        todo!()
+    }
+
+    fn is_expression(&self) -> bool {
+        todo!()
+    }
+
+    fn is_comment(&self) -> bool {
+        todo!()
+    }
+}
+
+// 269 + directory + spaces + error
+const COUNT: u16 = 272;
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl TryFrom<&str> for Type {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Type::from_str(value).ok_or(())
+    }
+}
+
+#[repr(u16)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Type {
+    End,
+    Identifier,
+    SemiColon,
+    Import,
+    Dot,
+    From,
+    TS9,
+    LParen,
+    RParen,
+    Comma,
+    As,
+    Star,
+    Print,
+    GtGt,
+    Assert,
+    ColonEq,
+    Return,
+    Del,
+    Raise,
+    Pass,
+    Break,
+    Continue,
+    If,
+    Colon,
+    Elif,
+    Else,
+    Match,
+    Case,
+    Async,
+    For,
+    In,
+    While,
+    Try,
+    Except,
+    TS0,
+    Finally,
+    With,
+    Def,
+    DashGt,
+    StarStar,
+    Global,
+    Nonlocal,
+    Exec,
+    TS1,
+    Eq,
+    Class,
+    LBracket,
+    RBracket,
+    At,
+    Dash,
+    TS10,
+    Pipe,
+    LBrace,
+    RBrace,
+    Plus,
+    Not,
+    And,
+    Or,
+    Slash,
+    Percent,
+    SlashSlash,
+    Amp,
+    Caret,
+    LtLt,
+    Tilde,
+    LT,
+    LTEq,
+    EqEq,
+    BangEq,
+    GTEq,
+    GT,
+    LTGT,
+    Is,
+    TS2,
+    PlusEq,
+    DashEq,
+    StarEq,
+    SlashEq,
+    AtEq,
+    SlashSlashEq,
+    PercentEq,
+    StarStarEq,
+    GtGtEq,
+    LtLtEq,
+    AmpEq,
+    CaretEq,
+    PipeEq,
+    TS3,
+    Ellipsis,
+    EscapeSequence,
+    NotEscapeSequence,
+    FormatSpecifierToken1,
+    TypeConversion,
+    Integer,
+    Float,
+    TS4,
+    True,
+    False,
+    None,
+    Comment,
+    LineContinuation,
+    Newline,
+    Indent,
+    Dedent,
+    StringStart,
+    TS7,
+    EscapeInterpolation,
+    StringEnd,
+    Module,
+    Statement,
+    SimpleStatements,
+    ImportStatement,
+    ImportPrefix,
+    RelativeImport,
+    FutureImportStatement,
+    ImportFromStatement,
+    ImportList,
+    AliasedImport,
+    WildcardImport,
+    PrintStatement,
+    Chevron,
+    AssertStatement,
+    ExpressionStatement,
+    NamedExpression,
+    NamedExpressionLhs,
+    ReturnStatement,
+    DeleteStatement,
+    RaiseStatement,
+    PassStatement,
+    BreakStatement,
+    ContinueStatement,
+    IfStatement,
+    ElifClause,
+    ElseClause,
+    MatchStatement,
+    Block,
+    CaseClause,
+    ForStatement,
+    WhileStatement,
+    TryStatement,
+    ExceptClause,
+    ExceptGroupClause,
+    FinallyClause,
+    WithStatement,
+    WithClause,
+    WithItem,
+    FunctionDefinition,
+    Parameters,
+    LambdaParameters,
+    ListSplat,
+    DictionarySplat,
+    GlobalStatement,
+    NonlocalStatement,
+    ExecStatement,
+    TypeAliasStatement,
+    ClassDefinition,
+    TypeParameter,
+    ParenthesizedListSplat,
+    ArgumentList,
+    DecoratedDefinition,
+    Decorator,
+    ExpressionList,
+    DottedName,
+    CasePattern,
+    SimplePattern,
+    AsPattern,
+    UnionPattern,
+    ListPattern,
+    TuplePattern,
+    DictPattern,
+    KeyValuePattern,
+    KeywordPattern,
+    SplatPattern,
+    ClassPattern,
+    ComplexPattern,
+    TS8,
+    Patterns,
+    Parameter,
+    Pattern,
+    DefaultParameter,
+    TypedDefaultParameter,
+    ListSplatPattern,
+    DictionarySplatPattern,
+    ExpressionWithinForInClause,
+    Expression,
+    PrimaryExpression,
+    NotOperator,
+    BooleanOperator,
+    BinaryOperator,
+    UnaryOperator,
+    ComparisonOperator,
+    Lambda,
+    Assignment,
+    AugmentedAssignment,
+    PatternList,
+    RightHandSide,
+    Yield,
+    Attribute,
+    Subscript,
+    Slice,
+    Call,
+    TypedParameter,
+    Type,
+    SplatType,
+    GenericType,
+    UnionType,
+    ConstrainedType,
+    MemberType,
+    KeywordArgument,
+    List,
+    Set,
+    Tuple,
+    Dictionary,
+    Pair,
+    ListComprehension,
+    DictionaryComprehension,
+    SetComprehension,
+    GeneratorExpression,
+    ComprehensionClauses,
+    ParenthesizedExpression,
+    CollectionElements,
+    ForInClause,
+    IfClause,
+    ConditionalExpression,
+    ConcatenatedString,
+    String,
+    StringContent,
+    Interpolation,
+    FExpression,
+    FormatSpecifier,
+    Await,
+    PositionalSeparator,
+    KeywordSeparator,
+    ModuleRepeat1,
+    TS11,
+    ImportPrefixRepeat1,
+    TS12,
+    PrintStatementRepeat1,
+    AssertStatementRepeat1,
+    IfStatementRepeat1,
+    MatchStatementRepeat1,
+    TS13,
+    CaseClauseRepeat1,
+    TryStatementRepeat1,
+    TryStatementRepeat2,
+    WithClauseRepeat1,
+    GlobalStatementRepeat1,
+    TypeParameterRepeat1,
+    ArgumentListRepeat1,
+    DecoratedDefinitionRepeat1,
+    DottedNameRepeat1,
+    UnionPatternRepeat1,
+    DictPatternRepeat1,
+    TS14,
+    TS15,
+    ComparisonOperatorRepeat1,
+    SubscriptRepeat1,
+    DictionaryRepeat1,
+    TS16,
+    TS17,
+    ForInClauseRepeat1,
+    ConcatenatedStringRepeat1,
+    StringRepeat1,
+    StringContentRepeat1,
+    FormatSpecifierRepeat1,
+    AsPatternTarget,
+    FormatExpression,
+    TS5,
+    TS6,
+    Spaces,
+    Directory,
+    ERROR,
+}
+impl Type {
+    pub fn from_u16(t: u16) -> Type {
+        match t {
+            0u16 => Type::End,
+            1u16 => Type::Identifier,
+            2u16 => Type::SemiColon,
+            3u16 => Type::Import,
+            4u16 => Type::Dot,
+            5u16 => Type::From,
+            6u16 => Type::TS9,
+            7u16 => Type::LParen,
+            8u16 => Type::RParen,
+            9u16 => Type::Comma,
+            10u16 => Type::As,
+            11u16 => Type::Star,
+            12u16 => Type::Print,
+            13u16 => Type::GtGt,
+            14u16 => Type::Assert,
+            15u16 => Type::ColonEq,
+            16u16 => Type::Return,
+            17u16 => Type::Del,
+            18u16 => Type::Raise,
+            19u16 => Type::Pass,
+            20u16 => Type::Break,
+            21u16 => Type::Continue,
+            22u16 => Type::If,
+            23u16 => Type::Colon,
+            24u16 => Type::Elif,
+            25u16 => Type::Else,
+            26u16 => Type::Match,
+            27u16 => Type::Case,
+            28u16 => Type::Async,
+            29u16 => Type::For,
+            30u16 => Type::In,
+            31u16 => Type::While,
+            32u16 => Type::Try,
+            33u16 => Type::Except,
+            34u16 => Type::TS0,
+            35u16 => Type::Finally,
+            36u16 => Type::With,
+            37u16 => Type::Def,
+            38u16 => Type::DashGt,
+            39u16 => Type::StarStar,
+            40u16 => Type::Global,
+            41u16 => Type::Nonlocal,
+            42u16 => Type::Exec,
+            43u16 => Type::TS1,
+            44u16 => Type::Eq,
+            45u16 => Type::Class,
+            46u16 => Type::LBracket,
+            47u16 => Type::RBracket,
+            48u16 => Type::At,
+            49u16 => Type::Dash,
+            50u16 => Type::TS10,
+            51u16 => Type::Pipe,
+            52u16 => Type::LBrace,
+            53u16 => Type::RBrace,
+            54u16 => Type::Plus,
+            55u16 => Type::Not,
+            56u16 => Type::And,
+            57u16 => Type::Or,
+            58u16 => Type::Slash,
+            59u16 => Type::Percent,
+            60u16 => Type::SlashSlash,
+            61u16 => Type::Amp,
+            62u16 => Type::Caret,
+            63u16 => Type::LtLt,
+            64u16 => Type::Tilde,
+            65u16 => Type::LT,
+            66u16 => Type::LTEq,
+            67u16 => Type::EqEq,
+            68u16 => Type::BangEq,
+            69u16 => Type::GTEq,
+            70u16 => Type::GT,
+            71u16 => Type::LTGT,
+            72u16 => Type::Is,
+            73u16 => Type::TS2,
+            74u16 => Type::PlusEq,
+            75u16 => Type::DashEq,
+            76u16 => Type::StarEq,
+            77u16 => Type::SlashEq,
+            78u16 => Type::AtEq,
+            79u16 => Type::SlashSlashEq,
+            80u16 => Type::PercentEq,
+            81u16 => Type::StarStarEq,
+            82u16 => Type::GtGtEq,
+            83u16 => Type::LtLtEq,
+            84u16 => Type::AmpEq,
+            85u16 => Type::CaretEq,
+            86u16 => Type::PipeEq,
+            87u16 => Type::TS3,
+            88u16 => Type::Ellipsis,
+            89u16 => Type::EscapeSequence,
+            90u16 => Type::NotEscapeSequence,
+            91u16 => Type::FormatSpecifierToken1,
+            92u16 => Type::TypeConversion,
+            93u16 => Type::Integer,
+            94u16 => Type::Float,
+            95u16 => Type::TS4,
+            96u16 => Type::True,
+            97u16 => Type::False,
+            98u16 => Type::None,
+            99u16 => Type::Comment,
+            100u16 => Type::LineContinuation,
+            101u16 => Type::Newline,
+            102u16 => Type::Indent,
+            103u16 => Type::Dedent,
+            104u16 => Type::StringStart,
+            105u16 => Type::TS7,
+            106u16 => Type::EscapeInterpolation,
+            107u16 => Type::StringEnd,
+            108u16 => Type::Module,
+            109u16 => Type::Statement,
+            110u16 => Type::SimpleStatements,
+            111u16 => Type::ImportStatement,
+            112u16 => Type::ImportPrefix,
+            113u16 => Type::RelativeImport,
+            114u16 => Type::FutureImportStatement,
+            115u16 => Type::ImportFromStatement,
+            116u16 => Type::ImportList,
+            117u16 => Type::AliasedImport,
+            118u16 => Type::WildcardImport,
+            119u16 => Type::PrintStatement,
+            120u16 => Type::Chevron,
+            121u16 => Type::AssertStatement,
+            122u16 => Type::ExpressionStatement,
+            123u16 => Type::NamedExpression,
+            124u16 => Type::NamedExpressionLhs,
+            125u16 => Type::ReturnStatement,
+            126u16 => Type::DeleteStatement,
+            127u16 => Type::RaiseStatement,
+            128u16 => Type::PassStatement,
+            129u16 => Type::BreakStatement,
+            130u16 => Type::ContinueStatement,
+            131u16 => Type::IfStatement,
+            132u16 => Type::ElifClause,
+            133u16 => Type::ElseClause,
+            134u16 => Type::MatchStatement,
+            135u16 => Type::Block,
+            136u16 => Type::CaseClause,
+            137u16 => Type::ForStatement,
+            138u16 => Type::WhileStatement,
+            139u16 => Type::TryStatement,
+            140u16 => Type::ExceptClause,
+            141u16 => Type::ExceptGroupClause,
+            142u16 => Type::FinallyClause,
+            143u16 => Type::WithStatement,
+            144u16 => Type::WithClause,
+            145u16 => Type::WithItem,
+            146u16 => Type::FunctionDefinition,
+            147u16 => Type::Parameters,
+            148u16 => Type::LambdaParameters,
+            149u16 => Type::ListSplat,
+            150u16 => Type::DictionarySplat,
+            151u16 => Type::GlobalStatement,
+            152u16 => Type::NonlocalStatement,
+            153u16 => Type::ExecStatement,
+            154u16 => Type::TypeAliasStatement,
+            155u16 => Type::ClassDefinition,
+            156u16 => Type::TypeParameter,
+            157u16 => Type::ParenthesizedListSplat,
+            158u16 => Type::ArgumentList,
+            159u16 => Type::DecoratedDefinition,
+            160u16 => Type::Decorator,
+            161u16 => Type::Block,
+            162u16 => Type::ExpressionList,
+            163u16 => Type::DottedName,
+            164u16 => Type::CasePattern,
+            165u16 => Type::SimplePattern,
+            166u16 => Type::AsPattern,
+            167u16 => Type::UnionPattern,
+            168u16 => Type::ListPattern,
+            169u16 => Type::TuplePattern,
+            170u16 => Type::DictPattern,
+            171u16 => Type::KeyValuePattern,
+            172u16 => Type::KeywordPattern,
+            173u16 => Type::SplatPattern,
+            174u16 => Type::ClassPattern,
+            175u16 => Type::ComplexPattern,
+            176u16 => Type::TS8,
+            177u16 => Type::Patterns,
+            178u16 => Type::Parameter,
+            179u16 => Type::Pattern,
+            180u16 => Type::TuplePattern,
+            181u16 => Type::ListPattern,
+            182u16 => Type::DefaultParameter,
+            183u16 => Type::TypedDefaultParameter,
+            184u16 => Type::ListSplatPattern,
+            185u16 => Type::DictionarySplatPattern,
+            186u16 => Type::AsPattern,
+            187u16 => Type::ExpressionWithinForInClause,
+            188u16 => Type::Expression,
+            189u16 => Type::PrimaryExpression,
+            190u16 => Type::NotOperator,
+            191u16 => Type::BooleanOperator,
+            192u16 => Type::BinaryOperator,
+            193u16 => Type::UnaryOperator,
+            194u16 => Type::ComparisonOperator,
+            195u16 => Type::Lambda,
+            196u16 => Type::Lambda,
+            197u16 => Type::Assignment,
+            198u16 => Type::AugmentedAssignment,
+            199u16 => Type::PatternList,
+            200u16 => Type::RightHandSide,
+            201u16 => Type::Yield,
+            202u16 => Type::Attribute,
+            203u16 => Type::Subscript,
+            204u16 => Type::Slice,
+            205u16 => Type::Call,
+            206u16 => Type::TypedParameter,
+            207u16 => Type::Type,
+            208u16 => Type::SplatType,
+            209u16 => Type::GenericType,
+            210u16 => Type::UnionType,
+            211u16 => Type::ConstrainedType,
+            212u16 => Type::MemberType,
+            213u16 => Type::KeywordArgument,
+            214u16 => Type::List,
+            215u16 => Type::Set,
+            216u16 => Type::Tuple,
+            217u16 => Type::Dictionary,
+            218u16 => Type::Pair,
+            219u16 => Type::ListComprehension,
+            220u16 => Type::DictionaryComprehension,
+            221u16 => Type::SetComprehension,
+            222u16 => Type::GeneratorExpression,
+            223u16 => Type::ComprehensionClauses,
+            224u16 => Type::ParenthesizedExpression,
+            225u16 => Type::CollectionElements,
+            226u16 => Type::ForInClause,
+            227u16 => Type::IfClause,
+            228u16 => Type::ConditionalExpression,
+            229u16 => Type::ConcatenatedString,
+            230u16 => Type::String,
+            231u16 => Type::StringContent,
+            232u16 => Type::Interpolation,
+            233u16 => Type::FExpression,
+            234u16 => Type::FormatSpecifier,
+            235u16 => Type::Await,
+            236u16 => Type::PositionalSeparator,
+            237u16 => Type::KeywordSeparator,
+            238u16 => Type::ModuleRepeat1,
+            239u16 => Type::TS11,
+            240u16 => Type::ImportPrefixRepeat1,
+            241u16 => Type::TS12,
+            242u16 => Type::PrintStatementRepeat1,
+            243u16 => Type::AssertStatementRepeat1,
+            244u16 => Type::IfStatementRepeat1,
+            245u16 => Type::MatchStatementRepeat1,
+            246u16 => Type::TS13,
+            247u16 => Type::CaseClauseRepeat1,
+            248u16 => Type::TryStatementRepeat1,
+            249u16 => Type::TryStatementRepeat2,
+            250u16 => Type::WithClauseRepeat1,
+            251u16 => Type::GlobalStatementRepeat1,
+            252u16 => Type::TypeParameterRepeat1,
+            253u16 => Type::ArgumentListRepeat1,
+            254u16 => Type::DecoratedDefinitionRepeat1,
+            255u16 => Type::DottedNameRepeat1,
+            256u16 => Type::UnionPatternRepeat1,
+            257u16 => Type::DictPatternRepeat1,
+            258u16 => Type::TS14,
+            259u16 => Type::TS15,
+            260u16 => Type::ComparisonOperatorRepeat1,
+            261u16 => Type::SubscriptRepeat1,
+            262u16 => Type::DictionaryRepeat1,
+            263u16 => Type::TS16,
+            264u16 => Type::TS17,
+            265u16 => Type::ForInClauseRepeat1,
+            266u16 => Type::ConcatenatedStringRepeat1,
+            267u16 => Type::StringRepeat1,
+            268u16 => Type::StringContentRepeat1,
+            269u16 => Type::FormatSpecifierRepeat1,
+            270u16 => Type::AsPatternTarget,
+            271u16 => Type::FormatExpression,
+            272u16 => Type::TS5,
+            273u16 => Type::TS6,
+            u16::MAX => Type::ERROR,
+            x => panic!("{}", x),
+        }
+    }
+    pub fn from_str(t: &str) -> Option<Type> {
+        Some(match t {
+            "end" => Type::End,
+            "identifier" => Type::Identifier,
+            ";" => Type::SemiColon,
+            "import" => Type::Import,
+            "." => Type::Dot,
+            "from" => Type::From,
+            "__future__" => Type::TS9,
+            "(" => Type::LParen,
+            ")" => Type::RParen,
+            "," => Type::Comma,
+            "as" => Type::As,
+            "*" => Type::Star,
+            "print" => Type::Print,
+            ">>" => Type::GtGt,
+            "assert" => Type::Assert,
+            ":=" => Type::ColonEq,
+            "return" => Type::Return,
+            "del" => Type::Del,
+            "raise" => Type::Raise,
+            "pass" => Type::Pass,
+            "break" => Type::Break,
+            "continue" => Type::Continue,
+            "if" => Type::If,
+            ":" => Type::Colon,
+            "elif" => Type::Elif,
+            "else" => Type::Else,
+            "match" => Type::Match,
+            "case" => Type::Case,
+            "async" => Type::Async,
+            "for" => Type::For,
+            "in" => Type::In,
+            "while" => Type::While,
+            "try" => Type::Try,
+            "except" => Type::Except,
+            "except*" => Type::TS0,
+            "finally" => Type::Finally,
+            "with" => Type::With,
+            "def" => Type::Def,
+            "->" => Type::DashGt,
+            "**" => Type::StarStar,
+            "global" => Type::Global,
+            "nonlocal" => Type::Nonlocal,
+            "exec" => Type::Exec,
+            "type" => Type::TS1,
+            "=" => Type::Eq,
+            "class" => Type::Class,
+            "[" => Type::LBracket,
+            "]" => Type::RBracket,
+            "@" => Type::At,
+            "-" => Type::Dash,
+            "_" => Type::TS10,
+            "|" => Type::Pipe,
+            "{" => Type::LBrace,
+            "}" => Type::RBrace,
+            "+" => Type::Plus,
+            "not" => Type::Not,
+            "and" => Type::And,
+            "or" => Type::Or,
+            "/" => Type::Slash,
+            "%" => Type::Percent,
+            "//" => Type::SlashSlash,
+            "&" => Type::Amp,
+            "^" => Type::Caret,
+            "<<" => Type::LtLt,
+            "~" => Type::Tilde,
+            "<" => Type::LT,
+            "<=" => Type::LTEq,
+            "==" => Type::EqEq,
+            "!=" => Type::BangEq,
+            ">=" => Type::GTEq,
+            ">" => Type::GT,
+            "<>" => Type::LTGT,
+            "is" => Type::Is,
+            "lambda" => Type::TS2,
+            "+=" => Type::PlusEq,
+            "-=" => Type::DashEq,
+            "*=" => Type::StarEq,
+            "/=" => Type::SlashEq,
+            "@=" => Type::AtEq,
+            "//=" => Type::SlashSlashEq,
+            "%=" => Type::PercentEq,
+            "**=" => Type::StarStarEq,
+            ">>=" => Type::GtGtEq,
+            "<<=" => Type::LtLtEq,
+            "&=" => Type::AmpEq,
+            "^=" => Type::CaretEq,
+            "|=" => Type::PipeEq,
+            "yield" => Type::TS3,
+            "ellipsis" => Type::Ellipsis,
+            "escape_sequence" => Type::EscapeSequence,
+            "_not_escape_sequence" => Type::NotEscapeSequence,
+            "format_specifier_token1" => Type::FormatSpecifierToken1,
+            "type_conversion" => Type::TypeConversion,
+            "integer" => Type::Integer,
+            "float" => Type::Float,
+            "await" => Type::TS4,
+            "true" => Type::True,
+            "false" => Type::False,
+            "none" => Type::None,
+            "comment" => Type::Comment,
+            "line_continuation" => Type::LineContinuation,
+            "_newline" => Type::Newline,
+            "_indent" => Type::Indent,
+            "_dedent" => Type::Dedent,
+            "string_start" => Type::StringStart,
+            "_string_content" => Type::TS7,
+            "escape_interpolation" => Type::EscapeInterpolation,
+            "string_end" => Type::StringEnd,
+            "module" => Type::Module,
+            "_statement" => Type::Statement,
+            "_simple_statements" => Type::SimpleStatements,
+            "import_statement" => Type::ImportStatement,
+            "import_prefix" => Type::ImportPrefix,
+            "relative_import" => Type::RelativeImport,
+            "future_import_statement" => Type::FutureImportStatement,
+            "import_from_statement" => Type::ImportFromStatement,
+            "_import_list" => Type::ImportList,
+            "aliased_import" => Type::AliasedImport,
+            "wildcard_import" => Type::WildcardImport,
+            "print_statement" => Type::PrintStatement,
+            "chevron" => Type::Chevron,
+            "assert_statement" => Type::AssertStatement,
+            "expression_statement" => Type::ExpressionStatement,
+            "named_expression" => Type::NamedExpression,
+            "_named_expression_lhs" => Type::NamedExpressionLhs,
+            "return_statement" => Type::ReturnStatement,
+            "delete_statement" => Type::DeleteStatement,
+            "raise_statement" => Type::RaiseStatement,
+            "pass_statement" => Type::PassStatement,
+            "break_statement" => Type::BreakStatement,
+            "continue_statement" => Type::ContinueStatement,
+            "if_statement" => Type::IfStatement,
+            "elif_clause" => Type::ElifClause,
+            "else_clause" => Type::ElseClause,
+            "match_statement" => Type::MatchStatement,
+            "block" => Type::Block,
+            "case_clause" => Type::CaseClause,
+            "for_statement" => Type::ForStatement,
+            "while_statement" => Type::WhileStatement,
+            "try_statement" => Type::TryStatement,
+            "except_clause" => Type::ExceptClause,
+            "except_group_clause" => Type::ExceptGroupClause,
+            "finally_clause" => Type::FinallyClause,
+            "with_statement" => Type::WithStatement,
+            "with_clause" => Type::WithClause,
+            "with_item" => Type::WithItem,
+            "function_definition" => Type::FunctionDefinition,
+            "parameters" => Type::Parameters,
+            "lambda_parameters" => Type::LambdaParameters,
+            "list_splat" => Type::ListSplat,
+            "dictionary_splat" => Type::DictionarySplat,
+            "global_statement" => Type::GlobalStatement,
+            "nonlocal_statement" => Type::NonlocalStatement,
+            "exec_statement" => Type::ExecStatement,
+            "type_alias_statement" => Type::TypeAliasStatement,
+            "class_definition" => Type::ClassDefinition,
+            "type_parameter" => Type::TypeParameter,
+            "parenthesized_list_splat" => Type::ParenthesizedListSplat,
+            "argument_list" => Type::ArgumentList,
+            "decorated_definition" => Type::DecoratedDefinition,
+            "decorator" => Type::Decorator,
+            "expression_list" => Type::ExpressionList,
+            "dotted_name" => Type::DottedName,
+            "case_pattern" => Type::CasePattern,
+            "_simple_pattern" => Type::SimplePattern,
+            "as_pattern" => Type::AsPattern,
+            "union_pattern" => Type::UnionPattern,
+            "list_pattern" => Type::ListPattern,
+            "tuple_pattern" => Type::TuplePattern,
+            "dict_pattern" => Type::DictPattern,
+            "_key_value_pattern" => Type::KeyValuePattern,
+            "keyword_pattern" => Type::KeywordPattern,
+            "splat_pattern" => Type::SplatPattern,
+            "class_pattern" => Type::ClassPattern,
+            "complex_pattern" => Type::ComplexPattern,
+            "_parameters" => Type::TS8,
+            "_patterns" => Type::Patterns,
+            "parameter" => Type::Parameter,
+            "pattern" => Type::Pattern,
+            "default_parameter" => Type::DefaultParameter,
+            "typed_default_parameter" => Type::TypedDefaultParameter,
+            "list_splat_pattern" => Type::ListSplatPattern,
+            "dictionary_splat_pattern" => Type::DictionarySplatPattern,
+            "_expression_within_for_in_clause" => Type::ExpressionWithinForInClause,
+            "expression" => Type::Expression,
+            "primary_expression" => Type::PrimaryExpression,
+            "not_operator" => Type::NotOperator,
+            "boolean_operator" => Type::BooleanOperator,
+            "binary_operator" => Type::BinaryOperator,
+            "unary_operator" => Type::UnaryOperator,
+            "comparison_operator" => Type::ComparisonOperator,
+            "assignment" => Type::Assignment,
+            "augmented_assignment" => Type::AugmentedAssignment,
+            "pattern_list" => Type::PatternList,
+            "_right_hand_side" => Type::RightHandSide,
+            "attribute" => Type::Attribute,
+            "subscript" => Type::Subscript,
+            "slice" => Type::Slice,
+            "call" => Type::Call,
+            "typed_parameter" => Type::TypedParameter,
+            "splat_type" => Type::SplatType,
+            "generic_type" => Type::GenericType,
+            "union_type" => Type::UnionType,
+            "constrained_type" => Type::ConstrainedType,
+            "member_type" => Type::MemberType,
+            "keyword_argument" => Type::KeywordArgument,
+            "list" => Type::List,
+            "set" => Type::Set,
+            "tuple" => Type::Tuple,
+            "dictionary" => Type::Dictionary,
+            "pair" => Type::Pair,
+            "list_comprehension" => Type::ListComprehension,
+            "dictionary_comprehension" => Type::DictionaryComprehension,
+            "set_comprehension" => Type::SetComprehension,
+            "generator_expression" => Type::GeneratorExpression,
+            "_comprehension_clauses" => Type::ComprehensionClauses,
+            "parenthesized_expression" => Type::ParenthesizedExpression,
+            "_collection_elements" => Type::CollectionElements,
+            "for_in_clause" => Type::ForInClause,
+            "if_clause" => Type::IfClause,
+            "conditional_expression" => Type::ConditionalExpression,
+            "concatenated_string" => Type::ConcatenatedString,
+            "string" => Type::String,
+            "string_content" => Type::StringContent,
+            "interpolation" => Type::Interpolation,
+            "_f_expression" => Type::FExpression,
+            "format_specifier" => Type::FormatSpecifier,
+            "positional_separator" => Type::PositionalSeparator,
+            "keyword_separator" => Type::KeywordSeparator,
+            "module_repeat1" => Type::ModuleRepeat1,
+            "_simple_statements_repeat1" => Type::TS11,
+            "import_prefix_repeat1" => Type::ImportPrefixRepeat1,
+            "_import_list_repeat1" => Type::TS12,
+            "print_statement_repeat1" => Type::PrintStatementRepeat1,
+            "assert_statement_repeat1" => Type::AssertStatementRepeat1,
+            "if_statement_repeat1" => Type::IfStatementRepeat1,
+            "match_statement_repeat1" => Type::MatchStatementRepeat1,
+            "_match_block_repeat1" => Type::TS13,
+            "case_clause_repeat1" => Type::CaseClauseRepeat1,
+            "try_statement_repeat1" => Type::TryStatementRepeat1,
+            "try_statement_repeat2" => Type::TryStatementRepeat2,
+            "with_clause_repeat1" => Type::WithClauseRepeat1,
+            "global_statement_repeat1" => Type::GlobalStatementRepeat1,
+            "type_parameter_repeat1" => Type::TypeParameterRepeat1,
+            "argument_list_repeat1" => Type::ArgumentListRepeat1,
+            "decorated_definition_repeat1" => Type::DecoratedDefinitionRepeat1,
+            "dotted_name_repeat1" => Type::DottedNameRepeat1,
+            "union_pattern_repeat1" => Type::UnionPatternRepeat1,
+            "dict_pattern_repeat1" => Type::DictPatternRepeat1,
+            "_parameters_repeat1" => Type::TS14,
+            "_patterns_repeat1" => Type::TS15,
+            "comparison_operator_repeat1" => Type::ComparisonOperatorRepeat1,
+            "subscript_repeat1" => Type::SubscriptRepeat1,
+            "dictionary_repeat1" => Type::DictionaryRepeat1,
+            "_comprehension_clauses_repeat1" => Type::TS16,
+            "_collection_elements_repeat1" => Type::TS17,
+            "for_in_clause_repeat1" => Type::ForInClauseRepeat1,
+            "concatenated_string_repeat1" => Type::ConcatenatedStringRepeat1,
+            "string_repeat1" => Type::StringRepeat1,
+            "string_content_repeat1" => Type::StringContentRepeat1,
+            "format_specifier_repeat1" => Type::FormatSpecifierRepeat1,
+            "as_pattern_target" => Type::AsPatternTarget,
+            "format_expression" => Type::FormatExpression,
+            "is not" => Type::TS5,
+            "not in" => Type::TS6,
+            "Spaces" => Type::Spaces,
+            "Directory" => Type::Directory,
+            "ERROR" => Type::ERROR,
+            _ => return None,
+        })
+    }
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::End => "end",
+            Type::Identifier => "identifier",
+            Type::SemiColon => ";",
+            Type::Import => "import",
+            Type::Dot => ".",
+            Type::From => "from",
+            Type::TS9 => "__future__",
+            Type::LParen => "(",
+            Type::RParen => ")",
+            Type::Comma => ",",
+            Type::As => "as",
+            Type::Star => "*",
+            Type::Print => "print",
+            Type::GtGt => ">>",
+            Type::Assert => "assert",
+            Type::ColonEq => ":=",
+            Type::Return => "return",
+            Type::Del => "del",
+            Type::Raise => "raise",
+            Type::Pass => "pass",
+            Type::Break => "break",
+            Type::Continue => "continue",
+            Type::If => "if",
+            Type::Colon => ":",
+            Type::Elif => "elif",
+            Type::Else => "else",
+            Type::Match => "match",
+            Type::Case => "case",
+            Type::Async => "async",
+            Type::For => "for",
+            Type::In => "in",
+            Type::While => "while",
+            Type::Try => "try",
+            Type::Except => "except",
+            Type::TS0 => "except*",
+            Type::Finally => "finally",
+            Type::With => "with",
+            Type::Def => "def",
+            Type::DashGt => "->",
+            Type::StarStar => "**",
+            Type::Global => "global",
+            Type::Nonlocal => "nonlocal",
+            Type::Exec => "exec",
+            Type::TS1 => "type",
+            Type::Eq => "=",
+            Type::Class => "class",
+            Type::LBracket => "[",
+            Type::RBracket => "]",
+            Type::At => "@",
+            Type::Dash => "-",
+            Type::TS10 => "_",
+            Type::Pipe => "|",
+            Type::LBrace => "{",
+            Type::RBrace => "}",
+            Type::Plus => "+",
+            Type::Not => "not",
+            Type::And => "and",
+            Type::Or => "or",
+            Type::Slash => "/",
+            Type::Percent => "%",
+            Type::SlashSlash => "//",
+            Type::Amp => "&",
+            Type::Caret => "^",
+            Type::LtLt => "<<",
+            Type::Tilde => "~",
+            Type::LT => "<",
+            Type::LTEq => "<=",
+            Type::EqEq => "==",
+            Type::BangEq => "!=",
+            Type::GTEq => ">=",
+            Type::GT => ">",
+            Type::LTGT => "<>",
+            Type::Is => "is",
+            Type::TS2 => "lambda",
+            Type::PlusEq => "+=",
+            Type::DashEq => "-=",
+            Type::StarEq => "*=",
+            Type::SlashEq => "/=",
+            Type::AtEq => "@=",
+            Type::SlashSlashEq => "//=",
+            Type::PercentEq => "%=",
+            Type::StarStarEq => "**=",
+            Type::GtGtEq => ">>=",
+            Type::LtLtEq => "<<=",
+            Type::AmpEq => "&=",
+            Type::CaretEq => "^=",
+            Type::PipeEq => "|=",
+            Type::TS3 => "yield",
+            Type::Ellipsis => "ellipsis",
+            Type::EscapeSequence => "escape_sequence",
+            Type::NotEscapeSequence => "_not_escape_sequence",
+            Type::FormatSpecifierToken1 => "format_specifier_token1",
+            Type::TypeConversion => "type_conversion",
+            Type::Integer => "integer",
+            Type::Float => "float",
+            Type::TS4 => "await",
+            Type::True => "true",
+            Type::False => "false",
+            Type::None => "none",
+            Type::Comment => "comment",
+            Type::LineContinuation => "line_continuation",
+            Type::Newline => "_newline",
+            Type::Indent => "_indent",
+            Type::Dedent => "_dedent",
+            Type::StringStart => "string_start",
+            Type::TS7 => "_string_content",
+            Type::EscapeInterpolation => "escape_interpolation",
+            Type::StringEnd => "string_end",
+            Type::Module => "module",
+            Type::Statement => "_statement",
+            Type::SimpleStatements => "_simple_statements",
+            Type::ImportStatement => "import_statement",
+            Type::ImportPrefix => "import_prefix",
+            Type::RelativeImport => "relative_import",
+            Type::FutureImportStatement => "future_import_statement",
+            Type::ImportFromStatement => "import_from_statement",
+            Type::ImportList => "_import_list",
+            Type::AliasedImport => "aliased_import",
+            Type::WildcardImport => "wildcard_import",
+            Type::PrintStatement => "print_statement",
+            Type::Chevron => "chevron",
+            Type::AssertStatement => "assert_statement",
+            Type::ExpressionStatement => "expression_statement",
+            Type::NamedExpression => "named_expression",
+            Type::NamedExpressionLhs => "_named_expression_lhs",
+            Type::ReturnStatement => "return_statement",
+            Type::DeleteStatement => "delete_statement",
+            Type::RaiseStatement => "raise_statement",
+            Type::PassStatement => "pass_statement",
+            Type::BreakStatement => "break_statement",
+            Type::ContinueStatement => "continue_statement",
+            Type::IfStatement => "if_statement",
+            Type::ElifClause => "elif_clause",
+            Type::ElseClause => "else_clause",
+            Type::MatchStatement => "match_statement",
+            Type::Block => "block",
+            Type::CaseClause => "case_clause",
+            Type::ForStatement => "for_statement",
+            Type::WhileStatement => "while_statement",
+            Type::TryStatement => "try_statement",
+            Type::ExceptClause => "except_clause",
+            Type::ExceptGroupClause => "except_group_clause",
+            Type::FinallyClause => "finally_clause",
+            Type::WithStatement => "with_statement",
+            Type::WithClause => "with_clause",
+            Type::WithItem => "with_item",
+            Type::FunctionDefinition => "function_definition",
+            Type::Parameters => "parameters",
+            Type::LambdaParameters => "lambda_parameters",
+            Type::ListSplat => "list_splat",
+            Type::DictionarySplat => "dictionary_splat",
+            Type::GlobalStatement => "global_statement",
+            Type::NonlocalStatement => "nonlocal_statement",
+            Type::ExecStatement => "exec_statement",
+            Type::TypeAliasStatement => "type_alias_statement",
+            Type::ClassDefinition => "class_definition",
+            Type::TypeParameter => "type_parameter",
+            Type::ParenthesizedListSplat => "parenthesized_list_splat",
+            Type::ArgumentList => "argument_list",
+            Type::DecoratedDefinition => "decorated_definition",
+            Type::Decorator => "decorator",
+            Type::ExpressionList => "expression_list",
+            Type::DottedName => "dotted_name",
+            Type::CasePattern => "case_pattern",
+            Type::SimplePattern => "_simple_pattern",
+            Type::AsPattern => "as_pattern",
+            Type::UnionPattern => "union_pattern",
+            Type::ListPattern => "list_pattern",
+            Type::TuplePattern => "tuple_pattern",
+            Type::DictPattern => "dict_pattern",
+            Type::KeyValuePattern => "_key_value_pattern",
+            Type::KeywordPattern => "keyword_pattern",
+            Type::SplatPattern => "splat_pattern",
+            Type::ClassPattern => "class_pattern",
+            Type::ComplexPattern => "complex_pattern",
+            Type::TS8 => "_parameters",
+            Type::Patterns => "_patterns",
+            Type::Parameter => "parameter",
+            Type::Pattern => "pattern",
+            Type::DefaultParameter => "default_parameter",
+            Type::TypedDefaultParameter => "typed_default_parameter",
+            Type::ListSplatPattern => "list_splat_pattern",
+            Type::DictionarySplatPattern => "dictionary_splat_pattern",
+            Type::ExpressionWithinForInClause => "_expression_within_for_in_clause",
+            Type::Expression => "expression",
+            Type::PrimaryExpression => "primary_expression",
+            Type::NotOperator => "not_operator",
+            Type::BooleanOperator => "boolean_operator",
+            Type::BinaryOperator => "binary_operator",
+            Type::UnaryOperator => "unary_operator",
+            Type::ComparisonOperator => "comparison_operator",
+            Type::Lambda => "lambda",
+            Type::Assignment => "assignment",
+            Type::AugmentedAssignment => "augmented_assignment",
+            Type::PatternList => "pattern_list",
+            Type::RightHandSide => "_right_hand_side",
+            Type::Yield => "yield",
+            Type::Attribute => "attribute",
+            Type::Subscript => "subscript",
+            Type::Slice => "slice",
+            Type::Call => "call",
+            Type::TypedParameter => "typed_parameter",
+            Type::Type => "type",
+            Type::SplatType => "splat_type",
+            Type::GenericType => "generic_type",
+            Type::UnionType => "union_type",
+            Type::ConstrainedType => "constrained_type",
+            Type::MemberType => "member_type",
+            Type::KeywordArgument => "keyword_argument",
+            Type::List => "list",
+            Type::Set => "set",
+            Type::Tuple => "tuple",
+            Type::Dictionary => "dictionary",
+            Type::Pair => "pair",
+            Type::ListComprehension => "list_comprehension",
+            Type::DictionaryComprehension => "dictionary_comprehension",
+            Type::SetComprehension => "set_comprehension",
+            Type::GeneratorExpression => "generator_expression",
+            Type::ComprehensionClauses => "_comprehension_clauses",
+            Type::ParenthesizedExpression => "parenthesized_expression",
+            Type::CollectionElements => "_collection_elements",
+            Type::ForInClause => "for_in_clause",
+            Type::IfClause => "if_clause",
+            Type::ConditionalExpression => "conditional_expression",
+            Type::ConcatenatedString => "concatenated_string",
+            Type::String => "string",
+            Type::StringContent => "string_content",
+            Type::Interpolation => "interpolation",
+            Type::FExpression => "_f_expression",
+            Type::FormatSpecifier => "format_specifier",
+            Type::Await => "await",
+            Type::PositionalSeparator => "positional_separator",
+            Type::KeywordSeparator => "keyword_separator",
+            Type::ModuleRepeat1 => "module_repeat1",
+            Type::TS11 => "_simple_statements_repeat1",
+            Type::ImportPrefixRepeat1 => "import_prefix_repeat1",
+            Type::TS12 => "_import_list_repeat1",
+            Type::PrintStatementRepeat1 => "print_statement_repeat1",
+            Type::AssertStatementRepeat1 => "assert_statement_repeat1",
+            Type::IfStatementRepeat1 => "if_statement_repeat1",
+            Type::MatchStatementRepeat1 => "match_statement_repeat1",
+            Type::TS13 => "_match_block_repeat1",
+            Type::CaseClauseRepeat1 => "case_clause_repeat1",
+            Type::TryStatementRepeat1 => "try_statement_repeat1",
+            Type::TryStatementRepeat2 => "try_statement_repeat2",
+            Type::WithClauseRepeat1 => "with_clause_repeat1",
+            Type::GlobalStatementRepeat1 => "global_statement_repeat1",
+            Type::TypeParameterRepeat1 => "type_parameter_repeat1",
+            Type::ArgumentListRepeat1 => "argument_list_repeat1",
+            Type::DecoratedDefinitionRepeat1 => "decorated_definition_repeat1",
+            Type::DottedNameRepeat1 => "dotted_name_repeat1",
+            Type::UnionPatternRepeat1 => "union_pattern_repeat1",
+            Type::DictPatternRepeat1 => "dict_pattern_repeat1",
+            Type::TS14 => "_parameters_repeat1",
+            Type::TS15 => "_patterns_repeat1",
+            Type::ComparisonOperatorRepeat1 => "comparison_operator_repeat1",
+            Type::SubscriptRepeat1 => "subscript_repeat1",
+            Type::DictionaryRepeat1 => "dictionary_repeat1",
+            Type::TS16 => "_comprehension_clauses_repeat1",
+            Type::TS17 => "_collection_elements_repeat1",
+            Type::ForInClauseRepeat1 => "for_in_clause_repeat1",
+            Type::ConcatenatedStringRepeat1 => "concatenated_string_repeat1",
+            Type::StringRepeat1 => "string_repeat1",
+            Type::StringContentRepeat1 => "string_content_repeat1",
+            Type::FormatSpecifierRepeat1 => "format_specifier_repeat1",
+            Type::AsPatternTarget => "as_pattern_target",
+            Type::FormatExpression => "format_expression",
+            Type::TS5 => "is not",
+            Type::TS6 => "not in",
+            Type::Spaces => "Spaces",
+            Type::Directory => "Directory",
+            Type::ERROR => "ERROR",
+        }
+    }
+}
+
+const S_T_L: &'static [Type] = &[
+    Type::End,
+    Type::Identifier,
+    Type::SemiColon,
+    Type::Import,
+    Type::Dot,
+    Type::From,
+    Type::TS9,
+    Type::LParen,
+    Type::RParen,
+    Type::Comma,
+    Type::As,
+    Type::Star,
+    Type::Print,
+    Type::GtGt,
+    Type::Assert,
+    Type::ColonEq,
+    Type::Return,
+    Type::Del,
+    Type::Raise,
+    Type::Pass,
+    Type::Break,
+    Type::Continue,
+    Type::If,
+    Type::Colon,
+    Type::Elif,
+    Type::Else,
+    Type::Match,
+    Type::Case,
+    Type::Async,
+    Type::For,
+    Type::In,
+    Type::While,
+    Type::Try,
+    Type::Except,
+    Type::TS0,
+    Type::Finally,
+    Type::With,
+    Type::Def,
+    Type::DashGt,
+    Type::StarStar,
+    Type::Global,
+    Type::Nonlocal,
+    Type::Exec,
+    Type::TS1,
+    Type::Eq,
+    Type::Class,
+    Type::LBracket,
+    Type::RBracket,
+    Type::At,
+    Type::Dash,
+    Type::TS10,
+    Type::Pipe,
+    Type::LBrace,
+    Type::RBrace,
+    Type::Plus,
+    Type::Not,
+    Type::And,
+    Type::Or,
+    Type::Slash,
+    Type::Percent,
+    Type::SlashSlash,
+    Type::Amp,
+    Type::Caret,
+    Type::LtLt,
+    Type::Tilde,
+    Type::LT,
+    Type::LTEq,
+    Type::EqEq,
+    Type::BangEq,
+    Type::GTEq,
+    Type::GT,
+    Type::LTGT,
+    Type::Is,
+    Type::TS2,
+    Type::PlusEq,
+    Type::DashEq,
+    Type::StarEq,
+    Type::SlashEq,
+    Type::AtEq,
+    Type::SlashSlashEq,
+    Type::PercentEq,
+    Type::StarStarEq,
+    Type::GtGtEq,
+    Type::LtLtEq,
+    Type::AmpEq,
+    Type::CaretEq,
+    Type::PipeEq,
+    Type::TS3,
+    Type::Ellipsis,
+    Type::EscapeSequence,
+    Type::NotEscapeSequence,
+    Type::FormatSpecifierToken1,
+    Type::TypeConversion,
+    Type::Integer,
+    Type::Float,
+    Type::TS4,
+    Type::True,
+    Type::False,
+    Type::None,
+    Type::Comment,
+    Type::LineContinuation,
+    Type::Newline,
+    Type::Indent,
+    Type::Dedent,
+    Type::StringStart,
+    Type::TS7,
+    Type::EscapeInterpolation,
+    Type::StringEnd,
+    Type::Module,
+    Type::Statement,
+    Type::SimpleStatements,
+    Type::ImportStatement,
+    Type::ImportPrefix,
+    Type::RelativeImport,
+    Type::FutureImportStatement,
+    Type::ImportFromStatement,
+    Type::ImportList,
+    Type::AliasedImport,
+    Type::WildcardImport,
+    Type::PrintStatement,
+    Type::Chevron,
+    Type::AssertStatement,
+    Type::ExpressionStatement,
+    Type::NamedExpression,
+    Type::NamedExpressionLhs,
+    Type::ReturnStatement,
+    Type::DeleteStatement,
+    Type::RaiseStatement,
+    Type::PassStatement,
+    Type::BreakStatement,
+    Type::ContinueStatement,
+    Type::IfStatement,
+    Type::ElifClause,
+    Type::ElseClause,
+    Type::MatchStatement,
+    Type::Block,
+    Type::CaseClause,
+    Type::ForStatement,
+    Type::WhileStatement,
+    Type::TryStatement,
+    Type::ExceptClause,
+    Type::ExceptGroupClause,
+    Type::FinallyClause,
+    Type::WithStatement,
+    Type::WithClause,
+    Type::WithItem,
+    Type::FunctionDefinition,
+    Type::Parameters,
+    Type::LambdaParameters,
+    Type::ListSplat,
+    Type::DictionarySplat,
+    Type::GlobalStatement,
+    Type::NonlocalStatement,
+    Type::ExecStatement,
+    Type::TypeAliasStatement,
+    Type::ClassDefinition,
+    Type::TypeParameter,
+    Type::ParenthesizedListSplat,
+    Type::ArgumentList,
+    Type::DecoratedDefinition,
+    Type::Decorator,
+    Type::ExpressionList,
+    Type::DottedName,
+    Type::CasePattern,
+    Type::SimplePattern,
+    Type::AsPattern,
+    Type::UnionPattern,
+    Type::ListPattern,
+    Type::TuplePattern,
+    Type::DictPattern,
+    Type::KeyValuePattern,
+    Type::KeywordPattern,
+    Type::SplatPattern,
+    Type::ClassPattern,
+    Type::ComplexPattern,
+    Type::TS8,
+    Type::Patterns,
+    Type::Parameter,
+    Type::Pattern,
+    Type::DefaultParameter,
+    Type::TypedDefaultParameter,
+    Type::ListSplatPattern,
+    Type::DictionarySplatPattern,
+    Type::ExpressionWithinForInClause,
+    Type::Expression,
+    Type::PrimaryExpression,
+    Type::NotOperator,
+    Type::BooleanOperator,
+    Type::BinaryOperator,
+    Type::UnaryOperator,
+    Type::ComparisonOperator,
+    Type::Lambda,
+    Type::Assignment,
+    Type::AugmentedAssignment,
+    Type::PatternList,
+    Type::RightHandSide,
+    Type::Yield,
+    Type::Attribute,
+    Type::Subscript,
+    Type::Slice,
+    Type::Call,
+    Type::TypedParameter,
+    Type::Type,
+    Type::SplatType,
+    Type::GenericType,
+    Type::UnionType,
+    Type::ConstrainedType,
+    Type::MemberType,
+    Type::KeywordArgument,
+    Type::List,
+    Type::Set,
+    Type::Tuple,
+    Type::Dictionary,
+    Type::Pair,
+    Type::ListComprehension,
+    Type::DictionaryComprehension,
+    Type::SetComprehension,
+    Type::GeneratorExpression,
+    Type::ComprehensionClauses,
+    Type::ParenthesizedExpression,
+    Type::CollectionElements,
+    Type::ForInClause,
+    Type::IfClause,
+    Type::ConditionalExpression,
+    Type::ConcatenatedString,
+    Type::String,
+    Type::StringContent,
+    Type::Interpolation,
+    Type::FExpression,
+    Type::FormatSpecifier,
+    Type::Await,
+    Type::PositionalSeparator,
+    Type::KeywordSeparator,
+    Type::ModuleRepeat1,
+    Type::TS11,
+    Type::ImportPrefixRepeat1,
+    Type::TS12,
+    Type::PrintStatementRepeat1,
+    Type::AssertStatementRepeat1,
+    Type::IfStatementRepeat1,
+    Type::MatchStatementRepeat1,
+    Type::TS13,
+    Type::CaseClauseRepeat1,
+    Type::TryStatementRepeat1,
+    Type::TryStatementRepeat2,
+    Type::WithClauseRepeat1,
+    Type::GlobalStatementRepeat1,
+    Type::TypeParameterRepeat1,
+    Type::ArgumentListRepeat1,
+    Type::DecoratedDefinitionRepeat1,
+    Type::DottedNameRepeat1,
+    Type::UnionPatternRepeat1,
+    Type::DictPatternRepeat1,
+    Type::TS14,
+    Type::TS15,
+    Type::ComparisonOperatorRepeat1,
+    Type::SubscriptRepeat1,
+    Type::DictionaryRepeat1,
+    Type::TS16,
+    Type::TS17,
+    Type::ForInClauseRepeat1,
+    Type::ConcatenatedStringRepeat1,
+    Type::StringRepeat1,
+    Type::StringContentRepeat1,
+    Type::FormatSpecifierRepeat1,
+    Type::AsPatternTarget,
+    Type::FormatExpression,
+    Type::TS5,
+    Type::TS6,
+    Type::Spaces,
+    Type::Directory,
+    Type::ERROR,
+];
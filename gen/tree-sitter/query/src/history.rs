@@ -0,0 +1,183 @@
+//! Querying a whole history of versions.
+//!
+//! Versions of a HyperAST share most of their subtrees,
+//! thus the query is evaluated only once per distinct subtree (ie. per node identifier),
+//! and the matches contained in a subtree are also computed once and reused by all its parents.
+
+use std::{collections::HashMap, hash::Hash, sync::Arc};
+
+use hyper_ast::store::SimpleStores;
+use hyper_ast::types::{
+    HyperAST, HyperType, IterableChildren, NodeStore, TypedHyperAST, WithChildren, WithRoles,
+};
+
+use crate::search::{CaptureRes, PreparedMatcher};
+use crate::types::TStore;
+
+/// The matches contained in a subtree,
+/// as the number of occurrences of each matched subtree.
+#[derive(Debug, Default)]
+pub struct Matches<IdN> {
+    occurrences: HashMap<IdN, usize>,
+}
+
+impl<IdN: Hash + Eq + Clone> Matches<IdN> {
+    pub fn iter(&self) -> impl Iterator<Item = (&IdN, usize)> {
+        self.occurrences.iter().map(|(k, v)| (k, *v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.occurrences.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.occurrences.is_empty()
+    }
+}
+
+/// How the matches of a version relate to the matches of the previous version
+#[derive(Debug)]
+pub struct VersionMatches<V, IdN> {
+    pub version: V,
+    pub appeared: Vec<(IdN, usize)>,
+    pub disappeared: Vec<(IdN, usize)>,
+    pub persisted: Vec<(IdN, usize)>,
+}
+
+/// Evaluates a query over many versions, remembering results across versions.
+pub struct HistoryMatcher<'a, Ty, IdN> {
+    matcher: &'a PreparedMatcher<'a, SimpleStores<TStore>, Ty>,
+    /// results of the query on each evaluated node
+    evaluated: HashMap<IdN, Option<HashMap<String, CaptureRes>>>,
+    /// matches contained in each visited subtree
+    contained: HashMap<IdN, Arc<Matches<IdN>>>,
+    empty: Arc<Matches<IdN>>,
+}
+
+impl<'a, Ty, IdN> HistoryMatcher<'a, Ty, IdN>
+where
+    Ty: for<'b> TryFrom<&'b str>,
+    for<'b> <Ty as TryFrom<&'b str>>::Error: std::fmt::Debug,
+    IdN: Hash + Eq + Clone,
+{
+    pub fn new(matcher: &'a PreparedMatcher<'a, SimpleStores<TStore>, Ty>) -> Self {
+        Self {
+            matcher,
+            evaluated: Default::default(),
+            contained: Default::default(),
+            empty: Default::default(),
+        }
+    }
+
+    /// The number of distinct subtrees on which the query was evaluated
+    pub fn evaluated_count(&self) -> usize {
+        self.evaluated.len()
+    }
+
+    /// The captures of a matched subtree
+    pub fn captures(&self, id: &IdN) -> Option<&HashMap<String, CaptureRes>> {
+        self.evaluated.get(id)?.as_ref()
+    }
+
+    /// The matches contained in the subtree `id`, including itself
+    pub fn matches<'store, HAST, TIdN>(
+        &mut self,
+        code_store: &'store HAST,
+        id: IdN,
+    ) -> Arc<Matches<IdN>>
+    where
+        HAST: TypedHyperAST<'store, TIdN, IdN = IdN>,
+        HAST::TT: WithRoles,
+        TIdN:
+            hyper_ast::types::NodeId<IdN = IdN> + hyper_ast::types::TypedNodeId<Ty = Ty> + 'static,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+        IdN: hyper_ast::types::NodeId<IdN = IdN>,
+    {
+        if let Some(m) = self.contained.get(&id) {
+            return m.clone();
+        }
+        let cs: Vec<IdN> = {
+            let n = code_store.node_store().resolve(&id);
+            n.children()
+                .map_or(vec![], |cs| cs.iter_children().cloned().collect())
+        };
+        let mut occurrences: HashMap<IdN, usize> = Default::default();
+        for c in cs {
+            let m = self.matches(code_store, c);
+            for (k, v) in m.iter() {
+                *occurrences.entry(k.clone()).or_default() += v;
+            }
+        }
+        let captures = self
+            .matcher
+            .is_matching_and_capture::<HAST, TIdN>(code_store, id.clone());
+        if captures.is_some() {
+            *occurrences.entry(id.clone()).or_default() += 1;
+        }
+        self.evaluated.insert(id.clone(), captures);
+        let m = if occurrences.is_empty() {
+            self.empty.clone()
+        } else {
+            Arc::new(Matches { occurrences })
+        };
+        self.contained.insert(id, m.clone());
+        m
+    }
+
+    /// Evaluates the query on each version, in order,
+    /// comparing the matches of each version with the ones of the previous version.
+    ///
+    /// All the matches of the first version are reported as appeared.
+    pub fn query_history<'store, HAST, TIdN, V>(
+        &mut self,
+        code_store: &'store HAST,
+        versions: impl IntoIterator<Item = (V, IdN)>,
+    ) -> Vec<VersionMatches<V, IdN>>
+    where
+        HAST: TypedHyperAST<'store, TIdN, IdN = IdN>,
+        HAST::TT: WithRoles,
+        TIdN:
+            hyper_ast::types::NodeId<IdN = IdN> + hyper_ast::types::TypedNodeId<Ty = Ty> + 'static,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+        IdN: hyper_ast::types::NodeId<IdN = IdN>,
+    {
+        let mut prev = self.empty.clone();
+        let mut res = vec![];
+        for (version, root) in versions {
+            let curr = self.matches(code_store, root);
+            res.push(compare(version, &prev, &curr));
+            prev = curr;
+        }
+        res
+    }
+}
+
+/// Compares the matches of two versions, occurrences of the same subtree are paired.
+pub fn compare<V, IdN: Hash + Eq + Clone>(
+    version: V,
+    prev: &Matches<IdN>,
+    curr: &Matches<IdN>,
+) -> VersionMatches<V, IdN> {
+    let mut res = VersionMatches {
+        version,
+        appeared: vec![],
+        disappeared: vec![],
+        persisted: vec![],
+    };
+    for (id, n) in curr.iter() {
+        let p = prev.occurrences.get(id).copied().unwrap_or_default();
+        if p.min(n) > 0 {
+            res.persisted.push((id.clone(), p.min(n)));
+        }
+        if n > p {
+            res.appeared.push((id.clone(), n - p));
+        }
+    }
+    for (id, p) in prev.iter() {
+        let n = curr.occurrences.get(id).copied().unwrap_or_default();
+        if p > n {
+            res.disappeared.push((id.clone(), p - n));
+        }
+    }
+    res
+}
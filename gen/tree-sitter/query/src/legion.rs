@@ -195,12 +195,12 @@ impl<'store, 'cache, TS: TsQueryEnabledTypeStore<HashedNodeRef<'store, TIdN<Node
         //     return None
         // };
         let kind = node.obtain_type(type_store);
-        // TODO remove this mitigations as it breaks captures on anonymous nodes
-        if kind == Type::TS0 || kind == Type::AnonymousNode || kind == Type::String {
+        // strings are kept as leaves, anonymous nodes keep their children to support captures and quantifiers
+        if kind == Type::TS0 || kind == Type::String {
             *skip = true;
         }
         let mut acc = self.pre(text, node, stack, global);
-        if kind == Type::TS0 || kind == Type::AnonymousNode || kind == Type::String {
+        if kind == Type::TS0 || kind == Type::String {
             acc.labeled = true;
         }
         Some(acc)
@@ -51,5 +51,7 @@ pub use tnode::TNode;
 
 pub mod search;
 
+pub mod history;
+
 #[cfg(feature = "legion")]
 pub mod iter;
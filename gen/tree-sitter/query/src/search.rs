@@ -5,8 +5,8 @@ use crate::types::TStore;
 
 use hyper_ast::store::{defaults::LabelIdentifier, labels::LabelStore};
 use hyper_ast::types::{
-    self, HyperAST, IterableChildren, Labeled, NodeStore, Typed, TypedHyperAST, TypedNodeStore,
-    WithChildren,
+    self, HyperAST, HyperType, IterableChildren, Labeled, NodeStore, Typed, TypedHyperAST,
+    TypedNodeStore, WithChildren, WithRoles,
 };
 
 use hyper_ast::store::nodes::legion::NodeIdentifier;
@@ -22,15 +22,64 @@ use std::sync::Arc;
 // for now just uses the root types
 // TODO implement approaches based on probabilitic sets
 pub(crate) struct QuickTrigger<T> {
-    pub(crate) root_types: Arc<[T]>,
+    /// None when the root of the pattern can be of any type, eg. a wildcard or an alternation
+    pub(crate) root_types: Arc<[Option<T>]>,
 }
 
 pub struct PreparedMatcher<'a, HAST, Ty> {
-    pub(crate) query_store: &'a HAST,
+    /// the prepared patterns do not borrow from the query store
+    pub(crate) query_store: std::marker::PhantomData<fn() -> &'a HAST>,
     pub(crate) quick_trigger: QuickTrigger<Ty>,
     pub(crate) patterns: Arc<[Pattern<Ty>]>,
 }
 
+impl<'a, HAST, Ty> PreparedMatcher<'a, HAST, Ty> {
+    /// Detaches the matcher from the query store, eg. to keep it in a cache
+    pub fn detach(self) -> PreparedMatcher<'static, HAST, Ty>
+    where
+        HAST: 'static,
+    {
+        PreparedMatcher {
+            query_store: std::marker::PhantomData,
+            quick_trigger: self.quick_trigger,
+            patterns: self.patterns,
+        }
+    }
+}
+
+/// Reasons for a query to be rejected by [`PreparedMatcher::new`]
+#[derive(Debug)]
+pub enum QueryError {
+    /// the query does not parse
+    Syntax,
+    /// the query does not have the expected structure, the reason is given
+    Malformed(&'static str),
+    /// the node type does not exist in the targeted language
+    UnknownType(String),
+    UnknownPredicate(String),
+    /// the arguments of a predicate are invalid
+    Predicate(String),
+    Regex(regex::Error),
+    /// a feature of the query language that cannot be matched on the HyperAST
+    Unsupported(&'static str),
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::Syntax => write!(f, "the query contains a syntax error"),
+            QueryError::Malformed(reason) => write!(f, "malformed query: {}", reason),
+            QueryError::UnknownType(t) => write!(f, "the node type {} does not exist", t),
+            QueryError::UnknownPredicate(name) => write!(f, "unknown predicate #{}?", name),
+            QueryError::Predicate(reason) => write!(f, "invalid predicate: {}", reason),
+            QueryError::Regex(e) => write!(f, "invalid regex: {}", e),
+            QueryError::Unsupported(feature) => write!(f, "{} are not supported", feature),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
 pub(crate) struct PatternMatcher<'a, 'b, Ty> {
     pub(crate) query_store: &'a SimpleStores<crate::types::TStore>,
     pub(crate) patterns: &'b Pattern<Ty>,
@@ -70,23 +119,11 @@ where
         TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
             + hyper_ast::types::TypedNodeId<Ty = Ty>
             + 'static,
-        Ty: std::fmt::Debug + Eq + Copy,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
     {
-        let Some((n, _)) = code_store.typed_node_store().try_resolve(&id) else {
-            return false;
-        };
-        let t = n.get_type();
-        for i in 0..self.quick_trigger.root_types.len() {
-            let tt = self.quick_trigger.root_types[i];
-            let pat = &self.patterns[i];
-            if t == tt {
-                let res: MatchingRes = pat.is_matching(code_store, id.clone());
-                if res.matched {
-                    return true;
-                }
-            }
-        }
-        false
+        self.is_matching_and_capture(code_store, id).is_some()
     }
     pub fn is_matching_and_capture<'store, HAST, TIdN>(
         &self,
@@ -98,7 +135,9 @@ where
         TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
             + hyper_ast::types::TypedNodeId<Ty = Ty>
             + 'static,
-        Ty: std::fmt::Debug + Eq + Copy,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
     {
         let Some((n, _)) = code_store.typed_node_store().try_resolve(&id) else {
             return None;
@@ -107,7 +146,7 @@ where
         for i in 0..self.quick_trigger.root_types.len() {
             let tt = self.quick_trigger.root_types[i];
             let pat = &self.patterns[i];
-            if t == tt {
+            if tt.map_or(true, |tt| t == tt) {
                 let res: MatchingRes = pat.is_matching(code_store, id.clone());
                 if res.matched {
                     return Some(res.captures);
@@ -120,17 +159,19 @@ where
 
 impl<'a, Ty> PreparedMatcher<'a, SimpleStores<TStore>, Ty>
 where
-    Ty: for<'b> TryFrom<&'b str> + std::fmt::Debug,
+    Ty: for<'b> TryFrom<&'b str> + std::fmt::Debug + Copy,
     for<'b> <Ty as TryFrom<&'b str>>::Error: std::fmt::Debug,
 {
+    /// Prepares `query` for the language of `Ty`.
+    ///
+    /// Fails on syntax errors, on node types that `Ty` does not know about,
+    /// and on the parts of the query language that cannot be matched on the HyperAST.
     pub fn new(
         query_store: &'a SimpleStores<crate::types::TStore>,
         query: NodeIdentifier,
-    ) -> Self {
+    ) -> Result<Self, QueryError> {
         use crate::types::TIdN;
         use crate::types::Type;
-        use hyper_ast::types::LabelStore;
-        let mut root_types = vec![];
         let mut patterns = vec![];
         let n = query_store
             .node_store
@@ -146,55 +187,92 @@ where
                 .unwrap()
                 .0;
             let t = rule.get_type();
-            if t == Type::NamedNode {
-                let ty = rule.child(&1).unwrap();
-                let ty = query_store
-                    .node_store
-                    .try_resolve_typed::<TIdN<NodeIdentifier>>(&ty)
-                    .unwrap()
-                    .0;
-                assert_eq!(ty.get_type(), Type::Identifier);
-                let l = ty.try_get_label();
-                let l = query_store.label_store.resolve(&l.unwrap());
-                let l = Ty::try_from(l).expect("the node type does not exist");
-                root_types.push(l);
-                patterns.push(Self::process_named_node(query_store, *rule_id).into());
-            } else if t == Type::AnonymousNode {
-                todo!()
-            } else if t == Type::Spaces {
-            } else if t == Type::Predicate {
-                let prev = patterns
-                    .pop()
-                    .expect("a predicate should be preceded by a pattern");
-
-                let predicate = Self::preprocess_predicate(query_store, *rule_id);
-
+            if t == Type::Predicate {
+                let prev = patterns.pop().ok_or(QueryError::Malformed(
+                    "a predicate should be preceded by a pattern",
+                ))?;
+                let Some(predicate) = Self::preprocess_predicate(query_store, *rule_id)? else {
+                    patterns.push(prev);
+                    continue;
+                };
                 let predicated = Pattern::Predicated {
                     predicate,
                     pat: Arc::new(prev),
                 };
                 patterns.push(predicated);
+            } else if t == Type::Spaces || t == Type::Comment {
+            } else if t == Type::Dot {
+                log::warn!("ignoring a top-level {}", t);
             } else {
-                todo!("{}", t)
+                let mut predicates = vec![];
+                let pat = Self::process_definition(query_store, *rule_id, &mut predicates)?
+                    .ok_or(QueryError::Malformed(
+                        "a top-level definition should be a pattern",
+                    ))?;
+                if let Pattern::Field { .. } = pat {
+                    return Err(QueryError::Malformed("a field should be in a named node"));
+                }
+                patterns.push(Pattern::predicated(pat, predicates));
             }
         }
 
-        Self {
-            query_store,
+        let root_types = patterns.iter().map(|x| x.root_type()).collect::<Vec<_>>();
+        Ok(Self {
+            query_store: std::marker::PhantomData,
             quick_trigger: QuickTrigger {
                 root_types: root_types.into(),
             },
             patterns: patterns.into(),
-        }
+        })
+    }
+
+    /// Predicates are accumulated in `predicates` as they apply to the whole enclosing top-level pattern.
+    ///
+    /// Returns None for definitions that are not patterns, ie. predicates.
+    pub(crate) fn process_definition(
+        query_store: &'a SimpleStores<crate::types::TStore>,
+        rule: NodeIdentifier,
+        predicates: &mut Vec<Predicate>,
+    ) -> Result<Option<Pattern<Ty>>, QueryError> {
+        use crate::types::TIdN;
+        use crate::types::Type;
+        let n = query_store
+            .node_store
+            .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule)
+            .unwrap()
+            .0;
+        let t = n.get_type();
+        Ok(Some(match t {
+            Type::NamedNode => Self::process_named_node(query_store, rule, predicates)?,
+            Type::AnonymousNode => Self::process_anonymous_node(query_store, rule)?,
+            Type::Grouping => Self::process_grouping(query_store, rule, predicates)?,
+            Type::List => Self::process_list(query_store, rule, predicates)?,
+            Type::FieldDefinition => Self::process_field(query_store, rule, predicates)?,
+            Type::NegatedField => {
+                return Err(QueryError::Malformed(
+                    "a negated field should be in a named node",
+                ))
+            }
+            Type::Dot => Pattern::Anchor,
+            Type::Predicate => {
+                predicates.extend(Self::preprocess_predicate(query_store, rule)?);
+                return Ok(None);
+            }
+            Type::Spaces | Type::Comment => return Ok(None),
+            _ => return Err(QueryError::Syntax),
+        }))
     }
+
     pub(crate) fn process_named_node(
         query_store: &'a SimpleStores<crate::types::TStore>,
         rule: NodeIdentifier,
-    ) -> Pattern<Ty> {
+        predicates: &mut Vec<Predicate>,
+    ) -> Result<Pattern<Ty>, QueryError> {
         use crate::types::TIdN;
         use crate::types::Type;
         use hyper_ast::types::LabelStore;
         let mut patterns = vec![];
+        let mut negated = vec![];
         let n = query_store
             .node_store
             .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule)
@@ -202,100 +280,209 @@ where
             .0;
         let t = n.get_type();
         assert_eq!(t, Type::NamedNode);
-        let mut cs = n.children().unwrap().iter_children().peekable();
+        let mut cs = n
+            .children()
+            .unwrap()
+            .iter_children()
+            .filter(|x| !is_spaces(query_store, **x))
+            .peekable();
         cs.next().unwrap();
-        let ty = cs.next().unwrap();
+        let ty = cs.next().ok_or(QueryError::Syntax)?;
         let ty = query_store
             .node_store
             .try_resolve_typed::<TIdN<NodeIdentifier>>(&ty)
             .unwrap()
             .0;
-        assert_eq!(ty.get_type(), Type::Identifier);
-        let l = ty.try_get_label();
-        let l = query_store.label_store.resolve(&l.unwrap());
-        let l = Ty::try_from(l).expect("the node type does not exist");
+        let ty = match ty.get_type() {
+            // the wildcard `(_)`
+            Type::TS2 => None,
+            Type::Identifier => {
+                let l = ty.try_get_label();
+                let l = query_store.label_store.resolve(&l.unwrap());
+                Some(Ty::try_from(l).map_err(|_| QueryError::UnknownType(l.to_string()))?)
+            }
+            _ => return Err(QueryError::Syntax),
+        };
         loop {
-            let Some(rule_id) = cs.peek() else { break };
+            let Some(rule_id) = cs.next() else { break };
             let rule = query_store
                 .node_store
                 .try_resolve_typed::<TIdN<NodeIdentifier>>(rule_id)
                 .unwrap()
                 .0;
-            let t = rule.get_type();
-            if t == Type::NamedNode {
-                patterns.push(Self::process_named_node(query_store, **rule_id).into())
-            } else if t == Type::Spaces {
-            } else if t == Type::RParen {
-            } else if t == Type::AnonymousNode {
-                patterns.push(Self::process_anonymous_node(query_store, **rule_id).into())
-            } else if t == Type::Capture {
+            if rule.get_type() == Type::RParen {
                 break;
-            } else if t == Type::Predicate {
-                let prev = patterns.pop().expect("predicate must be preceded by node");
-                let predicate = Self::preprocess_predicate(query_store, **rule_id);
-                patterns.push(Pattern::Predicated {
-                    predicate,
-                    pat: Arc::new(prev),
-                });
-            } else {
-                todo!("{}", t)
+            } else if rule.get_type() == Type::NegatedField {
+                // `!name`
+                let name = (rule.children().unwrap().iter_children())
+                    .find_map(|x| identifier(query_store, *x))
+                    .ok_or(QueryError::Syntax)?;
+                negated.push(name);
+                continue;
             }
-            cs.next();
+            patterns.extend(Self::process_definition(query_store, *rule_id, predicates)?);
         }
-        let mut res = Pattern::NamedNode {
-            ty: l,
+        let res = Pattern::NamedNode {
+            ty,
+            negated: negated.into(),
             children: patterns.into(),
         };
+        Self::process_suffix(query_store, res, cs)
+    }
+
+    /// `name: pat`
+    pub(crate) fn process_field(
+        query_store: &'a SimpleStores<crate::types::TStore>,
+        rule: NodeIdentifier,
+        predicates: &mut Vec<Predicate>,
+    ) -> Result<Pattern<Ty>, QueryError> {
+        use crate::types::TIdN;
+        use crate::types::Type;
+        let n = query_store
+            .node_store
+            .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule)
+            .unwrap()
+            .0;
+        assert_eq!(n.get_type(), Type::FieldDefinition);
+        let mut cs = n
+            .children()
+            .unwrap()
+            .iter_children()
+            .filter(|x| !is_spaces(query_store, **x));
+        let name = (cs.next())
+            .and_then(|x| identifier(query_store, *x))
+            .ok_or(QueryError::Syntax)?;
+        cs.next().ok_or(QueryError::Syntax)?;
+        let mut pat = None;
+        for rule_id in cs {
+            // eg. skips comments
+            pat = pat.or(Self::process_definition(query_store, *rule_id, predicates)?);
+        }
+        Pattern::field(name, pat.ok_or(QueryError::Syntax)?)
+    }
+
+    pub(crate) fn process_anonymous_node(
+        query_store: &SimpleStores<TStore>,
+        rule: NodeIdentifier,
+    ) -> Result<Pattern<Ty>, QueryError> {
+        use crate::types::TIdN;
+        use crate::types::Type;
+        use hyper_ast::types::LabelStore;
+        let n = query_store
+            .node_store()
+            .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule)
+            .unwrap()
+            .0;
+        let t = n.get_type();
+        assert_eq!(t, Type::AnonymousNode);
+        let to_pattern = |l: &str| {
+            if l == "_" {
+                Ok(Pattern::AnyNode)
+            } else {
+                let l = unescape(&l[1..l.len() - 1]);
+                match Ty::try_from(l.as_str()) {
+                    Ok(ty) => Ok(Pattern::AnonymousNode(ty)),
+                    Err(_) => Err(QueryError::UnknownType(l)),
+                }
+            }
+        };
+        // anonymous nodes without their children are still accepted, as generated by previous versions
+        if let Some(l) = n.try_get_label() {
+            return to_pattern(query_store.label_store().resolve(l));
+        }
+        let mut cs = n
+            .children()
+            .unwrap()
+            .iter_children()
+            .filter(|x| !is_spaces(query_store, **x))
+            .peekable();
+        let name = cs.next().ok_or(QueryError::Syntax)?;
+        let name = query_store
+            .node_store
+            .try_resolve_typed::<TIdN<NodeIdentifier>>(&name)
+            .unwrap()
+            .0;
+        let res = match name.get_type() {
+            Type::TS2 => Pattern::AnyNode,
+            Type::String => {
+                let l = name.try_get_label().unwrap();
+                to_pattern(query_store.label_store().resolve(l))?
+            }
+            _ => return Err(QueryError::Syntax),
+        };
+        Self::process_suffix(query_store, res, cs)
+    }
+
+    pub(crate) fn process_grouping(
+        query_store: &'a SimpleStores<crate::types::TStore>,
+        rule: NodeIdentifier,
+        predicates: &mut Vec<Predicate>,
+    ) -> Result<Pattern<Ty>, QueryError> {
+        let (patterns, cs) =
+            Self::process_delimited(query_store, rule, crate::types::Type::RParen, predicates)?;
+        let res = match &patterns[..] {
+            // a grouping of a single pattern, eg. to attach predicates to it
+            [p] if !matches!(p, Pattern::Anchor) => patterns.into_iter().next().unwrap(),
+            _ => Pattern::Grouping(patterns.into()),
+        };
+        Self::process_suffix(query_store, res, cs.into_iter().peekable())
+    }
+
+    pub(crate) fn process_list(
+        query_store: &'a SimpleStores<crate::types::TStore>,
+        rule: NodeIdentifier,
+        predicates: &mut Vec<Predicate>,
+    ) -> Result<Pattern<Ty>, QueryError> {
+        let (patterns, cs) =
+            Self::process_delimited(query_store, rule, crate::types::Type::RBracket, predicates)?;
+        let res = Pattern::List(patterns.into());
+        Self::process_suffix(query_store, res, cs.into_iter().peekable())
+    }
+
+    /// Returns the patterns between the delimiters of `rule`, then the remaining children of `rule`
+    fn process_delimited(
+        query_store: &'a SimpleStores<crate::types::TStore>,
+        rule: NodeIdentifier,
+        closing: crate::types::Type,
+        predicates: &mut Vec<Predicate>,
+    ) -> Result<(Vec<Pattern<Ty>>, Vec<NodeIdentifier>), QueryError> {
+        use crate::types::TIdN;
+        let n = query_store
+            .node_store
+            .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule)
+            .unwrap()
+            .0;
+        let mut cs = n
+            .children()
+            .unwrap()
+            .iter_children()
+            .filter(|x| !is_spaces(query_store, **x));
+        cs.next().unwrap();
+        let mut patterns = vec![];
         loop {
-            let Some(rule_id) = cs.peek() else {
-                return res;
-            };
-            let n = query_store
+            let Some(rule_id) = cs.next() else { break };
+            let rule = query_store
                 .node_store
                 .try_resolve_typed::<TIdN<NodeIdentifier>>(rule_id)
                 .unwrap()
                 .0;
-            let t = n.get_type();
-            if t == Type::Capture {
-                let mut cs = n.children().unwrap().iter_children();
-                let n = query_store
-                    .node_store
-                    .try_resolve_typed::<TIdN<NodeIdentifier>>(cs.next().unwrap())
-                    .unwrap()
-                    .0;
-                let t = n.get_type();
-                assert_eq!(t, Type::At);
-                let name = cs.next().unwrap();
-                let ty = query_store
-                    .node_store
-                    .try_resolve_typed::<TIdN<NodeIdentifier>>(name)
-                    .unwrap()
-                    .0;
-                assert_eq!(ty.get_type(), Type::Identifier);
-                let name = ty.try_get_label().unwrap();
-                let name = query_store.label_store.resolve(&name);
-                let name = name.to_string();
-                match &res {
-                    Pattern::NamedNode { .. } | Pattern::Capture { .. } => (),
-                    Pattern::Predicated { .. } => panic!(),
-                    Pattern::AnonymousNode { .. } => todo!("not sure if it works properly"),
-                    p => todo!("{:?} still not implemented to be captured", p), // TODO quantificators will need more works
-                }
-                res = Pattern::Capture {
-                    name,
-                    pat: Arc::new(res),
-                };
-            } else if t == Type::Quantifier {
+            if rule.get_type() == closing {
                 break;
-            } else {
-                todo!("{}", t)
             }
-            cs.next().unwrap();
+            patterns.extend(Self::process_definition(query_store, *rule_id, predicates)?);
         }
-        loop {
-            let Some(rule_id) = cs.next() else {
-                break res;
-            };
+        Ok((patterns, cs.copied().collect()))
+    }
+
+    /// Process the quantifier and captures following a pattern
+    fn process_suffix<'c>(
+        query_store: &SimpleStores<TStore>,
+        mut res: Pattern<Ty>,
+        cs: std::iter::Peekable<impl Iterator<Item = &'c NodeIdentifier>>,
+    ) -> Result<Pattern<Ty>, QueryError> {
+        use crate::types::TIdN;
+        use crate::types::Type;
+        for rule_id in cs {
             let n = query_store
                 .node_store
                 .try_resolve_typed::<TIdN<NodeIdentifier>>(rule_id)
@@ -303,64 +490,35 @@ where
                 .0;
             let t = n.get_type();
             if t == Type::Capture {
-                panic!("captures after predicated are not allowed");
+                let name = preprocess_capture_pred_arg(n, query_store);
+                res = match res {
+                    // each repetition is captured
+                    Pattern::Quantified { quantifier, pat } => Pattern::Quantified {
+                        quantifier,
+                        pat: Arc::new(Pattern::Capture { name, pat }),
+                    },
+                    res => Pattern::Capture {
+                        name,
+                        pat: Arc::new(res),
+                    },
+                };
             } else if t == Type::Quantifier {
-                todo!()
+                res = Pattern::Quantified {
+                    quantifier: preprocess_quantifier(n, query_store)?,
+                    pat: Arc::new(res),
+                };
+            } else if t == Type::Spaces || t == Type::Comment {
             } else {
-                todo!("{}", t)
+                return Err(QueryError::Syntax);
             }
         }
-    }
-
-    pub(crate) fn process_anonymous_node(
-        query_store: &SimpleStores<TStore>,
-        rule: NodeIdentifier,
-    ) -> Pattern<Ty> {
-        use crate::types::TIdN;
-        use crate::types::Type;
-        use hyper_ast::types::LabelStore;
-        let n = query_store
-            .node_store()
-            .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule)
-            .unwrap()
-            .0;
-        let t = n.get_type();
-        assert_eq!(t, Type::AnonymousNode);
-        // let mut cs = n.children().unwrap().iter_children();
-        // cs.next().unwrap();
-        // let ty = cs.next().unwrap();
-        // let ty = query_store
-        //     .node_store
-        //     .try_resolve_typed::<TIdN<NodeIdentifier>>(&ty)
-        //     .unwrap()
-        //     .0;
-        // let t = ty.get_type();
-        let l = n.try_get_label();
-        let l = query_store.label_store().resolve(&l.unwrap());
-        let l = &l[1..l.len() - 1];
-        let l = Ty::try_from(l).unwrap();
-        // for rule_id in cs {
-        //     let rule = query_store
-        //         .node_store
-        //         .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule_id)
-        //         .unwrap()
-        //         .0;
-        //     let t = rule.get_type();
-        //     dbg!(t);
-        //     if t == Type::NamedNode {
-        //         unreachable!()
-        //     } else if t == Type::Spaces {
-        //     } else {
-        //         todo!()
-        //     }
-        // }
-        Pattern::AnonymousNode(l)
+        Ok(res)
     }
 
     pub(crate) fn preprocess_predicate(
         query_store: &SimpleStores<TStore>,
         rule: NodeIdentifier,
-    ) -> Predicate {
+    ) -> Result<Option<Predicate>, QueryError> {
         use crate::types::TIdN;
         use crate::types::Type;
         use hyper_ast::types::LabelStore;
@@ -371,96 +529,139 @@ where
             .0;
         let t = n.get_type();
         assert_eq!(t, Type::Predicate);
-        let mut cs = n.children().unwrap().iter_children();
+        let mut cs = n
+            .children()
+            .unwrap()
+            .iter_children()
+            .filter(|x| !is_spaces(query_store, **x));
         cs.next().unwrap();
-        let sharp = cs.next().unwrap();
+        let sharp = cs.next().ok_or(QueryError::Syntax)?;
         let sharp = query_store
             .node_store
             .try_resolve_typed::<TIdN<NodeIdentifier>>(&sharp)
             .unwrap()
             .0;
-        let t = sharp.get_type();
-        assert_eq!(t, Type::TS3);
+        if sharp.get_type() != Type::TS3 {
+            return Err(QueryError::Syntax);
+        }
+
+        let name = (cs.next())
+            .and_then(|x| identifier(query_store, *x))
+            .ok_or(QueryError::Malformed("a predicate should have a name"))?;
 
-        let pred = cs.next().unwrap();
+        let pred = cs.next().ok_or(QueryError::Syntax)?;
         let pred = query_store
             .node_store
             .try_resolve_typed::<TIdN<NodeIdentifier>>(&pred)
             .unwrap()
             .0;
-        let t = pred.get_type();
-        assert_eq!(t, Type::Identifier);
-
+        if pred.get_type() != Type::PredicateType {
+            return Err(QueryError::Syntax);
+        }
         let l = pred.try_get_label();
         let l = query_store.label_store().resolve(&l.unwrap());
-        match l {
-            "eq" => {
-                let pred = cs.next().unwrap();
-                let pred = query_store
-                    .node_store
-                    .try_resolve_typed::<TIdN<NodeIdentifier>>(&pred)
-                    .unwrap()
-                    .0;
-                let t = pred.get_type();
-                assert_eq!(t, Type::PredicateType);
-                let l = pred.try_get_label();
-                let l = query_store.label_store().resolve(&l.unwrap());
-                assert_eq!(l, "?");
-                for rule_id in cs {
-                    let rule = query_store
+        if l == "!" {
+            // directives, such as `#set!`, do not filter matches
+            return Ok(None);
+        } else if l != "?" {
+            return Err(QueryError::Syntax);
+        }
+
+        let mut args = vec![];
+        for rule_id in cs {
+            let rule = query_store
+                .node_store
+                .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule_id)
+                .unwrap()
+                .0;
+            let t = rule.get_type();
+            if t == Type::Parameters {
+                for arg in rule.children().unwrap().iter_children() {
+                    let arg = query_store
                         .node_store
-                        .try_resolve_typed::<TIdN<NodeIdentifier>>(&rule_id)
+                        .try_resolve_typed::<TIdN<NodeIdentifier>>(arg)
                         .unwrap()
                         .0;
-                    let t = rule.get_type();
-                    if t == Type::Parameters {
-                        let mut cs = rule.children().unwrap().iter_children();
-                        let left = cs.next().unwrap();
-                        let left = query_store
-                            .node_store
-                            .try_resolve_typed::<TIdN<NodeIdentifier>>(left)
-                            .unwrap()
-                            .0;
-                        let left = match left.get_type() {
-                            Type::Capture => preprocess_capture_pred_arg(left, query_store),
-                            t => todo!("{}", t),
-                        };
-                        {
-                            let center = cs.next().unwrap();
-                            let center = query_store
-                                .node_store
-                                .try_resolve_typed::<TIdN<NodeIdentifier>>(center)
-                                .unwrap()
-                                .0;
-                            let t = center.get_type();
-                            assert_eq!(t, Type::Spaces);
+                    match arg.get_type() {
+                        Type::Capture => {
+                            args.push(PredArg::Capture(preprocess_capture_pred_arg(
+                                arg,
+                                query_store,
+                            )));
                         }
-                        let right = cs.next().unwrap();
-                        let right = query_store
-                            .node_store
-                            .try_resolve_typed::<TIdN<NodeIdentifier>>(right)
-                            .unwrap()
-                            .0;
-                        return match right.get_type() {
-                            Type::Capture => {
-                                let right = preprocess_capture_pred_arg(right, query_store);
-                                Predicate::Eq { left, right }
-                            }
-                            Type::String => {
-                                let right = preprocess_capture_pred_arg(right, query_store);
-                                Predicate::EqString { left, right }
-                            }
-                            t => todo!("{}", t),
-                        };
-                    } else if t == Type::Spaces {
-                    } else {
-                        todo!()
+                        Type::String => {
+                            args.push(PredArg::String(preprocess_capture_pred_arg(
+                                arg,
+                                query_store,
+                            )));
+                        }
+                        Type::Identifier => {
+                            let l = arg.try_get_label().unwrap();
+                            let l = query_store.label_store().resolve(l);
+                            args.push(PredArg::String(l.to_string()));
+                        }
+                        Type::Spaces | Type::Comment => (),
+                        _ => return Err(QueryError::Syntax),
                     }
                 }
-                panic!()
+            } else if t == Type::RParen {
+            } else {
+                return Err(QueryError::Syntax);
             }
-            l => todo!("{}", l),
         }
+        Predicate::new(&name, args).map(Some)
+    }
+}
+
+fn is_spaces(query_store: &SimpleStores<TStore>, id: NodeIdentifier) -> bool {
+    use crate::types::TIdN;
+    use crate::types::Type;
+    query_store
+        .node_store
+        .try_resolve_typed::<TIdN<NodeIdentifier>>(&id)
+        .map_or(false, |x| x.0.get_type() == Type::Spaces)
+}
+
+/// the label of `id` if it is an identifier
+fn identifier(query_store: &SimpleStores<TStore>, id: NodeIdentifier) -> Option<String> {
+    use crate::types::TIdN;
+    use crate::types::Type;
+    use hyper_ast::types::LabelStore;
+    let n = query_store
+        .node_store
+        .try_resolve_typed::<TIdN<NodeIdentifier>>(&id)?
+        .0;
+    if n.get_type() != Type::Identifier {
+        return None;
+    }
+    let l = n.try_get_label()?;
+    Some(query_store.label_store().resolve(l).to_string())
+}
+
+fn preprocess_quantifier(
+    quantifier: hyper_ast::store::nodes::legion::HashedNodeRef<'_, crate::types::TIdN<legion::Entity>>,
+    query_store: &SimpleStores<TStore>,
+) -> Result<Quantifier, QueryError> {
+    use crate::types::TIdN;
+    use crate::types::Type;
+    use hyper_ast::types::LabelStore;
+    let t = if let Some(l) = quantifier.try_get_label() {
+        Type::from_str(query_store.label_store().resolve(l))
+    } else {
+        let cs = quantifier.children().unwrap();
+        let op = cs.iter_children().next().unwrap();
+        let op = query_store
+            .node_store
+            .try_resolve_typed::<TIdN<NodeIdentifier>>(op)
+            .unwrap()
+            .0;
+        Some(op.get_type())
+    };
+    match t {
+        Some(Type::Star) => Ok(Quantifier::ZeroOrMore),
+        Some(Type::Plus) => Ok(Quantifier::OneOrMore),
+        Some(Type::QMark) => Ok(Quantifier::ZeroOrOne),
+        _ => Err(QueryError::Syntax),
     }
 }
 
@@ -496,37 +697,169 @@ fn preprocess_capture_pred_arg(
     } else if let Type::String = arg.get_type() {
         let l = arg.try_get_label();
         let l = query_store.label_store().resolve(&l.unwrap());
-        l[1..l.len() - 1].to_string()
+        unescape(&l[1..l.len() - 1])
     } else {
         unreachable!()
     }
 }
 
+/// Handles the escape sequences of query strings
+fn unescape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => res.push('\n'),
+            Some('r') => res.push('\r'),
+            Some('t') => res.push('\t'),
+            Some('0') => res.push('\0'),
+            Some(c) => res.push(c),
+            None => res.push('\\'),
+        }
+    }
+    res
+}
+
 #[derive(Debug)]
 pub(crate) enum Pattern<Ty> {
     NamedNode {
-        ty: Ty,
+        /// None for the wildcard `(_)`, matching any named node
+        ty: Option<Ty>,
+        /// The fields the node must not have, eg. `body` in `(function_definition !body)`
+        negated: Arc<[String]>,
         children: Arc<[Pattern<Ty>]>,
     },
     AnonymousNode(Ty),
+    /// The wildcard `_`, matching any node
+    AnyNode,
     Capture {
         name: String,
         pat: Arc<Pattern<Ty>>,
     },
+    /// The node must be the field `name` of its parent, eg. `name: (identifier)`
+    Field {
+        name: String,
+        pat: Arc<Pattern<Ty>>,
+    },
     Predicated {
         predicate: Predicate,
         pat: Arc<Pattern<Ty>>,
     },
+    Quantified {
+        quantifier: Quantifier,
+        pat: Arc<Pattern<Ty>>,
+    },
+    /// Alternation `[...]`
+    List(Arc<[Pattern<Ty>]>),
+    /// Sequence of siblings `(...)`
+    Grouping(Arc<[Pattern<Ty>]>),
+    /// The `.` operator, between two patterns the nodes must be consecutive named siblings,
+    /// first or last pattern respectively matching the first or last named child
+    Anchor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Quantifier {
+    ZeroOrOne,
+    ZeroOrMore,
+    OneOrMore,
 }
+
+#[derive(Debug)]
+pub(crate) enum PredArg {
+    Capture(String),
+    String(String),
+}
+
 #[derive(Debug)]
 pub(crate) enum Predicate {
     Eq { left: String, right: String },
     EqString { left: String, right: String },
+    NotEq { left: String, right: String },
+    NotEqString { left: String, right: String },
+    Match { left: String, re: regex::Regex },
+    NotMatch { left: String, re: regex::Regex },
+    AnyOf { left: String, values: Vec<String> },
+}
+
+impl Predicate {
+    fn new(name: &str, args: Vec<PredArg>) -> Result<Self, QueryError> {
+        let invalid = |msg: &str| QueryError::Predicate(format!("#{}? {}", name, msg));
+        let mut args = args.into_iter();
+        let Some(PredArg::Capture(left)) = args.next() else {
+            return Err(invalid("should start with a capture"));
+        };
+        let regex = |x: Option<PredArg>| match x {
+            Some(PredArg::String(re)) => regex::Regex::new(&re).map_err(QueryError::Regex),
+            _ => Err(invalid("takes a string as second argument")),
+        };
+        Ok(match name {
+            "eq" | "not-eq" => {
+                let negated = name == "not-eq";
+                match args.next() {
+                    Some(PredArg::Capture(right)) if negated => Predicate::NotEq { left, right },
+                    Some(PredArg::Capture(right)) => Predicate::Eq { left, right },
+                    Some(PredArg::String(right)) if negated => {
+                        Predicate::NotEqString { left, right }
+                    }
+                    Some(PredArg::String(right)) => Predicate::EqString { left, right },
+                    None => return Err(invalid("takes two arguments")),
+                }
+            }
+            "match" => Predicate::Match {
+                left,
+                re: regex(args.next())?,
+            },
+            "not-match" => Predicate::NotMatch {
+                left,
+                re: regex(args.next())?,
+            },
+            "any-of" => Predicate::AnyOf {
+                left,
+                values: args
+                    .map(|x| match x {
+                        PredArg::String(x) => Ok(x),
+                        PredArg::Capture(_) => Err(invalid("only takes strings as values")),
+                    })
+                    .collect::<Result<_, _>>()?,
+            },
+            name => return Err(QueryError::UnknownPredicate(name.to_string())),
+        })
+    }
+
+    fn check(&self, captures: &Captures) -> bool {
+        let label = |x: &String| match captures.get(x) {
+            Some(CaptureRes::Label(l)) => Some(l.as_str()),
+            _ => None,
+        };
+        match self {
+            Predicate::Eq { left, right } => captures
+                .get(left)
+                .map_or(false, |x| Some(x) == captures.get(right)),
+            Predicate::NotEq { left, right } => captures
+                .get(left)
+                .map_or(true, |x| Some(x) != captures.get(right)),
+            Predicate::EqString { left, right } => label(left) == Some(right.as_str()),
+            Predicate::NotEqString { left, right } => label(left) != Some(right.as_str()),
+            Predicate::Match { left, re } => label(left).map_or(false, |x| re.is_match(x)),
+            Predicate::NotMatch { left, re } => label(left).map_or(true, |x| !re.is_match(x)),
+            Predicate::AnyOf { left, values } => {
+                label(left).map_or(false, |x| values.iter().any(|v| v == x))
+            }
+        }
+    }
 }
+
+type Captures = std::collections::HashMap<String, CaptureRes>;
+
 #[derive(Debug)]
 pub(crate) struct MatchingRes {
     matched: bool,
-    captures: std::collections::HashMap<String, CaptureRes>,
+    captures: Captures,
 }
 
 impl MatchingRes {
@@ -539,21 +872,82 @@ impl MatchingRes {
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
-pub(crate) enum CaptureRes {
+pub enum CaptureRes {
     Label(String),
-    Node,
+    /// A node without label, identified by a hash of its id.
+    /// Subtrees are deduplicated, so equal nodes have the same text.
+    Node(u64),
 }
 
 impl CaptureRes {
-    pub(crate) fn label(self) -> Option<String> {
+    pub fn label(self) -> Option<String> {
         match self {
             CaptureRes::Label(l) => Some(l),
-            CaptureRes::Node => None,
+            CaptureRes::Node(_) => None,
         }
     }
 }
 
+impl<Ty: Copy> Pattern<Ty> {
+    fn predicated(pat: Self, predicates: Vec<Predicate>) -> Self {
+        predicates
+            .into_iter()
+            .fold(pat, |pat, predicate| Pattern::Predicated {
+                predicate,
+                pat: Arc::new(pat),
+            })
+    }
+
+    /// The field applies to each node matched by `pat`, ie. to each repetition
+    fn field(name: String, pat: Self) -> Result<Self, QueryError> {
+        let (quantifier, pat) = match pat {
+            Pattern::Quantified { quantifier, pat } => (Some(quantifier), pat),
+            pat => (None, Arc::new(pat)),
+        };
+        if !pat.is_single() {
+            return Err(QueryError::Malformed("a field should match a single node"));
+        }
+        let pat = Pattern::Field { name, pat };
+        Ok(match quantifier {
+            Some(quantifier) => Pattern::Quantified {
+                quantifier,
+                pat: Arc::new(pat),
+            },
+            None => pat,
+        })
+    }
+
+    /// The type of the node matched by this pattern, if known
+    fn root_type(&self) -> Option<Ty> {
+        match self {
+            Pattern::NamedNode { ty, .. } => *ty,
+            Pattern::AnonymousNode(ty) => Some(*ty),
+            Pattern::Capture { pat, .. }
+            | Pattern::Field { pat, .. }
+            | Pattern::Predicated { pat, .. }
+            | Pattern::Quantified { pat, .. } => pat.root_type(),
+            _ => None,
+        }
+    }
+
+    /// Does this pattern always match exactly one node
+    fn is_single(&self) -> bool {
+        match self {
+            Pattern::NamedNode { .. } | Pattern::AnonymousNode(_) | Pattern::AnyNode => true,
+            Pattern::Capture { pat, .. }
+            | Pattern::Field { pat, .. }
+            | Pattern::Predicated { pat, .. } => pat.is_single(),
+            Pattern::List(alts) => alts.iter().all(|x| x.is_single()),
+            _ => false,
+        }
+    }
+}
+
+type Cont<'k> = &'k mut dyn FnMut(usize, Captures) -> Option<Captures>;
+
 impl<Ty> Pattern<Ty> {
+    /// Matches `id` against this pattern.
+    /// A top-level sequence of siblings matches the children of `id`.
     fn is_matching<'store, HAST, TIdN>(
         &self,
         code_store: &'store HAST,
@@ -564,120 +958,322 @@ impl<Ty> Pattern<Ty> {
         TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
             + hyper_ast::types::TypedNodeId<Ty = Ty>
             + 'static,
-        Ty: std::fmt::Debug + Eq + Copy,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+    {
+        match self.matches_node(code_store, id, None, Default::default()) {
+            Some(captures) => MatchingRes {
+                matched: true,
+                captures,
+            },
+            None => MatchingRes::fals(),
+        }
+    }
+
+    /// `role` is the field of `id` in its parent, if any
+    fn matches_node<'store, HAST, TIdN>(
+        &self,
+        code_store: &'store HAST,
+        id: HAST::IdN,
+        role: Option<&str>,
+        captures: Captures,
+    ) -> Option<Captures>
+    where
+        HAST: TypedHyperAST<'store, TIdN>,
+        TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
+            + hyper_ast::types::TypedNodeId<Ty = Ty>
+            + 'static,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
     {
         let Some((n, _)) = code_store.typed_node_store().try_resolve(&id) else {
-            dbg!();
-            return MatchingRes::fals();
+            return None;
         };
         let t = n.get_type();
-
         match self {
-            Pattern::NamedNode { ty, children } => {
-                if *ty != t {
-                    return MatchingRes::fals();
+            Pattern::NamedNode {
+                ty,
+                negated,
+                children,
+            } => {
+                match ty {
+                    Some(ty) if *ty != t => return None,
+                    None if !is_named(&t) => return None,
+                    _ => (),
                 }
-                let Some(cs) = n.children() else {
-                    return MatchingRes {
-                        matched: children.is_empty(),
-                        captures: Default::default(),
-                    };
-                };
-                let mut cs = cs.iter_children();
-                let mut pats = children.iter().peekable();
-                let mut captures = Default::default();
-                if pats.peek().is_none() {
-                    return MatchingRes {
-                        matched: true,
-                        captures,
-                    };
-                }
-                let mut matched = false;
-                loop {
-                    let Some(curr_p) = pats.peek() else {
-                        return MatchingRes { matched, captures };
-                    };
-                    let Some(child) = cs.next() else {
-                        return MatchingRes::fals();
-                    };
-                    match curr_p.is_matching(code_store, child.clone()) {
-                        MatchingRes {
-                            matched: true,
-                            captures: capt,
-                        } => {
-                            pats.next();
-                            matched = true;
-                            captures.extend(capt);
-                        }
-                        MatchingRes { matched: false, .. } => {}
-                    }
+                let cs = Self::children(code_store, &id);
+                let has_negated = |r: &&str| negated.iter().any(|x| x == r);
+                if cs.roles.iter().flatten().any(has_negated) {
+                    return None;
                 }
+                Self::seq(children, code_store, &cs, 0, false, captures, &mut |_, c| {
+                    Some(c)
+                })
+            }
+            Pattern::AnonymousNode(ty) => (*ty == t).then_some(captures),
+            Pattern::AnyNode => (!t.is_spaces()).then_some(captures),
+            Pattern::Capture { name, pat } => {
+                let mut captures = pat.matches_node(code_store, id.clone(), role, captures)?;
+                captures.insert(name.clone(), Self::capture(code_store, &id));
+                Some(captures)
+            }
+            Pattern::Field { name, pat } if role == Some(name.as_str()) => {
+                pat.matches_node(code_store, id, role, captures)
             }
-            Pattern::AnonymousNode(ty) => MatchingRes {
-                matched: *ty == t,
-                captures: Default::default(),
+            Pattern::Field { .. } => None,
+            Pattern::Predicated { predicate, pat } => {
+                let captures = pat.matches_node(code_store, id, role, captures)?;
+                predicate.check(&captures).then_some(captures)
+            }
+            Pattern::Quantified { pat, .. } => pat.matches_node(code_store, id, role, captures),
+            Pattern::List(alts) => alts
+                .iter()
+                .find_map(|x| x.matches_node(code_store, id.clone(), role, captures.clone())),
+            Pattern::Grouping(pats) => {
+                let cs = Self::children(code_store, &id);
+                Self::seq(pats, code_store, &cs, 0, false, captures, &mut |_, c| Some(c))
+            }
+            Pattern::Anchor => Some(captures),
+        }
+    }
+
+    /// Matches the sequence of patterns `pats` against the siblings `cs` starting at `i`,
+    /// then continues with `k` on the index following the last matched sibling.
+    fn seq<'store, HAST, TIdN>(
+        pats: &[Pattern<Ty>],
+        code_store: &'store HAST,
+        cs: &Siblings<'_, HAST::IdN>,
+        i: usize,
+        anchored: bool,
+        captures: Captures,
+        k: Cont<'_>,
+    ) -> Option<Captures>
+    where
+        HAST: TypedHyperAST<'store, TIdN>,
+        TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
+            + hyper_ast::types::TypedNodeId<Ty = Ty>
+            + 'static,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+    {
+        let Some((p, rest)) = pats.split_first() else {
+            return k(i, captures);
+        };
+        match p {
+            Pattern::Anchor if rest.is_empty() => {
+                // the previous pattern matched the last named child
+                let is_last = (cs.ids[i..].iter()).all(|x| !Self::is_named_node(code_store, x));
+                is_last.then(|| k(i, captures)).flatten()
+            }
+            Pattern::Anchor => Self::seq(rest, code_store, cs, i, true, captures, k),
+            p => p.elem(code_store, cs, i, anchored, captures, &mut |j, captures| {
+                Self::seq(rest, code_store, cs, j, false, captures, &mut *k)
+            }),
+        }
+    }
+
+    /// Matches this pattern as an element of a sequence of siblings
+    fn elem<'store, HAST, TIdN>(
+        &self,
+        code_store: &'store HAST,
+        cs: &Siblings<'_, HAST::IdN>,
+        i: usize,
+        anchored: bool,
+        captures: Captures,
+        k: Cont<'_>,
+    ) -> Option<Captures>
+    where
+        HAST: TypedHyperAST<'store, TIdN>,
+        TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
+            + hyper_ast::types::TypedNodeId<Ty = Ty>
+            + 'static,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+    {
+        match self {
+            Pattern::Grouping(pats) => Self::seq(pats, code_store, cs, i, anchored, captures, k),
+            Pattern::List(alts) if !self.is_single() => alts.iter().find_map(|x| {
+                x.elem(code_store, cs, i, anchored, captures.clone(), &mut *k)
+            }),
+            Pattern::Quantified { quantifier, pat } => match quantifier {
+                Quantifier::ZeroOrOne => pat
+                    .elem(code_store, cs, i, anchored, captures.clone(), &mut *k)
+                    .or_else(|| k(i, captures)),
+                Quantifier::ZeroOrMore => pat.repeat(code_store, cs, i, anchored, 0, captures, k),
+                Quantifier::OneOrMore => pat.repeat(code_store, cs, i, anchored, 1, captures, k),
             },
-            Pattern::Capture { name, pat } => match pat.is_matching(code_store, id.clone()) {
-                MatchingRes {
-                    matched: true,
-                    mut captures,
-                } => {
-                    let name = name.clone();
-                    let n = code_store.typed_node_store().try_resolve(&id).unwrap().0;
-                    let v = if n.children().map_or(true, |x| x.is_empty()) {
-                        let l = n.try_get_label().unwrap();
-                        use hyper_ast::types::LabelStore;
-                        let l = code_store.label_store().resolve(l);
-                        CaptureRes::Label(l.to_owned())
-                    } else {
-                        todo!()
-                    };
-                    captures.insert(name, v);
-                    MatchingRes {
-                        matched: true,
-                        captures,
+            Pattern::Capture { name, pat } if !pat.is_single() => {
+                pat.elem(code_store, cs, i, anchored, captures, &mut |j, mut captures| {
+                    if j > i {
+                        // captures the last node of the sequence
+                        captures.insert(name.clone(), Self::capture(code_store, &cs.ids[j - 1]));
                     }
-                }
-                MatchingRes { matched: false, .. } => MatchingRes::fals(),
-            },
-            Pattern::Predicated { predicate, pat } => match predicate {
-                Predicate::Eq { left, right } => {
-                    let MatchingRes { matched, captures } = pat.is_matching(code_store, id);
-                    if matched {
-                        let matched = captures
-                            .get(left)
-                            .map_or(false, |x| Some(x) == captures.get(right));
-                        let captures = if matched {
-                            captures
-                        } else {
-                            Default::default()
-                        };
-                        MatchingRes { matched, captures }
+                    k(j, captures)
+                })
+            }
+            Pattern::Predicated { predicate, pat } if !pat.is_single() => {
+                pat.elem(code_store, cs, i, anchored, captures, &mut |j, captures| {
+                    if predicate.check(&captures) {
+                        k(j, captures)
                     } else {
-                        MatchingRes::fals()
+                        None
                     }
-                }
-                Predicate::EqString { left, right } => {
-                    let MatchingRes { matched, captures } = pat.is_matching(code_store, id);
-                    if matched {
-                        let Some(CaptureRes::Label(left)) = captures.get(left) else {
-                            return MatchingRes::fals();
-                        };
-                        let matched = left == right;
-                        let captures = if matched {
-                            captures
-                        } else {
-                            Default::default()
-                        };
-                        MatchingRes { matched, captures }
-                    } else {
-                        MatchingRes::fals()
+                })
+            }
+            Pattern::Anchor => k(i, captures),
+            p => {
+                let end = if anchored {
+                    // only anonymous nodes can be skipped
+                    cs.ids[i..]
+                        .iter()
+                        .position(|x| Self::is_named_node(code_store, x))
+                        .map_or(cs.ids.len(), |x| i + x + 1)
+                } else {
+                    cs.ids.len()
+                };
+                for j in i..end {
+                    let id = cs.ids[j].clone();
+                    let Some(captures) =
+                        p.matches_node(code_store, id, cs.roles[j], captures.clone())
+                    else {
+                        continue;
+                    };
+                    if let Some(captures) = k(j + 1, captures) {
+                        return Some(captures);
                     }
                 }
-                p => todo!("{:?}", p),
-            },
+                None
+            }
+        }
+    }
+
+    /// Greedily matches repetitions of this pattern, at least `min` times
+    fn repeat<'store, HAST, TIdN>(
+        &self,
+        code_store: &'store HAST,
+        cs: &Siblings<'_, HAST::IdN>,
+        i: usize,
+        anchored: bool,
+        min: usize,
+        captures: Captures,
+        k: Cont<'_>,
+    ) -> Option<Captures>
+    where
+        HAST: TypedHyperAST<'store, TIdN>,
+        TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
+            + hyper_ast::types::TypedNodeId<Ty = Ty>
+            + 'static,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+    {
+        let res = self.elem(code_store, cs, i, anchored, captures.clone(), &mut |j, captures| {
+            if j == i {
+                // each repetition must consume at least a node
+                return None;
+            }
+            self.repeat(code_store, cs, j, false, min.saturating_sub(1), captures, &mut *k)
+        });
+        if res.is_some() {
+            res
+        } else if min == 0 {
+            k(i, captures)
+        } else {
+            None
+        }
+    }
+
+    /// The children of `id`, without spaces
+    fn children<'store, HAST, TIdN>(
+        code_store: &'store HAST,
+        id: &HAST::IdN,
+    ) -> Siblings<'store, HAST::IdN>
+    where
+        HAST: TypedHyperAST<'store, TIdN>,
+        TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
+            + hyper_ast::types::TypedNodeId<Ty = Ty>
+            + 'static,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+    {
+        use hyper_ast::types::LabelStore;
+        let mut res = Siblings {
+            ids: vec![],
+            roles: vec![],
+        };
+        let Some((n, _)) = code_store.typed_node_store().try_resolve(id) else {
+            return res;
+        };
+        let Some(cs) = n.children() else {
+            return res;
+        };
+        for (i, x) in cs.iter_children().enumerate() {
+            let is_spaces = (code_store.typed_node_store().try_resolve(x))
+                .map_or(false, |(x, _)| x.get_type().is_spaces());
+            if is_spaces {
+                continue;
+            }
+            res.ids.push(x.clone());
+            let role = n.role_at(i).map(|l| code_store.label_store().resolve(l));
+            res.roles.push(role);
         }
+        res
     }
+
+    fn is_named_node<'store, HAST, TIdN>(code_store: &'store HAST, id: &HAST::IdN) -> bool
+    where
+        HAST: TypedHyperAST<'store, TIdN>,
+        TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
+            + hyper_ast::types::TypedNodeId<Ty = Ty>
+            + 'static,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+    {
+        code_store
+            .typed_node_store()
+            .try_resolve(id)
+            .map_or(false, |(n, _)| is_named(&n.get_type()))
+    }
+
+    fn capture<'store, HAST, TIdN>(code_store: &'store HAST, id: &HAST::IdN) -> CaptureRes
+    where
+        HAST: TypedHyperAST<'store, TIdN>,
+        TIdN: hyper_ast::types::NodeId<IdN = HAST::IdN>
+            + hyper_ast::types::TypedNodeId<Ty = Ty>
+            + 'static,
+        HAST::IdN: std::hash::Hash,
+        HAST::TT: WithRoles,
+        Ty: std::fmt::Debug + Eq + Copy + HyperType,
+    {
+        use hyper_ast::types::LabelStore;
+        use std::hash::{Hash, Hasher};
+        let n = code_store.typed_node_store().try_resolve(id).unwrap().0;
+        match n.try_get_label() {
+            Some(l) => CaptureRes::Label(code_store.label_store().resolve(l).to_owned()),
+            None => {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                id.hash(&mut hasher);
+                CaptureRes::Node(hasher.finish())
+            }
+        }
+    }
+}
+
+/// Siblings without spaces, with their fields
+struct Siblings<'store, IdN> {
+    ids: Vec<IdN>,
+    roles: Vec<Option<&'store str>>,
+}
+
+/// Approximates tree-sitter named nodes, as the HyperAST only knows about syntax tokens
+fn is_named<Ty: HyperType>(t: &Ty) -> bool {
+    !t.is_syntax() && !t.is_spaces()
 }
 
 pub(crate) struct QueryMatcher<'a, T, S> {
@@ -732,7 +1328,7 @@ pub(crate) fn extract_root_type(
     }
 }
 
-pub(crate) fn ts_query(text: &[u8]) -> (SimpleStores<crate::types::TStore>, legion::Entity) {
+pub fn ts_query(text: &[u8]) -> (SimpleStores<crate::types::TStore>, legion::Entity) {
     use crate::types::TStore;
     let mut stores = SimpleStores {
         label_store: LabelStore::new(),
@@ -72,7 +72,8 @@ fn gen_match_simple() {
             crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
                 &query_store,
                 query,
-            );
+            )
+            .unwrap();
         let mut matched = false;
         for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store, path, code) {
             if prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
@@ -100,7 +101,8 @@ fn gen_match_simple() {
             crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
                 &query_store,
                 query,
-            );
+            )
+            .unwrap();
         for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store1, path, code1) {
             if prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
                 &code_store1,
@@ -147,7 +149,8 @@ fn gen_match_named() {
         crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
             &query_store,
             query,
-        );
+        )
+        .unwrap();
     let mut matched = false;
     for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store, path, code) {
         if prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
@@ -174,7 +177,8 @@ fn gen_match_named() {
         crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
             &query_store,
             query,
-        );
+        )
+        .unwrap();
     for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store1, path, code1) {
         if prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
             &code_store1,
@@ -197,7 +201,8 @@ fn gen_match_named() {
         crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
             &query_store,
             query,
-        );
+        )
+        .unwrap();
     for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store2, path, code2) {
         if prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
             &code_store2,
@@ -216,7 +221,8 @@ fn gen_match_named() {
 
     let path = hyper_ast::position::StructuralPosition::new(query);
     let prepared_matcher =
-        crate::search::PreparedMatcher::<_, crate::types::Type>::new(&query_store1, query1);
+        crate::search::PreparedMatcher::<_, crate::types::Type>::new(&query_store1, query1)
+            .unwrap();
     let mut per_label = std::collections::HashMap::<
         String,
         Vec<(String, StructuralPosition<NodeIdentifier, u16>)>,
@@ -292,7 +298,8 @@ fn gen_match_named() {
             crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
                 &query_store,
                 query,
-            );
+            )
+            .unwrap();
         let mut matched = false;
         for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store2, path, code2) {
             if prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
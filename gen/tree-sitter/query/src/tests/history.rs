@@ -0,0 +1,70 @@
+use hyper_ast::store::{defaults::NodeIdentifier, SimpleStores};
+use hyper_ast_gen_ts_cpp::{legion::CppTreeGen, types::TStore};
+
+const Q0: &str = r#"(binary_expression (number_literal) "+" (number_literal))"#;
+
+const V0: &str = r#"int f() {
+    return 21 + 21;
+}"#;
+
+const V1: &str = r#"int f() {
+    int a = 1 + 2;
+    return 21 + 21;
+}"#;
+
+const V2: &str = r#"int f() {
+    int a = 1 + 2;
+    return a;
+}"#;
+
+#[test]
+fn appeared_disappeared_persisted() {
+    let mut code_store: SimpleStores<TStore> = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = CppTreeGen {
+        line_break: "\n".as_bytes().to_vec(),
+        stores: &mut code_store,
+        md_cache: &mut md_cache,
+    };
+    let versions: Vec<_> = [V0, V1, V2]
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let tree = match CppTreeGen::<TStore>::tree_sitter_parse(text.as_bytes()) {
+                Ok(t) => t,
+                Err(t) => t,
+            };
+            let x = tree_gen
+                .generate_file(b"", text.as_bytes(), tree.walk())
+                .local;
+            (i, x.compressed_node)
+        })
+        .collect();
+    let (query_store, query) = crate::search::ts_query(Q0.as_bytes());
+    let prepared_matcher =
+        crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
+            &query_store,
+            query,
+        )
+        .unwrap();
+    let res_roots: Vec<_> = versions.iter().map(|x| x.1).collect();
+    let mut matcher = crate::history::HistoryMatcher::new(&prepared_matcher);
+    let res = matcher.query_history::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>, _>(
+        &code_store,
+        versions,
+    );
+    let count = |x: &[(NodeIdentifier, usize)]| x.iter().map(|x| x.1).sum::<usize>();
+    assert_eq!(res.len(), 3);
+    assert_eq!(count(&res[0].appeared), 1);
+    assert_eq!(count(&res[1].appeared), 1);
+    assert_eq!(count(&res[1].persisted), 1);
+    assert_eq!(count(&res[2].disappeared), 1);
+    assert_eq!(count(&res[2].persisted), 1);
+    assert!(res[2].appeared.is_empty());
+    // already evaluated subtrees are not evaluated again
+    let evaluated = matcher.evaluated_count();
+    let m = matcher
+        .matches::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(&code_store, res_roots[1]);
+    assert_eq!(m.len(), 2);
+    assert_eq!(matcher.evaluated_count(), evaluated);
+}
@@ -54,4 +54,5 @@ fn run(text: &[u8]) {
 }
 
 mod search;
-mod auto;
\ No newline at end of file
+mod auto;
+mod history;
\ No newline at end of file
@@ -46,7 +46,8 @@ fn simple() {
         crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
             &query_store,
             query,
-        );
+        )
+        .unwrap();
     let mut matched = false;
     for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store, path, code) {
         if prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
@@ -71,7 +72,8 @@ fn simple() {
         crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
             &query_store,
             query,
-        );
+        )
+        .unwrap();
     for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store1, path, code1) {
         if prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
             &code_store1,
@@ -99,7 +101,8 @@ fn named() {
         crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
             &query_store,
             query,
-        );
+        )
+        .unwrap();
     let mut matched = false;
     for e in hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store, path, code) {
         if let Some(captures) = prepared_matcher
@@ -122,6 +125,184 @@ fn named() {
     assert!(matched);
 }
 
+/// count the nodes of `code` matching `query`
+fn count_matches(query: &str, code: &str) -> usize {
+    let (code_store, code) = cpp_tree(code.as_bytes());
+    let (query_store, query) = crate::search::ts_query(query.as_bytes());
+    let path = hyper_ast::position::StructuralPosition::new(code);
+    let prepared_matcher =
+        crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
+            &query_store,
+            query,
+        )
+        .unwrap();
+    hyper_ast_gen_ts_cpp::iter::IterAll::new(&code_store, path, code)
+        .filter(|e| {
+            prepared_matcher.is_matching::<_, hyper_ast_gen_ts_cpp::types::TIdN<NodeIdentifier>>(
+                &code_store,
+                *e.node().unwrap(),
+            )
+        })
+        .count()
+}
+
+#[test]
+fn full_language() {
+    // wildcards
+    assert_eq!(count_matches(r#"(binary_expression (_) _ (_))"#, C0), 1);
+    // alternations
+    assert_eq!(count_matches(r#"(binary_expression ["-" "+"])"#, C0), 1);
+    assert_eq!(count_matches(r#"(binary_expression ["-" "*"])"#, C0), 0);
+    // quantifiers
+    assert_eq!(
+        count_matches(r#"(compound_statement (declaration)? (return_statement))"#, C0),
+        1
+    );
+    assert_eq!(
+        count_matches(r#"(compound_statement (declaration)+ (return_statement))"#, C0),
+        0
+    );
+    assert_eq!(
+        count_matches(r#"(compound_statement (declaration)* (return_statement))"#, C2),
+        1
+    );
+    // anchors
+    assert_eq!(
+        count_matches(r#"(compound_statement . (declaration))"#, C2),
+        1
+    );
+    assert_eq!(
+        count_matches(r#"(compound_statement . (return_statement))"#, C2),
+        0
+    );
+    assert_eq!(
+        count_matches(r#"(compound_statement (declaration) .)"#, C2),
+        0
+    );
+    // predicates
+    assert_eq!(
+        count_matches(r#"((identifier) @id (#match? @id "^[a-z]$"))"#, C2),
+        4
+    );
+    assert_eq!(
+        count_matches(r#"((identifier) @id (#not-eq? @id "a"))"#, C2),
+        1
+    );
+    assert_eq!(
+        count_matches(r#"((number_literal) @n (#any-of? @n "21" "42"))"#, C2),
+        1
+    );
+    assert_eq!(
+        count_matches(r#"((identifier) @id (#not-match? @id "^[fa]$"))"#, C2),
+        0
+    );
+    assert_eq!(
+        count_matches(r#"((identifier) @id (#not-match? @id "^a$"))"#, C2),
+        1
+    );
+}
+
+#[test]
+fn fields() {
+    assert_eq!(count_matches(r#"(binary_expression left: (identifier))"#, C2), 1);
+    assert_eq!(count_matches(r#"(binary_expression left: (number_literal))"#, C2), 0);
+    assert_eq!(count_matches(r#"(binary_expression left: (number_literal))"#, C0), 1);
+    // the field is on the node, not on its position
+    assert_eq!(
+        count_matches(r#"(binary_expression right: (number_literal) "+")"#, C0),
+        0
+    );
+    assert_eq!(
+        count_matches(r#"(binary_expression "+" right: (number_literal))"#, C0),
+        1
+    );
+    assert_eq!(
+        count_matches(
+            r#"(function_declarator declarator: (identifier) @name (#eq? @name "f"))"#,
+            C0
+        ),
+        1
+    );
+    assert_eq!(
+        count_matches(r#"(init_declarator value: [(identifier) (number_literal)])"#, C2),
+        1
+    );
+    // negated fields
+    assert_eq!(count_matches(r#"(function_definition !body)"#, C0), 0);
+    assert_eq!(count_matches(r#"(compound_statement !body)"#, C0), 1);
+    assert_eq!(
+        count_matches(r#"(function_definition !declarator body: (_))"#, C0),
+        0
+    );
+}
+
+const C3: &str = r#"int f(int a) {
+    return a + 1 == a + 1 || a + 1 == a + 2;
+}"#;
+
+#[test]
+fn eq_non_leaf_captures() {
+    // only the first comparison has the same subtree on both sides
+    assert_eq!(
+        count_matches(
+            r#"(binary_expression (_) @l "==" (_) @r (#eq? @l @r))"#,
+            C3
+        ),
+        1
+    );
+    assert_eq!(
+        count_matches(
+            r#"(binary_expression (_) @l "==" (_) @r (#not-eq? @l @r))"#,
+            C3
+        ),
+        1
+    );
+}
+
+/// the error given when preparing `query` for C++
+fn prepare_error(query: &str) -> crate::search::QueryError {
+    let (query_store, query) = crate::search::ts_query(query.as_bytes());
+    let prepared_matcher =
+        crate::search::PreparedMatcher::<_, hyper_ast_gen_ts_cpp::types::Type>::new(
+            &query_store,
+            query,
+        );
+    match prepared_matcher {
+        Ok(_) => panic!("the query should be rejected"),
+        Err(e) => e,
+    }
+}
+
+#[test]
+fn rejected_queries() {
+    use crate::search::QueryError;
+    // fields only exist in a parent
+    assert!(matches!(
+        prepare_error(r#"left: (identifier)"#),
+        QueryError::Malformed(_)
+    ));
+    assert!(matches!(
+        prepare_error(r#"(not_a_cpp_node)"#),
+        QueryError::UnknownType(_)
+    ));
+    assert!(matches!(
+        prepare_error(r#"((identifier) @id (#is-odd? @id))"#),
+        QueryError::UnknownPredicate(_)
+    ));
+    assert!(matches!(
+        prepare_error(r#"((identifier) @id (#match? @id "("))"#),
+        QueryError::Regex(_)
+    ));
+    assert!(matches!(
+        prepare_error(r#"((identifier) @id (#eq? @id))"#),
+        QueryError::Predicate(_)
+    ));
+    assert!(matches!(
+        prepare_error(r#"(binary_expression (identifier)))"#),
+        QueryError::Syntax
+    ));
+}
+
 fn cpp_tree(
     text: &[u8],
 ) -> (
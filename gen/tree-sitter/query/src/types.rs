@@ -218,7 +218,21 @@ impl HyperType for Type {
     }
 
     fn is_syntax(&self) -> bool {
-        todo!()
+        self == &Type::End
+            || self == &Type::Dot // ".",
+            || self == &Type::TS0 // "\"",
+            || self == &Type::Star // "*",
+            || self == &Type::Plus // "+",
+            || self == &Type::QMark // "?",
+            || self == &Type::TS2 // "_",
+            || self == &Type::At // "@",
+            || self == &Type::LBracket // "[",
+            || self == &Type::RBracket // "]",
+            || self == &Type::LParen // "(",
+            || self == &Type::RParen // ")",
+            || self == &Type::Colon // ":",
+            || self == &Type::Bang // "!",
+            || self == &Type::TS3 // "#",
     }
 
     fn as_shared(&self) -> hyper_ast::types::Shared {
@@ -0,0 +1,45 @@
+#[test]
+fn type_test_generic_eq() {
+    use hyper_ast::types::HyperType;
+
+    let k = crate::types::Type::FunctionItem;
+    let k0 = crate::types::Type::FunctionItem;
+    let k1 = crate::types::Type::StructItem;
+    assert!(k.eq(&k));
+    assert!(k.eq(&k0));
+    assert!(k0.eq(&k));
+    assert!(k1.eq(&k1));
+    assert!(k.ne(&k1));
+    assert!(k1.ne(&k));
+
+    assert!(k.generic_eq(&k));
+    assert!(k.generic_eq(&k0));
+    assert!(!k.generic_eq(&k1));
+    assert!(!k1.generic_eq(&k));
+
+    let ak = crate::types::as_any(&crate::types::Type::FunctionItem);
+    let ak0 = crate::types::as_any(&crate::types::Type::FunctionItem);
+    let ak1 = crate::types::as_any(&crate::types::Type::StructItem);
+
+    assert!(ak.generic_eq(&ak0));
+    assert!(!ak.generic_eq(&ak1));
+    assert!(k.generic_eq(&ak));
+    assert!(!k.generic_eq(&ak1));
+    assert!(ak.generic_eq(&k0));
+    assert!(!ak1.generic_eq(&k));
+
+    assert!(ak.eq(&ak0));
+    assert!(!ak.eq(&ak1));
+}
+
+#[test]
+fn type_test_lang() {
+    use hyper_ast::types::{HyperType, Lang};
+
+    let t = crate::types::Type::FunctionItem;
+    let raw = <crate::types::Lang as Lang<_>>::to_u16(t);
+    assert_eq!(<crate::types::Lang as Lang<_>>::make(raw), &t);
+    assert!(crate::types::Type::Directory.is_directory());
+    assert!(crate::types::Type::Spaces.is_spaces());
+    assert!(!t.is_file());
+}
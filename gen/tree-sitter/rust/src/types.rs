@@ -0,0 +1,1958 @@
+use std::fmt::Display;
+
+use hyper_ast::{
+    store::defaults::NodeIdentifier,
+    tree_gen::parser::NodeWithU16TypeId,
+    types::{AnyType, HyperType, LangRef, NodeId, TypeStore, TypeTrait, TypedNodeId},
+};
+
+#[cfg(feature = "legion")]
+mod legion_impls {
+    use super::*;
+
+    use crate::TNode;
+
+    impl<'a> TNode<'a> {
+        pub fn obtain_type<T>(&self, _: &mut impl RustEnabledTypeStore<T>) -> Type {
+            let t = self.kind_id();
+            Type::from_u16(t)
+        }
+    }
+
+    use hyper_ast::{store::nodes::legion::HashedNodeRef, types::TypeIndex};
+
+    impl<'a> TypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        type Ty = Type;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Ty {
+            n.get_component::<Type>().unwrap().clone()
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<Type>)>::from(&Lang)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Lang),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+    impl<'a> RustEnabledTypeStore<HashedNodeRef<'a, TIdN<NodeIdentifier>>> for TStore {
+        const LANG: TypeInternalSize = Self::Rust as u16;
+
+        fn _intern(l: u16, t: u16) -> Self::Ty {
+            // T((u16::MAX - l as u16) | t)
+            todo!()
+        }
+        fn intern(&self, t: Type) -> Self::Ty {
+            t
+        }
+
+        fn resolve(&self, t: Self::Ty) -> Type {
+            t
+        }
+    }
+    impl<'a> TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TStore {
+        type Ty = AnyType;
+        const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
+        fn resolve_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Ty {
+            From::<&'static (dyn HyperType)>::from(LangRef::<Type>::make(
+                &Lang,
+                *n.get_component::<Type>().unwrap() as u16,
+            ))
+        }
+
+        fn resolve_lang(
+            &self,
+            n: &HashedNodeRef<'a, NodeIdentifier>,
+        ) -> hyper_ast::types::LangWrapper<Self::Ty> {
+            From::<&'static (dyn LangRef<AnyType>)>::from(&Lang)
+        }
+
+        type Marshaled = TypeIndex;
+
+        fn marshal_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Marshaled {
+            TypeIndex {
+                lang: LangRef::<Type>::name(&Lang),
+                ty: *n.get_component::<Type>().unwrap() as u16,
+            }
+        }
+    }
+}
+
+pub trait RustEnabledTypeStore<T>: TypeStore<T> {
+    const LANG: u16;
+    fn intern(&self, t: Type) -> Self::Ty {
+        let t = t as u16;
+        Self::_intern(Self::LANG, t)
+    }
+    fn _intern(l: u16, t: u16) -> Self::Ty;
+    fn resolve(&self, t: Self::Ty) -> Type;
+}
+
+impl Type {
+    pub fn resolve(t: u16) -> Self {
+        assert!(t < COUNT);
+        unsafe { std::mem::transmute(t) }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TIdN<IdN>(IdN);
+
+impl<IdN: Clone + Eq + NodeId> NodeId for TIdN<IdN> {
+    type IdN = IdN;
+
+    fn as_id(&self) -> &Self::IdN {
+        &self.0
+    }
+
+    unsafe fn from_id(id: Self::IdN) -> Self {
+        Self(id)
+    }
+
+    unsafe fn from_ref_id(id: &Self::IdN) -> &Self {
+        todo!()
+    }
+}
+
+impl<IdN: Clone + Eq + NodeId> TypedNodeId for TIdN<IdN> {
+    type Ty = Type;
+}
+
+#[repr(u8)]
+pub enum TStore {
+    Rust = 0,
+}
+
+impl Default for TStore {
+    fn default() -> Self {
+        Self::Rust
+    }
+}
+
+type TypeInternalSize = u16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct T(TypeInternalSize);
+
+pub struct Lang;
+pub type Rust = Lang;
+
+pub fn as_any(t: &Type) -> AnyType {
+    let t = <Rust as hyper_ast::types::Lang<Type>>::to_u16(*t);
+    let t = <Rust as hyper_ast::types::Lang<Type>>::make(t);
+    let t: &'static dyn HyperType = t;
+    t.into()
+}
+
+impl LangRef<AnyType> for Rust {
+    fn make(&self, t: u16) -> &'static AnyType {
+        panic!()
+        // &From::<&'static dyn HyperType>::from(&S_T_L[t as usize])
+    }
+    fn to_u16(&self, t: AnyType) -> u16 {
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Rust>()
+    }
+}
+
+impl LangRef<Type> for Rust {
+    fn make(&self, t: u16) -> &'static Type {
+        &S_T_L[t as usize]
+    }
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Rust>()
+    }
+}
+
+impl hyper_ast::types::Lang<Type> for Rust {
+    fn make(t: u16) -> &'static Type {
+        Lang.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Lang.to_u16(t)
+    }
+}
+
+impl HyperType for Type {
+    fn generic_eq(&self, other: &dyn HyperType) -> bool
+    where
+        Self: 'static + PartialEq + Sized,
+    {
+        // Do a type-safe casting. If the types are different,
+        // return false, otherwise test the values for equality.
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn is_directory(&self) -> bool {
+        self == &Type::Directory
+    }
+
+    fn is_file(&self) -> bool {
+        self == &Type::SourceFile
+    }
+
+    fn is_spaces(&self) -> bool {
+        self == &Type::Spaces
+    }
+
+    fn is_syntax(&self) -> bool {
+        matches!(
+            self,
+            Type::LParen
+                | Type::RParen
+                | Type::LBrace
+                | Type::RBrace
+                | Type::LBracket
+                | Type::RBracket
+                | Type::SemiColon
+                | Type::Colon
+                | Type::ColonColon
+                | Type::Comma
+                | Type::Dot
+                | Type::DotDot
+                | Type::DotDotDot
+                | Type::DotDotEq
+                | Type::DashGt
+                | Type::EqGt
+                | Type::Hash
+                | Type::Bang
+                | Type::QMark
+                | Type::SQuote
+                | Type::DQuote
+        )
+    }
+
+    fn as_shared(&self) -> hyper_ast::types::Shared {
+        use hyper_ast::types::Shared;
+        match self {
+            Type::StructItem => Shared::TypeDeclaration,
+            Type::EnumItem => Shared::TypeDeclaration,
+            Type::UnionItem => Shared::TypeDeclaration,
+            Type::TraitItem => Shared::TypeDeclaration,
+            Type::TypeItem => Shared::TypeDeclaration,
+            Type::LineComment => Shared::Comment,
+            Type::BlockComment => Shared::Comment,
+            Type::Identifier => Shared::Identifier,
+            Type::ScopedIdentifier => Shared::Identifier,
+            _ => Shared::Other,
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_static(&self) -> &'static dyn HyperType {
+        let t = <Rust as hyper_ast::types::Lang<Type>>::to_u16(*self);
+        let t = <Rust as hyper_ast::types::Lang<Type>>::make(t);
+        t
+    }
+
+    fn get_lang(&self) -> hyper_ast::types::LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        From::<&'static (dyn LangRef<Self>)>::from(&Lang)
+    }
+}
+impl TypeTrait for Type {
+    type Lang = Rust;
+
+    fn is_fork(&self) -> bool {
+        todo!()
+    }
+
+    fn is_literal(&self) -> bool {
+        todo!()
+    }
+
+    fn is_primitive(&self) -> bool {
+        todo!()
+    }
+
+    fn is_type_declaration(&self) -> bool {
+        todo!()
+    }
+
+    fn is_identifier(&self) -> bool {
+        todo!()
+    }
+
+    fn is_instance_ref(&self) -> bool {
+        todo!()
+    }
+
+    fn is_type_body(&self) -> bool {
+        todo!()
+    }
+
+    fn is_value_member(&self) -> bool {
+        todo!()
+    }
+
+    fn is_executable_member(&self) -> bool {
+        todo!()
+    }
+
+    fn is_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_declarative_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_structural_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_block_related(&self) -> bool {
+        todo!()
+    }
+
+    fn is_simple_statement(&self) -> bool {
+        todo!()
+    }
+
+    fn is_local_declare(&self) -> bool {
+        todo!()
+    }
+
+    fn is_parameter(&self) -> bool {
+        todo!()
+    }
+
+    fn is_parameter_list(&self) -> bool {
+        todo!()
+    }
+
+    fn is_argument_list(&self) -> bool {
+        todo!()
+    }
+
+    fn is_expression(&self) -> bool {
+        todo!()
+    }
+
+    fn is_comment(&self) -> bool {
+        todo!()
+    }
+}
+
+// 305 + directory + spaces + error
+const COUNT: u16 = 308;
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl TryFrom<&str> for Type {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Type::from_str(value).ok_or(())
+    }
+}
+
+#[repr(u16)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Type {
+    End,
+    Identifier,
+    SemiColon,
+    TS1,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    EqGt,
+    LBracket,
+    RBracket,
+    Colon,
+    Dollar,
+    TokenRepetitionPatternToken1,
+    Plus,
+    Star,
+    QMark,
+    TS2,
+    Expr,
+    Ident,
+    Item,
+    TS3,
+    Literal,
+    Meta,
+    Pat,
+    Path,
+    Stmt,
+    Tt,
+    Ty,
+    Vis,
+    PrimitiveType,
+    Slash,
+    TS8,
+    TS4,
+    Dash,
+    Eq,
+    DashGt,
+    Comma,
+    ColonColon,
+    Bang,
+    Dot,
+    At,
+    Amp,
+    Hash,
+    Percent,
+    Caret,
+    LT,
+    GT,
+    Pipe,
+    Tilde,
+    SQuote,
+    As,
+    Async,
+    Await,
+    Break,
+    Const,
+    Continue,
+    Default,
+    Enum,
+    Fn,
+    For,
+    If,
+    Impl,
+    Let,
+    Loop,
+    Match,
+    Mod,
+    Pub,
+    Return,
+    Static,
+    Struct,
+    Trait,
+    Type,
+    Union,
+    Unsafe,
+    Use,
+    Where,
+    While,
+    Extern,
+    Ref,
+    Else,
+    DotDotDot,
+    In,
+    Dyn,
+    MutableSpecifier,
+    DotDot,
+    DotDotEq,
+    AmpAmp,
+    PipePipe,
+    EqEq,
+    BangEq,
+    LTEq,
+    GTEq,
+    LtLt,
+    GtGt,
+    PlusEq,
+    DashEq,
+    StarEq,
+    SlashEq,
+    PercentEq,
+    AmpEq,
+    PipeEq,
+    CaretEq,
+    LtLtEq,
+    GtGtEq,
+    Yield,
+    Move,
+    IntegerLiteral,
+    DQuote,
+    CharLiteral,
+    EscapeSequence,
+    True,
+    False,
+    LineComment,
+    Shebang,
+    TS0,
+    Super,
+    Crate,
+    Metavariable,
+    StringContent,
+    RawStringLiteral,
+    FloatLiteral,
+    BlockComment,
+    SourceFile,
+    Statement,
+    EmptyStatement,
+    ExpressionStatement,
+    MacroDefinition,
+    MacroRule,
+    TokenPattern,
+    TokenTreePattern,
+    TokenBindingPattern,
+    TokenRepetitionPattern,
+    FragmentSpecifier,
+    TokenTree,
+    TokenRepetition,
+    AttributeItem,
+    InnerAttributeItem,
+    Attribute,
+    ModItem,
+    ForeignModItem,
+    DeclarationList,
+    StructItem,
+    UnionItem,
+    EnumItem,
+    EnumVariantList,
+    EnumVariant,
+    FieldDeclarationList,
+    FieldDeclaration,
+    OrderedFieldDeclarationList,
+    ExternCrateDeclaration,
+    ConstItem,
+    StaticItem,
+    TypeItem,
+    FunctionItem,
+    FunctionSignatureItem,
+    FunctionModifiers,
+    WhereClause,
+    WherePredicate,
+    ImplItem,
+    TraitItem,
+    AssociatedType,
+    TraitBounds,
+    HigherRankedTraitBound,
+    RemovedTraitBound,
+    TypeParameters,
+    ConstParameter,
+    ConstrainedTypeParameter,
+    OptionalTypeParameter,
+    LetDeclaration,
+    UseDeclaration,
+    UseClause,
+    ScopedUseList,
+    UseList,
+    UseAsClause,
+    UseWildcard,
+    Parameters,
+    SelfParameter,
+    VariadicParameter,
+    Parameter,
+    ExternModifier,
+    VisibilityModifier,
+    TS5,
+    BracketedType,
+    QualifiedType,
+    Lifetime,
+    ArrayType,
+    ForLifetimes,
+    FunctionType,
+    TupleType,
+    UnitType,
+    GenericFunction,
+    GenericType,
+    GenericTypeWithTurbofish,
+    BoundedType,
+    TypeArguments,
+    TypeBinding,
+    ReferenceType,
+    PointerType,
+    EmptyType,
+    AbstractType,
+    DynamicType,
+    ExpressionExceptRange,
+    Expression,
+    MacroInvocation,
+    DelimTokens,
+    NonDelimToken,
+    ScopedIdentifier,
+    ScopedTypeIdentifier,
+    RangeExpression,
+    UnaryExpression,
+    TryExpression,
+    ReferenceExpression,
+    BinaryExpression,
+    AssignmentExpression,
+    CompoundAssignmentExpr,
+    TypeCastExpression,
+    ReturnExpression,
+    YieldExpression,
+    CallExpression,
+    Arguments,
+    ArrayExpression,
+    ParenthesizedExpression,
+    TupleExpression,
+    UnitExpression,
+    StructExpression,
+    FieldInitializerList,
+    ShorthandFieldInitializer,
+    FieldInitializer,
+    BaseFieldInitializer,
+    IfExpression,
+    LetCondition,
+    TS6,
+    Condition,
+    ElseClause,
+    MatchExpression,
+    MatchBlock,
+    MatchArm,
+    MatchPattern,
+    WhileExpression,
+    LoopExpression,
+    ForExpression,
+    ConstBlock,
+    ClosureExpression,
+    ClosureParameters,
+    LoopLabel,
+    BreakExpression,
+    ContinueExpression,
+    IndexExpression,
+    AwaitExpression,
+    FieldExpression,
+    UnsafeBlock,
+    AsyncBlock,
+    Block,
+    Pattern,
+    TuplePattern,
+    SlicePattern,
+    TupleStructPattern,
+    StructPattern,
+    FieldPattern,
+    RemainingFieldPattern,
+    MutPattern,
+    RangePattern,
+    RefPattern,
+    CapturedPattern,
+    ReferencePattern,
+    OrPattern,
+    TS7,
+    LiteralPattern,
+    NegativeLiteral,
+    StringLiteral,
+    BooleanLiteral,
+    SourceFileRepeat1,
+    MacroDefinitionRepeat1,
+    TokenTreePatternRepeat1,
+    TokenTreeRepeat1,
+    TS9,
+    DeclarationListRepeat1,
+    EnumVariantListRepeat1,
+    EnumVariantListRepeat2,
+    FieldDeclarationListRepeat1,
+    OrderedFieldDeclarationListRepeat1,
+    FunctionModifiersRepeat1,
+    WhereClauseRepeat1,
+    TraitBoundsRepeat1,
+    TypeParametersRepeat1,
+    UseListRepeat1,
+    ParametersRepeat1,
+    ForLifetimesRepeat1,
+    TupleTypeRepeat1,
+    TypeArgumentsRepeat1,
+    DelimTokenTreeRepeat1,
+    ArgumentsRepeat1,
+    ArrayExpressionRepeat1,
+    TupleExpressionRepeat1,
+    FieldInitializerListRepeat1,
+    MatchBlockRepeat1,
+    ClosureParametersRepeat1,
+    TuplePatternRepeat1,
+    StructPatternRepeat1,
+    StringLiteralRepeat1,
+    FieldIdentifier,
+    LetChain,
+    ShorthandFieldIdentifier,
+    TypeIdentifier,
+    Spaces,
+    Directory,
+    ERROR,
+}
+impl Type {
+    pub fn from_u16(t: u16) -> Type {
+        match t {
+            0u16 => Type::End,
+            1u16 => Type::Identifier,
+            2u16 => Type::SemiColon,
+            3u16 => Type::TS1,
+            4u16 => Type::LParen,
+            5u16 => Type::RParen,
+            6u16 => Type::LBrace,
+            7u16 => Type::RBrace,
+            8u16 => Type::EqGt,
+            9u16 => Type::LBracket,
+            10u16 => Type::RBracket,
+            11u16 => Type::Colon,
+            12u16 => Type::Dollar,
+            13u16 => Type::TokenRepetitionPatternToken1,
+            14u16 => Type::Plus,
+            15u16 => Type::Star,
+            16u16 => Type::QMark,
+            17u16 => Type::TS2,
+            18u16 => Type::Expr,
+            19u16 => Type::Ident,
+            20u16 => Type::Item,
+            21u16 => Type::TS3,
+            22u16 => Type::Literal,
+            23u16 => Type::Meta,
+            24u16 => Type::Pat,
+            25u16 => Type::Path,
+            26u16 => Type::Stmt,
+            27u16 => Type::Tt,
+            28u16 => Type::Ty,
+            29u16 => Type::Vis,
+            30u16 => Type::PrimitiveType,
+            31u16 => Type::PrimitiveType,
+            32u16 => Type::PrimitiveType,
+            33u16 => Type::PrimitiveType,
+            34u16 => Type::PrimitiveType,
+            35u16 => Type::PrimitiveType,
+            36u16 => Type::PrimitiveType,
+            37u16 => Type::PrimitiveType,
+            38u16 => Type::PrimitiveType,
+            39u16 => Type::PrimitiveType,
+            40u16 => Type::PrimitiveType,
+            41u16 => Type::PrimitiveType,
+            42u16 => Type::PrimitiveType,
+            43u16 => Type::PrimitiveType,
+            44u16 => Type::PrimitiveType,
+            45u16 => Type::PrimitiveType,
+            46u16 => Type::PrimitiveType,
+            47u16 => Type::Slash,
+            48u16 => Type::TS8,
+            49u16 => Type::TS4,
+            50u16 => Type::Dash,
+            51u16 => Type::Eq,
+            52u16 => Type::DashGt,
+            53u16 => Type::Comma,
+            54u16 => Type::ColonColon,
+            55u16 => Type::Bang,
+            56u16 => Type::Dot,
+            57u16 => Type::At,
+            58u16 => Type::Amp,
+            59u16 => Type::Hash,
+            60u16 => Type::Percent,
+            61u16 => Type::Caret,
+            62u16 => Type::LT,
+            63u16 => Type::GT,
+            64u16 => Type::Pipe,
+            65u16 => Type::Tilde,
+            66u16 => Type::SQuote,
+            67u16 => Type::As,
+            68u16 => Type::Async,
+            69u16 => Type::Await,
+            70u16 => Type::Break,
+            71u16 => Type::Const,
+            72u16 => Type::Continue,
+            73u16 => Type::Default,
+            74u16 => Type::Enum,
+            75u16 => Type::Fn,
+            76u16 => Type::For,
+            77u16 => Type::If,
+            78u16 => Type::Impl,
+            79u16 => Type::Let,
+            80u16 => Type::Loop,
+            81u16 => Type::Match,
+            82u16 => Type::Mod,
+            83u16 => Type::Pub,
+            84u16 => Type::Return,
+            85u16 => Type::Static,
+            86u16 => Type::Struct,
+            87u16 => Type::Trait,
+            88u16 => Type::Type,
+            89u16 => Type::Union,
+            90u16 => Type::Unsafe,
+            91u16 => Type::Use,
+            92u16 => Type::Where,
+            93u16 => Type::While,
+            94u16 => Type::Extern,
+            95u16 => Type::Ref,
+            96u16 => Type::Else,
+            97u16 => Type::DotDotDot,
+            98u16 => Type::In,
+            99u16 => Type::LT,
+            100u16 => Type::Dyn,
+            101u16 => Type::MutableSpecifier,
+            102u16 => Type::DotDot,
+            103u16 => Type::DotDotEq,
+            104u16 => Type::AmpAmp,
+            105u16 => Type::PipePipe,
+            106u16 => Type::EqEq,
+            107u16 => Type::BangEq,
+            108u16 => Type::LTEq,
+            109u16 => Type::GTEq,
+            110u16 => Type::LtLt,
+            111u16 => Type::GtGt,
+            112u16 => Type::PlusEq,
+            113u16 => Type::DashEq,
+            114u16 => Type::StarEq,
+            115u16 => Type::SlashEq,
+            116u16 => Type::PercentEq,
+            117u16 => Type::AmpEq,
+            118u16 => Type::PipeEq,
+            119u16 => Type::CaretEq,
+            120u16 => Type::LtLtEq,
+            121u16 => Type::GtGtEq,
+            122u16 => Type::Yield,
+            123u16 => Type::Move,
+            124u16 => Type::IntegerLiteral,
+            125u16 => Type::DQuote,
+            126u16 => Type::DQuote,
+            127u16 => Type::CharLiteral,
+            128u16 => Type::EscapeSequence,
+            129u16 => Type::True,
+            130u16 => Type::False,
+            131u16 => Type::LineComment,
+            132u16 => Type::Shebang,
+            133u16 => Type::TS0,
+            134u16 => Type::Super,
+            135u16 => Type::Crate,
+            136u16 => Type::Metavariable,
+            137u16 => Type::StringContent,
+            138u16 => Type::RawStringLiteral,
+            139u16 => Type::FloatLiteral,
+            140u16 => Type::BlockComment,
+            141u16 => Type::SourceFile,
+            142u16 => Type::Statement,
+            143u16 => Type::EmptyStatement,
+            144u16 => Type::ExpressionStatement,
+            145u16 => Type::MacroDefinition,
+            146u16 => Type::MacroRule,
+            147u16 => Type::TokenPattern,
+            148u16 => Type::TokenTreePattern,
+            149u16 => Type::TokenBindingPattern,
+            150u16 => Type::TokenRepetitionPattern,
+            151u16 => Type::FragmentSpecifier,
+            152u16 => Type::TokenTree,
+            153u16 => Type::TokenRepetition,
+            154u16 => Type::AttributeItem,
+            155u16 => Type::InnerAttributeItem,
+            156u16 => Type::Attribute,
+            157u16 => Type::ModItem,
+            158u16 => Type::ForeignModItem,
+            159u16 => Type::DeclarationList,
+            160u16 => Type::StructItem,
+            161u16 => Type::UnionItem,
+            162u16 => Type::EnumItem,
+            163u16 => Type::EnumVariantList,
+            164u16 => Type::EnumVariant,
+            165u16 => Type::FieldDeclarationList,
+            166u16 => Type::FieldDeclaration,
+            167u16 => Type::OrderedFieldDeclarationList,
+            168u16 => Type::ExternCrateDeclaration,
+            169u16 => Type::ConstItem,
+            170u16 => Type::StaticItem,
+            171u16 => Type::TypeItem,
+            172u16 => Type::FunctionItem,
+            173u16 => Type::FunctionSignatureItem,
+            174u16 => Type::FunctionModifiers,
+            175u16 => Type::WhereClause,
+            176u16 => Type::WherePredicate,
+            177u16 => Type::ImplItem,
+            178u16 => Type::TraitItem,
+            179u16 => Type::AssociatedType,
+            180u16 => Type::TraitBounds,
+            181u16 => Type::HigherRankedTraitBound,
+            182u16 => Type::RemovedTraitBound,
+            183u16 => Type::TypeParameters,
+            184u16 => Type::ConstParameter,
+            185u16 => Type::ConstrainedTypeParameter,
+            186u16 => Type::OptionalTypeParameter,
+            187u16 => Type::LetDeclaration,
+            188u16 => Type::UseDeclaration,
+            189u16 => Type::UseClause,
+            190u16 => Type::ScopedUseList,
+            191u16 => Type::UseList,
+            192u16 => Type::UseAsClause,
+            193u16 => Type::UseWildcard,
+            194u16 => Type::Parameters,
+            195u16 => Type::SelfParameter,
+            196u16 => Type::VariadicParameter,
+            197u16 => Type::Parameter,
+            198u16 => Type::ExternModifier,
+            199u16 => Type::VisibilityModifier,
+            200u16 => Type::TS5,
+            201u16 => Type::BracketedType,
+            202u16 => Type::QualifiedType,
+            203u16 => Type::Lifetime,
+            204u16 => Type::ArrayType,
+            205u16 => Type::ForLifetimes,
+            206u16 => Type::FunctionType,
+            207u16 => Type::TupleType,
+            208u16 => Type::UnitType,
+            209u16 => Type::GenericFunction,
+            210u16 => Type::GenericType,
+            211u16 => Type::GenericTypeWithTurbofish,
+            212u16 => Type::BoundedType,
+            213u16 => Type::TypeArguments,
+            214u16 => Type::TypeBinding,
+            215u16 => Type::ReferenceType,
+            216u16 => Type::PointerType,
+            217u16 => Type::EmptyType,
+            218u16 => Type::AbstractType,
+            219u16 => Type::DynamicType,
+            220u16 => Type::ExpressionExceptRange,
+            221u16 => Type::Expression,
+            222u16 => Type::MacroInvocation,
+            223u16 => Type::TokenTree,
+            224u16 => Type::DelimTokens,
+            225u16 => Type::NonDelimToken,
+            226u16 => Type::ScopedIdentifier,
+            227u16 => Type::ScopedTypeIdentifier,
+            228u16 => Type::ScopedTypeIdentifier,
+            229u16 => Type::RangeExpression,
+            230u16 => Type::UnaryExpression,
+            231u16 => Type::TryExpression,
+            232u16 => Type::ReferenceExpression,
+            233u16 => Type::BinaryExpression,
+            234u16 => Type::AssignmentExpression,
+            235u16 => Type::CompoundAssignmentExpr,
+            236u16 => Type::TypeCastExpression,
+            237u16 => Type::ReturnExpression,
+            238u16 => Type::YieldExpression,
+            239u16 => Type::CallExpression,
+            240u16 => Type::Arguments,
+            241u16 => Type::ArrayExpression,
+            242u16 => Type::ParenthesizedExpression,
+            243u16 => Type::TupleExpression,
+            244u16 => Type::UnitExpression,
+            245u16 => Type::StructExpression,
+            246u16 => Type::FieldInitializerList,
+            247u16 => Type::ShorthandFieldInitializer,
+            248u16 => Type::FieldInitializer,
+            249u16 => Type::BaseFieldInitializer,
+            250u16 => Type::IfExpression,
+            251u16 => Type::LetCondition,
+            252u16 => Type::TS6,
+            253u16 => Type::Condition,
+            254u16 => Type::ElseClause,
+            255u16 => Type::MatchExpression,
+            256u16 => Type::MatchBlock,
+            257u16 => Type::MatchArm,
+            258u16 => Type::MatchArm,
+            259u16 => Type::MatchPattern,
+            260u16 => Type::WhileExpression,
+            261u16 => Type::LoopExpression,
+            262u16 => Type::ForExpression,
+            263u16 => Type::ConstBlock,
+            264u16 => Type::ClosureExpression,
+            265u16 => Type::ClosureParameters,
+            266u16 => Type::LoopLabel,
+            267u16 => Type::BreakExpression,
+            268u16 => Type::ContinueExpression,
+            269u16 => Type::IndexExpression,
+            270u16 => Type::AwaitExpression,
+            271u16 => Type::FieldExpression,
+            272u16 => Type::UnsafeBlock,
+            273u16 => Type::AsyncBlock,
+            274u16 => Type::Block,
+            275u16 => Type::Pattern,
+            276u16 => Type::TuplePattern,
+            277u16 => Type::SlicePattern,
+            278u16 => Type::TupleStructPattern,
+            279u16 => Type::StructPattern,
+            280u16 => Type::FieldPattern,
+            281u16 => Type::RemainingFieldPattern,
+            282u16 => Type::MutPattern,
+            283u16 => Type::RangePattern,
+            284u16 => Type::RefPattern,
+            285u16 => Type::CapturedPattern,
+            286u16 => Type::ReferencePattern,
+            287u16 => Type::OrPattern,
+            288u16 => Type::TS7,
+            289u16 => Type::LiteralPattern,
+            290u16 => Type::NegativeLiteral,
+            291u16 => Type::StringLiteral,
+            292u16 => Type::BooleanLiteral,
+            293u16 => Type::SourceFileRepeat1,
+            294u16 => Type::MacroDefinitionRepeat1,
+            295u16 => Type::TokenTreePatternRepeat1,
+            296u16 => Type::TokenTreeRepeat1,
+            297u16 => Type::TS9,
+            298u16 => Type::DeclarationListRepeat1,
+            299u16 => Type::EnumVariantListRepeat1,
+            300u16 => Type::EnumVariantListRepeat2,
+            301u16 => Type::FieldDeclarationListRepeat1,
+            302u16 => Type::OrderedFieldDeclarationListRepeat1,
+            303u16 => Type::FunctionModifiersRepeat1,
+            304u16 => Type::WhereClauseRepeat1,
+            305u16 => Type::TraitBoundsRepeat1,
+            306u16 => Type::TypeParametersRepeat1,
+            307u16 => Type::UseListRepeat1,
+            308u16 => Type::ParametersRepeat1,
+            309u16 => Type::ForLifetimesRepeat1,
+            310u16 => Type::TupleTypeRepeat1,
+            311u16 => Type::TypeArgumentsRepeat1,
+            312u16 => Type::DelimTokenTreeRepeat1,
+            313u16 => Type::ArgumentsRepeat1,
+            314u16 => Type::ArrayExpressionRepeat1,
+            315u16 => Type::TupleExpressionRepeat1,
+            316u16 => Type::FieldInitializerListRepeat1,
+            317u16 => Type::MatchBlockRepeat1,
+            318u16 => Type::ClosureParametersRepeat1,
+            319u16 => Type::TuplePatternRepeat1,
+            320u16 => Type::StructPatternRepeat1,
+            321u16 => Type::StringLiteralRepeat1,
+            322u16 => Type::FieldIdentifier,
+            323u16 => Type::LetChain,
+            324u16 => Type::ShorthandFieldIdentifier,
+            325u16 => Type::TypeIdentifier,
+            u16::MAX => Type::ERROR,
+            x => panic!("{}", x),
+        }
+    }
+    pub fn from_str(t: &str) -> Option<Type> {
+        Some(match t {
+            "end" => Type::End,
+            "identifier" => Type::Identifier,
+            ";" => Type::SemiColon,
+            "macro_rules!" => Type::TS1,
+            "(" => Type::LParen,
+            ")" => Type::RParen,
+            "{" => Type::LBrace,
+            "}" => Type::RBrace,
+            "=>" => Type::EqGt,
+            "[" => Type::LBracket,
+            "]" => Type::RBracket,
+            ":" => Type::Colon,
+            "$" => Type::Dollar,
+            "token_repetition_pattern_token1" => Type::TokenRepetitionPatternToken1,
+            "+" => Type::Plus,
+            "*" => Type::Star,
+            "?" => Type::QMark,
+            "block" => Type::TS2,
+            "expr" => Type::Expr,
+            "ident" => Type::Ident,
+            "item" => Type::Item,
+            "lifetime" => Type::TS3,
+            "literal" => Type::Literal,
+            "meta" => Type::Meta,
+            "pat" => Type::Pat,
+            "path" => Type::Path,
+            "stmt" => Type::Stmt,
+            "tt" => Type::Tt,
+            "ty" => Type::Ty,
+            "vis" => Type::Vis,
+            "primitive_type" => Type::PrimitiveType,
+            "/" => Type::Slash,
+            "_" => Type::TS8,
+            "\\" => Type::TS4,
+            "-" => Type::Dash,
+            "=" => Type::Eq,
+            "->" => Type::DashGt,
+            "," => Type::Comma,
+            "::" => Type::ColonColon,
+            "!" => Type::Bang,
+            "." => Type::Dot,
+            "@" => Type::At,
+            "&" => Type::Amp,
+            "#" => Type::Hash,
+            "%" => Type::Percent,
+            "^" => Type::Caret,
+            "<" => Type::LT,
+            ">" => Type::GT,
+            "|" => Type::Pipe,
+            "~" => Type::Tilde,
+            "'" => Type::SQuote,
+            "as" => Type::As,
+            "async" => Type::Async,
+            "await" => Type::Await,
+            "break" => Type::Break,
+            "const" => Type::Const,
+            "continue" => Type::Continue,
+            "default" => Type::Default,
+            "enum" => Type::Enum,
+            "fn" => Type::Fn,
+            "for" => Type::For,
+            "if" => Type::If,
+            "impl" => Type::Impl,
+            "let" => Type::Let,
+            "loop" => Type::Loop,
+            "match" => Type::Match,
+            "mod" => Type::Mod,
+            "pub" => Type::Pub,
+            "return" => Type::Return,
+            "static" => Type::Static,
+            "struct" => Type::Struct,
+            "trait" => Type::Trait,
+            "type" => Type::Type,
+            "union" => Type::Union,
+            "unsafe" => Type::Unsafe,
+            "use" => Type::Use,
+            "where" => Type::Where,
+            "while" => Type::While,
+            "extern" => Type::Extern,
+            "ref" => Type::Ref,
+            "else" => Type::Else,
+            "..." => Type::DotDotDot,
+            "in" => Type::In,
+            "dyn" => Type::Dyn,
+            "mutable_specifier" => Type::MutableSpecifier,
+            ".." => Type::DotDot,
+            "..=" => Type::DotDotEq,
+            "&&" => Type::AmpAmp,
+            "||" => Type::PipePipe,
+            "==" => Type::EqEq,
+            "!=" => Type::BangEq,
+            "<=" => Type::LTEq,
+            ">=" => Type::GTEq,
+            "<<" => Type::LtLt,
+            ">>" => Type::GtGt,
+            "+=" => Type::PlusEq,
+            "-=" => Type::DashEq,
+            "*=" => Type::StarEq,
+            "/=" => Type::SlashEq,
+            "%=" => Type::PercentEq,
+            "&=" => Type::AmpEq,
+            "|=" => Type::PipeEq,
+            "^=" => Type::CaretEq,
+            "<<=" => Type::LtLtEq,
+            ">>=" => Type::GtGtEq,
+            "yield" => Type::Yield,
+            "move" => Type::Move,
+            "integer_literal" => Type::IntegerLiteral,
+            "\"" => Type::DQuote,
+            "char_literal" => Type::CharLiteral,
+            "escape_sequence" => Type::EscapeSequence,
+            "true" => Type::True,
+            "false" => Type::False,
+            "line_comment" => Type::LineComment,
+            "shebang" => Type::Shebang,
+            "self" => Type::TS0,
+            "super" => Type::Super,
+            "crate" => Type::Crate,
+            "metavariable" => Type::Metavariable,
+            "_string_content" => Type::StringContent,
+            "raw_string_literal" => Type::RawStringLiteral,
+            "float_literal" => Type::FloatLiteral,
+            "block_comment" => Type::BlockComment,
+            "source_file" => Type::SourceFile,
+            "_statement" => Type::Statement,
+            "empty_statement" => Type::EmptyStatement,
+            "expression_statement" => Type::ExpressionStatement,
+            "macro_definition" => Type::MacroDefinition,
+            "macro_rule" => Type::MacroRule,
+            "_token_pattern" => Type::TokenPattern,
+            "token_tree_pattern" => Type::TokenTreePattern,
+            "token_binding_pattern" => Type::TokenBindingPattern,
+            "token_repetition_pattern" => Type::TokenRepetitionPattern,
+            "fragment_specifier" => Type::FragmentSpecifier,
+            "token_tree" => Type::TokenTree,
+            "token_repetition" => Type::TokenRepetition,
+            "attribute_item" => Type::AttributeItem,
+            "inner_attribute_item" => Type::InnerAttributeItem,
+            "attribute" => Type::Attribute,
+            "mod_item" => Type::ModItem,
+            "foreign_mod_item" => Type::ForeignModItem,
+            "declaration_list" => Type::DeclarationList,
+            "struct_item" => Type::StructItem,
+            "union_item" => Type::UnionItem,
+            "enum_item" => Type::EnumItem,
+            "enum_variant_list" => Type::EnumVariantList,
+            "enum_variant" => Type::EnumVariant,
+            "field_declaration_list" => Type::FieldDeclarationList,
+            "field_declaration" => Type::FieldDeclaration,
+            "ordered_field_declaration_list" => Type::OrderedFieldDeclarationList,
+            "extern_crate_declaration" => Type::ExternCrateDeclaration,
+            "const_item" => Type::ConstItem,
+            "static_item" => Type::StaticItem,
+            "type_item" => Type::TypeItem,
+            "function_item" => Type::FunctionItem,
+            "function_signature_item" => Type::FunctionSignatureItem,
+            "function_modifiers" => Type::FunctionModifiers,
+            "where_clause" => Type::WhereClause,
+            "where_predicate" => Type::WherePredicate,
+            "impl_item" => Type::ImplItem,
+            "trait_item" => Type::TraitItem,
+            "associated_type" => Type::AssociatedType,
+            "trait_bounds" => Type::TraitBounds,
+            "higher_ranked_trait_bound" => Type::HigherRankedTraitBound,
+            "removed_trait_bound" => Type::RemovedTraitBound,
+            "type_parameters" => Type::TypeParameters,
+            "const_parameter" => Type::ConstParameter,
+            "constrained_type_parameter" => Type::ConstrainedTypeParameter,
+            "optional_type_parameter" => Type::OptionalTypeParameter,
+            "let_declaration" => Type::LetDeclaration,
+            "use_declaration" => Type::UseDeclaration,
+            "_use_clause" => Type::UseClause,
+            "scoped_use_list" => Type::ScopedUseList,
+            "use_list" => Type::UseList,
+            "use_as_clause" => Type::UseAsClause,
+            "use_wildcard" => Type::UseWildcard,
+            "parameters" => Type::Parameters,
+            "self_parameter" => Type::SelfParameter,
+            "variadic_parameter" => Type::VariadicParameter,
+            "parameter" => Type::Parameter,
+            "extern_modifier" => Type::ExternModifier,
+            "visibility_modifier" => Type::VisibilityModifier,
+            "_type" => Type::TS5,
+            "bracketed_type" => Type::BracketedType,
+            "qualified_type" => Type::QualifiedType,
+            "array_type" => Type::ArrayType,
+            "for_lifetimes" => Type::ForLifetimes,
+            "function_type" => Type::FunctionType,
+            "tuple_type" => Type::TupleType,
+            "unit_type" => Type::UnitType,
+            "generic_function" => Type::GenericFunction,
+            "generic_type" => Type::GenericType,
+            "generic_type_with_turbofish" => Type::GenericTypeWithTurbofish,
+            "bounded_type" => Type::BoundedType,
+            "type_arguments" => Type::TypeArguments,
+            "type_binding" => Type::TypeBinding,
+            "reference_type" => Type::ReferenceType,
+            "pointer_type" => Type::PointerType,
+            "empty_type" => Type::EmptyType,
+            "abstract_type" => Type::AbstractType,
+            "dynamic_type" => Type::DynamicType,
+            "_expression_except_range" => Type::ExpressionExceptRange,
+            "_expression" => Type::Expression,
+            "macro_invocation" => Type::MacroInvocation,
+            "_delim_tokens" => Type::DelimTokens,
+            "_non_delim_token" => Type::NonDelimToken,
+            "scoped_identifier" => Type::ScopedIdentifier,
+            "scoped_type_identifier" => Type::ScopedTypeIdentifier,
+            "range_expression" => Type::RangeExpression,
+            "unary_expression" => Type::UnaryExpression,
+            "try_expression" => Type::TryExpression,
+            "reference_expression" => Type::ReferenceExpression,
+            "binary_expression" => Type::BinaryExpression,
+            "assignment_expression" => Type::AssignmentExpression,
+            "compound_assignment_expr" => Type::CompoundAssignmentExpr,
+            "type_cast_expression" => Type::TypeCastExpression,
+            "return_expression" => Type::ReturnExpression,
+            "yield_expression" => Type::YieldExpression,
+            "call_expression" => Type::CallExpression,
+            "arguments" => Type::Arguments,
+            "array_expression" => Type::ArrayExpression,
+            "parenthesized_expression" => Type::ParenthesizedExpression,
+            "tuple_expression" => Type::TupleExpression,
+            "unit_expression" => Type::UnitExpression,
+            "struct_expression" => Type::StructExpression,
+            "field_initializer_list" => Type::FieldInitializerList,
+            "shorthand_field_initializer" => Type::ShorthandFieldInitializer,
+            "field_initializer" => Type::FieldInitializer,
+            "base_field_initializer" => Type::BaseFieldInitializer,
+            "if_expression" => Type::IfExpression,
+            "let_condition" => Type::LetCondition,
+            "_let_chain" => Type::TS6,
+            "_condition" => Type::Condition,
+            "else_clause" => Type::ElseClause,
+            "match_expression" => Type::MatchExpression,
+            "match_block" => Type::MatchBlock,
+            "match_arm" => Type::MatchArm,
+            "match_pattern" => Type::MatchPattern,
+            "while_expression" => Type::WhileExpression,
+            "loop_expression" => Type::LoopExpression,
+            "for_expression" => Type::ForExpression,
+            "const_block" => Type::ConstBlock,
+            "closure_expression" => Type::ClosureExpression,
+            "closure_parameters" => Type::ClosureParameters,
+            "loop_label" => Type::LoopLabel,
+            "break_expression" => Type::BreakExpression,
+            "continue_expression" => Type::ContinueExpression,
+            "index_expression" => Type::IndexExpression,
+            "await_expression" => Type::AwaitExpression,
+            "field_expression" => Type::FieldExpression,
+            "unsafe_block" => Type::UnsafeBlock,
+            "async_block" => Type::AsyncBlock,
+            "_pattern" => Type::Pattern,
+            "tuple_pattern" => Type::TuplePattern,
+            "slice_pattern" => Type::SlicePattern,
+            "tuple_struct_pattern" => Type::TupleStructPattern,
+            "struct_pattern" => Type::StructPattern,
+            "field_pattern" => Type::FieldPattern,
+            "remaining_field_pattern" => Type::RemainingFieldPattern,
+            "mut_pattern" => Type::MutPattern,
+            "range_pattern" => Type::RangePattern,
+            "ref_pattern" => Type::RefPattern,
+            "captured_pattern" => Type::CapturedPattern,
+            "reference_pattern" => Type::ReferencePattern,
+            "or_pattern" => Type::OrPattern,
+            "_literal" => Type::TS7,
+            "_literal_pattern" => Type::LiteralPattern,
+            "negative_literal" => Type::NegativeLiteral,
+            "string_literal" => Type::StringLiteral,
+            "boolean_literal" => Type::BooleanLiteral,
+            "source_file_repeat1" => Type::SourceFileRepeat1,
+            "macro_definition_repeat1" => Type::MacroDefinitionRepeat1,
+            "token_tree_pattern_repeat1" => Type::TokenTreePatternRepeat1,
+            "token_tree_repeat1" => Type::TokenTreeRepeat1,
+            "_non_special_token_repeat1" => Type::TS9,
+            "declaration_list_repeat1" => Type::DeclarationListRepeat1,
+            "enum_variant_list_repeat1" => Type::EnumVariantListRepeat1,
+            "enum_variant_list_repeat2" => Type::EnumVariantListRepeat2,
+            "field_declaration_list_repeat1" => Type::FieldDeclarationListRepeat1,
+            "ordered_field_declaration_list_repeat1" => Type::OrderedFieldDeclarationListRepeat1,
+            "function_modifiers_repeat1" => Type::FunctionModifiersRepeat1,
+            "where_clause_repeat1" => Type::WhereClauseRepeat1,
+            "trait_bounds_repeat1" => Type::TraitBoundsRepeat1,
+            "type_parameters_repeat1" => Type::TypeParametersRepeat1,
+            "use_list_repeat1" => Type::UseListRepeat1,
+            "parameters_repeat1" => Type::ParametersRepeat1,
+            "for_lifetimes_repeat1" => Type::ForLifetimesRepeat1,
+            "tuple_type_repeat1" => Type::TupleTypeRepeat1,
+            "type_arguments_repeat1" => Type::TypeArgumentsRepeat1,
+            "delim_token_tree_repeat1" => Type::DelimTokenTreeRepeat1,
+            "arguments_repeat1" => Type::ArgumentsRepeat1,
+            "array_expression_repeat1" => Type::ArrayExpressionRepeat1,
+            "tuple_expression_repeat1" => Type::TupleExpressionRepeat1,
+            "field_initializer_list_repeat1" => Type::FieldInitializerListRepeat1,
+            "match_block_repeat1" => Type::MatchBlockRepeat1,
+            "closure_parameters_repeat1" => Type::ClosureParametersRepeat1,
+            "tuple_pattern_repeat1" => Type::TuplePatternRepeat1,
+            "struct_pattern_repeat1" => Type::StructPatternRepeat1,
+            "string_literal_repeat1" => Type::StringLiteralRepeat1,
+            "field_identifier" => Type::FieldIdentifier,
+            "let_chain" => Type::LetChain,
+            "shorthand_field_identifier" => Type::ShorthandFieldIdentifier,
+            "type_identifier" => Type::TypeIdentifier,
+            "Spaces" => Type::Spaces,
+            "Directory" => Type::Directory,
+            "ERROR" => Type::ERROR,
+            _ => return None,
+        })
+    }
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::End => "end",
+            Type::Identifier => "identifier",
+            Type::SemiColon => ";",
+            Type::TS1 => "macro_rules!",
+            Type::LParen => "(",
+            Type::RParen => ")",
+            Type::LBrace => "{",
+            Type::RBrace => "}",
+            Type::EqGt => "=>",
+            Type::LBracket => "[",
+            Type::RBracket => "]",
+            Type::Colon => ":",
+            Type::Dollar => "$",
+            Type::TokenRepetitionPatternToken1 => "token_repetition_pattern_token1",
+            Type::Plus => "+",
+            Type::Star => "*",
+            Type::QMark => "?",
+            Type::TS2 => "block",
+            Type::Expr => "expr",
+            Type::Ident => "ident",
+            Type::Item => "item",
+            Type::TS3 => "lifetime",
+            Type::Literal => "literal",
+            Type::Meta => "meta",
+            Type::Pat => "pat",
+            Type::Path => "path",
+            Type::Stmt => "stmt",
+            Type::Tt => "tt",
+            Type::Ty => "ty",
+            Type::Vis => "vis",
+            Type::PrimitiveType => "primitive_type",
+            Type::Slash => "/",
+            Type::TS8 => "_",
+            Type::TS4 => "\\",
+            Type::Dash => "-",
+            Type::Eq => "=",
+            Type::DashGt => "->",
+            Type::Comma => ",",
+            Type::ColonColon => "::",
+            Type::Bang => "!",
+            Type::Dot => ".",
+            Type::At => "@",
+            Type::Amp => "&",
+            Type::Hash => "#",
+            Type::Percent => "%",
+            Type::Caret => "^",
+            Type::LT => "<",
+            Type::GT => ">",
+            Type::Pipe => "|",
+            Type::Tilde => "~",
+            Type::SQuote => "'",
+            Type::As => "as",
+            Type::Async => "async",
+            Type::Await => "await",
+            Type::Break => "break",
+            Type::Const => "const",
+            Type::Continue => "continue",
+            Type::Default => "default",
+            Type::Enum => "enum",
+            Type::Fn => "fn",
+            Type::For => "for",
+            Type::If => "if",
+            Type::Impl => "impl",
+            Type::Let => "let",
+            Type::Loop => "loop",
+            Type::Match => "match",
+            Type::Mod => "mod",
+            Type::Pub => "pub",
+            Type::Return => "return",
+            Type::Static => "static",
+            Type::Struct => "struct",
+            Type::Trait => "trait",
+            Type::Type => "type",
+            Type::Union => "union",
+            Type::Unsafe => "unsafe",
+            Type::Use => "use",
+            Type::Where => "where",
+            Type::While => "while",
+            Type::Extern => "extern",
+            Type::Ref => "ref",
+            Type::Else => "else",
+            Type::DotDotDot => "...",
+            Type::In => "in",
+            Type::Dyn => "dyn",
+            Type::MutableSpecifier => "mutable_specifier",
+            Type::DotDot => "..",
+            Type::DotDotEq => "..=",
+            Type::AmpAmp => "&&",
+            Type::PipePipe => "||",
+            Type::EqEq => "==",
+            Type::BangEq => "!=",
+            Type::LTEq => "<=",
+            Type::GTEq => ">=",
+            Type::LtLt => "<<",
+            Type::GtGt => ">>",
+            Type::PlusEq => "+=",
+            Type::DashEq => "-=",
+            Type::StarEq => "*=",
+            Type::SlashEq => "/=",
+            Type::PercentEq => "%=",
+            Type::AmpEq => "&=",
+            Type::PipeEq => "|=",
+            Type::CaretEq => "^=",
+            Type::LtLtEq => "<<=",
+            Type::GtGtEq => ">>=",
+            Type::Yield => "yield",
+            Type::Move => "move",
+            Type::IntegerLiteral => "integer_literal",
+            Type::DQuote => "\"",
+            Type::CharLiteral => "char_literal",
+            Type::EscapeSequence => "escape_sequence",
+            Type::True => "true",
+            Type::False => "false",
+            Type::LineComment => "line_comment",
+            Type::Shebang => "shebang",
+            Type::TS0 => "self",
+            Type::Super => "super",
+            Type::Crate => "crate",
+            Type::Metavariable => "metavariable",
+            Type::StringContent => "_string_content",
+            Type::RawStringLiteral => "raw_string_literal",
+            Type::FloatLiteral => "float_literal",
+            Type::BlockComment => "block_comment",
+            Type::SourceFile => "source_file",
+            Type::Statement => "_statement",
+            Type::EmptyStatement => "empty_statement",
+            Type::ExpressionStatement => "expression_statement",
+            Type::MacroDefinition => "macro_definition",
+            Type::MacroRule => "macro_rule",
+            Type::TokenPattern => "_token_pattern",
+            Type::TokenTreePattern => "token_tree_pattern",
+            Type::TokenBindingPattern => "token_binding_pattern",
+            Type::TokenRepetitionPattern => "token_repetition_pattern",
+            Type::FragmentSpecifier => "fragment_specifier",
+            Type::TokenTree => "token_tree",
+            Type::TokenRepetition => "token_repetition",
+            Type::AttributeItem => "attribute_item",
+            Type::InnerAttributeItem => "inner_attribute_item",
+            Type::Attribute => "attribute",
+            Type::ModItem => "mod_item",
+            Type::ForeignModItem => "foreign_mod_item",
+            Type::DeclarationList => "declaration_list",
+            Type::StructItem => "struct_item",
+            Type::UnionItem => "union_item",
+            Type::EnumItem => "enum_item",
+            Type::EnumVariantList => "enum_variant_list",
+            Type::EnumVariant => "enum_variant",
+            Type::FieldDeclarationList => "field_declaration_list",
+            Type::FieldDeclaration => "field_declaration",
+            Type::OrderedFieldDeclarationList => "ordered_field_declaration_list",
+            Type::ExternCrateDeclaration => "extern_crate_declaration",
+            Type::ConstItem => "const_item",
+            Type::StaticItem => "static_item",
+            Type::TypeItem => "type_item",
+            Type::FunctionItem => "function_item",
+            Type::FunctionSignatureItem => "function_signature_item",
+            Type::FunctionModifiers => "function_modifiers",
+            Type::WhereClause => "where_clause",
+            Type::WherePredicate => "where_predicate",
+            Type::ImplItem => "impl_item",
+            Type::TraitItem => "trait_item",
+            Type::AssociatedType => "associated_type",
+            Type::TraitBounds => "trait_bounds",
+            Type::HigherRankedTraitBound => "higher_ranked_trait_bound",
+            Type::RemovedTraitBound => "removed_trait_bound",
+            Type::TypeParameters => "type_parameters",
+            Type::ConstParameter => "const_parameter",
+            Type::ConstrainedTypeParameter => "constrained_type_parameter",
+            Type::OptionalTypeParameter => "optional_type_parameter",
+            Type::LetDeclaration => "let_declaration",
+            Type::UseDeclaration => "use_declaration",
+            Type::UseClause => "_use_clause",
+            Type::ScopedUseList => "scoped_use_list",
+            Type::UseList => "use_list",
+            Type::UseAsClause => "use_as_clause",
+            Type::UseWildcard => "use_wildcard",
+            Type::Parameters => "parameters",
+            Type::SelfParameter => "self_parameter",
+            Type::VariadicParameter => "variadic_parameter",
+            Type::Parameter => "parameter",
+            Type::ExternModifier => "extern_modifier",
+            Type::VisibilityModifier => "visibility_modifier",
+            Type::TS5 => "_type",
+            Type::BracketedType => "bracketed_type",
+            Type::QualifiedType => "qualified_type",
+            Type::Lifetime => "lifetime",
+            Type::ArrayType => "array_type",
+            Type::ForLifetimes => "for_lifetimes",
+            Type::FunctionType => "function_type",
+            Type::TupleType => "tuple_type",
+            Type::UnitType => "unit_type",
+            Type::GenericFunction => "generic_function",
+            Type::GenericType => "generic_type",
+            Type::GenericTypeWithTurbofish => "generic_type_with_turbofish",
+            Type::BoundedType => "bounded_type",
+            Type::TypeArguments => "type_arguments",
+            Type::TypeBinding => "type_binding",
+            Type::ReferenceType => "reference_type",
+            Type::PointerType => "pointer_type",
+            Type::EmptyType => "empty_type",
+            Type::AbstractType => "abstract_type",
+            Type::DynamicType => "dynamic_type",
+            Type::ExpressionExceptRange => "_expression_except_range",
+            Type::Expression => "_expression",
+            Type::MacroInvocation => "macro_invocation",
+            Type::DelimTokens => "_delim_tokens",
+            Type::NonDelimToken => "_non_delim_token",
+            Type::ScopedIdentifier => "scoped_identifier",
+            Type::ScopedTypeIdentifier => "scoped_type_identifier",
+            Type::RangeExpression => "range_expression",
+            Type::UnaryExpression => "unary_expression",
+            Type::TryExpression => "try_expression",
+            Type::ReferenceExpression => "reference_expression",
+            Type::BinaryExpression => "binary_expression",
+            Type::AssignmentExpression => "assignment_expression",
+            Type::CompoundAssignmentExpr => "compound_assignment_expr",
+            Type::TypeCastExpression => "type_cast_expression",
+            Type::ReturnExpression => "return_expression",
+            Type::YieldExpression => "yield_expression",
+            Type::CallExpression => "call_expression",
+            Type::Arguments => "arguments",
+            Type::ArrayExpression => "array_expression",
+            Type::ParenthesizedExpression => "parenthesized_expression",
+            Type::TupleExpression => "tuple_expression",
+            Type::UnitExpression => "unit_expression",
+            Type::StructExpression => "struct_expression",
+            Type::FieldInitializerList => "field_initializer_list",
+            Type::ShorthandFieldInitializer => "shorthand_field_initializer",
+            Type::FieldInitializer => "field_initializer",
+            Type::BaseFieldInitializer => "base_field_initializer",
+            Type::IfExpression => "if_expression",
+            Type::LetCondition => "let_condition",
+            Type::TS6 => "_let_chain",
+            Type::Condition => "_condition",
+            Type::ElseClause => "else_clause",
+            Type::MatchExpression => "match_expression",
+            Type::MatchBlock => "match_block",
+            Type::MatchArm => "match_arm",
+            Type::MatchPattern => "match_pattern",
+            Type::WhileExpression => "while_expression",
+            Type::LoopExpression => "loop_expression",
+            Type::ForExpression => "for_expression",
+            Type::ConstBlock => "const_block",
+            Type::ClosureExpression => "closure_expression",
+            Type::ClosureParameters => "closure_parameters",
+            Type::LoopLabel => "loop_label",
+            Type::BreakExpression => "break_expression",
+            Type::ContinueExpression => "continue_expression",
+            Type::IndexExpression => "index_expression",
+            Type::AwaitExpression => "await_expression",
+            Type::FieldExpression => "field_expression",
+            Type::UnsafeBlock => "unsafe_block",
+            Type::AsyncBlock => "async_block",
+            Type::Block => "block",
+            Type::Pattern => "_pattern",
+            Type::TuplePattern => "tuple_pattern",
+            Type::SlicePattern => "slice_pattern",
+            Type::TupleStructPattern => "tuple_struct_pattern",
+            Type::StructPattern => "struct_pattern",
+            Type::FieldPattern => "field_pattern",
+            Type::RemainingFieldPattern => "remaining_field_pattern",
+            Type::MutPattern => "mut_pattern",
+            Type::RangePattern => "range_pattern",
+            Type::RefPattern => "ref_pattern",
+            Type::CapturedPattern => "captured_pattern",
+            Type::ReferencePattern => "reference_pattern",
+            Type::OrPattern => "or_pattern",
+            Type::TS7 => "_literal",
+            Type::LiteralPattern => "_literal_pattern",
+            Type::NegativeLiteral => "negative_literal",
+            Type::StringLiteral => "string_literal",
+            Type::BooleanLiteral => "boolean_literal",
+            Type::SourceFileRepeat1 => "source_file_repeat1",
+            Type::MacroDefinitionRepeat1 => "macro_definition_repeat1",
+            Type::TokenTreePatternRepeat1 => "token_tree_pattern_repeat1",
+            Type::TokenTreeRepeat1 => "token_tree_repeat1",
+            Type::TS9 => "_non_special_token_repeat1",
+            Type::DeclarationListRepeat1 => "declaration_list_repeat1",
+            Type::EnumVariantListRepeat1 => "enum_variant_list_repeat1",
+            Type::EnumVariantListRepeat2 => "enum_variant_list_repeat2",
+            Type::FieldDeclarationListRepeat1 => "field_declaration_list_repeat1",
+            Type::OrderedFieldDeclarationListRepeat1 => "ordered_field_declaration_list_repeat1",
+            Type::FunctionModifiersRepeat1 => "function_modifiers_repeat1",
+            Type::WhereClauseRepeat1 => "where_clause_repeat1",
+            Type::TraitBoundsRepeat1 => "trait_bounds_repeat1",
+            Type::TypeParametersRepeat1 => "type_parameters_repeat1",
+            Type::UseListRepeat1 => "use_list_repeat1",
+            Type::ParametersRepeat1 => "parameters_repeat1",
+            Type::ForLifetimesRepeat1 => "for_lifetimes_repeat1",
+            Type::TupleTypeRepeat1 => "tuple_type_repeat1",
+            Type::TypeArgumentsRepeat1 => "type_arguments_repeat1",
+            Type::DelimTokenTreeRepeat1 => "delim_token_tree_repeat1",
+            Type::ArgumentsRepeat1 => "arguments_repeat1",
+            Type::ArrayExpressionRepeat1 => "array_expression_repeat1",
+            Type::TupleExpressionRepeat1 => "tuple_expression_repeat1",
+            Type::FieldInitializerListRepeat1 => "field_initializer_list_repeat1",
+            Type::MatchBlockRepeat1 => "match_block_repeat1",
+            Type::ClosureParametersRepeat1 => "closure_parameters_repeat1",
+            Type::TuplePatternRepeat1 => "tuple_pattern_repeat1",
+            Type::StructPatternRepeat1 => "struct_pattern_repeat1",
+            Type::StringLiteralRepeat1 => "string_literal_repeat1",
+            Type::FieldIdentifier => "field_identifier",
+            Type::LetChain => "let_chain",
+            Type::ShorthandFieldIdentifier => "shorthand_field_identifier",
+            Type::TypeIdentifier => "type_identifier",
+            Type::Spaces => "Spaces",
+            Type::Directory => "Directory",
+            Type::ERROR => "ERROR",
+        }
+    }
+}
+
+const S_T_L: &'static [Type] = &[
+    Type::End,
+    Type::Identifier,
+    Type::SemiColon,
+    Type::TS1,
+    Type::LParen,
+    Type::RParen,
+    Type::LBrace,
+    Type::RBrace,
+    Type::EqGt,
+    Type::LBracket,
+    Type::RBracket,
+    Type::Colon,
+    Type::Dollar,
+    Type::TokenRepetitionPatternToken1,
+    Type::Plus,
+    Type::Star,
+    Type::QMark,
+    Type::TS2,
+    Type::Expr,
+    Type::Ident,
+    Type::Item,
+    Type::TS3,
+    Type::Literal,
+    Type::Meta,
+    Type::Pat,
+    Type::Path,
+    Type::Stmt,
+    Type::Tt,
+    Type::Ty,
+    Type::Vis,
+    Type::PrimitiveType,
+    Type::Slash,
+    Type::TS8,
+    Type::TS4,
+    Type::Dash,
+    Type::Eq,
+    Type::DashGt,
+    Type::Comma,
+    Type::ColonColon,
+    Type::Bang,
+    Type::Dot,
+    Type::At,
+    Type::Amp,
+    Type::Hash,
+    Type::Percent,
+    Type::Caret,
+    Type::LT,
+    Type::GT,
+    Type::Pipe,
+    Type::Tilde,
+    Type::SQuote,
+    Type::As,
+    Type::Async,
+    Type::Await,
+    Type::Break,
+    Type::Const,
+    Type::Continue,
+    Type::Default,
+    Type::Enum,
+    Type::Fn,
+    Type::For,
+    Type::If,
+    Type::Impl,
+    Type::Let,
+    Type::Loop,
+    Type::Match,
+    Type::Mod,
+    Type::Pub,
+    Type::Return,
+    Type::Static,
+    Type::Struct,
+    Type::Trait,
+    Type::Type,
+    Type::Union,
+    Type::Unsafe,
+    Type::Use,
+    Type::Where,
+    Type::While,
+    Type::Extern,
+    Type::Ref,
+    Type::Else,
+    Type::DotDotDot,
+    Type::In,
+    Type::Dyn,
+    Type::MutableSpecifier,
+    Type::DotDot,
+    Type::DotDotEq,
+    Type::AmpAmp,
+    Type::PipePipe,
+    Type::EqEq,
+    Type::BangEq,
+    Type::LTEq,
+    Type::GTEq,
+    Type::LtLt,
+    Type::GtGt,
+    Type::PlusEq,
+    Type::DashEq,
+    Type::StarEq,
+    Type::SlashEq,
+    Type::PercentEq,
+    Type::AmpEq,
+    Type::PipeEq,
+    Type::CaretEq,
+    Type::LtLtEq,
+    Type::GtGtEq,
+    Type::Yield,
+    Type::Move,
+    Type::IntegerLiteral,
+    Type::DQuote,
+    Type::CharLiteral,
+    Type::EscapeSequence,
+    Type::True,
+    Type::False,
+    Type::LineComment,
+    Type::Shebang,
+    Type::TS0,
+    Type::Super,
+    Type::Crate,
+    Type::Metavariable,
+    Type::StringContent,
+    Type::RawStringLiteral,
+    Type::FloatLiteral,
+    Type::BlockComment,
+    Type::SourceFile,
+    Type::Statement,
+    Type::EmptyStatement,
+    Type::ExpressionStatement,
+    Type::MacroDefinition,
+    Type::MacroRule,
+    Type::TokenPattern,
+    Type::TokenTreePattern,
+    Type::TokenBindingPattern,
+    Type::TokenRepetitionPattern,
+    Type::FragmentSpecifier,
+    Type::TokenTree,
+    Type::TokenRepetition,
+    Type::AttributeItem,
+    Type::InnerAttributeItem,
+    Type::Attribute,
+    Type::ModItem,
+    Type::ForeignModItem,
+    Type::DeclarationList,
+    Type::StructItem,
+    Type::UnionItem,
+    Type::EnumItem,
+    Type::EnumVariantList,
+    Type::EnumVariant,
+    Type::FieldDeclarationList,
+    Type::FieldDeclaration,
+    Type::OrderedFieldDeclarationList,
+    Type::ExternCrateDeclaration,
+    Type::ConstItem,
+    Type::StaticItem,
+    Type::TypeItem,
+    Type::FunctionItem,
+    Type::FunctionSignatureItem,
+    Type::FunctionModifiers,
+    Type::WhereClause,
+    Type::WherePredicate,
+    Type::ImplItem,
+    Type::TraitItem,
+    Type::AssociatedType,
+    Type::TraitBounds,
+    Type::HigherRankedTraitBound,
+    Type::RemovedTraitBound,
+    Type::TypeParameters,
+    Type::ConstParameter,
+    Type::ConstrainedTypeParameter,
+    Type::OptionalTypeParameter,
+    Type::LetDeclaration,
+    Type::UseDeclaration,
+    Type::UseClause,
+    Type::ScopedUseList,
+    Type::UseList,
+    Type::UseAsClause,
+    Type::UseWildcard,
+    Type::Parameters,
+    Type::SelfParameter,
+    Type::VariadicParameter,
+    Type::Parameter,
+    Type::ExternModifier,
+    Type::VisibilityModifier,
+    Type::TS5,
+    Type::BracketedType,
+    Type::QualifiedType,
+    Type::Lifetime,
+    Type::ArrayType,
+    Type::ForLifetimes,
+    Type::FunctionType,
+    Type::TupleType,
+    Type::UnitType,
+    Type::GenericFunction,
+    Type::GenericType,
+    Type::GenericTypeWithTurbofish,
+    Type::BoundedType,
+    Type::TypeArguments,
+    Type::TypeBinding,
+    Type::ReferenceType,
+    Type::PointerType,
+    Type::EmptyType,
+    Type::AbstractType,
+    Type::DynamicType,
+    Type::ExpressionExceptRange,
+    Type::Expression,
+    Type::MacroInvocation,
+    Type::DelimTokens,
+    Type::NonDelimToken,
+    Type::ScopedIdentifier,
+    Type::ScopedTypeIdentifier,
+    Type::RangeExpression,
+    Type::UnaryExpression,
+    Type::TryExpression,
+    Type::ReferenceExpression,
+    Type::BinaryExpression,
+    Type::AssignmentExpression,
+    Type::CompoundAssignmentExpr,
+    Type::TypeCastExpression,
+    Type::ReturnExpression,
+    Type::YieldExpression,
+    Type::CallExpression,
+    Type::Arguments,
+    Type::ArrayExpression,
+    Type::ParenthesizedExpression,
+    Type::TupleExpression,
+    Type::UnitExpression,
+    Type::StructExpression,
+    Type::FieldInitializerList,
+    Type::ShorthandFieldInitializer,
+    Type::FieldInitializer,
+    Type::BaseFieldInitializer,
+    Type::IfExpression,
+    Type::LetCondition,
+    Type::TS6,
+    Type::Condition,
+    Type::ElseClause,
+    Type::MatchExpression,
+    Type::MatchBlock,
+    Type::MatchArm,
+    Type::MatchPattern,
+    Type::WhileExpression,
+    Type::LoopExpression,
+    Type::ForExpression,
+    Type::ConstBlock,
+    Type::ClosureExpression,
+    Type::ClosureParameters,
+    Type::LoopLabel,
+    Type::BreakExpression,
+    Type::ContinueExpression,
+    Type::IndexExpression,
+    Type::AwaitExpression,
+    Type::FieldExpression,
+    Type::UnsafeBlock,
+    Type::AsyncBlock,
+    Type::Block,
+    Type::Pattern,
+    Type::TuplePattern,
+    Type::SlicePattern,
+    Type::TupleStructPattern,
+    Type::StructPattern,
+    Type::FieldPattern,
+    Type::RemainingFieldPattern,
+    Type::MutPattern,
+    Type::RangePattern,
+    Type::RefPattern,
+    Type::CapturedPattern,
+    Type::ReferencePattern,
+    Type::OrPattern,
+    Type::TS7,
+    Type::LiteralPattern,
+    Type::NegativeLiteral,
+    Type::StringLiteral,
+    Type::BooleanLiteral,
+    Type::SourceFileRepeat1,
+    Type::MacroDefinitionRepeat1,
+    Type::TokenTreePatternRepeat1,
+    Type::TokenTreeRepeat1,
+    Type::TS9,
+    Type::DeclarationListRepeat1,
+    Type::EnumVariantListRepeat1,
+    Type::EnumVariantListRepeat2,
+    Type::FieldDeclarationListRepeat1,
+    Type::OrderedFieldDeclarationListRepeat1,
+    Type::FunctionModifiersRepeat1,
+    Type::WhereClauseRepeat1,
+    Type::TraitBoundsRepeat1,
+    Type::TypeParametersRepeat1,
+    Type::UseListRepeat1,
+    Type::ParametersRepeat1,
+    Type::ForLifetimesRepeat1,
+    Type::TupleTypeRepeat1,
+    Type::TypeArgumentsRepeat1,
+    Type::DelimTokenTreeRepeat1,
+    Type::ArgumentsRepeat1,
+    Type::ArrayExpressionRepeat1,
+    Type::TupleExpressionRepeat1,
+    Type::FieldInitializerListRepeat1,
+    Type::MatchBlockRepeat1,
+    Type::ClosureParametersRepeat1,
+    Type::TuplePatternRepeat1,
+    Type::StructPatternRepeat1,
+    Type::StringLiteralRepeat1,
+    Type::FieldIdentifier,
+    Type::LetChain,
+    Type::ShorthandFieldIdentifier,
+    Type::TypeIdentifier,
+    Type::Spaces,
+    Type::Directory,
+    Type::ERROR,
+];
@@ -86,6 +86,8 @@ pub struct Acc {
     metrics: SubTreeMetrics<SyntaxNodeHashs<u32>>,
     padding_start: usize,
     indentation: Spaces,
+    /// the fields of the children, by index
+    roles: Vec<(usize, LabelIdentifier)>,
 }
 
 pub type FNode = FullNode<BasicGlobalData, Local>;
@@ -128,6 +130,10 @@ impl<'a> hyper_ast::tree_gen::parser::TreeCursor<'a, TNode<'a>> for TTreeCursor<
     fn goto_next_sibling(&mut self) -> bool {
         self.0.goto_next_sibling()
     }
+
+    fn role(&self) -> Option<&'static str> {
+        self.0.field_name()
+    }
 }
 
 impl<'store, 'cache, TS: TsEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIdentifier>>>>
@@ -167,6 +173,7 @@ impl<'store, 'cache, TS: TsEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIdent
             metrics: Default::default(),
             padding_start: 0,
             indentation: indent,
+            roles: vec![],
         }
     }
     fn pre(
@@ -198,6 +205,7 @@ impl<'store, 'cache, TS: TsEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIdent
                 children: vec![],
             },
             no_space: vec![],
+            roles: vec![],
         }
     }
 
@@ -229,6 +237,11 @@ impl<'store, 'cache, TS: TsEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIdent
         };
         self.make(global, acc, label)
     }
+
+    fn role(&mut self, parent: &mut <Self as TreeGen>::Acc, role: &'static str) {
+        let role = self.stores.label_store.get_or_insert(role);
+        parent.roles.push((parent.simple.children.len(), role));
+    }
 }
 
 impl<'store, 'cache, TS: TsEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIdentifier>>>>
@@ -308,6 +321,19 @@ impl<'store, 'cache, TS: TsEnabledTypeStore<HashedNodeRef<'store, TIdN<NodeIdent
         }
     }
 
+    /// For .tsx files, and also javascript as it is mostly a subset of tsx
+    pub fn tree_sitter_parse_tsx(text: &[u8]) -> Result<tree_sitter::Tree, tree_sitter::Tree> {
+        let mut parser = tree_sitter::Parser::new();
+        let language = tree_sitter_typescript::language_tsx();
+        parser.set_language(language).unwrap();
+        let tree = parser.parse(text, None).unwrap();
+        if tree.root_node().has_error() {
+            Err(tree)
+        } else {
+            Ok(tree)
+        }
+    }
+
     pub fn generate_file(
         &mut self,
         name: &[u8],
@@ -447,6 +473,9 @@ impl<'stores, 'cache, TS: TsEnabledTypeStore<HashedNodeRef<'stores, TIdN<NodeIde
             match acc.simple.children.len() {
                 0 => {}
                 x => {
+                    if let Some(roles) = compo::Roles::new(x, acc.roles) {
+                        dyn_builder.add(roles);
+                    }
                     let a = acc.simple.children.into_boxed_slice();
                     dyn_builder.add(compo::Size(size));
                     dyn_builder.add(compo::SizeNoSpaces(size_no_spaces));
@@ -0,0 +1,96 @@
+use hyper_ast::{
+    store::defaults::NodeIdentifier,
+    types::{LabelStore as _, Labeled, Typed, WithChildren},
+};
+
+use crate::types::{TIdN, TStore, Type};
+
+type TsTreeGen<'store, 'cache> = crate::legion::TsTreeGen<'store, 'cache, TStore>;
+type SimpleStores = hyper_ast::store::SimpleStores<TStore>;
+
+/// the first child of `id` of type `t`
+fn child_of_type(stores: &SimpleStores, id: NodeIdentifier, t: Type) -> Option<NodeIdentifier> {
+    let n = stores.node_store.resolve(id);
+    (0..n.child_count())
+        .filter_map(|i| n.child(&i))
+        .find(|x| {
+            stores
+                .node_store
+                .try_resolve_typed::<TIdN<_>>(x)
+                .map_or(false, |(x, _)| x.get_type() == t)
+        })
+}
+
+fn label(stores: &SimpleStores, id: NodeIdentifier) -> &str {
+    let n = stores.node_store.resolve(id);
+    stores.label_store.resolve(n.get_label_unchecked())
+}
+
+#[test]
+pub(crate) fn ts_simple_test() {
+    let text = {
+        let source_code1 = r#"
+function main(a: number): string {
+    return `a ${a} a`;
+}"#;
+        source_code1.as_bytes()
+    };
+    let tree = match TsTreeGen::tree_sitter_parse(text) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    assert!(!tree.root_node().has_error());
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = TsTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"main.ts", text, tree.walk()).local;
+    // the serialized tree gives back the source code
+    assert_eq!(
+        std::str::from_utf8(text).unwrap(),
+        hyper_ast::nodes::TextSerializer::new(&stores, x.compressed_node).to_string()
+    );
+    let n = stores
+        .node_store
+        .try_resolve_typed::<TIdN<_>>(&x.compressed_node)
+        .unwrap()
+        .0;
+    assert_eq!(n.get_type(), Type::Program);
+    assert_eq!(label(&stores, x.compressed_node), "main.ts");
+
+    let function =
+        child_of_type(&stores, x.compressed_node, Type::FunctionDeclaration).unwrap();
+    let name = child_of_type(&stores, function, Type::Identifier).unwrap();
+    assert_eq!(label(&stores, name), "main");
+    let body = child_of_type(&stores, function, Type::StatementBlock).unwrap();
+    let statement = child_of_type(&stores, body, Type::ReturnStatement).unwrap();
+    assert!(child_of_type(&stores, statement, Type::TemplateString).is_some());
+}
+
+#[test]
+pub(crate) fn tsx_test() {
+    let text = {
+        let source_code1 = r#"
+const App = (props: { name: string }) => <div className="a">{props.name}</div>;
+"#;
+        source_code1.as_bytes()
+    };
+    let tree = match TsTreeGen::tree_sitter_parse_tsx(text) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    let mut stores = SimpleStores::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = TsTreeGen::new(&mut stores, &mut md_cache);
+    let x = tree_gen.generate_file(b"App.tsx", text, tree.walk()).local;
+    assert_eq!(
+        std::str::from_utf8(text).unwrap(),
+        hyper_ast::nodes::TextSerializer::new(&stores, x.compressed_node).to_string()
+    );
+    let n = stores
+        .node_store
+        .try_resolve_typed::<crate::types::TIdN<_>>(&x.compressed_node)
+        .unwrap()
+        .0;
+    // kinds are mapped by name as tsx has its own kind ids
+    assert_eq!(n.get_type(), Type::Program);
+}
@@ -4,7 +4,7 @@ use hyper_ast::{
     store::defaults::NodeIdentifier,
     tree_gen::parser::NodeWithU16TypeId,
     types::{
-        AnyType, HyperType, Lang, LangRef, NodeId, TypeStore, TypeTrait, TypedNodeId,
+        AnyType, HyperType, LangRef, NodeId, TypeStore, TypeTrait, TypedNodeId,
     },
 };
 
@@ -16,6 +16,11 @@ mod legion_impls {
 
     impl<'a> TNode<'a> {
         pub fn obtain_type<T>(&self, _: &mut impl TsEnabledTypeStore<T>) -> Type {
+            #[cfg(feature = "impl")]
+            if self.0.language() != tree_sitter_typescript::language_typescript() {
+                // tsx does not share the kind ids of typescript
+                return Type::from_str(self.0.kind()).unwrap_or(Type::ERROR);
+            }
             let t = self.kind_id();
             Type::from_u16(t)
         }
@@ -34,14 +39,14 @@ mod legion_impls {
             &self,
             n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>,
         ) -> hyper_ast::types::LangWrapper<Self::Ty> {
-            From::<&'static (dyn LangRef<Type>)>::from(&Ts)
+            From::<&'static (dyn LangRef<Type>)>::from(&Lang)
         }
 
         type Marshaled = TypeIndex;
 
         fn marshal_type(&self, n: &HashedNodeRef<'a, TIdN<NodeIdentifier>>) -> Self::Marshaled {
             TypeIndex {
-                lang: LangRef::<Type>::name(&Ts),
+                lang: LangRef::<Type>::name(&Lang),
                 ty: *n.get_component::<Type>().unwrap() as u16,
             }
         }
@@ -69,7 +74,7 @@ mod legion_impls {
         const MASK: TypeInternalSize = 0b1000_0000_0000_0000;
         fn resolve_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Ty {
             From::<&'static (dyn HyperType)>::from(LangRef::<Type>::make(
-                &Ts,
+                &Lang,
                 *n.get_component::<Type>().unwrap() as u16,
             ))
         }
@@ -78,14 +83,14 @@ mod legion_impls {
             &self,
             n: &HashedNodeRef<'a, NodeIdentifier>,
         ) -> hyper_ast::types::LangWrapper<Self::Ty> {
-            From::<&'static (dyn LangRef<AnyType>)>::from(&Ts)
+            From::<&'static (dyn LangRef<AnyType>)>::from(&Lang)
         }
 
         type Marshaled = TypeIndex;
 
         fn marshal_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Marshaled {
             TypeIndex {
-                lang: LangRef::<Type>::name(&Ts),
+                lang: LangRef::<Type>::name(&Lang),
                 ty: *n.get_component::<Type>().unwrap() as u16,
             }
         }
@@ -148,7 +153,15 @@ type TypeInternalSize = u16;
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct T(TypeInternalSize);
 
-pub struct Ts;
+pub struct Lang;
+pub type Ts = Lang;
+
+pub fn as_any(t: &Type) -> AnyType {
+    let t = <Ts as hyper_ast::types::Lang<Type>>::to_u16(*t);
+    let t = <Ts as hyper_ast::types::Lang<Type>>::make(t);
+    let t: &'static dyn HyperType = t;
+    t.into()
+}
 
 impl LangRef<AnyType> for Ts {
     fn make(&self, t: u16) -> &'static AnyType {
@@ -179,12 +192,12 @@ impl LangRef<Type> for Ts {
     }
 }
 
-impl Lang<Type> for Ts {
+impl hyper_ast::types::Lang<Type> for Ts {
     fn make(t: u16) -> &'static Type {
-        Ts.make(t)
+        Lang.make(t)
     }
     fn to_u16(t: Type) -> u16 {
-        Ts.to_u16(t)
+        Lang.to_u16(t)
     }
 }
 
@@ -206,7 +219,7 @@ impl HyperType for Type {
     }
 
     fn is_file(&self) -> bool {
-        todo!()
+        self == &Type::Program
     }
 
     fn is_spaces(&self) -> bool {
@@ -219,7 +232,23 @@ impl HyperType for Type {
     }
 
     fn is_syntax(&self) -> bool {
-        todo!()
+        matches!(
+            self,
+            Type::LParen
+                | Type::RParen
+                | Type::LBrace
+                | Type::RBrace
+                | Type::LBracket
+                | Type::RBracket
+                | Type::SemiColon
+                | Type::Colon
+                | Type::Comma
+                | Type::Dot
+                | Type::DotDotDot
+                | Type::TS10
+                | Type::TS11
+                | Type::TS12
+        )
     }
 
     fn as_shared(&self) -> hyper_ast::types::Shared {
@@ -243,8 +272,8 @@ impl HyperType for Type {
     }
 
     fn as_static(&self) -> &'static dyn HyperType {
-        let t = <Ts as Lang<Type>>::to_u16(*self);
-        let t = <Ts as Lang<Type>>::make(t);
+        let t = <Ts as hyper_ast::types::Lang<Type>>::to_u16(*self);
+        let t = <Ts as hyper_ast::types::Lang<Type>>::make(t);
         t
     }
 
@@ -252,7 +281,7 @@ impl HyperType for Type {
     where
         Self: Sized,
     {
-        From::<&'static (dyn LangRef<Self>)>::from(&Ts)
+        From::<&'static (dyn LangRef<Self>)>::from(&Lang)
     }
 }
 impl TypeTrait for Type {
@@ -339,8 +368,8 @@ impl TypeTrait for Type {
     }
 }
 
-// 356 + directory  + spaces
-const COUNT: u16 = 358;
+// 337 + 9 jsx kinds from tsx + spaces + directory + error
+const COUNT: u16 = 349;
 
 impl Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -348,6 +377,14 @@ impl Display for Type {
     }
 }
 
+impl TryFrom<&str> for Type {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Type::from_str(value).ok_or(())
+    }
+}
+
 #[repr(u16)]
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum Type {
@@ -688,6 +725,15 @@ pub enum Type {
     StatementIdentifier,
     ThisType,
     TypeIdentifier,
+    JsxText,
+    JsxElement,
+    JsxFragment,
+    JsxExpression,
+    JsxOpeningElement,
+    JsxNamespaceName,
+    JsxClosingElement,
+    JsxSelfClosingElement,
+    JsxAttribute,
     Spaces,
     Directory,
     ERROR,
@@ -1052,11 +1098,364 @@ impl Type {
             354u16 => Type::ThisType,
             355u16 => Type::TypeIdentifier,
             356u16 => Type::ERROR,
+            u16::MAX => Type::ERROR,
             x => panic!("{}", x),
         }
     }
-    pub fn from_str(&self) -> Option<Type> {
-        todo!()
+    /// By kind name, used for grammars that do not share the kind ids of typescript, eg. tsx
+    pub fn from_str(t: &str) -> Option<Type> {
+        Some(match t {
+            "end" => Type::End,
+            "identifier" => Type::Identifier,
+            "hash_bang_line" => Type::HashBangLine,
+            "export" => Type::Export,
+            "*" => Type::Star,
+            "default" => Type::Default,
+            "type" => Type::Type,
+            "=" => Type::Eq,
+            "as" => Type::As,
+            "namespace" => Type::Namespace,
+            "{" => Type::LBrace,
+            "," => Type::Comma,
+            "}" => Type::RBrace,
+            "typeof" => Type::Typeof,
+            "import" => Type::Import,
+            "from" => Type::From,
+            "var" => Type::Var,
+            "let" => Type::Let,
+            "const" => Type::Const,
+            "!" => Type::Bang,
+            "else" => Type::Else,
+            "if" => Type::If,
+            "switch" => Type::Switch,
+            "for" => Type::For,
+            "(" => Type::LParen,
+            ")" => Type::RParen,
+            "await" => Type::Await,
+            "in" => Type::In,
+            "of" => Type::Of,
+            "while" => Type::While,
+            "do" => Type::Do,
+            "try" => Type::Try,
+            "with" => Type::With,
+            "break" => Type::Break,
+            "continue" => Type::Continue,
+            "debugger" => Type::Debugger,
+            "return" => Type::Return,
+            "throw" => Type::Throw,
+            ";" => Type::SemiColon,
+            ":" => Type::Colon,
+            "case" => Type::Case,
+            "catch" => Type::Catch,
+            "finally" => Type::Finally,
+            "yield" => Type::Yield,
+            "[" => Type::LBracket,
+            "]" => Type::RBracket,
+            "<" => Type::LT,
+            ">" => Type::GT,
+            "/" => Type::Slash,
+            "." => Type::Dot,
+            "class" => Type::Class,
+            "async" => Type::Async,
+            "function" => Type::Function,
+            "=>" => Type::TS0,
+            "?." => Type::TS1,
+            "new" => Type::New,
+            "+=" => Type::PlusEq,
+            "-=" => Type::DashEq,
+            "*=" => Type::StarEq,
+            "/=" => Type::SlashEq,
+            "%=" => Type::PercentEq,
+            "^=" => Type::CaretEq,
+            "&=" => Type::AmpEq,
+            "|=" => Type::PipeEq,
+            ">>=" => Type::GtGtEq,
+            ">>>=" => Type::GtGtGtEq,
+            "<<=" => Type::LtLtEq,
+            "**=" => Type::TS2,
+            "&&=" => Type::TS3,
+            "||=" => Type::TS4,
+            "??=" => Type::TS5,
+            "..." => Type::DotDotDot,
+            "&&" => Type::AmpAmp,
+            "||" => Type::PipePipe,
+            ">>" => Type::GtGt,
+            ">>>" => Type::GtGtGt,
+            "<<" => Type::LtLt,
+            "&" => Type::Amp,
+            "^" => Type::Caret,
+            "|" => Type::Pipe,
+            "+" => Type::Plus,
+            "-" => Type::Dash,
+            "%" => Type::Percent,
+            "**" => Type::TS6,
+            "<=" => Type::LTEq,
+            "==" => Type::EqEq,
+            "===" => Type::TS7,
+            "!=" => Type::BangEq,
+            "!==" => Type::TS8,
+            ">=" => Type::GTEq,
+            "??" => Type::TS9,
+            "instanceof" => Type::Instanceof,
+            "~" => Type::Tilde,
+            "void" => Type::Void,
+            "delete" => Type::Delete,
+            "++" => Type::PlusPlus,
+            "--" => Type::DashDash,
+            "\"" => Type::TS10,
+            "'" => Type::TS11,
+            "string_fragment" => Type::StringFragment,
+            "escape_sequence" => Type::EscapeSequence,
+            "comment" => Type::Comment,
+            "`" => Type::TS12,
+            "${" => Type::TS13,
+            "regex_pattern" => Type::RegexPattern,
+            "regex_flags" => Type::RegexFlags,
+            "number" => Type::Number,
+            "private_property_identifier" => Type::PrivatePropertyIdentifier,
+            "target" => Type::Target,
+            "this" => Type::This,
+            "super" => Type::Super,
+            "true" => Type::True,
+            "false" => Type::False,
+            "null" => Type::Null,
+            "undefined" => Type::Undefined,
+            "@" => Type::At,
+            "static" => Type::Static,
+            "readonly" => Type::Readonly,
+            "get" => Type::Get,
+            "set" => Type::Set,
+            "?" => Type::QMark,
+            "declare" => Type::Declare,
+            "public" => Type::Public,
+            "private" => Type::Private,
+            "protected" => Type::Protected,
+            "override" => Type::Override,
+            "module" => Type::Module,
+            "any" => Type::Any,
+            "boolean" => Type::Boolean,
+            "string" => Type::String,
+            "symbol" => Type::Symbol,
+            "abstract" => Type::Abstract,
+            "require" => Type::Require,
+            "extends" => Type::Extends,
+            "implements" => Type::Implements,
+            "global" => Type::Global,
+            "interface" => Type::Interface,
+            "enum" => Type::Enum,
+            "-?:" => Type::TS14,
+            "?:" => Type::TS15,
+            "asserts" => Type::Asserts,
+            "infer" => Type::Infer,
+            "is" => Type::Is,
+            "keyof" => Type::Keyof,
+            "unknown" => Type::Unknown,
+            "never" => Type::Never,
+            "object" => Type::Object,
+            "{|" => Type::TS16,
+            "|}" => Type::TS17,
+            "_automatic_semicolon" => Type::TS18,
+            "_template_chars" => Type::TS19,
+            "_function_signature_automatic_semicolon" => Type::TS20,
+            "program" => Type::Program,
+            "export_statement" => Type::ExportStatement,
+            "export_clause" => Type::ExportClause,
+            "export_specifier" => Type::ExportSpecifier,
+            "declaration" => Type::Declaration,
+            "import_statement" => Type::ImportStatement,
+            "import_clause" => Type::ImportClause,
+            "_from_clause" => Type::TS21,
+            "namespace_import" => Type::NamespaceImport,
+            "named_imports" => Type::NamedImports,
+            "expression_statement" => Type::ExpressionStatement,
+            "variable_declaration" => Type::VariableDeclaration,
+            "lexical_declaration" => Type::LexicalDeclaration,
+            "variable_declarator" => Type::VariableDeclarator,
+            "statement_block" => Type::StatementBlock,
+            "else_clause" => Type::ElseClause,
+            "if_statement" => Type::IfStatement,
+            "switch_statement" => Type::SwitchStatement,
+            "for_statement" => Type::ForStatement,
+            "for_in_statement" => Type::ForInStatement,
+            "_for_header" => Type::TS22,
+            "while_statement" => Type::WhileStatement,
+            "do_statement" => Type::DoStatement,
+            "try_statement" => Type::TryStatement,
+            "with_statement" => Type::WithStatement,
+            "break_statement" => Type::BreakStatement,
+            "continue_statement" => Type::ContinueStatement,
+            "debugger_statement" => Type::DebuggerStatement,
+            "return_statement" => Type::ReturnStatement,
+            "throw_statement" => Type::ThrowStatement,
+            "empty_statement" => Type::EmptyStatement,
+            "labeled_statement" => Type::LabeledStatement,
+            "switch_body" => Type::SwitchBody,
+            "switch_case" => Type::SwitchCase,
+            "switch_default" => Type::SwitchDefault,
+            "catch_clause" => Type::CatchClause,
+            "finally_clause" => Type::FinallyClause,
+            "parenthesized_expression" => Type::ParenthesizedExpression,
+            "expression" => Type::Expression,
+            "primary_expression" => Type::PrimaryExpression,
+            "yield_expression" => Type::YieldExpression,
+            "object_pattern" => Type::ObjectPattern,
+            "assignment_pattern" => Type::AssignmentPattern,
+            "object_assignment_pattern" => Type::ObjectAssignmentPattern,
+            "array" => Type::Array,
+            "array_pattern" => Type::ArrayPattern,
+            "nested_identifier" => Type::NestedIdentifier,
+            "class_declaration" => Type::ClassDeclaration,
+            "class_heritage" => Type::ClassHeritage,
+            "function_declaration" => Type::FunctionDeclaration,
+            "generator_function" => Type::GeneratorFunction,
+            "generator_function_declaration" => Type::GeneratorFunctionDeclaration,
+            "arrow_function" => Type::ArrowFunction,
+            "_call_signature" => Type::TS23,
+            "_formal_parameter" => Type::TS24,
+            "call_expression" => Type::CallExpression,
+            "new_expression" => Type::NewExpression,
+            "await_expression" => Type::AwaitExpression,
+            "member_expression" => Type::MemberExpression,
+            "subscript_expression" => Type::SubscriptExpression,
+            "assignment_expression" => Type::AssignmentExpression,
+            "_augmented_assignment_lhs" => Type::TS25,
+            "augmented_assignment_expression" => Type::AugmentedAssignmentExpression,
+            "_initializer" => Type::TS26,
+            "_destructuring_pattern" => Type::TS27,
+            "spread_element" => Type::SpreadElement,
+            "ternary_expression" => Type::TernaryExpression,
+            "binary_expression" => Type::BinaryExpression,
+            "unary_expression" => Type::UnaryExpression,
+            "update_expression" => Type::UpdateExpression,
+            "sequence_expression" => Type::SequenceExpression,
+            "template_string" => Type::TemplateString,
+            "template_substitution" => Type::TemplateSubstitution,
+            "regex" => Type::Regex,
+            "meta_property" => Type::MetaProperty,
+            "arguments" => Type::Arguments,
+            "decorator" => Type::Decorator,
+            "class_body" => Type::ClassBody,
+            "formal_parameters" => Type::FormalParameters,
+            "pattern" => Type::Pattern,
+            "rest_pattern" => Type::RestPattern,
+            "method_definition" => Type::MethodDefinition,
+            "pair" => Type::Pair,
+            "pair_pattern" => Type::PairPattern,
+            "_property_name" => Type::TS28,
+            "computed_property_name" => Type::ComputedPropertyName,
+            "public_field_definition" => Type::PublicFieldDefinition,
+            "non_null_expression" => Type::NonNullExpression,
+            "method_signature" => Type::MethodSignature,
+            "abstract_method_signature" => Type::AbstractMethodSignature,
+            "function_signature" => Type::FunctionSignature,
+            "type_assertion" => Type::TypeAssertion,
+            "as_expression" => Type::AsExpression,
+            "import_require_clause" => Type::ImportRequireClause,
+            "extends_clause" => Type::ExtendsClause,
+            "implements_clause" => Type::ImplementsClause,
+            "ambient_declaration" => Type::AmbientDeclaration,
+            "abstract_class_declaration" => Type::AbstractClassDeclaration,
+            "internal_module" => Type::InternalModule,
+            "_module" => Type::TS29,
+            "import_alias" => Type::ImportAlias,
+            "nested_type_identifier" => Type::NestedTypeIdentifier,
+            "interface_declaration" => Type::InterfaceDeclaration,
+            "extends_type_clause" => Type::ExtendsTypeClause,
+            "enum_declaration" => Type::EnumDeclaration,
+            "enum_body" => Type::EnumBody,
+            "enum_assignment" => Type::EnumAssignment,
+            "type_alias_declaration" => Type::TypeAliasDeclaration,
+            "accessibility_modifier" => Type::AccessibilityModifier,
+            "override_modifier" => Type::OverrideModifier,
+            "required_parameter" => Type::RequiredParameter,
+            "optional_parameter" => Type::OptionalParameter,
+            "_parameter_name" => Type::TS30,
+            "omitting_type_annotation" => Type::OmittingTypeAnnotation,
+            "opting_type_annotation" => Type::OptingTypeAnnotation,
+            "type_annotation" => Type::TypeAnnotation,
+            "_type" => Type::TS31,
+            "optional_type" => Type::OptionalType,
+            "rest_type" => Type::RestType,
+            "_tuple_type_member" => Type::TS32,
+            "constructor_type" => Type::ConstructorType,
+            "_primary_type" => Type::PrimaryType,
+            "template_type" => Type::TemplateType,
+            "template_literal_type" => Type::TemplateLiteralType,
+            "infer_type" => Type::InferType,
+            "conditional_type" => Type::ConditionalType,
+            "generic_type" => Type::GenericType,
+            "type_predicate" => Type::TypePredicate,
+            "type_predicate_annotation" => Type::TypePredicateAnnotation,
+            "type_query" => Type::TypeQuery,
+            "index_type_query" => Type::IndexTypeQuery,
+            "lookup_type" => Type::LookupType,
+            "mapped_type_clause" => Type::MappedTypeClause,
+            "literal_type" => Type::LiteralType,
+            "existential_type" => Type::ExistentialType,
+            "flow_maybe_type" => Type::FlowMaybeType,
+            "parenthesized_type" => Type::ParenthesizedType,
+            "predefined_type" => Type::PredefinedType,
+            "type_arguments" => Type::TypeArguments,
+            "object_type" => Type::ObjectType,
+            "call_signature" => Type::CallSignature,
+            "property_signature" => Type::PropertySignature,
+            "type_parameters" => Type::TypeParameters,
+            "type_parameter" => Type::TypeParameter,
+            "default_type" => Type::DefaultType,
+            "constraint" => Type::Constraint,
+            "construct_signature" => Type::ConstructSignature,
+            "index_signature" => Type::IndexSignature,
+            "array_type" => Type::ArrayType,
+            "tuple_type" => Type::TupleType,
+            "readonly_type" => Type::ReadonlyType,
+            "union_type" => Type::UnionType,
+            "intersection_type" => Type::IntersectionType,
+            "function_type" => Type::FunctionType,
+            "program_repeat1" => Type::ProgramRepeat1,
+            "export_statement_repeat1" => Type::ExportStatementRepeat1,
+            "export_clause_repeat1" => Type::ExportClauseRepeat1,
+            "named_imports_repeat1" => Type::NamedImportsRepeat1,
+            "variable_declaration_repeat1" => Type::VariableDeclarationRepeat1,
+            "switch_body_repeat1" => Type::SwitchBodyRepeat1,
+            "object_repeat1" => Type::ObjectRepeat1,
+            "object_pattern_repeat1" => Type::ObjectPatternRepeat1,
+            "array_repeat1" => Type::ArrayRepeat1,
+            "array_pattern_repeat1" => Type::ArrayPatternRepeat1,
+            "string_repeat1" => Type::StringRepeat1,
+            "string_repeat2" => Type::StringRepeat2,
+            "template_string_repeat1" => Type::TemplateStringRepeat1,
+            "class_body_repeat1" => Type::ClassBodyRepeat1,
+            "formal_parameters_repeat1" => Type::FormalParametersRepeat1,
+            "extends_clause_repeat1" => Type::ExtendsClauseRepeat1,
+            "implements_clause_repeat1" => Type::ImplementsClauseRepeat1,
+            "extends_type_clause_repeat1" => Type::ExtendsTypeClauseRepeat1,
+            "enum_body_repeat1" => Type::EnumBodyRepeat1,
+            "template_literal_type_repeat1" => Type::TemplateLiteralTypeRepeat1,
+            "object_type_repeat1" => Type::ObjectTypeRepeat1,
+            "type_parameters_repeat1" => Type::TypeParametersRepeat1,
+            "tuple_type_repeat1" => Type::TupleTypeRepeat1,
+            "import_specifier" => Type::ImportSpecifier,
+            "namespace_export" => Type::NamespaceExport,
+            "property_identifier" => Type::PropertyIdentifier,
+            "shorthand_property_identifier" => Type::ShorthandPropertyIdentifier,
+            "shorthand_property_identifier_pattern" => Type::ShorthandPropertyIdentifierPattern,
+            "statement_identifier" => Type::StatementIdentifier,
+            "this_type" => Type::ThisType,
+            "type_identifier" => Type::TypeIdentifier,
+            "jsx_text" => Type::JsxText,
+            "jsx_element" => Type::JsxElement,
+            "jsx_fragment" => Type::JsxFragment,
+            "jsx_expression" => Type::JsxExpression,
+            "jsx_opening_element" => Type::JsxOpeningElement,
+            "jsx_namespace_name" => Type::JsxNamespaceName,
+            "jsx_closing_element" => Type::JsxClosingElement,
+            "jsx_self_closing_element" => Type::JsxSelfClosingElement,
+            "jsx_attribute" => Type::JsxAttribute,
+            "Spaces" => Type::Spaces,
+            "Directory" => Type::Directory,
+            "ERROR" => Type::ERROR,
+            _ => return None,
+        })
     }
     pub fn to_str(&self) -> &'static str {
         match self {
@@ -1399,6 +1798,15 @@ impl Type {
             Type::StatementIdentifier => "statement_identifier",
             Type::ThisType => "this_type",
             Type::TypeIdentifier => "type_identifier",
+            Type::JsxText => "jsx_text",
+            Type::JsxElement => "jsx_element",
+            Type::JsxFragment => "jsx_fragment",
+            Type::JsxExpression => "jsx_expression",
+            Type::JsxOpeningElement => "jsx_opening_element",
+            Type::JsxNamespaceName => "jsx_namespace_name",
+            Type::JsxClosingElement => "jsx_closing_element",
+            Type::JsxSelfClosingElement => "jsx_self_closing_element",
+            Type::JsxAttribute => "jsx_attribute",
             Type::Spaces => "Spaces",
             Type::Directory => "Directory",
             Type::ERROR => "ERROR",
@@ -1744,6 +2152,15 @@ const S_T_L: &'static [Type] = &[
     Type::StatementIdentifier,
     Type::ThisType,
     Type::TypeIdentifier,
+    Type::JsxText,
+    Type::JsxElement,
+    Type::JsxFragment,
+    Type::JsxExpression,
+    Type::JsxOpeningElement,
+    Type::JsxNamespaceName,
+    Type::JsxClosingElement,
+    Type::JsxSelfClosingElement,
+    Type::JsxAttribute,
     Type::Spaces,
     Type::Directory,
     Type::ERROR,
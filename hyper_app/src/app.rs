@@ -5,7 +5,6 @@ use self::{
 };
 use egui_addon::{
     code_editor::{self, generic_text_buffer::byte_index_from_char_index},
-    egui_utils::{radio_collapsing, show_wip},
     interactive_split::interactive_splitter::InteractiveSplitter,
     syntax_highlighting::{self, syntax_highlighting_async},
     Lang,
@@ -23,6 +22,8 @@ mod code_tracking;
 mod commit;
 pub(crate) mod crdt_over_ws;
 mod long_tracking;
+mod multi_repo;
+mod semantic_diff;
 mod single_repo;
 mod tree_view;
 mod ts_highlight;
@@ -65,6 +66,10 @@ pub struct HyperApp {
     #[serde(skip)]
     aspects_result: Option<code_aspects::RemoteView>,
     #[serde(skip)]
+    diff_result: Buffered<semantic_diff::RemoteResult>,
+    #[serde(skip)]
+    multi_result: multi_repo::RemoteResults,
+    #[serde(skip)]
     store: Arc<FetchedHyperAST>,
 
     long_tracking: long_tracking::LongTacking,
@@ -288,6 +293,8 @@ impl Default for HyperApp {
             tracking_result: Default::default(),
             aspects: Default::default(),
             aspects_result: Default::default(),
+            diff_result: Default::default(),
+            multi_result: Default::default(),
             long_tracking: Default::default(),
             store: Default::default(),
         }
@@ -388,6 +395,8 @@ impl eframe::App for HyperApp {
             fetched_files,
             tracking_result,
             aspects_result,
+            diff_result,
+            multi_result,
             long_tracking,
             store,
         } = self;
@@ -422,15 +431,9 @@ impl eframe::App for HyperApp {
                     single_repo::show_single_repo_menu(ui, selected, single);
                     ui.separator();
 
-                    ui.add_enabled_ui(false, |ui| {
-                        show_multi_repo(ui, selected, multi);
-                        show_wip(ui, Some(" soon available"));
-                    });
+                    multi_repo::show_multi_repo_menu(ui, selected, multi);
                     ui.separator();
-                    ui.add_enabled_ui(false, |ui| {
-                        show_diff(ui, selected, diff);
-                        show_wip(ui, Some(" soon available"));
-                    });
+                    semantic_diff::show_diff_menu(ui, selected, diff, diff_result);
                     ui.separator();
                     // ui.add_enabled_ui(false, |ui| {
                     code_tracking::show_code_tracking_menu(ui, selected, tracking, tracking_result);
@@ -500,6 +503,14 @@ impl eframe::App for HyperApp {
                         fetched_files,
                     );
                 });
+        } else if *selected == types::SelectedConfig::Multi {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                multi_repo::show_multi_repo(ui, api_addr, multi, scripting_context, multi_result);
+            });
+        } else if *selected == types::SelectedConfig::Diff {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                semantic_diff::show_diff_results(ui, api_addr, diff, diff_result, fetched_files);
+            });
         } else if *selected == types::SelectedConfig::Aspects {
             egui::CentralPanel::default().show(ctx, |ui| {
                 if let Some(aspects_result) = aspects_result {
@@ -851,36 +862,6 @@ fn show_code_scrolled2(
     })
 }
 
-fn show_multi_repo(
-    ui: &mut egui::Ui,
-    selected: &mut types::SelectedConfig,
-    multi: &mut types::ComputeConfigMulti,
-) {
-    let title = "Multi Repo";
-    let wanted = types::SelectedConfig::Multi;
-    let id = ui.make_persistent_id(title);
-    let add_body = |ui: &mut egui::Ui| {
-        ui.text_edit_singleline(&mut "github.com/INRIA/spoon");
-    };
-
-    radio_collapsing(ui, id, title, selected, &wanted, add_body);
-}
-
-fn show_diff(
-    ui: &mut egui::Ui,
-    selected: &mut types::SelectedConfig,
-    diff: &mut types::ComputeConfigDiff,
-) {
-    let title = "Semantic Diff";
-    let wanted = types::SelectedConfig::Diff;
-    let id = ui.make_persistent_id(title);
-    let add_body = |ui: &mut egui::Ui| {
-        ui.text_edit_singleline(&mut "github.com/INRIA/spoon");
-    };
-
-    radio_collapsing(ui, id, title, selected, &wanted, add_body);
-}
-
 mod code_aspects;
 
 use lazy_static::lazy_static;
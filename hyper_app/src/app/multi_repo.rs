@@ -0,0 +1,333 @@
+use egui_addon::egui_utils::radio_collapsing;
+use poll_promise::Promise;
+
+use super::{
+    show_repo_menu,
+    single_repo::{
+        self, script_content, ComputeResult, ComputeResultIdentified, ComputeResults,
+        ScriptingError, SecFmt,
+    },
+    types::{self, Commit, MultiKind, Resource},
+    Accumulable, AccumulableResult, MultiBuffered,
+};
+
+/// Results of every commit of the workspace, rows keep the order of the commits
+pub(super) type RemoteResults =
+    MultiBuffered<AccumulableResult<ComputeResults, Vec<String>>, Result<ComputeResults, String>>;
+
+impl Accumulable<ComputeResults> for ComputeResults {
+    fn acc(&mut self, rhs: ComputeResults) -> bool {
+        self.prepare_time += rhs.prepare_time;
+        self.results.extend(rhs.results);
+        true
+    }
+}
+
+type ScriptingContext = super::ScriptingContext<
+    super::types::CodeEditors,
+    super::types::CodeEditors<super::code_editor_automerge::CodeEditor>,
+>;
+
+pub(super) fn show_multi_repo_menu(
+    ui: &mut egui::Ui,
+    selected: &mut types::SelectedConfig,
+    multi: &mut types::ComputeConfigMulti,
+) {
+    let title = "Multi Repo";
+    let wanted = types::SelectedConfig::Multi;
+    let id = ui.make_persistent_id(title);
+    let add_body = |ui: &mut egui::Ui| {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut multi.kind, MultiKind::Script, "Script");
+            ui.selectable_value(&mut multi.kind, MultiKind::Query, "Query");
+        });
+        let mut removed = None;
+        for (i, commit) in multi.list.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                show_repo_menu(ui, &mut commit.repo);
+                ui.horizontal(|ui| {
+                    egui::TextEdit::singleline(&mut commit.id)
+                        .clip_text(true)
+                        .desired_width(150.0)
+                        .desired_rows(1)
+                        .hint_text("commit")
+                        .interactive(true)
+                        .show(ui);
+                    if ui.small_button("🗑").clicked() {
+                        removed = Some(i);
+                    }
+                });
+            });
+        }
+        if let Some(i) = removed {
+            multi.list.remove(i);
+        }
+        if ui.button("Add Commit").clicked() {
+            let commit = multi.list.last().cloned().unwrap_or_default();
+            multi.list.push(commit);
+        }
+    };
+
+    radio_collapsing(ui, id, title, selected, &wanted, add_body);
+}
+
+pub(super) fn show_multi_repo(
+    ui: &mut egui::Ui,
+    api_addr: &str,
+    multi: &mut types::ComputeConfigMulti,
+    scripting_context: &mut ScriptingContext,
+    results: &mut RemoteResults,
+) {
+    while results.try_poll() {}
+    let compute = ui
+        .horizontal(|ui| {
+            let compute = ui.add_enabled(!multi.list.is_empty(), egui::Button::new("Compute"));
+            let repos: std::collections::HashSet<_> = multi.list.iter().map(|x| &x.repo).collect();
+            ui.label(format!(
+                "{} commits in {} repositories",
+                multi.list.len(),
+                repos.len()
+            ));
+            if results.is_waiting() {
+                ui.spinner();
+            }
+            compute
+        })
+        .inner;
+    match multi.kind {
+        MultiKind::Script => {
+            ui.label("evaluates the scripts of the Single Repository view on each commit");
+        }
+        MultiKind::Query => {
+            egui::TextEdit::multiline(&mut multi.query)
+                .code_editor()
+                .desired_rows(4)
+                .desired_width(f32::INFINITY)
+                .hint_text("a tree-sitter query, eg. (method_declaration) @method")
+                .show(ui);
+        }
+    }
+    if compute.clicked() {
+        *results = Default::default();
+        match multi.kind {
+            MultiKind::Script => {
+                let script = script_content(scripting_context);
+                results.buffer(remote_compute_batch(
+                    ui.ctx(),
+                    api_addr,
+                    &multi.list,
+                    script,
+                ));
+            }
+            MultiKind::Query => {
+                results.buffer(remote_query_batch(
+                    ui.ctx(),
+                    api_addr,
+                    &multi.list,
+                    &multi.query,
+                ));
+            }
+        }
+    }
+    ui.separator();
+    let Some(content) = results.get_mut() else {
+        return;
+    };
+    for error in &content.errors {
+        ui.colored_label(ui.visuals().error_fg_color, error);
+    }
+    let content = &content.content;
+    if content.results.is_empty() {
+        return;
+    }
+    ui.label(format!("prepare time: {:.3}", SecFmt(content.prepare_time)));
+    show_chart(ui, content);
+    egui::ScrollArea::both()
+        .auto_shrink([false, false])
+        .show(ui, |ui| single_repo::show_long_result_table(content, ui));
+}
+
+/// Bar chart of one of the numeric fields of the results, selected with a combo box
+fn show_chart(ui: &mut egui::Ui, content: &ComputeResults) {
+    use egui::plot::{Bar, BarChart, Plot};
+    let rows: Vec<_> = (content.results.iter())
+        .filter_map(|x| x.as_ref().ok())
+        .collect();
+    let Some(fields) = rows.first().and_then(|x| x.inner.result.as_object()) else {
+        return;
+    };
+    let fields: Vec<&String> = (fields.iter())
+        .filter(|(_, v)| v.is_number())
+        .map(|(k, _)| k)
+        .collect();
+    let Some(first) = fields.first() else {
+        return;
+    };
+    let id = ui.id().with("chart field");
+    let mut field: String = ui
+        .memory_mut(|mem| mem.data.get_temp(id))
+        .filter(|x: &String| fields.contains(&x))
+        .unwrap_or_else(|| first.to_string());
+    egui::ComboBox::from_label("charted field")
+        .selected_text(&field)
+        .show_ui(ui, |ui| {
+            for f in &fields {
+                ui.selectable_value(&mut field, f.to_string(), f.as_str());
+            }
+        });
+    let bars = (rows.iter().enumerate())
+        .map(|(i, x)| {
+            let value = x.inner.result.get(&field).and_then(|x| x.as_f64());
+            let commit = &x.commit[..x.commit.len().min(8)];
+            let name = match &x.repo {
+                Some(repo) => format!("{}@{}", repo, commit),
+                None => commit.to_string(),
+            };
+            Bar::new(i as f64, value.unwrap_or_default()).name(name)
+        })
+        .collect();
+    Plot::new(ui.id().with("chart"))
+        .height(200.0)
+        .allow_scroll(false)
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new(bars).name(&field))
+        });
+    ui.memory_mut(|mem| mem.data.insert_temp(id, field));
+}
+
+pub(super) fn remote_compute_batch(
+    ctx: &egui::Context,
+    api_addr: &str,
+    commits: &[Commit],
+    script: single_repo::ScriptContent,
+) -> Promise<Result<ComputeResults, String>> {
+    let ctx = ctx.clone();
+    let (sender, promise) = Promise::new();
+    let url = format!("http://{}/script-batch", api_addr);
+    #[derive(serde::Serialize)]
+    struct BatchCommit<'a> {
+        user: &'a str,
+        name: &'a str,
+        commit: &'a str,
+    }
+    #[derive(serde::Serialize)]
+    struct ScriptContentBatch<'a> {
+        #[serde(flatten)]
+        inner: single_repo::ScriptContent,
+        commits: Vec<BatchCommit<'a>>,
+    }
+    let script = ScriptContentBatch {
+        inner: script,
+        commits: (commits.iter())
+            .map(|x| BatchCommit {
+                user: &x.repo.user,
+                name: &x.repo.name,
+                commit: &x.id,
+            })
+            .collect(),
+    };
+
+    let mut request = ehttp::Request::post(&url, serde_json::to_vec(&script).unwrap());
+    request.headers.insert(
+        "Content-Type".to_string(),
+        "application/json; charset=utf-8".to_string(),
+    );
+
+    ehttp::fetch(request, move |response| {
+        ctx.request_repaint(); // wake up UI thread
+        let resource = response.and_then(|response| {
+            Resource::<Result<ComputeResults, ScriptingError>>::from_response(&ctx, response)
+        });
+        let result = match resource.map(|x| x.content) {
+            Ok(Some(Ok(content))) => Ok(content),
+            Ok(Some(Err(error))) => Err(format!("{:?}", error)),
+            Ok(None) => Err("empty response".to_string()),
+            Err(error) => Err(error),
+        };
+        sender.send(result);
+    });
+    promise
+}
+
+/// The parts of the query results used to fill the rows
+#[derive(serde::Deserialize)]
+struct QueryBatchResults {
+    prepare_time: f64,
+    results: Vec<Result<BatchMatches, String>>,
+}
+
+#[derive(serde::Deserialize)]
+struct BatchMatches {
+    repo: String,
+    commit: String,
+    compute_time: f64,
+    appeared: Vec<Match>,
+}
+
+#[derive(serde::Deserialize)]
+struct Match {
+    count: usize,
+}
+
+/// Queries all the commits in a single request, the matches of each commit become a row
+/// with the number of occurrences and the number of distinct matched subtrees
+pub(super) fn remote_query_batch(
+    ctx: &egui::Context,
+    api_addr: &str,
+    commits: &[Commit],
+    query: &str,
+) -> Promise<Result<ComputeResults, String>> {
+    let ctx = ctx.clone();
+    let (sender, promise) = Promise::new();
+    let url = format!("http://{}/query-batch", api_addr);
+    let commits: Vec<_> = (commits.iter())
+        .map(|x| {
+            serde_json::json!({
+                "user": &x.repo.user,
+                "name": &x.repo.name,
+                "commit": &x.id,
+            })
+        })
+        .collect();
+    let content = serde_json::json!({ "query": query, "commits": commits });
+    let mut request = ehttp::Request::post(&url, serde_json::to_vec(&content).unwrap());
+    request.headers.insert(
+        "Content-Type".to_string(),
+        "application/json; charset=utf-8".to_string(),
+    );
+
+    ehttp::fetch(request, move |response| {
+        ctx.request_repaint(); // wake up UI thread
+        let result = response.and_then(|response| {
+            let text = response.text().unwrap_or_default();
+            if response.status != 200 {
+                return Err(text.to_string());
+            }
+            let results: QueryBatchResults =
+                serde_json::from_str(text).map_err(|x| x.to_string())?;
+            let results = (results.results.into_iter())
+                .map(|x| {
+                    let x = x?;
+                    let matches: usize = x.appeared.iter().map(|x| x.count).sum();
+                    Ok(ComputeResultIdentified {
+                        repo: Some(x.repo),
+                        commit: x.commit,
+                        inner: ComputeResult {
+                            compute_time: x.compute_time,
+                            result: serde_json::json!({
+                                "matches": matches,
+                                "distinct": x.appeared.len(),
+                            }),
+                        },
+                    })
+                })
+                .collect();
+            Ok(ComputeResults {
+                prepare_time: results.prepare_time,
+                results,
+            })
+        });
+        sender.send(result);
+    });
+    promise
+}
@@ -0,0 +1,377 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Range,
+};
+
+use egui_addon::{
+    egui_utils::{highlight_byte_range, radio_collapsing},
+    interactive_split::interactive_splitter::InteractiveSplitter,
+};
+use poll_promise::Promise;
+
+use super::{
+    code_tracking::RemoteFile,
+    show_remote_code1, show_repo_menu,
+    types::{self, Commit, Resource},
+    Buffered,
+};
+
+const DELETE_COLOR: egui::Color32 = egui::Color32::RED;
+const INSERT_COLOR: egui::Color32 = egui::Color32::GREEN;
+const UPDATE_COLOR: egui::Color32 = egui::Color32::GOLD;
+const MOVE_COLOR: egui::Color32 = egui::Color32::BLUE;
+const MOV_UPD_COLOR: egui::Color32 = egui::Color32::from_rgb(150, 0, 200);
+
+/// A range of code in one of the diffed versions
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct CodeRange {
+    pub file: String,
+    pub start: usize,
+    pub end: usize,
+    /// offsets from the diffed root, spaces included
+    pub path: Vec<usize>,
+}
+
+impl CodeRange {
+    fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// An action of the edit script, as resolved by the server
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Act {
+    Delete {
+        at: CodeRange,
+    },
+    Update {
+        at: CodeRange,
+        /// the node mapped with `at` in the `after` version
+        to: Option<CodeRange>,
+        new: String,
+    },
+    Move {
+        from: CodeRange,
+        at: CodeRange,
+    },
+    MovUpd {
+        from: CodeRange,
+        at: CodeRange,
+        new: String,
+    },
+    Insert {
+        at: CodeRange,
+        r#type: String,
+    },
+}
+
+impl Act {
+    /// the range in the `before` version, if any
+    fn before(&self) -> Option<&CodeRange> {
+        match self {
+            Act::Delete { at } | Act::Update { at, .. } => Some(at),
+            Act::Move { from, .. } | Act::MovUpd { from, .. } => Some(from),
+            Act::Insert { .. } => None,
+        }
+    }
+
+    /// the range in the `after` version, if any
+    fn after(&self) -> Option<&CodeRange> {
+        match self {
+            Act::Move { at, .. } | Act::MovUpd { at, .. } | Act::Insert { at, .. } => Some(at),
+            Act::Update { to, .. } => to.as_ref(),
+            Act::Delete { .. } => None,
+        }
+    }
+
+    fn color(&self) -> egui::Color32 {
+        match self {
+            Act::Delete { .. } => DELETE_COLOR,
+            Act::Update { .. } => UPDATE_COLOR,
+            Act::Move { .. } => MOVE_COLOR,
+            Act::MovUpd { .. } => MOV_UPD_COLOR,
+            Act::Insert { .. } => INSERT_COLOR,
+        }
+    }
+
+    fn is_move(&self) -> bool {
+        matches!(self, Act::Move { .. } | Act::MovUpd { .. })
+    }
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct DiffResult {
+    pub compute_time: f64,
+    pub before: String,
+    pub after: String,
+    pub actions: Vec<Act>,
+}
+
+impl DiffResult {
+    /// the files touched by the edit script, in both versions
+    fn files(&self) -> BTreeSet<&str> {
+        (self.actions.iter())
+            .flat_map(|a| a.before().into_iter().chain(a.after()))
+            .map(|x| x.file.as_str())
+            .collect()
+    }
+}
+
+impl Resource<DiffResult> {
+    pub(super) fn from_response(
+        _ctx: &egui::Context,
+        response: ehttp::Response,
+    ) -> Result<Self, String> {
+        let text = response.text();
+        let text = text.ok_or("")?;
+
+        let text = if response.status == 200 {
+            serde_json::from_str(text).map_err(|x| x.to_string())?
+        } else {
+            return Err(text.into());
+        };
+
+        Ok(Self {
+            response,
+            content: text,
+        })
+    }
+}
+
+pub(super) type RemoteResult = ehttp::Result<Resource<DiffResult>>;
+
+pub(super) fn remote_fetch_diff(
+    ctx: &egui::Context,
+    api_addr: &str,
+    diff: &types::ComputeConfigDiff,
+) -> Promise<RemoteResult> {
+    let ctx = ctx.clone();
+    let (sender, promise) = Promise::new();
+    let url = format!(
+        "http://{}/diff/github/{}/{}/{}/{}/{}",
+        api_addr, &diff.repo.user, &diff.repo.name, &diff.before, &diff.after, &diff.path,
+    );
+
+    wasm_rs_dbg::dbg!(&url);
+    let request = ehttp::Request::get(&url);
+
+    ehttp::fetch(request, move |response| {
+        ctx.request_repaint(); // wake up UI thread
+        let resource =
+            response.and_then(|response| Resource::<DiffResult>::from_response(&ctx, response));
+        sender.send(resource);
+    });
+    promise
+}
+
+pub(super) fn show_diff_menu(
+    ui: &mut egui::Ui,
+    selected: &mut types::SelectedConfig,
+    diff: &mut types::ComputeConfigDiff,
+    diff_result: &mut Buffered<RemoteResult>,
+) {
+    let title = "Semantic Diff";
+    let wanted = types::SelectedConfig::Diff;
+    let id = ui.make_persistent_id(title);
+    let add_body = |ui: &mut egui::Ui| {
+        let mut changed = show_repo_menu(ui, &mut diff.repo);
+        let text_edit = |ui: &mut egui::Ui, text: &mut String, hint: &str| {
+            egui::TextEdit::singleline(text)
+                .clip_text(true)
+                .desired_width(150.0)
+                .desired_rows(1)
+                .hint_text(hint)
+                .id(ui.id().with(hint))
+                .interactive(true)
+                .show(ui)
+                .response
+                .changed()
+        };
+        changed |= text_edit(ui, &mut diff.before, "before");
+        changed |= text_edit(ui, &mut diff.after, "after");
+        changed |= text_edit(ui, &mut diff.path, "path");
+        if changed {
+            *diff_result = Default::default();
+        }
+        ui.horizontal(|ui| {
+            let legend = [
+                ("delete", DELETE_COLOR),
+                ("insert", INSERT_COLOR),
+                ("update", UPDATE_COLOR),
+                ("move", MOVE_COLOR),
+                ("move+update", MOV_UPD_COLOR),
+            ];
+            for (name, color) in legend {
+                ui.colored_label(color, name);
+            }
+        });
+    };
+
+    radio_collapsing(ui, id, title, selected, &wanted, add_body);
+}
+
+pub(super) fn show_diff_results(
+    ui: &mut egui::Ui,
+    api_addr: &str,
+    diff: &mut types::ComputeConfigDiff,
+    diff_result: &mut Buffered<RemoteResult>,
+    fetched_files: &mut HashMap<types::FileIdentifier, RemoteFile>,
+) {
+    diff_result.try_poll();
+    if diff.before.is_empty() || diff.after.is_empty() {
+        ui.label("choose the two commits to compare");
+        return;
+    }
+    if !diff_result.is_waiting() && diff_result.get_mut().is_none() {
+        diff_result.buffer(remote_fetch_diff(ui.ctx(), api_addr, diff));
+    }
+    let waiting = diff_result.is_waiting();
+    let result = match diff_result.get_mut() {
+        Some(Ok(Resource {
+            content: Some(result),
+            ..
+        })) => result,
+        Some(Ok(_)) => {
+            ui.label("empty response");
+            return;
+        }
+        Some(Err(error)) => {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                if error.is_empty() { "Error" } else { error },
+            );
+            return;
+        }
+        None => {
+            ui.spinner();
+            return;
+        }
+    };
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "{} actions computed in {:.3}s",
+            result.actions.len(),
+            result.compute_time
+        ));
+        if waiting {
+            ui.spinner();
+        }
+    });
+    if result.actions.is_empty() {
+        ui.label("no structural changes");
+        return;
+    }
+    let files = result.files();
+    // the actions of a file can also touch other files, eg. code moved from another file,
+    // so only a path holding some of the touched files is a directory
+    let dir = diff.path.trim_end_matches('/');
+    let is_dir = dir.is_empty()
+        || (files.iter()).any(|x| x.strip_prefix(dir).map_or(false, |x| x.starts_with('/')));
+    if is_dir {
+        // the diff covers a directory, pick one of the changed files
+        let mut clicked = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for file in files {
+                if ui.link(file).clicked() {
+                    clicked = Some(file.to_string());
+                }
+            }
+        });
+        if let Some(file) = clicked {
+            diff.path = file;
+            *diff_result = Default::default();
+        }
+        return;
+    }
+
+    let mut before_rects = HashMap::new();
+    let mut after_rects = HashMap::new();
+    let commit = |id: &str| Commit {
+        repo: diff.repo.clone(),
+        id: id.to_string(),
+    };
+    InteractiveSplitter::vertical().show(ui, |ui1, ui2| {
+        ui1.push_id(ui1.id().with("before"), |ui| {
+            let commit = commit(&diff.before);
+            let actions = (result.actions.iter()).map(|a| a.before().map(|x| (x, a.color())));
+            before_rects = show_version(ui, api_addr, commit, &diff.path, actions, fetched_files);
+        });
+        ui2.push_id(ui2.id().with("after"), |ui| {
+            let commit = commit(&diff.after);
+            let actions = (result.actions.iter()).map(|a| a.after().map(|x| (x, a.color())));
+            after_rects = show_version(ui, api_addr, commit, &diff.path, actions, fetched_files);
+        });
+    });
+    // link moved code across the split
+    for (i, a) in result.actions.iter().enumerate() {
+        if !a.is_move() {
+            continue;
+        }
+        let (Some(from), Some(to)) = (before_rects.get(&i), after_rects.get(&i)) else {
+            continue;
+        };
+        let src_pos = from.right_center();
+        let dst_pos = to.left_center();
+        let d = ((dst_pos.x - src_pos.x) / 2.0).max(20.0);
+        let link = epaint::CubicBezierShape::from_points_stroke(
+            [
+                src_pos,
+                src_pos + egui::vec2(d, 0.0),
+                dst_pos - egui::vec2(d, 0.0),
+                dst_pos,
+            ],
+            false,
+            egui::Color32::TRANSPARENT,
+            (2.0, a.color()),
+        );
+        ui.painter().add(link);
+    }
+}
+
+/// Shows `file_path` at `commit` and highlights the given ranges,
+/// returns the screen rects of the visible highlights by index of action.
+fn show_version<'a>(
+    ui: &mut egui::Ui,
+    api_addr: &str,
+    mut commit: Commit,
+    file_path: &str,
+    actions: impl Iterator<Item = Option<(&'a CodeRange, egui::Color32)>>,
+    fetched_files: &mut HashMap<types::FileIdentifier, RemoteFile>,
+) -> HashMap<usize, egui::Rect> {
+    let mut rects = HashMap::new();
+    let mut file_path = file_path.to_string();
+    let file = types::FileIdentifier {
+        commit: commit.clone(),
+        file_path: file_path.clone(),
+    };
+    let file_result = fetched_files.entry(file);
+    let te = show_remote_code1(
+        ui,
+        api_addr,
+        &mut commit,
+        &mut file_path,
+        file_result,
+        f32::INFINITY,
+        false,
+    )
+    .2;
+    let Some(egui::InnerResponse {
+        inner: Some(aa), ..
+    }) = te
+    else {
+        return rects;
+    };
+    let te = &aa.inner;
+    for (i, at) in actions.enumerate() {
+        let Some((at, color)) = at.filter(|(x, _)| x.file == file_path) else {
+            continue;
+        };
+        let rect = highlight_byte_range(ui, te, &at.range(), color.linear_multiply(0.1));
+        let rect = rect.translate(te.text_draw_pos.to_vec2());
+        if aa.inner_rect.intersects(rect) {
+            rects.insert(i, rect.intersect(aa.inner_rect));
+        }
+    }
+    rects
+}
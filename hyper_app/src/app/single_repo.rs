@@ -1,5 +1,5 @@
 use std::{
-    ops::DerefMut,
+    ops::{ControlFlow, DerefMut},
     sync::{Arc, Mutex, RwLock},
 };
 
@@ -97,6 +97,8 @@ pub(super) struct ComputeConfigSingle {
     ws: Option<crdt_over_ws::WsDoc>,
     #[serde(skip)]
     doc_db: Option<crdt_over_ws::WsDocsDb>,
+    #[serde(skip)]
+    progress: Arc<Mutex<DepthProgress>>,
 }
 
 impl Default for ComputeConfigSingle {
@@ -113,6 +115,7 @@ impl Default for ComputeConfigSingle {
             rt,
             ws,
             doc_db,
+            progress: Default::default(),
         }
     }
 }
@@ -127,6 +130,35 @@ type ScriptingContext = super::ScriptingContext<
     super::types::CodeEditors<code_editor_automerge::CodeEditor>,
 >;
 
+#[derive(serde::Serialize)]
+pub(super) struct ScriptContent {
+    init: String,
+    filter: String,
+    accumulate: String,
+}
+
+/// The scripts currently edited, either shared or local
+pub(super) fn script_content(code_editors: &mut ScriptingContext) -> ScriptContent {
+    match &mut code_editors.current {
+        super::EditStatus::Shared(_, shared_script) | super::EditStatus::Sharing(shared_script) => {
+            let code_editors = shared_script.lock().unwrap();
+            ScriptContent {
+                init: code_editors.init.code().to_string(),
+                filter: code_editors.filter.code().to_string(),
+                accumulate: code_editors.accumulate.code().to_string(),
+            }
+        }
+        super::EditStatus::Local { name: _, content }
+        | super::EditStatus::Example { i: _, content } => ScriptContent {
+            init: content.init.code().to_string(),
+            filter: content.filter.code().to_string(),
+            accumulate: content.accumulate.code().to_string(),
+        },
+    }
+}
+
+/// Evaluates the script on the server, streaming its progress into `single.progress`,
+/// see [`DepthStream`].
 pub(super) fn remote_compute_single(
     ctx: &egui::Context,
     api_addr: &str,
@@ -140,35 +172,19 @@ pub(super) fn remote_compute_single(
     let ctx = ctx.clone();
     let (sender, promise) = Promise::new();
     let url = format!(
-        "http://{}/script-depth/github/{}/{}/{}",
+        "http://{}/script-depth-stream/github/{}/{}/{}",
         api_addr, &single.commit.repo.user, &single.commit.repo.name, &single.commit.id,
     );
     #[derive(serde::Serialize)]
-    struct ScriptContent {
-        init: String,
-        filter: String,
-        accumulate: String,
+    struct ScriptContentDepth {
+        #[serde(flatten)]
+        inner: ScriptContent,
         commits: usize,
     }
-    let script = match &mut code_editors.current {
-        super::EditStatus::Shared(_, shared_script) | super::EditStatus::Sharing(shared_script) => {
-            let code_editors = shared_script.lock().unwrap();
-            ScriptContent {
-                init: code_editors.init.code().to_string(),
-                filter: code_editors.filter.code().to_string(),
-                accumulate: code_editors.accumulate.code().to_string(),
-                commits: single.len,
-            }
-        }
-        super::EditStatus::Local { name: _, content }
-        | super::EditStatus::Example { i: _, content } => ScriptContent {
-            init: content.init.code().to_string(),
-            filter: content.filter.code().to_string(),
-            accumulate: content.accumulate.code().to_string(),
-            commits: single.len,
-        },
+    let script = ScriptContentDepth {
+        inner: script_content(code_editors),
+        commits: single.len,
     };
-    drop(code_editors);
 
     let mut request = ehttp::Request::post(&url, serde_json::to_vec(&script).unwrap());
     request.headers.insert(
@@ -176,17 +192,183 @@ pub(super) fn remote_compute_single(
         "application/json; charset=utf-8".to_string(),
     );
 
-    ehttp::fetch(request, move |response| {
+    *single.progress.lock().unwrap() = Default::default();
+    let stream = Mutex::new(DepthStream::new(single.progress.clone()));
+    let sender = Mutex::new(Some(sender));
+    ehttp::streaming::fetch(request, move |part| {
         ctx.request_repaint(); // wake up UI thread
-        let resource = response.and_then(|response| {
-            Resource::<Result<ComputeResults, ScriptingError>>::from_response(&ctx, response)
-        });
-        sender.send(resource);
+        let mut stream = stream.lock().unwrap();
+        let resource = match part {
+            Ok(ehttp::streaming::Part::Response(response)) => {
+                stream.response = Some(response);
+                None
+            }
+            Ok(ehttp::streaming::Part::Chunk(chunk)) => stream.push(&ctx, chunk),
+            Err(err) => Some(Err(err)),
+        };
+        match resource {
+            Some(resource) => {
+                if let Some(sender) = sender.lock().unwrap().take() {
+                    sender.send(resource);
+                }
+                ControlFlow::Break(())
+            }
+            None => ControlFlow::Continue(()),
+        }
     });
     promise
 }
 
-#[derive(serde::Deserialize, serde::Serialize)]
+/// What is known of an evaluation while the server streams it
+#[derive(Default)]
+pub(super) struct DepthProgress {
+    built: usize,
+    missing: usize,
+    /// number of commits to evaluate, known once they are all built
+    commits: Option<usize>,
+    done: usize,
+}
+
+/// Same as the `DepthEvent`s sent by the server
+#[derive(serde::Deserialize)]
+enum DepthEvent {
+    Preparing {
+        built: usize,
+        missing: usize,
+    },
+    Prepared {
+        prepare_time: f64,
+        commits: usize,
+    },
+    Computed {
+        done: usize,
+        result: Result<ComputeResultIdentified, String>,
+    },
+}
+
+/// Accumulates the server-sent events of `/script-depth-stream` until a `done` or `failed` event.
+struct DepthStream {
+    response: Option<ehttp::PartialResponse>,
+    bytes: Vec<u8>,
+    /// start of the first event that is not handled yet
+    handled: usize,
+    results: ComputeResults,
+    progress: Arc<Mutex<DepthProgress>>,
+}
+
+impl DepthStream {
+    fn new(progress: Arc<Mutex<DepthProgress>>) -> Self {
+        Self {
+            response: None,
+            bytes: vec![],
+            handled: 0,
+            results: Default::default(),
+            progress,
+        }
+    }
+
+    /// Gives the resource once the stream is complete, an empty chunk marking its end.
+    fn push(
+        &mut self,
+        ctx: &egui::Context,
+        chunk: Vec<u8>,
+    ) -> Option<Result<Resource<Result<ComputeResults, ScriptingError>>, String>> {
+        let end = chunk.is_empty();
+        self.bytes.extend(chunk);
+        let ok = self.response.as_ref()?.ok;
+        if !ok {
+            // errors are not streamed, e.g. when the body of the request is rejected
+            return end.then(|| Resource::from_response(ctx, self.take_response()));
+        }
+        let content = self.handle_events().or_else(|| {
+            end.then(|| {
+                Err(ScriptingError::Other(
+                    "the stream ended before the evaluation".to_string(),
+                ))
+            })
+        })?;
+        Some(Ok(Resource {
+            response: self.take_response(),
+            content: Some(content),
+        }))
+    }
+
+    fn handle_events(&mut self) -> Option<Result<ComputeResults, ScriptingError>> {
+        while let Some(len) = self.bytes[self.handled..]
+            .windows(2)
+            .position(|x| x == b"\n\n")
+        {
+            let frame = &self.bytes[self.handled..self.handled + len];
+            let frame = String::from_utf8_lossy(frame).into_owned();
+            self.handled += len + 2;
+            let mut event = "message";
+            let mut data = String::new();
+            for line in frame.lines() {
+                if let Some(x) = line.strip_prefix("event:") {
+                    event = x.trim_start();
+                } else if let Some(x) = line.strip_prefix("data:") {
+                    if !data.is_empty() {
+                        data.push('\n');
+                    }
+                    data.push_str(x.strip_prefix(' ').unwrap_or(x));
+                }
+            }
+            match event {
+                "done" => return Some(Ok(std::mem::take(&mut self.results))),
+                "failed" => {
+                    return Some(Err(serde_json::from_str(&data)
+                        .unwrap_or_else(|e| ScriptingError::Other(e.to_string()))))
+                }
+                "preparing" | "prepared" | "computed" => (),
+                // keep-alive comments
+                _ => continue,
+            }
+            let event = match serde_json::from_str(&data) {
+                Ok(event) => event,
+                Err(e) => return Some(Err(ScriptingError::Other(e.to_string()))),
+            };
+            let mut progress = self.progress.lock().unwrap();
+            match event {
+                DepthEvent::Preparing { built, missing } => {
+                    progress.built = built;
+                    progress.missing = missing;
+                }
+                DepthEvent::Prepared {
+                    prepare_time,
+                    commits,
+                } => {
+                    self.results.prepare_time = prepare_time;
+                    progress.commits = Some(commits);
+                }
+                DepthEvent::Computed { done, result } => {
+                    self.results.results.push(result);
+                    progress.done = done;
+                }
+            }
+        }
+        None
+    }
+
+    fn take_response(&mut self) -> ehttp::Response {
+        let ehttp::PartialResponse {
+            url,
+            ok,
+            status,
+            status_text,
+            headers,
+        } = self.response.take().unwrap();
+        ehttp::Response {
+            url,
+            ok,
+            status,
+            status_text,
+            headers,
+            bytes: std::mem::take(&mut self.bytes),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Default)]
 pub struct ComputeResults {
     pub prepare_time: f64,
     pub results: Vec<Result<ComputeResultIdentified, String>>,
@@ -194,6 +376,9 @@ pub struct ComputeResults {
 
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct ComputeResultIdentified {
+    /// user/name of the repository, only given by batches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
     pub commit: super::types::CommitId,
     #[serde(flatten)]
     pub inner: ComputeResult,
@@ -209,6 +394,7 @@ pub struct ComputeResult {
 pub enum ScriptingError {
     AtCompilation(String),
     AtEvaluation(String),
+    LimitExceeded(String),
     Other(String),
 }
 
@@ -342,7 +528,13 @@ fn handle_interactions(
     single: &mut ComputeConfigSingle,
     trigger_compute: &mut bool,
 ) {
-    let interaction = show_interactions(ui, code_editors, &single.doc_db, compute_single_result);
+    let interaction = show_interactions(
+        ui,
+        code_editors,
+        &single.doc_db,
+        compute_single_result,
+        &single.progress,
+    );
     if interaction.share_button.map_or(false, |x| x.clicked()) {
         let (name, content) = interaction.editor.unwrap();
         let content = content.clone().to_shared();
@@ -375,6 +567,7 @@ fn show_interactions<'a>(
             Result<super::types::Resource<Result<ComputeResults, ScriptingError>>, String>,
         >,
     >,
+    progress: &Mutex<DepthProgress>,
 ) -> InteractionResp<'a> {
     let mut save_button = None;
     let mut share_button = None;
@@ -400,7 +593,7 @@ fn show_interactions<'a>(
     let compute_button = ui
         .horizontal(|ui| {
             let compute_button = ui.add(egui::Button::new("Compute"));
-            show_short_result(&*compute_single_result, ui);
+            show_short_result(&*compute_single_result, &progress.lock().unwrap(), ui);
             compute_button
         })
         .inner;
@@ -963,7 +1156,11 @@ pub(super) fn show_single_repo_menu(
     radio_collapsing(ui, id, title, selected, &wanted, add_body);
 }
 
-pub(super) fn show_short_result(promise: &Option<RemoteResult>, ui: &mut egui::Ui) {
+pub(super) fn show_short_result(
+    promise: &Option<RemoteResult>,
+    progress: &DepthProgress,
+    ui: &mut egui::Ui,
+) {
     if let Some(promise) = &promise {
         if let Some(result) = promise.ready() {
             match result {
@@ -1011,7 +1208,13 @@ pub(super) fn show_short_result(promise: &Option<RemoteResult>, ui: &mut egui::U
                 }
             }
         } else {
-            ui.label(format!("compute time: "));
+            match progress.commits {
+                Some(commits) => ui.label(format!("computed: {}/{}", progress.done, commits)),
+                None if progress.missing > 0 => {
+                    ui.label(format!("built: {}/{}", progress.built, progress.missing))
+                }
+                None => ui.label(format!("compute time: ")),
+            };
             ui.spinner();
         }
     }
@@ -1055,6 +1258,7 @@ fn show_long_result_compute_failure(error: &ScriptingError, ui: &mut egui::Ui) {
     let (h, c) = match error {
         ScriptingError::AtCompilation(err) => ("Error at compilation:", err),
         ScriptingError::AtEvaluation(err) => ("Error at evaluation:", err),
+        ScriptingError::LimitExceeded(err) => ("Limit exceeded:", err),
         ScriptingError::Other(err) => ("Error somewhere else:", err),
     };
     ui.label(
@@ -1122,7 +1326,7 @@ fn show_long_result_list(content: &ComputeResults, ui: &mut egui::Ui) {
     }
 }
 
-fn show_long_result_table(content: &ComputeResults, ui: &mut egui::Ui) {
+pub(super) fn show_long_result_table(content: &ComputeResults, ui: &mut egui::Ui) {
     // header
     let header = content.results.iter().find(|x| x.is_ok());
     let Some(header) = header
@@ -1197,7 +1401,12 @@ fn show_long_result_table(content: &ComputeResults, ui: &mut egui::Ui) {
                     Ok(cont) => {
                         body.row(30.0, |mut row| {
                             row.col(|ui| {
-                                ui.label(&cont.commit[..8]);
+                                let commit = &cont.commit[..cont.commit.len().min(8)];
+                                if let Some(repo) = &cont.repo {
+                                    ui.label(format!("{}@{}", repo, commit));
+                                } else {
+                                    ui.label(commit);
+                                }
                             });
                             for (_, v) in cont.inner.result.as_object().unwrap() {
                                 if v.is_object() {
@@ -1229,7 +1438,7 @@ fn show_long_result_table(content: &ComputeResults, ui: &mut egui::Ui) {
         });
 }
 
-struct SecFmt(f64);
+pub(super) struct SecFmt(pub(super) f64);
 
 impl From<f64> for SecFmt {
     fn from(value: f64) -> Self {
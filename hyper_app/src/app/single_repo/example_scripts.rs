@@ -149,6 +149,42 @@ Made to work on Cpp source code."#,
     p.type_decl += 1; 
 } else if type() == "declaration" {
     p.type_decl += 1; 
+}"##,
+        },
+    },
+    Example {
+        name: "lines per file (any language)",
+        commit: Commit {
+            repo: Repo {
+                forge: Forge::GitHub,
+                user: "official-stockfish",
+                name: "Stockfish",
+            },
+            id: "7f2eb10e93879bc569c7ddf6fb51d6f812cc477c",
+        },
+        config: Config::MakeCpp,
+        commits: 1,
+        scripts: Scripts {
+            description: r#"Computes the number of parsed files, their lines and their bytes.
+Only uses the language agnostic functions, so it also works on Java and TS code bases."#,
+            init: r##"#{ files: 0, lines: 0, bytes: 0 }"##,
+            filter: r##"if is_directory() {
+    children().map(|x| [x, #{
+        files: 0,
+        lines: 0,
+        bytes: 0,
+    }])
+} else {
+    []
+}"##,
+            accumulate: r##"if is_directory() {
+    p.files += s.files;
+    p.lines += s.lines;
+    p.bytes += s.bytes;
+} else if is_file() && lang() != "blob" {
+    p.files += 1;
+    p.lines += line_count();
+    p.bytes += byte_len();
 }"##,
         },
     },
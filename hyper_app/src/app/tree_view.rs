@@ -56,6 +56,30 @@ impl<'a> hyper_ast::types::TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TSto
                     <hyper_ast_gen_ts_xml::types::Xml as Lang<_>>::make(raw);
                 t
             }
+            "hyper_ast_gen_ts_ts::types::Lang" => {
+                let raw = n.get_raw_type();
+                let t: &'static (dyn HyperType + 'static) =
+                    <hyper_ast_gen_ts_ts::types::Ts as Lang<_>>::make(raw);
+                t
+            }
+            "hyper_ast_gen_ts_rust::types::Lang" => {
+                let raw = n.get_raw_type();
+                let t: &'static (dyn HyperType + 'static) =
+                    <hyper_ast_gen_ts_rust::types::Rust as Lang<_>>::make(raw);
+                t
+            }
+            "hyper_ast_gen_ts_python::types::Lang" => {
+                let raw = n.get_raw_type();
+                let t: &'static (dyn HyperType + 'static) =
+                    <hyper_ast_gen_ts_python::types::Python as Lang<_>>::make(raw);
+                t
+            }
+            "hyper_ast::blob::types::Lang" => {
+                let raw = n.get_raw_type();
+                let t: &'static (dyn HyperType + 'static) =
+                    <hyper_ast::blob::types::Blob as Lang<_>>::make(raw);
+                t
+            }
             "" => {
                 let t: &'static (dyn HyperType + 'static) =
                     <hyper_ast_gen_ts_java::types::Java as Lang<_>>::make(
@@ -84,6 +108,18 @@ impl<'a> hyper_ast::types::TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TSto
             "hyper_ast_gen_ts_xml::types::Lang" => {
                 From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_xml::types::Lang)
             }
+            "hyper_ast_gen_ts_ts::types::Lang" => {
+                From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_ts::types::Lang)
+            }
+            "hyper_ast_gen_ts_rust::types::Lang" => {
+                From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_rust::types::Lang)
+            }
+            "hyper_ast_gen_ts_python::types::Lang" => From::<&'static (dyn LangRef<AnyType>)>::from(
+                &hyper_ast_gen_ts_python::types::Lang,
+            ),
+            "hyper_ast::blob::types::Lang" => {
+                From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast::blob::types::Lang)
+            }
             "" => {
                 From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_java::types::Lang)
             }
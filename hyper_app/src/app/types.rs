@@ -19,6 +19,18 @@ pub(crate) type CommitId = String;
 #[serde(default)]
 pub(crate) struct ComputeConfigMulti {
     pub(crate) list: Vec<Commit>,
+    pub(crate) kind: MultiKind,
+    /// a tree-sitter query, used with [`MultiKind::Query`]
+    pub(crate) query: String,
+}
+
+/// What is evaluated on each commit of a [`ComputeConfigMulti`]
+#[derive(serde::Deserialize, serde::Serialize, Default, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum MultiKind {
+    /// the scripts of the single repository view
+    #[default]
+    Script,
+    Query,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Default)]
@@ -27,6 +39,8 @@ pub(crate) struct ComputeConfigDiff {
     pub(crate) repo: Repo,
     pub(crate) before: CommitId,
     pub(crate) after: CommitId,
+    /// restrict the diff to the subtree at this path, a file is shown side by side
+    pub(crate) path: String,
 }
 
 #[derive(Hash, PartialEq, Eq, Clone, Debug, serde::Deserialize, serde::Serialize)]
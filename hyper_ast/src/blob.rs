@@ -0,0 +1,8 @@
+//! Opaque files, ie. files stored without their syntax,
+//! eg. because there is no grammar for them or because their content does not matter.
+//!
+//! A blob is a leaf labeled with the name of the file,
+//! its content is only hashed, so that two versions of a file are not deduplicated.
+//! Its type does not belong to any language, so processors of any language can use it.
+
+pub mod types;
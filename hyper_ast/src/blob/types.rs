@@ -0,0 +1,121 @@
+use std::fmt::Display;
+
+use crate::types::{AnyType, HyperType, LangRef};
+
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Type {
+    Blob,
+}
+
+/// Persisted with its discriminant, only append new variants
+const S_T_L: &'static [Type] = &[Type::Blob];
+
+impl Type {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Type::Blob => "Blob",
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_str())
+    }
+}
+
+pub struct Lang;
+pub type Blob = Lang;
+
+impl crate::types::Lang<Type> for Blob {
+    fn make(t: u16) -> &'static Type {
+        Lang.make(t)
+    }
+    fn to_u16(t: Type) -> u16 {
+        Lang.to_u16(t)
+    }
+}
+
+impl LangRef<Type> for Lang {
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Lang>()
+    }
+
+    fn make(&self, t: u16) -> &'static Type {
+        &S_T_L[t as usize]
+    }
+
+    fn to_u16(&self, t: Type) -> u16 {
+        t as u16
+    }
+}
+
+impl LangRef<AnyType> for Lang {
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Lang>()
+    }
+
+    fn make(&self, t: u16) -> &'static AnyType {
+        panic!()
+    }
+
+    fn to_u16(&self, t: AnyType) -> u16 {
+        let t = t.as_any().downcast_ref::<Type>().unwrap();
+        *t as u16
+    }
+}
+
+pub fn as_any(t: &Type) -> AnyType {
+    let t = <Lang as crate::types::Lang<Type>>::to_u16(*t);
+    let t = <Lang as crate::types::Lang<Type>>::make(t);
+    let t: &'static dyn HyperType = t;
+    t.into()
+}
+
+impl HyperType for Type {
+    fn generic_eq(&self, other: &dyn HyperType) -> bool
+    where
+        Self: 'static + PartialEq + Sized,
+    {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |a| self == a)
+    }
+
+    fn as_shared(&self) -> crate::types::Shared {
+        crate::types::Shared::Other
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_static(&self) -> &'static dyn HyperType {
+        <Lang as crate::types::Lang<Type>>::make(*self as u16)
+    }
+
+    fn is_file(&self) -> bool {
+        true
+    }
+
+    fn is_directory(&self) -> bool {
+        false
+    }
+
+    fn is_spaces(&self) -> bool {
+        false
+    }
+
+    fn is_syntax(&self) -> bool {
+        false
+    }
+
+    fn get_lang(&self) -> crate::types::LangWrapper<Self>
+    where
+        Self: Sized,
+    {
+        From::<&'static (dyn LangRef<Type>)>::from(&Lang)
+    }
+}
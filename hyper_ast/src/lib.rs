@@ -2,6 +2,7 @@
 #![feature(exact_size_is_empty)]
 #![feature(slice_index_methods)]
 
+pub mod blob;
 pub mod compat;
 #[cfg(feature = "legion")]
 pub mod cyclomatic;
@@ -77,4 +77,9 @@ impl LabelStore {
         r.get_or_insert("length"); // TODO verify/model statically
         r
     }
+
+    /// number of distinct labels
+    pub fn len(&self) -> usize {
+        self.internal.len()
+    }
 }
@@ -14,6 +14,22 @@ pub struct CSE<const N: usize>([legion::Entity; N]);
 #[derive(PartialEq, Eq, Debug)]
 pub struct CS<T: Eq>(pub Box<[T]>);
 pub struct NoSpacesCS<T: Eq>(pub Box<[T]>);
+/// The fields of the children, eg. `name` in `(method_declaration name: (identifier))`,
+/// aligned with the children in [`CS`], spaces included.
+///
+/// Not used to deduplicate nodes, the fields of a node follow from its type and children.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Roles<T: Eq>(pub Box<[Option<T>]>);
+impl<T: Eq + Copy> Roles<T> {
+    /// From the indexes of the children having a field, None if none has
+    pub fn new(len: usize, roles: impl IntoIterator<Item = (usize, T)>) -> Option<Self> {
+        let mut res = None;
+        for (i, r) in roles {
+            res.get_or_insert_with(|| vec![None; len])[i] = Some(r);
+        }
+        res.map(|x| Roles(x.into_boxed_slice()))
+    }
+}
 impl<'a, T: Eq> From<&'a CS<T>> for &'a [T] {
     fn from(cs: &'a CS<T>) -> Self {
         &cs.0
@@ -14,7 +14,7 @@ use crate::{
     store::defaults::LabelIdentifier,
     types::{
         AnyType, Children, HyperType, IterableChildren, MySlice, NodeId, TypeTrait, Typed,
-        TypedNodeId, WithChildren, WithMetaData,
+        TypedNodeId, WithChildren, WithMetaData, WithRoles,
     },
 };
 
@@ -264,6 +264,13 @@ impl<'a, T, C: Component> WithMetaData<C> for HashedNodeRef<'a, T> {
     }
 }
 
+impl<'a, T> WithRoles for HashedNodeRef<'a, T> {
+    fn role_at(&self, idx: usize) -> Option<&LabelIdentifier> {
+        let roles = self.0.get_component::<compo::Roles<LabelIdentifier>>().ok()?;
+        roles.0.get(idx)?.as_ref()
+    }
+}
+
 impl<'a, T> HashedNodeRef<'a, T> {
     // pub(crate) fn new(entry: EntryRef<'a>) -> Self {
     //     Self(entry)
@@ -17,6 +17,8 @@ pub mod compo;
 
 mod elem;
 
+pub mod persist;
+
 pub use elem::{EntryRef, HashedNode, HashedNodeRef, NodeIdentifier};
 
 pub struct NodeStore {
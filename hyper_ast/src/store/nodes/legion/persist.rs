@@ -0,0 +1,1147 @@
+//! Persistence of the legion [`NodeStore`] and of the [`LabelStore`] on disk.
+//!
+//! Nodes are appended to a segment file in post-order,
+//! thus children are always written before their parents and are referred to by their offset in the segment.
+//! A segment is loaded lazily, from the offset of a root down to its descendants that are not loaded yet.
+//! Loaded nodes are deduplicated against the nodes of the store,
+//! thus new nodes can be built before, between and after loads.
+//!
+//! Nodes built while their equal persisted node is not loaded get persisted again under a new offset,
+//! it wastes some space but both offsets load as the same node.
+//!
+//! Components are stored through [`Codecs`], components without a registered codec are left out with a warning,
+//! so processors must register every component they read back from persisted nodes.
+//! Labels are appended to their own file, in the order of their identifiers,
+//! they must be fully loaded before any node as they are referred to by identifier in nodes.
+//!
+//! # Segment layout
+//! A segment is a sequence of records, each starting with a tag byte:
+//! - [`DECL`] `[u16 len][name]`, declares the next codec identifier of the segment,
+//! - [`NODE`] `[u32 len][u16 count]` then for each component `[u16 codec][u32 len][bytes]`.
+//!
+//! A partially written record (eg. after a crash) is truncated when opening the segment.
+//!
+//! The same codecs are used by [`Transfer`] to copy nodes from a store to another.
+
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use legion::storage::Component;
+use string_interner::Symbol;
+
+use crate::{
+    cyclomatic::Mcc,
+    filter::{Bloom, BloomSize},
+    hashed::SyntaxNodeHashs,
+    store::labels::{label_id_from_usize, DefaultLabelIdentifier, LabelStore},
+    types::LabelStore as _,
+    utils::make_hash,
+};
+
+use super::{
+    compo::{self, NoSpacesCS, CS},
+    dyn_builder::EntityBuilder,
+    EntryRef, HashedNodeRef, NodeIdentifier, NodeStore,
+};
+
+const DECL: u8 = 0;
+const NODE: u8 = 1;
+
+#[derive(Debug)]
+pub enum PersistError {
+    Io(io::Error),
+    /// the segment uses a codec that is not registered
+    UnknownCodec(String),
+    /// a node refers to a node that is not (yet) persisted or loaded
+    MissingNode(u64),
+    Corrupted(u64),
+}
+
+impl Display for PersistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersistError::Io(e) => write!(f, "io error: {}", e),
+            PersistError::UnknownCodec(name) => write!(
+                f,
+                "codec {} is not registered, eg. the store was written with other types",
+                name
+            ),
+            PersistError::MissingNode(offset) => write!(f, "missing node at {}", offset),
+            PersistError::Corrupted(offset) => write!(f, "corrupted record at {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl From<io::Error> for PersistError {
+    fn from(e: io::Error) -> Self {
+        PersistError::Io(e)
+    }
+}
+
+type Encode =
+    fn(&EntryRef, &dyn Fn(NodeIdentifier) -> Option<u64>, &mut Vec<u8>) -> Result<(), PersistError>;
+type Decode = fn(
+    &[u8],
+    &dyn Fn(u64) -> Option<NodeIdentifier>,
+    &mut EntityBuilder,
+) -> Result<(), PersistError>;
+/// compares the components of two entities, both have the component
+type Equal = fn(&EntryRef, &EntryRef) -> bool;
+
+struct Codec {
+    name: String,
+    encode: Encode,
+    decode: Decode,
+    eq: Equal,
+}
+
+/// Registry of the codecs used to persist the components of nodes.
+///
+/// Names identify codecs in segments, so they must be stable across runs.
+/// Changing the layout of a component (eg. adding a variant to a type enum) requires a new name,
+/// see [`Codecs::register_pod_with_layout`] to derive it from the layout.
+pub struct Codecs {
+    codecs: Vec<Codec>,
+    by_type: HashMap<TypeId, usize>,
+    by_name: HashMap<String, usize>,
+}
+
+impl Default for Codecs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codecs {
+    /// Codecs of the components provided by this crate,
+    /// components of generators (eg. their types) must be registered on top.
+    pub fn new() -> Self {
+        let mut r = Self {
+            codecs: vec![],
+            by_type: Default::default(),
+            by_name: Default::default(),
+        };
+        r.register_children::<CS<NodeIdentifier>>("CS")
+            .register_children::<NoSpacesCS<NodeIdentifier>>("NoSpacesCS");
+        r.register::<compo::Roles<DefaultLabelIdentifier>>(
+            "Roles<Label>".into(),
+            encode_roles,
+            decode_roles,
+            eq_roles,
+        );
+        // SAFETY: all the following components are plain data
+        unsafe {
+            r.register_pod::<compo::Size>("Size")
+                .register_pod::<compo::SizeNoSpaces>("SizeNoSpaces")
+                .register_pod::<compo::Height>("Height")
+                .register_pod::<compo::BytesLen>("BytesLen")
+                .register_pod::<SyntaxNodeHashs<u32>>("SyntaxNodeHashs<u32>")
+                .register_pod::<DefaultLabelIdentifier>("Label")
+                .register_pod::<BloomSize>("BloomSize")
+                .register_pod::<Mcc>("Mcc")
+                .register_pod_list::<DefaultLabelIdentifier>("CS<Label>")
+                .register_pod::<Bloom<&'static [u8], u16>>("Bloom<u16>")
+                .register_pod::<Bloom<&'static [u8], u32>>("Bloom<u32>")
+                .register_pod::<Bloom<&'static [u8], u64>>("Bloom<u64>")
+                .register_pod::<Bloom<&'static [u8], [u64; 2]>>("Bloom<[u64; 2]>")
+                .register_pod::<Bloom<&'static [u8], [u64; 4]>>("Bloom<[u64; 4]>")
+                .register_pod::<Bloom<&'static [u8], [u64; 8]>>("Bloom<[u64; 8]>")
+                .register_pod::<Bloom<&'static [u8], [u64; 16]>>("Bloom<[u64; 16]>")
+                .register_pod::<Bloom<&'static [u8], [u64; 32]>>("Bloom<[u64; 32]>")
+                .register_pod::<Bloom<&'static [u8], [u64; 64]>>("Bloom<[u64; 64]>");
+        }
+        r
+    }
+
+    fn register<T: Component>(&mut self, name: String, encode: Encode, decode: Decode, eq: Equal) {
+        let i = self.codecs.len();
+        assert!(
+            self.by_name.insert(name.clone(), i).is_none(),
+            "codec {} already registered",
+            name
+        );
+        self.by_type.insert(TypeId::of::<T>(), i);
+        self.codecs.push(Codec {
+            name,
+            encode,
+            decode,
+            eq,
+        });
+    }
+
+    /// Components copied byte per byte.
+    ///
+    /// # Safety
+    /// `T` must be plain data: no pointers, no references, no padding, and any persisted bit pattern must stay valid.
+    pub unsafe fn register_pod<T: Component>(&mut self, name: &'static str) -> &mut Self {
+        self.register::<T>(name.into(), encode_pod::<T>, decode_pod::<T>, eq_pod::<T>);
+        self
+    }
+
+    /// Same as [`Codecs::register_pod`], but the name is suffixed with a hash of `layout`,
+    /// eg. the names of the variants of an enum in the order of their discriminants.
+    ///
+    /// A segment written with another layout then fails to open with [`PersistError::UnknownCodec`],
+    /// instead of decoding its components as other values.
+    ///
+    /// # Safety
+    /// same as [`Codecs::register_pod`]
+    pub unsafe fn register_pod_with_layout<T: Component>(
+        &mut self,
+        name: &str,
+        layout: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> &mut Self {
+        let name = format!("{}#{:016x}", name, layout_hash(layout));
+        self.register::<T>(name, encode_pod::<T>, decode_pod::<T>, eq_pod::<T>);
+        self
+    }
+
+    /// Type enums, see [`Codecs::register_pod_with_layout`],
+    /// the layout is made of the debug names of the variants, from the first one to `last`.
+    ///
+    /// # Safety
+    /// `T` must be a fieldless `repr(u16)` enum without explicit discriminants, and `last` its last variant.
+    pub unsafe fn register_type_enum<T: Component + Copy + std::fmt::Debug>(
+        &mut self,
+        name: &str,
+        last: T,
+    ) -> &mut Self {
+        assert_eq!(std::mem::size_of::<T>(), std::mem::size_of::<u16>());
+        let last: u16 = std::mem::transmute_copy(&last);
+        let variants = (0..=last).map(|i| format!("{:?}", std::mem::transmute_copy::<u16, T>(&i)));
+        self.register_pod_with_layout::<T>(name, variants)
+    }
+
+    /// Lists of plain data, stored in a [`CS`].
+    ///
+    /// # Safety
+    /// same as [`Codecs::register_pod`] for `T`
+    pub unsafe fn register_pod_list<T: 'static + Send + Sync + Eq>(
+        &mut self,
+        name: &'static str,
+    ) -> &mut Self {
+        self.register::<CS<T>>(
+            name.into(),
+            encode_pod_list::<T>,
+            decode_pod_list::<T>,
+            eq_pod_list::<T>,
+        );
+        self
+    }
+
+    /// Lists of nodes, persisted nodes are referred to by their offset in the segment.
+    pub fn register_children<T: Component + NodeList>(&mut self, name: &'static str) -> &mut Self {
+        self.register::<T>(
+            name.into(),
+            encode_children::<T>,
+            decode_children::<T>,
+            eq_children::<T>,
+        );
+        self
+    }
+}
+
+/// FNV-1a, as the hash must be the same across runs and versions of the standard library
+fn layout_hash(layout: impl IntoIterator<Item = impl AsRef<str>>) -> u64 {
+    let mut h: u64 = 0xcbf29ce484222325;
+    for x in layout {
+        for b in x.as_ref().bytes().chain([0]) {
+            h ^= b as u64;
+            h = h.wrapping_mul(0x100000001b3);
+        }
+    }
+    h
+}
+
+/// Components holding references to other nodes
+pub trait NodeList {
+    fn nodes(&self) -> &[NodeIdentifier];
+    fn from_nodes(nodes: Box<[NodeIdentifier]>) -> Self;
+}
+
+impl NodeList for CS<NodeIdentifier> {
+    fn nodes(&self) -> &[NodeIdentifier] {
+        &self.0
+    }
+    fn from_nodes(nodes: Box<[NodeIdentifier]>) -> Self {
+        CS(nodes)
+    }
+}
+
+impl NodeList for NoSpacesCS<NodeIdentifier> {
+    fn nodes(&self) -> &[NodeIdentifier] {
+        &self.0
+    }
+    fn from_nodes(nodes: Box<[NodeIdentifier]>) -> Self {
+        NoSpacesCS(nodes)
+    }
+}
+
+fn component<'a, T: Component>(entry: &'a EntryRef) -> &'a T {
+    // the codec is only called for components present on the entity
+    entry.get_component::<T>().unwrap()
+}
+
+fn pod_bytes<T>(c: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts((c as *const T).cast::<u8>(), std::mem::size_of::<T>()) }
+}
+
+fn pod_list_bytes<T>(c: &[T]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(c.as_ptr().cast::<u8>(), std::mem::size_of_val(c)) }
+}
+
+fn encode_pod<T: Component>(
+    entry: &EntryRef,
+    _: &dyn Fn(NodeIdentifier) -> Option<u64>,
+    out: &mut Vec<u8>,
+) -> Result<(), PersistError> {
+    let c: &T = component(entry);
+    out.extend_from_slice(pod_bytes(c));
+    Ok(())
+}
+
+fn eq_pod<T: Component>(a: &EntryRef, b: &EntryRef) -> bool {
+    pod_bytes::<T>(component(a)) == pod_bytes::<T>(component(b))
+}
+
+fn decode_pod<T: Component>(
+    bytes: &[u8],
+    _: &dyn Fn(u64) -> Option<NodeIdentifier>,
+    builder: &mut EntityBuilder,
+) -> Result<(), PersistError> {
+    if bytes.len() != std::mem::size_of::<T>() {
+        return Err(PersistError::UnknownCodec(
+            std::any::type_name::<T>().to_string(),
+        ));
+    }
+    let c = unsafe { std::ptr::read_unaligned(bytes.as_ptr().cast::<T>()) };
+    builder.add(c);
+    Ok(())
+}
+
+fn encode_pod_list<T: 'static + Send + Sync + Eq>(
+    entry: &EntryRef,
+    _: &dyn Fn(NodeIdentifier) -> Option<u64>,
+    out: &mut Vec<u8>,
+) -> Result<(), PersistError> {
+    let c: &CS<T> = component(entry);
+    out.extend_from_slice(pod_list_bytes(&c.0));
+    Ok(())
+}
+
+fn eq_pod_list<T: 'static + Send + Sync + Eq>(a: &EntryRef, b: &EntryRef) -> bool {
+    let a: &CS<T> = component(a);
+    let b: &CS<T> = component(b);
+    pod_list_bytes(&a.0) == pod_list_bytes(&b.0)
+}
+
+fn decode_pod_list<T: 'static + Send + Sync + Eq>(
+    bytes: &[u8],
+    _: &dyn Fn(u64) -> Option<NodeIdentifier>,
+    builder: &mut EntityBuilder,
+) -> Result<(), PersistError> {
+    let size = std::mem::size_of::<T>();
+    if bytes.len() % size != 0 {
+        return Err(PersistError::UnknownCodec(
+            std::any::type_name::<T>().to_string(),
+        ));
+    }
+    let c: Box<[T]> = bytes
+        .chunks_exact(size)
+        .map(|x| unsafe { std::ptr::read_unaligned(x.as_ptr().cast::<T>()) })
+        .collect();
+    builder.add(CS(c));
+    Ok(())
+}
+
+/// Fields are labels, 0 stands for children without field
+fn encode_roles(
+    entry: &EntryRef,
+    _: &dyn Fn(NodeIdentifier) -> Option<u64>,
+    out: &mut Vec<u8>,
+) -> Result<(), PersistError> {
+    let c: &compo::Roles<DefaultLabelIdentifier> = component(entry);
+    for x in c.0.iter() {
+        let x = x.map_or(0, |l| l.to_usize() as u32 + 1);
+        out.extend_from_slice(&x.to_le_bytes());
+    }
+    Ok(())
+}
+
+fn eq_roles(a: &EntryRef, b: &EntryRef) -> bool {
+    let a: &compo::Roles<DefaultLabelIdentifier> = component(a);
+    let b: &compo::Roles<DefaultLabelIdentifier> = component(b);
+    a == b
+}
+
+fn decode_roles(
+    bytes: &[u8],
+    _: &dyn Fn(u64) -> Option<NodeIdentifier>,
+    builder: &mut EntityBuilder,
+) -> Result<(), PersistError> {
+    let invalid = || PersistError::UnknownCodec("Roles<Label>".to_string());
+    if bytes.len() % 4 != 0 {
+        return Err(invalid());
+    }
+    let c = bytes
+        .chunks_exact(4)
+        .map(|x| match u32::from_le_bytes(x.try_into().unwrap()) {
+            0 => Ok(None),
+            x => label_id_from_usize(x as usize - 1)
+                .map(Some)
+                .ok_or_else(invalid),
+        })
+        .collect::<Result<Box<[_]>, _>>()?;
+    builder.add(compo::Roles(c));
+    Ok(())
+}
+
+fn encode_children<T: Component + NodeList>(
+    entry: &EntryRef,
+    offsets: &dyn Fn(NodeIdentifier) -> Option<u64>,
+    out: &mut Vec<u8>,
+) -> Result<(), PersistError> {
+    let c: &T = component(entry);
+    for x in c.nodes() {
+        let offset = offsets(*x).ok_or(PersistError::MissingNode(u64::MAX))?;
+        out.extend_from_slice(&offset.to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Looks up every child before failing, so that all the missing ones can be loaded at once.
+fn decode_children<T: Component + NodeList>(
+    bytes: &[u8],
+    entities: &dyn Fn(u64) -> Option<NodeIdentifier>,
+    builder: &mut EntityBuilder,
+) -> Result<(), PersistError> {
+    let offsets: Vec<u64> = bytes
+        .chunks_exact(8)
+        .map(|x| u64::from_le_bytes(x.try_into().unwrap()))
+        .collect();
+    let c: Vec<Option<NodeIdentifier>> = offsets.iter().map(|x| entities(*x)).collect();
+    if let Some(i) = c.iter().position(Option::is_none) {
+        return Err(PersistError::MissingNode(offsets[i]));
+    }
+    builder.add(T::from_nodes(c.into_iter().flatten().collect()));
+    Ok(())
+}
+
+/// Children are deduplicated before their parents, so comparing their identifiers is enough.
+fn eq_children<T: Component + NodeList>(a: &EntryRef, b: &EntryRef) -> bool {
+    component::<T>(a).nodes() == component::<T>(b).nodes()
+}
+
+/// An append-only file of nodes, see the module documentation for its layout.
+pub struct Segment {
+    file: File,
+    /// end of the last complete record
+    end: u64,
+    /// codec identifiers of the segment to the index of the codec in [`Codecs`]
+    declared: Vec<usize>,
+    declared_rev: HashMap<usize, u16>,
+    entities: HashMap<u64, NodeIdentifier>,
+    offsets: HashMap<NodeIdentifier, u64>,
+}
+
+impl Segment {
+    /// Opens or creates the segment at `path`,
+    /// only codec declarations are read, nodes are loaded on demand with [`Segment::load`].
+    pub fn open(path: &Path, codecs: &Codecs) -> Result<Self, PersistError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(path)?;
+        let len = file.metadata()?.len();
+        let mut declared = vec![];
+        let mut reader = BufReader::new(&file);
+        let mut end = 0;
+        loop {
+            match read_header(&mut reader) {
+                Ok(Some(Header::Decl(name))) => {
+                    end += 1 + 2 + name.len() as u64;
+                    let i = *codecs
+                        .by_name
+                        .get(name.as_str())
+                        .ok_or(PersistError::UnknownCodec(name))?;
+                    declared.push(i);
+                }
+                Ok(Some(Header::Node(body))) => {
+                    if end + 1 + 4 + body as u64 > len {
+                        break;
+                    }
+                    reader.seek_relative(body as i64)?;
+                    end += 1 + 4 + body as u64;
+                }
+                Ok(None) => break,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        drop(reader);
+        if end < len {
+            log::warn!(
+                "truncating the partially written segment {:?} from {} to {}",
+                path,
+                len,
+                end
+            );
+            file.set_len(end)?;
+        }
+        let declared_rev = declared
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (*x, i as u16))
+            .collect();
+        Ok(Self {
+            file,
+            end,
+            declared,
+            declared_rev,
+            entities: Default::default(),
+            offsets: Default::default(),
+        })
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Number of nodes of the segment loaded in the node store or persisted from it
+    pub fn loaded_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Loads the node at `offset` and its descendants that are not loaded yet, then returns its identifier.
+    ///
+    /// Only the records of these nodes are read.
+    /// Loaded nodes are deduplicated against the nodes already in `store`.
+    pub fn load(
+        &mut self,
+        store: &mut NodeStore,
+        codecs: &Codecs,
+        offset: u64,
+    ) -> Result<NodeIdentifier, PersistError> {
+        if let Some(id) = self.entities.get(&offset) {
+            return Ok(*id);
+        }
+        if offset >= self.end {
+            return Err(PersistError::MissingNode(offset));
+        }
+        let mut reader = BufReader::new(&self.file);
+        let mut body = vec![];
+        let mut stack = vec![offset];
+        while let Some(&offset) = stack.last() {
+            if self.entities.contains_key(&offset) {
+                stack.pop();
+                continue;
+            }
+            reader.seek(SeekFrom::Start(offset))?;
+            match read_header(&mut reader)? {
+                Some(Header::Node(len)) => {
+                    body.resize(len as usize, 0);
+                    reader.read_exact(&mut body)?;
+                }
+                _ => return Err(PersistError::Corrupted(offset)),
+            }
+            let missing = std::cell::RefCell::new(vec![]);
+            let entities = |x: u64| {
+                let r = self.entities.get(&x).copied();
+                if r.is_none() {
+                    missing.borrow_mut().push(x);
+                }
+                r
+            };
+            match self.decode(codecs, offset, &body, &entities) {
+                Ok(built) => {
+                    let id = insert_persisted(store, codecs, built.build());
+                    stack.pop();
+                    self.entities.insert(offset, id);
+                    self.offsets.entry(id).or_insert(offset);
+                }
+                Err(PersistError::MissingNode(_)) => {
+                    let missing = missing.into_inner();
+                    // children are always written before their parents
+                    if missing.is_empty() || missing.iter().any(|x| *x >= offset) {
+                        return Err(PersistError::Corrupted(offset));
+                    }
+                    stack.extend(missing);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(self.entities[&offset])
+    }
+
+    fn decode(
+        &self,
+        codecs: &Codecs,
+        offset: u64,
+        mut body: &[u8],
+        entities: &dyn Fn(u64) -> Option<NodeIdentifier>,
+    ) -> Result<EntityBuilder, PersistError> {
+        let mut builder = EntityBuilder::new();
+        let count = read_u16(&mut body).ok_or(PersistError::Corrupted(offset))?;
+        for _ in 0..count {
+            let codec = read_u16(&mut body).ok_or(PersistError::Corrupted(offset))?;
+            let len = read_u32(&mut body).ok_or(PersistError::Corrupted(offset))? as usize;
+            if body.len() < len {
+                return Err(PersistError::Corrupted(offset));
+            }
+            let (bytes, rest) = body.split_at(len);
+            body = rest;
+            let codec = self
+                .declared
+                .get(codec as usize)
+                .ok_or(PersistError::Corrupted(offset))?;
+            (codecs.codecs[*codec].decode)(bytes, entities, &mut builder)?;
+        }
+        Ok(builder)
+    }
+
+    /// Offset of `id` in the segment, if persisted or loaded
+    pub fn offset(&self, id: NodeIdentifier) -> Option<u64> {
+        self.offsets.get(&id).copied()
+    }
+
+    /// Appends the subtree at `root` to the segment, skipping nodes loaded from or already persisted in it,
+    /// then returns the offset of `root`.
+    pub fn persist(
+        &mut self,
+        store: &NodeStore,
+        codecs: &Codecs,
+        root: NodeIdentifier,
+    ) -> Result<u64, PersistError> {
+        let mut file = self.file.try_clone()?;
+        file.seek(SeekFrom::Start(self.end))?;
+        let mut writer = BufWriter::new(file);
+        let mut stack = vec![(root, false)];
+        let mut record = vec![];
+        while let Some((id, expanded)) = stack.pop() {
+            if self.offsets.contains_key(&id) {
+                continue;
+            }
+            let entry = store.internal.entry_ref(id).unwrap();
+            if !expanded {
+                stack.push((id, true));
+                if let Ok(cs) = entry.get_component::<CS<NodeIdentifier>>() {
+                    stack.extend(
+                        cs.0.iter()
+                            .rev()
+                            .filter(|x| !self.offsets.contains_key(x))
+                            .map(|x| (*x, false)),
+                    );
+                }
+                continue;
+            }
+            record.clear();
+            // components without codec are derived data of some processor, they are left out
+            let types: Vec<usize> = entry
+                .archetype()
+                .layout()
+                .component_types()
+                .iter()
+                .filter_map(|t| {
+                    let codec = codecs.by_type.get(&t.type_id()).copied();
+                    if codec.is_none() {
+                        log::warn!("not persisting component {:?}, it has no codec", t);
+                    }
+                    codec
+                })
+                .collect();
+            record.extend_from_slice(&(types.len() as u16).to_le_bytes());
+            for codec in types {
+                let codec_id = match self.declared_rev.get(&codec) {
+                    Some(x) => *x,
+                    None => {
+                        let name = codecs.codecs[codec].name.as_bytes();
+                        writer.write_all(&[DECL])?;
+                        writer.write_all(&(name.len() as u16).to_le_bytes())?;
+                        writer.write_all(name)?;
+                        self.end += 1 + 2 + name.len() as u64;
+                        let x = self.declared.len() as u16;
+                        self.declared.push(codec);
+                        self.declared_rev.insert(codec, x);
+                        x
+                    }
+                };
+                record.extend_from_slice(&codec_id.to_le_bytes());
+                let len_at = record.len();
+                record.extend_from_slice(&0u32.to_le_bytes());
+                let offsets = |x| self.offsets.get(&x).copied();
+                (codecs.codecs[codec].encode)(&entry, &offsets, &mut record)?;
+                let len = (record.len() - len_at - 4) as u32;
+                record[len_at..len_at + 4].copy_from_slice(&len.to_le_bytes());
+            }
+            writer.write_all(&[NODE])?;
+            writer.write_all(&(record.len() as u32).to_le_bytes())?;
+            writer.write_all(&record)?;
+            self.entities.insert(self.end, id);
+            self.offsets.insert(id, self.end);
+            self.end += 1 + 4 + record.len() as u64;
+        }
+        writer.flush()?;
+        writer.get_ref().sync_data()?;
+        Ok(self.offsets[&root])
+    }
+}
+
+/// Inserts a loaded node, unless an equal node is already in `store`
+fn insert_persisted(
+    store: &mut NodeStore,
+    codecs: &Codecs,
+    built: super::dyn_builder::BuiltEntity,
+) -> NodeIdentifier {
+    let NodeStore {
+        dedup,
+        internal,
+        hasher,
+        ..
+    } = store;
+    let hasher = &*hasher;
+    let id = internal.extend(built)[0];
+    let node: HashedNodeRef<'_, NodeIdentifier> = internal
+        .entry_ref(id)
+        .map(|x| HashedNodeRef::new(x))
+        .unwrap();
+    let hash = make_hash(hasher, &node);
+    let entry = {
+        let new = internal.entry_ref(id).unwrap();
+        dedup.raw_entry_mut().from_hash(hash, |x| {
+            same_components(codecs, &new, &internal.entry_ref(*x).unwrap())
+        })
+    };
+    match entry {
+        hashbrown::hash_map::RawEntryMut::Occupied(occupied) => {
+            let already = *occupied.key();
+            internal.remove(id);
+            already
+        }
+        hashbrown::hash_map::RawEntryMut::Vacant(vacant) => {
+            vacant.insert_with_hasher(hash, id, (), |id| {
+                let node: HashedNodeRef<'_, NodeIdentifier> = internal
+                    .entry_ref(*id)
+                    .map(|x| HashedNodeRef::new(x))
+                    .unwrap();
+                make_hash(hasher, &node)
+            });
+            id
+        }
+    }
+}
+
+/// Nodes are equal if they have the same components with the same content.
+///
+/// Components without codec cannot be persisted, so they make nodes different.
+fn same_components(codecs: &Codecs, a: &EntryRef, b: &EntryRef) -> bool {
+    let ta = a.archetype().layout().component_types();
+    let tb = b.archetype().layout().component_types();
+    if ta.len() != tb.len() || ta.iter().any(|t| !tb.contains(t)) {
+        return false;
+    }
+    ta.iter().all(|t| match codecs.by_type.get(&t.type_id()) {
+        Some(codec) => (codecs.codecs[*codec].eq)(a, b),
+        None => false,
+    })
+}
+
+/// Copies subtrees from a node store to another, through the codecs of their components.
+///
+/// Copies are deduplicated against the nodes of the destination, and labels are interned in its label store.
+/// Copied nodes are remembered, so shared subtrees are only copied once across calls.
+#[derive(Default)]
+pub struct Transfer {
+    nodes: HashMap<NodeIdentifier, NodeIdentifier>,
+    labels: HashMap<DefaultLabelIdentifier, DefaultLabelIdentifier>,
+}
+
+impl Transfer {
+    /// The copy of `id`, if it was copied
+    pub fn get(&self, id: NodeIdentifier) -> Option<NodeIdentifier> {
+        self.nodes.get(&id).copied()
+    }
+
+    /// Records `to` as the copy of `from`, eg. when `from` is itself a copy of `to`
+    pub fn insert(&mut self, from: NodeIdentifier, to: NodeIdentifier) {
+        self.nodes.insert(from, to);
+    }
+
+    /// Copies the subtree at `root`, skipping the nodes already copied, then returns the copy of `root`.
+    pub fn copy(
+        &mut self,
+        codecs: &Codecs,
+        from: (&NodeStore, &LabelStore),
+        to: (&mut NodeStore, &mut LabelStore),
+        root: NodeIdentifier,
+    ) -> Result<NodeIdentifier, PersistError> {
+        let (from, from_labels) = from;
+        let (to, to_labels) = to;
+        let mut stack = vec![(root, false)];
+        let mut bytes = vec![];
+        while let Some((id, expanded)) = stack.pop() {
+            if self.nodes.contains_key(&id) {
+                continue;
+            }
+            let entry = from.internal.entry_ref(id).unwrap();
+            if !expanded {
+                stack.push((id, true));
+                if let Ok(cs) = entry.get_component::<CS<NodeIdentifier>>() {
+                    stack.extend(
+                        cs.0.iter()
+                            .rev()
+                            .filter(|x| !self.nodes.contains_key(x))
+                            .map(|x| (*x, false)),
+                    );
+                }
+                continue;
+            }
+            let mut builder = EntityBuilder::new();
+            for t in entry.archetype().layout().component_types() {
+                let t = t.type_id();
+                // labels are identifiers in the label stores, they must be interned again
+                if t == TypeId::of::<DefaultLabelIdentifier>() {
+                    let l = *component::<DefaultLabelIdentifier>(&entry);
+                    builder.add(self.label(from_labels, to_labels, l));
+                    continue;
+                } else if t == TypeId::of::<CS<DefaultLabelIdentifier>>() {
+                    let ls = component::<CS<DefaultLabelIdentifier>>(&entry);
+                    let ls: Box<[_]> =
+                        ls.0.iter()
+                            .map(|l| self.label(from_labels, to_labels, *l))
+                            .collect();
+                    builder.add(CS(ls));
+                    continue;
+                } else if t == TypeId::of::<compo::Roles<DefaultLabelIdentifier>>() {
+                    let rs = component::<compo::Roles<DefaultLabelIdentifier>>(&entry);
+                    let rs: Box<[_]> =
+                        rs.0.iter()
+                            .map(|r| r.map(|l| self.label(from_labels, to_labels, l)))
+                            .collect();
+                    builder.add(compo::Roles(rs));
+                    continue;
+                }
+                let Some(codec) = codecs.by_type.get(&t) else {
+                    log::warn!("not copying component {:?}, it has no codec", t);
+                    continue;
+                };
+                let codec = &codecs.codecs[*codec];
+                // children are encoded as indexes in the copies of the children
+                let children = std::cell::RefCell::new(vec![]);
+                let offsets = |x: NodeIdentifier| {
+                    let x = self.nodes.get(&x)?;
+                    let mut children = children.borrow_mut();
+                    children.push(*x);
+                    Some(children.len() as u64 - 1)
+                };
+                bytes.clear();
+                (codec.encode)(&entry, &offsets, &mut bytes)?;
+                let children = children.into_inner();
+                let entities = |i: u64| children.get(i as usize).copied();
+                (codec.decode)(&bytes, &entities, &mut builder)?;
+            }
+            let copy = insert_persisted(to, codecs, builder.build());
+            self.nodes.insert(id, copy);
+        }
+        Ok(self.nodes[&root])
+    }
+
+    fn label(
+        &mut self,
+        from: &LabelStore,
+        to: &mut LabelStore,
+        l: DefaultLabelIdentifier,
+    ) -> DefaultLabelIdentifier {
+        *self
+            .labels
+            .entry(l)
+            .or_insert_with(|| to.get_or_insert(from.resolve(&l)))
+    }
+}
+
+enum Header {
+    Decl(String),
+    Node(u32),
+}
+
+fn read_header(reader: &mut impl Read) -> io::Result<Option<Header>> {
+    let mut tag = [0u8];
+    if reader.read(&mut tag)? == 0 {
+        return Ok(None);
+    }
+    match tag[0] {
+        DECL => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            let mut name = vec![0u8; u16::from_le_bytes(len) as usize];
+            reader.read_exact(&mut name)?;
+            let name = String::from_utf8(name)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            Ok(Some(Header::Decl(name)))
+        }
+        NODE => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            Ok(Some(Header::Node(u32::from_le_bytes(len))))
+        }
+        x => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown record tag {}", x),
+        )),
+    }
+}
+
+fn read_u16(bytes: &mut &[u8]) -> Option<u16> {
+    let (x, rest) = (bytes.get(..2)?, &bytes[2..]);
+    *bytes = rest;
+    Some(u16::from_le_bytes(x.try_into().unwrap()))
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Option<u32> {
+    let (x, rest) = (bytes.get(..4)?, &bytes[4..]);
+    *bytes = rest;
+    Some(u32::from_le_bytes(x.try_into().unwrap()))
+}
+
+/// An append-only file of labels, in the order of their identifiers.
+pub struct LabelsFile {
+    file: File,
+    /// number of labels in the file
+    count: usize,
+}
+
+impl LabelsFile {
+    /// Opens or creates the file at `path`, then loads its labels in `store`.
+    ///
+    /// `store` must not contain other labels than the persisted ones,
+    /// to keep the same identifiers.
+    pub fn open(path: &Path, store: &mut LabelStore) -> Result<Self, PersistError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(path)?;
+        let len = file.metadata()?.len();
+        let mut reader = BufReader::new(&file);
+        let mut count = 0;
+        let mut end = 0;
+        loop {
+            let mut l = [0u8; 4];
+            match reader.read_exact(&mut l) {
+                Ok(()) => (),
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+            let l = u32::from_le_bytes(l) as u64;
+            if end + 4 + l > len {
+                break;
+            }
+            let mut label = vec![0u8; l as usize];
+            reader.read_exact(&mut label)?;
+            let label = std::str::from_utf8(&label).map_err(|_| PersistError::Corrupted(end))?;
+            let id = store.get_or_insert(label);
+            if id.to_usize() != count {
+                return Err(PersistError::Corrupted(end));
+            }
+            count += 1;
+            end += 4 + l;
+        }
+        drop(reader);
+        if end < len {
+            log::warn!(
+                "truncating the partially written labels {:?} from {} to {}",
+                path,
+                len,
+                end
+            );
+            file.set_len(end)?;
+        }
+        Ok(Self { file, count })
+    }
+
+    /// Appends the labels of `store` that are not yet persisted
+    pub fn persist(&mut self, store: &LabelStore) -> Result<(), PersistError> {
+        let mut file = self.file.try_clone()?;
+        file.seek(SeekFrom::End(0))?;
+        let mut writer = BufWriter::new(file);
+        while self.count < store.len() {
+            let id = label_id_from_usize(self.count).unwrap();
+            let label = store.resolve(&id).as_bytes();
+            writer.write_all(&(label.len() as u32).to_le_bytes())?;
+            writer.write_all(label)?;
+            self.count += 1;
+        }
+        writer.flush()?;
+        writer.get_ref().sync_data()?;
+        Ok(())
+    }
+}
+
+#[test]
+fn persist_then_load() {
+    let dir = std::env::temp_dir().join(format!("hyperast_persist_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("nodes.seg");
+    let _ = std::fs::remove_file(&path);
+    let codecs = Codecs::new();
+
+    let mut label_store = LabelStore::new();
+    let label = label_store.get_or_insert("a");
+    let insert_leaf = |node_store: &mut NodeStore| {
+        let mut leaf = EntityBuilder::new();
+        leaf.add(SyntaxNodeHashs::<u32>::default());
+        leaf.add(label);
+        insert_persisted(node_store, &codecs, leaf.build())
+    };
+    let mut node_store = NodeStore::new();
+    let leaf = insert_leaf(&mut node_store);
+    // equal nodes are deduplicated
+    assert_eq!(leaf, insert_leaf(&mut node_store));
+    let mut root = EntityBuilder::new();
+    root.add(SyntaxNodeHashs::<u32> {
+        structt: 1,
+        label: 1,
+        syntax: 1,
+    });
+    root.add(compo::Size(3));
+    root.add(CS(vec![leaf, leaf].into_boxed_slice()));
+    let root = insert_persisted(&mut node_store, &codecs, root.build());
+
+    let mut segment = Segment::open(&path, &codecs).unwrap();
+    let offset = segment.persist(&node_store, &codecs, root).unwrap();
+    let end = segment.end();
+    let leaf_offset = segment.offset(leaf).unwrap();
+    // already persisted nodes are skipped
+    assert_eq!(offset, segment.persist(&node_store, &codecs, root).unwrap());
+    assert_eq!(end, segment.end());
+
+    // a node built before loading is shared with the loaded nodes
+    let mut node_store = NodeStore::new();
+    let built_leaf = insert_leaf(&mut node_store);
+    let mut segment = Segment::open(&path, &codecs).unwrap();
+    assert_eq!(end, segment.end());
+    assert_eq!(0, segment.loaded_count());
+    let root = segment.load(&mut node_store, &codecs, offset).unwrap();
+    assert_eq!(2, segment.loaded_count());
+    let root = node_store.resolve(root);
+    assert_eq!(3, root.get_component::<compo::Size>().unwrap().0);
+    let cs = root.get_component::<CS<NodeIdentifier>>().unwrap();
+    assert_eq!(2, cs.0.len());
+    assert_eq!(cs.0[0], cs.0[1]);
+    assert_eq!(built_leaf, cs.0[0]);
+    let leaf = node_store.resolve(cs.0[0]);
+    assert_eq!(Ok(&label), leaf.get_component::<DefaultLabelIdentifier>());
+
+    // only the requested subtree is loaded
+    let mut node_store = NodeStore::new();
+    let mut segment = Segment::open(&path, &codecs).unwrap();
+    segment.load(&mut node_store, &codecs, leaf_offset).unwrap();
+    assert_eq!(1, segment.loaded_count());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn changed_layouts_are_refused() {
+    #[repr(u16)]
+    #[derive(Clone, Copy, Debug)]
+    enum Ty {
+        A,
+        B,
+    }
+    let dir = std::env::temp_dir().join(format!("hyperast_layout_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("nodes.seg");
+    let _ = std::fs::remove_file(&path);
+    let mut codecs = Codecs::new();
+    unsafe { codecs.register_type_enum("Ty", Ty::B) };
+
+    let mut node_store = NodeStore::new();
+    let mut leaf = EntityBuilder::new();
+    leaf.add(SyntaxNodeHashs::<u32>::default());
+    leaf.add(Ty::B);
+    let leaf = insert_persisted(&mut node_store, &codecs, leaf.build());
+    let mut segment = Segment::open(&path, &codecs).unwrap();
+    segment.persist(&node_store, &codecs, leaf).unwrap();
+    assert!(Segment::open(&path, &codecs).is_ok());
+
+    // a variant was inserted before B
+    let mut codecs = Codecs::new();
+    unsafe { codecs.register_pod_with_layout::<Ty>("Ty", ["A", "C", "B"]) };
+    assert!(matches!(
+        Segment::open(&path, &codecs),
+        Err(PersistError::UnknownCodec(_))
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn transfer_between_stores() {
+    let codecs = Codecs::new();
+    let mut from_labels = LabelStore::new();
+    // shifts the identifiers of the labels of the source
+    from_labels.get_or_insert("b");
+    let label = from_labels.get_or_insert("a");
+    let mut from = NodeStore::new();
+    let mut leaf = EntityBuilder::new();
+    leaf.add(SyntaxNodeHashs::<u32>::default());
+    leaf.add(label);
+    let leaf = insert_persisted(&mut from, &codecs, leaf.build());
+    let mut root = EntityBuilder::new();
+    root.add(SyntaxNodeHashs::<u32> {
+        structt: 1,
+        label: 1,
+        syntax: 1,
+    });
+    root.add(CS(vec![leaf, leaf].into_boxed_slice()));
+    root.add(CS(vec![label].into_boxed_slice()));
+    root.add(compo::Roles(vec![None, Some(label)].into_boxed_slice()));
+    let root = insert_persisted(&mut from, &codecs, root.build());
+
+    let mut to_labels = LabelStore::new();
+    let mut to = NodeStore::new();
+    let mut transfer = Transfer::default();
+    let copy = transfer
+        .copy(
+            &codecs,
+            (&from, &from_labels),
+            (&mut to, &mut to_labels),
+            root,
+        )
+        .unwrap();
+    assert_eq!(Some(copy), transfer.get(root));
+    let copied_leaf = transfer.get(leaf).unwrap();
+    let node = to.resolve(copy);
+    let cs = node.get_component::<CS<NodeIdentifier>>().unwrap();
+    assert_eq!(&[copied_leaf, copied_leaf][..], &cs.0[..]);
+    let names = node.get_component::<CS<DefaultLabelIdentifier>>().unwrap();
+    assert_eq!("a", to_labels.resolve(&names.0[0]));
+    let roles = node
+        .get_component::<compo::Roles<DefaultLabelIdentifier>>()
+        .unwrap();
+    assert_eq!(None, roles.0[0]);
+    assert_eq!("a", to_labels.resolve(&roles.0[1].unwrap()));
+    let l = *to
+        .resolve(copied_leaf)
+        .get_component::<DefaultLabelIdentifier>()
+        .unwrap();
+    assert_eq!("a", to_labels.resolve(&l));
+
+    // copies are deduplicated against the nodes of the destination
+    let copy_again = Transfer::default()
+        .copy(
+            &codecs,
+            (&from, &from_labels),
+            (&mut to, &mut to_labels),
+            root,
+        )
+        .unwrap();
+    assert_eq!(copy, copy_again);
+}
@@ -318,6 +318,15 @@ where
         acc: <Self as TreeGen>::Acc,
     ) -> <<Self as TreeGen>::Acc as Accumulator>::Node;
 
+    /// Called after [`ZippedTreeGen::post`] with the field of the node in its parent,
+    /// eg. `name` in `(method_declaration name: (identifier))`,
+    /// the node being the next one pushed in `parent`.
+    ///
+    /// The default implementation does not keep fields.
+    fn role(&mut self, parent: &mut <Self as TreeGen>::Acc, role: &'static str) {
+        let _ = (parent, role);
+    }
+
     fn stores(&mut self) -> &mut Self::Stores;
 
     fn gen(
@@ -350,7 +359,13 @@ where
                     global.up();
                 }
                 let full_node: Option<_> = if let Some(parent) = stack.parent_mut() {
-                    acc.map(|acc| self.post(parent, global, text, acc))
+                    acc.map(|acc| {
+                        let full_node = self.post(parent, global, text, acc);
+                        if let Some(role) = cursor.role() {
+                            self.role(parent, role);
+                        }
+                        full_node
+                    })
                 } else {
                     stack.push(acc);
                     None
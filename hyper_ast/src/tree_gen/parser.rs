@@ -38,4 +38,8 @@ pub trait TreeCursor<'a, N: Node<'a>> {
     fn goto_first_child(&mut self) -> bool;
     fn goto_parent(&mut self) -> bool;
     fn goto_next_sibling(&mut self) -> bool;
+    /// The field of the current node in its parent, eg. `name` in `(method_declaration name: (identifier))`
+    fn role(&self) -> Option<&'static str> {
+        None
+    }
 }
@@ -2436,6 +2436,12 @@ pub trait Labeled {
     fn get_label_unchecked<'a>(&'a self) -> &'a Self::Label;
     fn try_get_label<'a>(&'a self) -> Option<&'a Self::Label>;
 }
+
+/// Nodes knowing the fields of their children, eg. `name` in `(method_declaration name: (identifier))`
+pub trait WithRoles: Labeled {
+    /// The field of the child at `idx`, spaces included, named with a label
+    fn role_at(&self, idx: usize) -> Option<&Self::Label>;
+}
 pub trait Tree: Labeled + WithChildren
 // where
 //     <Self::Children as std::ops::Deref>::Target: std::ops::Index<<Self as WithChildren>::ChildIdx, Output = <Self as Stored>::TreeId>
@@ -0,0 +1,219 @@
+//! Application of edit scripts on HyperASTs backed by legion, see [`apply_on`].
+//!
+//! Nodes changed by the actions are rebuilt bottom-up and deduplicated like in the generators,
+//! so unchanged subtrees are shared between the source and the resulting tree,
+//! and the result is the node of the destination when the destination is in the store.
+
+use std::{fmt::Debug, marker::PhantomData};
+
+use hyper_ast::{
+    filter::BloomSize,
+    hashed::{IndexingHashBuilder, MetaDataHashsBuilder, SyntaxNodeHashs, SyntaxNodeHashsKinds},
+    store::{
+        defaults::LabelIdentifier,
+        labels::LabelStore,
+        nodes::legion::{
+            compo::{self, NoSpacesCS, CS},
+            dyn_builder, EntryRef, HashedNodeRef, NodeIdentifier,
+        },
+    },
+    tree_gen::SubTreeMetrics,
+    types::{
+        self, HyperType, LabelStore as _, Labeled, NodeStoreExt, Typed, TypedNodeId, WithChildren,
+        WithHashs, WithSerialization, WithStats,
+    },
+};
+
+use crate::tree::tree_path::TreePath;
+
+use super::{
+    action_vec::{apply_actions, ActionsVec},
+    script_generator2::SimpleAction,
+};
+
+/// Applies `actions`, eg. computed by [`super::script_generator2::ScriptGenerator`],
+/// on the tree at `root` and returns the root of the resulting tree.
+///
+/// `TIdN` gives the language of the tree, eg. Java or C++, all its nodes must be of this language.
+///
+/// Nodes created here do not carry the metadata specific to a language (eg. references or Mcc),
+/// their bloom filters are thus considered as possibly containing anything.
+pub fn apply_on<TIdN, P>(
+    node_store: &mut hyper_ast::store::nodes::legion::NodeStore,
+    label_store: &LabelStore,
+    actions: ActionsVec<SimpleAction<LabelIdentifier, P, NodeIdentifier>>,
+    root: NodeIdentifier,
+) -> NodeIdentifier
+where
+    TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>,
+    TIdN::Ty: 'static + Debug,
+    P: TreePath<Item = u16> + Debug,
+{
+    let mut stores = LegionStoreMut::<TIdN>::new(node_store, label_store);
+    let mut roots = vec![root];
+    apply_actions::<HashedNodeRef<'static, TIdN>, _, _>(actions, &mut roots, &mut stores);
+    *roots.last().unwrap()
+}
+
+/// Checks that the trees at `a` and `b` have the same structure, ie. same types and same sizes,
+/// eg. to check that applying diff(A,B) on A gives B.
+pub fn same_structure(
+    node_store: &hyper_ast::store::nodes::legion::NodeStore,
+    a: NodeIdentifier,
+    b: NodeIdentifier,
+) -> bool {
+    let a = node_store.resolve(a).hash(&SyntaxNodeHashsKinds::Struct);
+    let b = node_store.resolve(b).hash(&SyntaxNodeHashsKinds::Struct);
+    a == b
+}
+
+/// Gives the typed view on nodes and the node building needed by [`apply_actions`].
+pub(crate) struct LegionStoreMut<'a, TIdN> {
+    node_store: &'a mut hyper_ast::store::nodes::legion::NodeStore,
+    label_store: &'a LabelStore,
+    phantom: PhantomData<TIdN>,
+}
+
+impl<'a, TIdN> LegionStoreMut<'a, TIdN> {
+    pub(crate) fn new(
+        node_store: &'a mut hyper_ast::store::nodes::legion::NodeStore,
+        label_store: &'a LabelStore,
+    ) -> Self {
+        Self {
+            node_store,
+            label_store,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>> types::NodeStore<NodeIdentifier>
+    for LegionStoreMut<'a, TIdN>
+{
+    type R<'b> = HashedNodeRef<'b, TIdN> where Self: 'b;
+
+    fn resolve(&self, id: &NodeIdentifier) -> Self::R<'_> {
+        self.node_store
+            .try_resolve_typed::<TIdN>(id)
+            .expect("node of another language")
+            .0
+    }
+}
+
+impl<'a, TIdN> NodeStoreExt<HashedNodeRef<'static, TIdN>> for LegionStoreMut<'a, TIdN>
+where
+    TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>,
+    TIdN::Ty: 'static,
+{
+    fn build_then_insert(
+        &mut self,
+        i: NodeIdentifier,
+        t: TIdN::Ty,
+        l: Option<LabelIdentifier>,
+        cs: Vec<NodeIdentifier>,
+    ) -> NodeIdentifier {
+        use types::NodeStore;
+        let bytes_len = {
+            let ori = self.resolve(&i);
+            let ori_cs = ori.children().map_or(&[][..], |x| &x.0);
+            if ori.get_type() == t && ori.try_get_label() == l.as_ref() && ori_cs == &cs[..] {
+                // unchanged, eg. leaves given to an insertion
+                return i;
+            }
+            ori.try_bytes_len().unwrap_or(0) as u32
+        };
+        let label_store = self.label_store;
+        let label = l.map(|l| label_store.resolve(&l));
+
+        let mut acc: SubTreeMetrics<SyntaxNodeHashs<u32>> = Default::default();
+        let mut no_spaces = Vec::with_capacity(cs.len());
+        let mut children_len = 0;
+        for c in &cs {
+            let n = self.resolve(c);
+            let hashs = *n.get_component::<SyntaxNodeHashs<u32>>().unwrap();
+            // leaves do not store their size without spaces
+            let size_no_spaces = if n.get_type().is_spaces() {
+                0
+            } else {
+                n.size_no_spaces() as u32
+            };
+            if size_no_spaces > 0 {
+                no_spaces.push(*c);
+            }
+            children_len += n.try_bytes_len().unwrap_or(0) as u32;
+            acc.acc(SubTreeMetrics {
+                hashs,
+                size: n.size() as u32,
+                height: n.height() as u32,
+                size_no_spaces,
+            });
+        }
+        let size = acc.size + 1;
+        let height = acc.height + 1;
+
+        // computed as in the generators, to find the nodes they already inserted
+        let (hbuilder, hashs) = if t.is_spaces() {
+            let hbuilder = hyper_ast::hashed::Builder::new(
+                Default::default(),
+                &t,
+                label.unwrap_or_default(),
+                1,
+            );
+            let mut hashs: SyntaxNodeHashs<u32> = hbuilder.build();
+            hashs.structt = 0;
+            hashs.label = 0;
+            (hbuilder, hashs)
+        } else {
+            let hbuilder =
+                hyper_ast::hashed::Builder::new(acc.hashs, &t, &label, acc.size_no_spaces + 1);
+            let hashs = hbuilder.build();
+            (hbuilder, hashs)
+        };
+        let hsyntax = hbuilder.most_discriminating();
+        let eq = |x: EntryRef| {
+            if x.get_component::<TIdN::Ty>() != Ok(&t) {
+                return false;
+            }
+            if x.get_component::<LabelIdentifier>().ok() != l.as_ref() {
+                return false;
+            }
+            match x.get_component::<CS<NodeIdentifier>>() {
+                Ok(CS(x)) => x.as_ref() == &cs[..],
+                Err(_) => cs.is_empty(),
+            }
+        };
+        let bytes_len = match (cs.is_empty(), label) {
+            (false, _) => children_len,
+            (true, Some(label)) => label.len() as u32,
+            (true, None) => bytes_len,
+        };
+
+        let insertion = self.node_store.prepare_insertion(&hsyntax, eq);
+        if let Some(id) = insertion.occupied_id() {
+            return id;
+        }
+        let mut dyn_builder = dyn_builder::EntityBuilder::new();
+        dyn_builder.add(t);
+        dyn_builder.add(hashs);
+        dyn_builder.add(compo::BytesLen(bytes_len));
+        if let Some(l) = l {
+            dyn_builder.add(l);
+        }
+        if cs.is_empty() {
+            dyn_builder.add(BloomSize::None);
+        } else {
+            dyn_builder.add(BloomSize::Much);
+            dyn_builder.add(compo::Size(size));
+            dyn_builder.add(compo::SizeNoSpaces(acc.size_no_spaces + 1));
+            dyn_builder.add(compo::Height(height));
+            if no_spaces.len() != cs.len() {
+                dyn_builder.add(NoSpacesCS(no_spaces.into_boxed_slice()));
+            }
+            dyn_builder.add(CS(cs.into_boxed_slice()));
+        }
+        hyper_ast::store::nodes::legion::NodeStore::insert_built_after_prepare(
+            insertion.vacant(),
+            dyn_builder.build(),
+        )
+    }
+}
@@ -0,0 +1,270 @@
+//! Inversion and composition of edit scripts, without running matchers again.
+//!
+//! The mappings used to generate a script are recovered by replaying its actions on the source,
+//! the script inverting or composing others is then generated from the reversed or composed mappings,
+//! thus it shares the representation of the scripts given by [`ScriptGenerator`].
+
+use std::fmt::Debug;
+
+use num_traits::ToPrimitive;
+
+use hyper_ast::{
+    types::{self, HyperAST, NodeStore, WithChildren},
+    PrimInt,
+};
+
+use crate::{
+    decompressed_tree_store::{
+        bfs_wrapper::SimpleBfsMapper, CompletePostOrder, ShallowDecompressedTreeStore,
+    },
+    matchers::mapping_store::{MappingStore, MonoMappingStore, VecStore},
+    tree::tree_path::{CompressedTreePath, TreePath},
+};
+
+use super::{
+    action_vec::ActionsVec,
+    script_generator2::{Act, ScriptGenerator, SimpleAction},
+};
+
+type CDS<T> = CompletePostOrder<T, u32>;
+
+/// Computes the script going from `dst` to `src` given the script `actions` going from `src` to `dst`.
+pub fn invert<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+    actions: &ActionsVec<SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>>,
+) -> Result<ActionsVec<SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>>, String>
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Idx: PrimInt,
+    HAST::Label: Debug + Clone + Copy,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    let node_store = hyperast.node_store();
+    let src_arena = hyperast.decompress::<CDS<HAST::T>>(src).1;
+    let dst_arena = hyperast.decompress::<CDS<HAST::T>>(dst).1;
+    let mappings: VecStore<u32> = mappings_of(node_store, &src_arena, &dst_arena, actions)?;
+    let mappings = reversed(&mappings, dst_arena.len(), src_arena.len());
+    let src_arena = SimpleBfsMapper::from(node_store, src_arena);
+    ScriptGenerator::_compute_actions(node_store, &dst_arena, &src_arena, &mappings)
+}
+
+/// Computes the script going from `a` to `c`
+/// given the script `ab` going from `a` to `b` and the script `bc` going from `b` to `c`.
+///
+/// Nodes deleted then inserted back are not mapped, thus they stay in the composed script.
+pub fn compose<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    a: &HAST::IdN,
+    b: &HAST::IdN,
+    c: &HAST::IdN,
+    ab: &ActionsVec<SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>>,
+    bc: &ActionsVec<SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>>,
+) -> Result<ActionsVec<SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>>, String>
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Idx: PrimInt,
+    HAST::Label: Debug + Clone + Copy,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    let node_store = hyperast.node_store();
+    let a_arena = hyperast.decompress::<CDS<HAST::T>>(a).1;
+    let b_arena = hyperast.decompress::<CDS<HAST::T>>(b).1;
+    let c_arena = hyperast.decompress::<CDS<HAST::T>>(c).1;
+    let ab: VecStore<u32> = mappings_of(node_store, &a_arena, &b_arena, ab)?;
+    let bc: VecStore<u32> = mappings_of(node_store, &b_arena, &c_arena, bc)?;
+    let mappings = composed(&ab, &bc, a_arena.len(), c_arena.len());
+    let c_arena = SimpleBfsMapper::from(node_store, c_arena);
+    ScriptGenerator::_compute_actions(node_store, &a_arena, &c_arena, &mappings)
+}
+
+/// Recovers the mappings between `src_arena` and `dst_arena` used to generate `actions`,
+/// by replaying the actions on the shape of the source.
+pub fn mappings_of<'store, 'a, T, S, D1, D2, IdD, P, M>(
+    store: &'store S,
+    src_arena: &D1,
+    dst_arena: &D2,
+    actions: &ActionsVec<SimpleAction<T::Label, P, T::TreeId>>,
+) -> Result<M, String>
+where
+    T: 'store + WithChildren + types::Labeled,
+    S: NodeStore<T::TreeId, R<'store> = T>,
+    D1: ShallowDecompressedTreeStore<'a, T, IdD>,
+    D2: ShallowDecompressedTreeStore<'a, T, IdD>,
+    IdD: PrimInt,
+    P: TreePath<Item = T::ChildIdx> + Debug,
+    M: MonoMappingStore<Src = IdD, Dst = IdD> + Default,
+{
+    let mut mid = MidTree::new(store, src_arena);
+    for a in actions.iter() {
+        mid.apply(&a.path.mid, &a.action)?;
+    }
+    let mut mappings = M::default();
+    mappings.topit(src_arena.len(), dst_arena.len());
+    let root = *mid.roots.last().unwrap();
+    let mut stack = vec![(root, dst_arena.root())];
+    while let Some((x, y)) = stack.pop() {
+        let (ori, cs) = &mid.nodes[x];
+        if let Some(ori) = ori {
+            mappings.link(*ori, y);
+        }
+        let dst_cs = dst_arena.children(store, &y);
+        if cs.len() != dst_cs.len() {
+            return Err(format!(
+                "replayed actions do not lead to the destination, {} children instead of {}",
+                cs.len(),
+                dst_cs.len()
+            ));
+        }
+        stack.extend(cs.iter().copied().zip(dst_cs));
+    }
+    Ok(mappings)
+}
+
+/// The mappings from `dst` to `src`
+pub fn reversed<M>(mappings: &M, dst_len: usize, src_len: usize) -> M
+where
+    M: MonoMappingStore + Default,
+    M: MappingStore<Src = <M as MappingStore>::Dst>,
+{
+    let mut r = M::default();
+    r.topit(dst_len, src_len);
+    for (src, dst) in mappings.iter() {
+        r.link(dst, src);
+    }
+    r
+}
+
+/// The mappings from `a` to `c`, going through `b`
+pub fn composed<M>(ab: &M, bc: &M, a_len: usize, c_len: usize) -> M
+where
+    M: MonoMappingStore + Default,
+    M: MappingStore<Src = <M as MappingStore>::Dst>,
+{
+    let mut r = M::default();
+    r.topit(a_len, c_len);
+    for (a, b) in ab.iter() {
+        if let Some(c) = bc.get_dst(&b) {
+            r.link(a, c);
+        }
+    }
+    r
+}
+
+/// The shape of the tree while actions are replayed,
+/// nodes are the ones of the source, or None when they were inserted.
+struct MidTree<IdD> {
+    nodes: Vec<(Option<IdD>, Vec<usize>)>,
+    roots: Vec<usize>,
+}
+
+impl<IdD: PrimInt> MidTree<IdD> {
+    fn new<'store, 'a, T, S, D>(store: &'store S, arena: &D) -> Self
+    where
+        T: 'store + WithChildren,
+        S: NodeStore<T::TreeId, R<'store> = T>,
+        D: ShallowDecompressedTreeStore<'a, T, IdD>,
+    {
+        let mut r = Self {
+            nodes: vec![(Some(arena.root()), vec![])],
+            roots: vec![0],
+        };
+        let mut stack = vec![(0, arena.root())];
+        while let Some((i, x)) = stack.pop() {
+            for c in arena.children(store, &x) {
+                let j = r.nodes.len();
+                r.nodes.push((Some(c), vec![]));
+                r.nodes[i].1.push(j);
+                stack.push((j, c));
+            }
+        }
+        r
+    }
+
+    /// Follows what [`super::action_vec::apply_action`] does to the tree
+    fn apply<L, P, I>(&mut self, path: &P, action: &Act<L, P, I>) -> Result<(), String>
+    where
+        P: TreePath + Debug,
+        P::Item: ToPrimitive,
+    {
+        match action {
+            Act::Delete {} => {
+                let (p, i) = self.slot(path)?;
+                let p = p.ok_or_else(|| format!("cannot delete a root at {:?}", path))?;
+                self.remove(p, i, path)?;
+            }
+            Act::Update { .. } => (),
+            Act::Insert { .. } => {
+                let x = self.nodes.len();
+                self.nodes.push((None, vec![]));
+                self.put(path, x)?;
+            }
+            Act::Move { from } | Act::MovUpd { from, .. } => {
+                let x = match self.slot(&from.mid)? {
+                    // roots are left in place
+                    (None, i) => *self
+                        .roots
+                        .get(i)
+                        .ok_or_else(|| format!("no root at {:?}", from.mid))?,
+                    (Some(p), i) => self.remove(p, i, &from.mid)?,
+                };
+                self.put(path, x)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The parent, or None for roots, and the offset designated by `path`
+    fn slot<P>(&self, path: &P) -> Result<(Option<usize>, usize), String>
+    where
+        P: TreePath + Debug,
+        P::Item: ToPrimitive,
+    {
+        let mut it = path.iter();
+        let mut i = it
+            .next()
+            .ok_or_else(|| "empty path".to_string())?
+            .to_usize()
+            .unwrap();
+        let mut p = None;
+        for x in it {
+            let cs = match p {
+                None => &self.roots,
+                Some(p) => &self.nodes[p].1,
+            };
+            let c = cs.get(i).ok_or_else(|| format!("wrong path {:?}", path))?;
+            p = Some(*c);
+            i = x.to_usize().unwrap();
+        }
+        Ok((p, i))
+    }
+
+    fn remove<P: Debug>(&mut self, p: usize, i: usize, path: &P) -> Result<usize, String> {
+        let cs = &mut self.nodes[p].1;
+        if i < cs.len() {
+            Ok(cs.remove(i))
+        } else {
+            Err(format!("nothing to remove at {:?}", path))
+        }
+    }
+
+    fn put<P>(&mut self, path: &P, x: usize) -> Result<(), String>
+    where
+        P: TreePath + Debug,
+        P::Item: ToPrimitive,
+    {
+        match self.slot(path)? {
+            (None, i) if i < self.roots.len() => self.roots[i] = x,
+            (None, _) => self.roots.push(x),
+            (Some(p), i) => {
+                let cs = &mut self.nodes[p].1;
+                if i > cs.len() {
+                    return Err(format!("cannot insert at {:?}", path));
+                }
+                cs.insert(i, x)
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 pub mod action_tree;
 pub mod action_vec;
+pub mod apply;
+pub mod compose;
 pub mod script_generator;
 pub mod script_generator2;
 
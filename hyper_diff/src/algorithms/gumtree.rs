@@ -34,6 +34,35 @@ pub fn diff<'store, HAST: HyperAST<'store>>(
     Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
     PreparedMappingDurations<2>,
 >
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Idx: hyper_ast::PrimInt,
+    HAST::Label: Debug + Clone + Copy,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    diff_with_hyperparameters(hyperast, src, dst, 1, 1000, 0.5)
+}
+
+/// Same as [`diff`] with the hyperparameters of the matchers:
+/// the minimal height of subtrees matched in the top-down phase,
+/// the maximal size of subtrees matched optimally in the bottom-up phase
+/// and the similarity threshold of the bottom-up phase.
+pub fn diff_with_hyperparameters<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+    min_height: usize,
+    size_threshold: usize,
+    sim_threshold: f64,
+) -> DiffResult<
+    SimpleAction<
+        HAST::Label,
+        CompressedTreePath<<HAST::T as types::WithChildren>::ChildIdx>,
+        HAST::IdN,
+    >,
+    Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+    PreparedMappingDurations<2>,
+>
 where
     HAST::IdN: Clone + Debug + Eq,
     HAST::Idx: hyper_ast::PrimInt,
@@ -45,17 +74,23 @@ where
         hyperast.decompress_pair(src, dst).into();
     let subtree_prepare_t = now.elapsed().as_secs_f64();
     let now = Instant::now();
-    let mapper =
-        GreedySubtreeMatcher::<_, _, _, _, _>::match_it::<DefaultMultiMappingStore<_>>(mapper);
+    let mapper = GreedySubtreeMatcher::<_, _, _, _, _>::match_it_with::<DefaultMultiMappingStore<_>>(
+        mapper, min_height,
+    );
     let subtree_matcher_t = now.elapsed().as_secs_f64();
     let subtree_mappings_s = mapper.mappings().len();
-    dbg!(&subtree_matcher_t, &subtree_mappings_s);
+    log::debug!("subtree_matcher_t={subtree_matcher_t} subtree_mappings_s={subtree_mappings_s}");
     let now = Instant::now();
-    let mapper = GreedyBottomUpMatcher::<_, _, _, _, _>::match_it(mapper);
-    dbg!(&now.elapsed().as_secs_f64());
+    let mapper = GreedyBottomUpMatcher::<_, _, _, _, _>::match_it_with(
+        mapper,
+        size_threshold,
+        sim_threshold,
+    );
     let bottomup_matcher_t = now.elapsed().as_secs_f64();
     let bottomup_mappings_s = mapper.mappings().len();
-    dbg!(&bottomup_matcher_t, &bottomup_mappings_s);
+    log::debug!(
+        "bottomup_matcher_t={bottomup_matcher_t} bottomup_mappings_s={bottomup_mappings_s}"
+    );
     let now = Instant::now();
 
     let node_store = hyperast.node_store();
@@ -68,7 +103,7 @@ where
     let now = Instant::now();
     let actions = ScriptGenerator::compute_actions(mapper.hyperast, &mapper.mapping).ok();
     let gen_t = now.elapsed().as_secs_f64();
-    dbg!(gen_t);
+    log::debug!("gen_t={gen_t}");
     let mapper = mapper.map(|x| x, |dst_arena| dst_arena.back);
     DiffResult {
         mapping_durations: PreparedMappingDurations {
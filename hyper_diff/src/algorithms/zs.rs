@@ -0,0 +1,87 @@
+use std::{fmt::Debug, time::Instant};
+
+use crate::{
+    actions::script_generator2::{ScriptGenerator, SimpleAction},
+    decompressed_tree_store::{bfs_wrapper::SimpleBfsMapper, CompletePostOrder},
+    matchers::{
+        mapping_store::{MappingStore, VecStore},
+        optimal::zs::ZsMatcher,
+        Mapper, Mapping,
+    },
+    tree::tree_path::CompressedTreePath,
+};
+use hyper_ast::types::{self, HyperAST};
+
+type CDS<T> = CompletePostOrder<T, u32>;
+
+use super::MappingDurations;
+
+use super::{DiffResult, PreparedMappingDurations};
+
+/// Diff using the optimal matching of Zhang and Shasha,
+/// its time and memory are quadratic in the size of the trees so it is only practical on small ones.
+pub fn diff<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    src: &HAST::IdN,
+    dst: &HAST::IdN,
+) -> DiffResult<
+    SimpleAction<
+        HAST::Label,
+        CompressedTreePath<<HAST::T as types::WithChildren>::ChildIdx>,
+        HAST::IdN,
+    >,
+    Mapper<'store, HAST, CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+    PreparedMappingDurations<1>,
+>
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Idx: hyper_ast::PrimInt,
+    HAST::Label: Debug + Clone + Copy,
+    HAST::T: 'store,
+{
+    let now = Instant::now();
+    let ZsMatcher {
+        src_arena,
+        dst_arena,
+        mappings,
+    } = ZsMatcher::<VecStore<u32>, CDS<HAST::T>>::matchh::<HAST::T, HAST>(
+        hyperast,
+        src.clone(),
+        dst.clone(),
+    );
+    let matcher_t = now.elapsed().as_secs_f64();
+    let mapper = Mapper {
+        hyperast,
+        mapping: Mapping {
+            src_arena,
+            dst_arena,
+            mappings,
+        },
+    };
+    let mappings_s = mapper.mappings().len();
+    log::debug!("matcher_t={matcher_t} mappings_s={mappings_s}");
+    let now = Instant::now();
+
+    let node_store = hyperast.node_store();
+
+    let mapper = mapper.map(
+        |x| x,
+        |dst_arena| SimpleBfsMapper::from(node_store, dst_arena),
+    );
+    let prepare_gen_t = now.elapsed().as_secs_f64();
+    let now = Instant::now();
+    let actions = ScriptGenerator::compute_actions(mapper.hyperast, &mapper.mapping).ok();
+    let gen_t = now.elapsed().as_secs_f64();
+    log::debug!("gen_t={gen_t}");
+    let mapper = mapper.map(|x| x, |dst_arena| dst_arena.back);
+    DiffResult {
+        mapping_durations: PreparedMappingDurations {
+            mappings: MappingDurations([matcher_t]),
+            preparation: [0.0],
+        },
+        mapper,
+        actions,
+        prepare_gen_t,
+        gen_t,
+    }
+}
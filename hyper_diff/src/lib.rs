@@ -4,6 +4,10 @@ pub mod decompressed_tree_store;
 #[cfg(feature = "experimental")]
 pub mod mapping;
 pub mod matchers;
+pub mod merge;
+pub mod output;
+#[cfg(any(feature = "java", test))]
+pub mod semantic;
 pub mod tree;
 pub mod utils;
 // TODO rename to helpers
@@ -133,6 +133,21 @@ where
     pub fn match_it(
         mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
     ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    {
+        Self::match_it_with(
+            mapping,
+            SIZE_THRESHOLD,
+            SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64,
+        )
+    }
+
+    /// Same as [`Self::match_it`] but with the thresholds given at runtime,
+    /// instead of SIZE_THRESHOLD and SIM_THRESHOLD_NUM / SIM_THRESHOLD_DEN
+    pub fn match_it_with(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        size_threshold: usize,
+        sim_threshold: f64,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
     {
         let mut matcher = Self {
             internal: BottomUpMatcher {
@@ -147,7 +162,7 @@ where
             matcher.internal.src_arena.len(),
             matcher.internal.dst_arena.len(),
         );
-        Self::execute(&mut matcher);
+        matcher.execute_with(size_threshold, sim_threshold);
         crate::matchers::Mapper {
             hyperast: mapping.hyperast,
             mapping: crate::matchers::Mapping {
@@ -179,6 +194,13 @@ where
     }
 
     pub fn execute<'b>(&mut self) {
+        self.execute_with(
+            SIZE_THRESHOLD,
+            SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64,
+        )
+    }
+
+    pub fn execute_with(&mut self, size_threshold: usize, sim_threshold: f64) {
         assert_eq!(
             // TODO move it inside the arena ...
             self.internal.src_arena.root(),
@@ -204,14 +226,14 @@ where
                         &self.internal.mappings,
                     )
                     .dice();
-                    if sim > max && sim >= SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64 {
+                    if sim > max && sim >= sim_threshold {
                         max = sim;
                         best = Some(cand);
                     }
                 }
 
                 if let Some(best) = best {
-                    self.last_chance_match_zs(a, best);
+                    self.last_chance_match_zs(a, best, size_threshold);
                     self.internal.mappings.link(a, best);
                 }
             }
@@ -224,6 +246,7 @@ where
         self.last_chance_match_zs(
             self.internal.src_arena.root(),
             self.internal.dst_arena.root(),
+            size_threshold,
         );
         // println!("nodes:{}", c);
         // println!("nodes:{}", c2);
@@ -246,7 +269,7 @@ where
         r
     }
 
-    pub(crate) fn last_chance_match_zs(&mut self, src: M::Src, dst: M::Dst) {
+    pub(crate) fn last_chance_match_zs(&mut self, src: M::Src, dst: M::Dst, size_threshold: usize) {
         // WIP https://blog.rust-lang.org/2022/10/28/gats-stabilization.html#implied-static-requirement-from-higher-ranked-trait-bounds
         let src_s = self
             .internal
@@ -256,7 +279,7 @@ where
             .internal
             .dst_arena
             .descendants_count(self.internal.stores.node_store(), &dst);
-        if !(src_s < cast(SIZE_THRESHOLD).unwrap() || dst_s < cast(SIZE_THRESHOLD).unwrap()) {
+        if !(src_s < cast(size_threshold).unwrap() || dst_s < cast(size_threshold).unwrap()) {
             return;
         }
         let node_store = self.internal.stores.node_store();
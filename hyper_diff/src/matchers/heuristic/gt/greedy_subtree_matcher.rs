@@ -47,6 +47,19 @@ where
     pub fn match_it<MM>(
         mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
     ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        Self: 'a,
+        MM: MultiMappingStore<Src = M::Src, Dst = M::Dst> + Default,
+    {
+        Self::match_it_with::<MM>(mapping, MIN_HEIGHT)
+    }
+
+    /// Same as [`Self::match_it`] but with the minimal height given at runtime,
+    /// instead of MIN_HEIGHT
+    pub fn match_it_with<MM>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        min_height: usize,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
     where
         Self: 'a,
         MM: MultiMappingStore<Src = M::Src, Dst = M::Dst> + Default,
@@ -64,7 +77,7 @@ where
             matcher.internal.src_arena.len(),
             matcher.internal.dst_arena.len(),
         );
-        Self::execute::<MM>(&mut matcher);
+        Self::execute::<MM>(&mut matcher, min_height);
         crate::matchers::Mapper {
             hyperast: mapping.hyperast,
             mapping: crate::matchers::Mapping {
@@ -97,14 +110,17 @@ where
             matcher.internal.src_arena.len(),
             matcher.internal.dst_arena.len(),
         );
-        Self::execute::<MM>(&mut matcher);
+        Self::execute::<MM>(&mut matcher, MIN_HEIGHT);
         matcher
     }
 
-    pub(crate) fn execute<MM: MultiMappingStore<Src = M::Src, Dst = M::Dst> + Default>(&mut self) {
+    pub(crate) fn execute<MM: MultiMappingStore<Src = M::Src, Dst = M::Dst> + Default>(
+        &mut self,
+        min_height: usize,
+    ) {
         let mut mm: MM = Default::default();
         mm.topit(self.internal.src_arena.len(), self.internal.dst_arena.len());
-        self.internal.matchh_to_be_filtered(&mut mm);
+        self.internal.matchh_to_be_filtered(&mut mm, min_height);
         self.filter_mappings(&mm);
     }
 
@@ -174,7 +190,7 @@ where
         let mut sib_sim = HashMap::<(M::Src, M::Dst), f64>::default();
         let mut psib_sim = HashMap::<(M::Src, M::Dst), f64>::default();
         let mut p_in_p_sim = HashMap::<(M::Src, M::Dst), f64>::default();
-        dbg!(&ambiguous_mappings.len());
+        log::debug!("ambiguous_mappings={}", ambiguous_mappings.len());
         ambiguous_mappings.sort_by(|a, b| {
             let cached_coef_sib = |l: &(M::Src, M::Dst)| {
                 sib_sim
@@ -481,8 +497,8 @@ where
 
     fn pop_larger<'b>(
         &self,
-        src_trees: &mut PriorityTreeList<'a, 'b, Dsrc, M::Src, T, HAST::NS, MIN_HEIGHT>,
-        dst_trees: &mut PriorityTreeList<'a, 'b, Ddst, M::Dst, T, HAST::NS, MIN_HEIGHT>,
+        src_trees: &mut PriorityTreeList<'a, 'b, Dsrc, M::Src, T, HAST::NS>,
+        dst_trees: &mut PriorityTreeList<'a, 'b, Ddst, M::Dst, T, HAST::NS>,
     ) {
         if src_trees.peek_height() > dst_trees.peek_height() {
             src_trees.open();
@@ -494,11 +510,20 @@ where
     fn matchh_to_be_filtered<MM: MultiMappingStore<Src = M::Src, Dst = M::Dst>>(
         &self,
         multi_mappings: &mut MM,
+        min_height: usize,
     ) {
-        let mut src_trees =
-            PriorityTreeList::new(self.stores.node_store(), &self.src_arena, self.src_arena.root());
-        let mut dst_trees =
-            PriorityTreeList::new(self.stores.node_store(), &self.dst_arena, self.dst_arena.root());
+        let mut src_trees = PriorityTreeList::new(
+            self.stores.node_store(),
+            &self.src_arena,
+            self.src_arena.root(),
+            min_height,
+        );
+        let mut dst_trees = PriorityTreeList::new(
+            self.stores.node_store(),
+            &self.dst_arena,
+            self.dst_arena.root(),
+            min_height,
+        );
         // let mut aaa = 0;
         while src_trees.peek_height() != -1 && dst_trees.peek_height() != -1 {
             // aaa += 1;
@@ -684,13 +709,14 @@ where
     }
 }
 
-struct PriorityTreeList<'a, 'b, D, IdD, T: Tree, S, const MIN_HEIGHT: usize> {
+struct PriorityTreeList<'a, 'b, D, IdD, T: Tree, S> {
     trees: Vec<Option<Vec<IdD>>>,
 
     store: &'a S,
     arena: &'b D,
 
     max_height: usize,
+    min_height: usize,
 
     current_idx: isize,
 
@@ -704,15 +730,14 @@ impl<
         IdD: PrimInt,
         T: Tree,
         S: 'a + NodeStore<T::TreeId, R<'a> = T>,
-        const MIN_HEIGHT: usize,
-    > PriorityTreeList<'a, 'b, D, IdD, T, S, MIN_HEIGHT>
+    > PriorityTreeList<'a, 'b, D, IdD, T, S>
 where
     T::TreeId: Clone + NodeId<IdN = T::TreeId>,
 {
-    pub(super) fn new(store: &'a S, arena: &'b D, tree: IdD) -> Self {
+    pub(super) fn new(store: &'a S, arena: &'b D, tree: IdD, min_height: usize) -> Self {
         let h = height(store, &arena.original(&tree)); // TODO subtree opti, use metadata
-        let list_size = if h >= MIN_HEIGHT {
-            h + 1 - MIN_HEIGHT
+        let list_size = if h >= min_height {
+            h + 1 - min_height
         } else {
             0
         };
@@ -721,6 +746,7 @@ where
             store,
             arena,
             max_height: h,
+            min_height,
             current_idx: if list_size == 0 { -1 } else { 0 },
             phantom: PhantomData,
         };
@@ -742,7 +768,7 @@ where
     }
 
     fn add_tree_aux(&mut self, tree: IdD, h: usize) {
-        if h >= MIN_HEIGHT {
+        if h >= self.min_height {
             let idx = self.idx(h);
             if self.trees[idx].is_none() {
                 self.trees[idx] = Some(vec![]);
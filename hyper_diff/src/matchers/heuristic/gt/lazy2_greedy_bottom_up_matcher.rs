@@ -170,6 +170,20 @@ where
 {
     pub fn match_it(
         mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M> {
+        Self::match_it_with(
+            mapping,
+            SIZE_THRESHOLD,
+            SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64,
+        )
+    }
+
+    /// Same as [`Self::match_it`] but with the thresholds given at runtime,
+    /// instead of SIZE_THRESHOLD and SIM_THRESHOLD_NUM / SIM_THRESHOLD_DEN
+    pub fn match_it_with(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        size_threshold: usize,
+        sim_threshold: f64,
     ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M> {
         let mut matcher = Self {
             label_store: mapping.hyperast.label_store(),
@@ -180,11 +194,30 @@ where
             matcher.internal.mapping.src_arena.len(),
             matcher.internal.mapping.dst_arena.len(),
         );
-        Self::execute(&mut matcher.internal, &matcher.label_store);
+        Self::execute_with(
+            &mut matcher.internal,
+            &matcher.label_store,
+            size_threshold,
+            sim_threshold,
+        );
         matcher.internal
     }
 
     pub fn execute<'b>(internal: &mut Mapper<'a, HAST, Dsrc, Ddst, M>, label_store: &'a HAST::LS) {
+        Self::execute_with(
+            internal,
+            label_store,
+            SIZE_THRESHOLD,
+            SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64,
+        )
+    }
+
+    pub fn execute_with(
+        internal: &mut Mapper<'a, HAST, Dsrc, Ddst, M>,
+        _label_store: &'a HAST::LS,
+        size_threshold: usize,
+        sim_threshold: f64,
+    ) {
         assert_eq!(
             // TODO move it inside the arena ...
             internal.src_arena.root(),
@@ -216,14 +249,14 @@ where
                         &internal.mappings,
                     )
                     .dice();
-                    if sim > max && sim >= SIM_THRESHOLD_NUM as f64 / SIM_THRESHOLD_DEN as f64 {
+                    if sim > max && sim >= sim_threshold {
                         max = sim;
                         best = Some(cand);
                     }
                 }
 
                 if let Some(best) = best {
-                    Self::last_chance_match_zs(internal, a, best);
+                    Self::last_chance_match_zs(internal, a, best, size_threshold);
                     internal.mappings.link(*a.shallow(), *best.shallow());
                 }
             }
@@ -235,7 +268,7 @@ where
         );
         let src = internal.src_arena.starter();
         let dst = internal.dst_arena.starter();
-        Self::last_chance_match_zs(internal, src, dst);
+        Self::last_chance_match_zs(internal, src, dst, size_threshold);
         // println!("nodes:{}", c);
         // println!("nodes:{}", c2);
     }
@@ -259,6 +292,7 @@ where
         internal: &mut Mapper<'a, HAST, Dsrc, Ddst, M>,
         src: Dsrc::IdD,
         dst: Ddst::IdD,
+        size_threshold: usize,
     ) {
         // allow using another internal mapping store
         // WIP https://blog.rust-lang.org/2022/10/28/gats-stabilization.html#implied-static-requirement-from-higher-ranked-trait-bounds
@@ -268,7 +302,7 @@ where
         let dst_arena = &mut mapping.dst_arena;
         let src_s = src_arena.descendants_count(node_store, &src);
         let dst_s = dst_arena.descendants_count(node_store, &dst);
-        if !(src_s < cast(SIZE_THRESHOLD).unwrap() || dst_s < cast(SIZE_THRESHOLD).unwrap()) {
+        if !(src_s < cast(size_threshold).unwrap() || dst_s < cast(size_threshold).unwrap()) {
             // dbg!(src_s, dst_s);
             return;
         }
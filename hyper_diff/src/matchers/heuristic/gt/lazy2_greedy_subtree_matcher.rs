@@ -50,6 +50,19 @@ where
     pub fn match_it<MM>(
         mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
     ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
+    where
+        Self: 'a,
+        MM: MultiMappingStore<Src = Dsrc::IdD, Dst = Ddst::IdD> + Default,
+    {
+        Self::match_it_with::<MM>(mapping, MIN_HEIGHT)
+    }
+
+    /// Same as [`Self::match_it`] but with the minimal height given at runtime,
+    /// instead of MIN_HEIGHT
+    pub fn match_it_with<MM>(
+        mapping: crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>,
+        min_height: usize,
+    ) -> crate::matchers::Mapper<'a, HAST, Dsrc, Ddst, M>
     where
         Self: 'a,
         MM: MultiMappingStore<Src = Dsrc::IdD, Dst = Ddst::IdD> + Default,
@@ -59,7 +72,8 @@ where
             matcher.internal.mapping.src_arena.len(),
             matcher.internal.mapping.dst_arena.len(),
         );
-        Self::execute::<MM>(&mut matcher);
+        let mm: MM = Self::compute_multi_mapping_with(&mut matcher.internal, min_height);
+        Self::filter_mappings(&mut matcher.internal, &mm);
         matcher.internal
     }
 
@@ -96,14 +110,24 @@ where
         MM: MultiMappingStore<Src = Dsrc::IdD, Dst = Ddst::IdD> + Default,
     >(
         internal: &mut Mapper<'a, HAST, Dsrc, Ddst, M>,
+    ) -> MM {
+        Self::compute_multi_mapping_with(internal, MIN_HEIGHT)
+    }
+
+    pub fn compute_multi_mapping_with<
+        MM: MultiMappingStore<Src = Dsrc::IdD, Dst = Ddst::IdD> + Default,
+    >(
+        internal: &mut Mapper<'a, HAST, Dsrc, Ddst, M>,
+        min_height: usize,
     ) -> MM {
         let mut mm: MM = Default::default();
         mm.topit(internal.src_arena.len(), internal.dst_arena.len());
-        Mapper::<HAST, Dsrc, Ddst, M>::compute_multimapping::<_, MIN_HEIGHT>(
+        Mapper::<HAST, Dsrc, Ddst, M>::compute_multimapping_with(
             internal.hyperast,
             &mut internal.mapping.src_arena,
             &mut internal.mapping.dst_arena,
             &mut mm,
+            min_height,
         );
         mm
     }
@@ -239,7 +263,7 @@ where
         let mut sib_sim = HashMap::<(Dsrc::IdD, Ddst::IdD), f64>::default();
         let mut psib_sim = HashMap::<(Dsrc::IdD, Ddst::IdD), f64>::default();
         let mut p_in_p_sim = HashMap::<(Dsrc::IdD, Ddst::IdD), f64>::default();
-        dbg!(&ambiguous_mappings.len());
+        log::debug!("ambiguous_mappings={}", ambiguous_mappings.len());
         ambiguous_mappings.sort_by(|a, b| {
             let cached_coef_sib = |l: &(Dsrc::IdD, Ddst::IdD)| {
                 sib_sim
@@ -486,7 +510,6 @@ where
     M::Src: Debug + Copy,
     M::Dst: Debug + Copy,
 {
-    #[time("warn")]
     pub fn compute_multimapping<
         MM: MultiMappingStore<Src = Dsrc::IdD, Dst = Ddst::IdD>,
         const MIN_HEIGHT: usize,
@@ -495,30 +518,35 @@ where
         src_arena: &mut Dsrc,
         dst_arena: &mut Ddst,
         multi_mappings: &mut MM,
+    ) {
+        Self::compute_multimapping_with(hyperast, src_arena, dst_arena, multi_mappings, MIN_HEIGHT)
+    }
+
+    #[time("warn")]
+    pub fn compute_multimapping_with<MM: MultiMappingStore<Src = Dsrc::IdD, Dst = Ddst::IdD>>(
+        hyperast: &'a HAST,
+        src_arena: &mut Dsrc,
+        dst_arena: &mut Ddst,
+        multi_mappings: &mut MM,
+        min_height: usize,
     ) {
         let now = std::time::Instant::now();
-        let mut src_trees = PriorityTreeList::<
-            'a,
-            '_,
-            Dsrc,
-            M::Src,
-            Dsrc::IdD,
-            HAST::T,
-            HAST::NS,
-            MIN_HEIGHT,
-        >::new(hyperast.node_store(), src_arena.starter(), src_arena);
-        let mut dst_trees = PriorityTreeList::<
-            'a,
-            '_,
-            Ddst,
-            M::Dst,
-            Ddst::IdD,
-            HAST::T,
-            HAST::NS,
-            MIN_HEIGHT,
-        >::new(hyperast.node_store(), dst_arena.starter(), dst_arena);
+        let mut src_trees =
+            PriorityTreeList::<'a, '_, Dsrc, M::Src, Dsrc::IdD, HAST::T, HAST::NS>::new(
+                hyperast.node_store(),
+                src_arena.starter(),
+                src_arena,
+                min_height,
+            );
+        let mut dst_trees =
+            PriorityTreeList::<'a, '_, Ddst, M::Dst, Ddst::IdD, HAST::T, HAST::NS>::new(
+                hyperast.node_store(),
+                dst_arena.starter(),
+                dst_arena,
+                min_height,
+            );
         let match_init_t = now.elapsed().as_secs_f64();
-        dbg!(match_init_t);
+        log::debug!("match_init_t={match_init_t}");
         while src_trees.peek_height() != -1 && dst_trees.peek_height() != -1 {
             // println!("multi_mappings={}", multi_mappings.len());
             while src_trees.peek_height() != dst_trees.peek_height() {
@@ -635,13 +663,14 @@ where
     }
 }
 
-struct PriorityTreeList<'a, 'b, D, IdS, IdD, T: Tree, S, const MIN_HEIGHT: usize> {
+struct PriorityTreeList<'a, 'b, D, IdS, IdD, T: Tree, S> {
     trees: Vec<Option<Vec<IdD>>>,
 
     store: &'a S,
     arena: &'b mut D,
 
     max_height: usize,
+    min_height: usize,
 
     current_idx: isize,
 
@@ -655,16 +684,15 @@ impl<
         IdD,
         T: Tree + WithStats,
         S: 'a + NodeStore<T::TreeId, R<'a> = T>,
-        const MIN_HEIGHT: usize,
-    > PriorityTreeList<'a, 'b, D, IdD, D::IdD, T, S, MIN_HEIGHT>
+    > PriorityTreeList<'a, 'b, D, IdD, D::IdD, T, S>
 where
     T::TreeId: Clone,
     D::IdD: Clone,
 {
-    pub(super) fn new(store: &'a S, tree: D::IdD, arena: &'b mut D) -> Self {
+    pub(super) fn new(store: &'a S, tree: D::IdD, arena: &'b mut D, min_height: usize) -> Self {
         let h = store.resolve(&arena.original(&tree)).height() - 1;
-        let list_size = if h >= MIN_HEIGHT {
-            h + 1 - MIN_HEIGHT
+        let list_size = if h >= min_height {
+            h + 1 - min_height
         } else {
             0
         };
@@ -673,6 +701,7 @@ where
             store,
             arena,
             max_height: h,
+            min_height,
             current_idx: if list_size == 0 { -1 } else { 0 },
             phantom: PhantomData,
         };
@@ -694,7 +723,7 @@ where
     }
 
     fn add_tree_aux(&mut self, tree: D::IdD, h: usize) {
-        if h >= MIN_HEIGHT {
+        if h >= self.min_height {
             let idx = self.idx(h);
             let t = self.trees[idx].get_or_insert_with(Vec::new);
             t.push(tree);
@@ -0,0 +1,454 @@
+//! Three-way structural merge of HyperASTs, see [`merge`].
+//!
+//! The base is matched with each side using the Gumtree matchers, see [`gumtree::diff`].
+//! Each node of the base is then placed, labeled and ordered among its siblings
+//! like the side that changed it.
+//! When both sides changed a node in different ways, a [`Conflict`] is reported
+//! and the merged tree follows the left side.
+
+use std::fmt::Debug;
+
+use hyper_ast::{
+    nodes::IndentedSerializer,
+    store::{
+        defaults::{LabelIdentifier, NodeIdentifier},
+        nodes::legion::HashedNodeRef,
+        SimpleStores,
+    },
+    types::{
+        self, AnyType, HyperAST, Labeled, NodeId, NodeStore, NodeStoreExt, TypeStore, Typed,
+        TypedNodeId, WithChildren,
+    },
+    PrimInt,
+};
+
+use crate::{
+    actions::{action_vec::ActionsVec, apply::LegionStoreMut, script_generator2::SimpleAction},
+    algorithms::gumtree,
+    decompressed_tree_store::{
+        CompletePostOrder, DecompressedWithParent, ShallowDecompressedTreeStore,
+    },
+    matchers::mapping_store::{MonoMappingStore, VecStore},
+    tree::tree_path::CompressedTreePath,
+};
+
+type CDS<T> = CompletePostOrder<T, u32>;
+
+/// A change made by one side on a node of the base
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Change {
+    /// its label changed
+    Update,
+    /// it is in another parent or at another offset
+    Move,
+    Delete,
+    /// something changed in its subtree
+    Modify,
+}
+
+/// A node of the base changed by both sides in incompatible ways.
+#[derive(Clone, Debug)]
+pub struct Conflict<IdN, Idx> {
+    /// the subtree in the base
+    pub base: IdN,
+    /// the offsets leading to the node from the root of the base
+    pub path: Vec<Idx>,
+    pub left: Change,
+    pub right: Change,
+}
+
+/// The merged tree, to be inserted in a node store with [`build`]
+#[derive(Clone, Debug)]
+pub enum MergedTree<IdN, L> {
+    /// unchanged on both sides
+    Same(IdN),
+    Node {
+        /// the node of the base, or of the side which inserted it
+        ori: IdN,
+        label: Option<L>,
+        children: Vec<MergedTree<IdN, L>>,
+    },
+}
+
+pub struct Merge<R, IdN, Idx, A> {
+    pub merged: R,
+    /// the merged tree follows the left side on each conflict
+    pub conflicts: Vec<Conflict<IdN, Idx>>,
+    /// the edit script from the base to the left side
+    pub left: Option<ActionsVec<A>>,
+    /// the edit script from the base to the right side
+    pub right: Option<ActionsVec<A>>,
+}
+
+/// Merges the changes made from `base` to `left` and from `base` to `right`.
+///
+/// Subtrees inserted on both sides are only kept once if they are the same.
+pub fn merge<'store, HAST: HyperAST<'store>>(
+    hyperast: &'store HAST,
+    base: &HAST::IdN,
+    left: &HAST::IdN,
+    right: &HAST::IdN,
+) -> Merge<
+    MergedTree<HAST::IdN, HAST::Label>,
+    HAST::IdN,
+    HAST::Idx,
+    SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>,
+>
+where
+    HAST::IdN: Clone + Debug + Eq,
+    HAST::Idx: PrimInt,
+    HAST::Label: Debug + Clone + Copy,
+    HAST::T: 'store + types::WithHashs + types::WithStats,
+{
+    let l = gumtree::diff(hyperast, base, left);
+    let r = gumtree::diff(hyperast, base, right);
+    let mut merger = Merger {
+        store: hyperast.node_store(),
+        base: &l.mapper.mapping.src_arena,
+        left: &l.mapper.mapping.dst_arena,
+        right: &r.mapper.mapping.dst_arena,
+        ml: &l.mapper.mapping.mappings,
+        mr: &r.mapper.mapping.mappings,
+        locs: vec![],
+        conflicts: vec![],
+    };
+    merger.locate();
+    let merged = merger.tree(merger.root());
+    let conflicts = merger.conflicts;
+    Merge {
+        merged,
+        conflicts,
+        left: l.actions,
+        right: r.actions,
+    }
+}
+
+/// Inserts the merged tree in `store`, reusing the nodes already there, and returns its root.
+pub fn build<T, S>(store: &mut S, tree: MergedTree<T::TreeId, T::Label>) -> T::TreeId
+where
+    T: types::TypedTree,
+    S: NodeStoreExt<T> + NodeStore<T::TreeId>,
+    for<'d> S::R<'d>: types::TypedTree<
+        TreeId = T::TreeId,
+        Type = T::Type,
+        Label = T::Label,
+        ChildIdx = T::ChildIdx,
+    >,
+{
+    match tree {
+        MergedTree::Same(id) => id,
+        MergedTree::Node {
+            ori,
+            label,
+            children,
+        } => {
+            let cs = children.into_iter().map(|c| build(store, c)).collect();
+            let t = store.resolve(&ori).get_type();
+            store.build_then_insert(ori, t, label, cs)
+        }
+    }
+}
+
+/// [`merge`] then [`build`] on HyperASTs backed by legion.
+///
+/// `TIdN` gives the language of the trees, see [`crate::actions::apply::apply_on`].
+pub fn merge_on<TIdN, TS>(
+    stores: &mut SimpleStores<TS>,
+    base: NodeIdentifier,
+    left: NodeIdentifier,
+    right: NodeIdentifier,
+) -> Merge<
+    NodeIdentifier,
+    NodeIdentifier,
+    u16,
+    SimpleAction<LabelIdentifier, CompressedTreePath<u16>, NodeIdentifier>,
+>
+where
+    TIdN: 'static + TypedNodeId<IdN = NodeIdentifier>,
+    TIdN::Ty: 'static + Debug,
+    for<'a> TS: TypeStore<HashedNodeRef<'a, NodeIdentifier>, Ty = AnyType>,
+{
+    let Merge {
+        merged,
+        conflicts,
+        left,
+        right,
+    } = merge(&*stores, &base, &left, &right);
+    let SimpleStores {
+        node_store,
+        label_store,
+        ..
+    } = stores;
+    let mut store = LegionStoreMut::<TIdN>::new(node_store, label_store);
+    let merged = build::<HashedNodeRef<'static, TIdN>, _>(&mut store, merged);
+    Merge {
+        merged,
+        conflicts,
+        left,
+        right,
+    }
+}
+
+/// Serializes the tree at `root`, eg. a merged tree, back to source code,
+/// keeping the indentation of the subtrees coming from another place.
+pub fn to_source<'store, IdN, HAST>(stores: &'store HAST, root: IdN) -> String
+where
+    IdN: NodeId<IdN = IdN>,
+    HAST: NodeStore<IdN> + types::LabelStore<str> + TypeStore<HAST::R<'store>>,
+    HAST::R<'store>: Labeled<Label = HAST::I> + WithChildren<TreeId = IdN>,
+{
+    IndentedSerializer::<_, _>::new(stores, root).to_string()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// A node of the merged tree,
+/// given by its decompressed id in the base or in the side which inserted it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Elem {
+    Base(u32),
+    New(Side, u32),
+}
+
+/// The parent of a node of the base
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Loc {
+    Root,
+    Deleted,
+    In(Elem),
+}
+
+struct Merger<'a, 'store, T: WithChildren, S> {
+    store: &'store S,
+    base: &'a CDS<T>,
+    left: &'a CDS<T>,
+    right: &'a CDS<T>,
+    ml: &'a VecStore<u32>,
+    mr: &'a VecStore<u32>,
+    /// the merged parent of each node of the base
+    locs: Vec<Loc>,
+    conflicts: Vec<Conflict<T::TreeId, T::ChildIdx>>,
+}
+
+impl<'a, 'store, T, S> Merger<'a, 'store, T, S>
+where
+    T: types::Tree,
+    T::TreeId: Clone + Eq,
+    T::Label: Copy,
+    S: NodeStore<T::TreeId, R<'store> = T>,
+{
+    fn side(&self, s: Side) -> (&'a CDS<T>, &'a VecStore<u32>) {
+        match s {
+            Side::Left => (self.left, self.ml),
+            Side::Right => (self.right, self.mr),
+        }
+    }
+
+    /// the element of the node `x` of a side
+    fn elem(&self, s: Side, x: u32) -> Elem {
+        self.side(s)
+            .1
+            .get_src(&x)
+            .map_or(Elem::New(s, x), Elem::Base)
+    }
+
+    /// the node of a side corresponding to the element `e`
+    fn view(&self, s: Side, e: Elem) -> Option<u32> {
+        match e {
+            Elem::Base(b) => self.side(s).1.get_dst(&b),
+            Elem::New(s2, x) => (s == s2).then_some(x),
+        }
+    }
+
+    fn original(&self, e: Elem) -> T::TreeId {
+        match e {
+            Elem::Base(b) => self.base.original(&b),
+            Elem::New(s, x) => self.side(s).0.original(&x),
+        }
+    }
+
+    fn label(&self, id: &T::TreeId) -> Option<T::Label> {
+        self.store.resolve(id).try_get_label().copied()
+    }
+
+    /// the parent of the node `b` of the base on a side
+    fn loc(&self, s: Side, b: u32) -> Loc {
+        let (arena, m) = self.side(s);
+        match m.get_dst(&b) {
+            None => Loc::Deleted,
+            Some(x) => arena
+                .parent(&x)
+                .map_or(Loc::Root, |p| Loc::In(self.elem(s, p))),
+        }
+    }
+
+    fn modified(&self, s: Side, b: u32) -> bool {
+        let (arena, m) = self.side(s);
+        m.get_dst(&b)
+            .map_or(true, |x| arena.original(&x) != self.base.original(&b))
+    }
+
+    fn conflict(&mut self, b: u32, left: Change, right: Change) {
+        self.conflicts.push(Conflict {
+            base: self.base.original(&b),
+            path: self.base.path_rooted(&b),
+            left,
+            right,
+        });
+    }
+
+    /// Computes the merged parent of each node of the base,
+    /// parents first so that deletions of whole subtrees are only considered at their root.
+    fn locate(&mut self) {
+        self.locs = vec![Loc::Deleted; self.base.len()];
+        for b in (0..self.base.len() as u32).rev() {
+            let p = self.base.parent(&b);
+            let o = p.map_or(Loc::Root, |p| Loc::In(Elem::Base(p)));
+            let l = self.loc(Side::Left, b);
+            let r = self.loc(Side::Right, b);
+            let loc = if l == o {
+                let parent_deleted = p.map_or(false, |p| self.mr.get_dst(&p).is_none());
+                if r != Loc::Deleted {
+                    r
+                } else if parent_deleted {
+                    // follows its parent, which may have been kept on a conflict
+                    match p.map(|p| self.locs[p as usize]) {
+                        None | Some(Loc::Deleted) => r,
+                        Some(_) => l,
+                    }
+                } else if self.modified(Side::Left, b) {
+                    self.conflict(b, Change::Modify, Change::Delete);
+                    l
+                } else {
+                    r
+                }
+            } else if r == o {
+                let parent_deleted = p.map_or(false, |p| self.ml.get_dst(&p).is_none());
+                if l == Loc::Deleted && !parent_deleted && self.modified(Side::Right, b) {
+                    self.conflict(b, Change::Delete, Change::Modify);
+                }
+                l
+            } else if l == r {
+                l
+            } else {
+                let change = |x| match x {
+                    Loc::Deleted => Change::Delete,
+                    _ => Change::Move,
+                };
+                self.conflict(b, change(l), change(r));
+                l
+            };
+            self.locs[b as usize] = loc;
+        }
+    }
+
+    fn root(&self) -> Elem {
+        match self.locs.iter().position(|x| *x == Loc::Root) {
+            Some(b) => Elem::Base(b as u32),
+            None => self.elem(Side::Left, self.left.root()),
+        }
+    }
+
+    fn tree(&mut self, e: Elem) -> MergedTree<T::TreeId, T::Label> {
+        let ori = self.original(e);
+        let l = self.view(Side::Left, e);
+        let r = self.view(Side::Right, e);
+        let same = |s, x: Option<u32>| x.map_or(false, |x| self.side(s).0.original(&x) == ori);
+        if matches!(e, Elem::Base(_)) && same(Side::Left, l) && same(Side::Right, r) {
+            return MergedTree::Same(ori);
+        }
+        let label = match e {
+            Elem::Base(b) => {
+                let o = self.label(&ori);
+                let label =
+                    |s, x: Option<u32>| x.map_or(o, |x| self.label(&self.side(s).0.original(&x)));
+                let (ll, lr) = (label(Side::Left, l), label(Side::Right, r));
+                if ll == o {
+                    lr
+                } else if lr == o || ll == lr {
+                    ll
+                } else {
+                    self.conflict(b, Change::Update, Change::Update);
+                    ll
+                }
+            }
+            Elem::New(..) => self.label(&ori),
+        };
+        let children = self
+            .children(e, l, r)
+            .into_iter()
+            .map(|c| self.tree(c))
+            .collect();
+        MergedTree::Node {
+            ori,
+            label,
+            children,
+        }
+    }
+
+    /// Gives the merged children of `e`, ordered like the side which reordered them.
+    fn children(&mut self, e: Elem, l: Option<u32>, r: Option<u32>) -> Vec<Elem> {
+        let seq = |s: Side, x: Option<u32>| -> Vec<Elem> {
+            let Some(x) = x else { return vec![] };
+            (self.side(s).0.children(self.store, &x).into_iter())
+                .map(|c| self.elem(s, c))
+                .filter(|c| match c {
+                    Elem::Base(b) => self.locs[*b as usize] == Loc::In(e),
+                    Elem::New(..) => true,
+                })
+                .collect()
+        };
+        let left = seq(Side::Left, l);
+        let mut right = seq(Side::Right, r);
+        // the same subtree inserted on both sides
+        for c in right.iter_mut() {
+            if let Elem::New(_, x) = *c {
+                let id = self.right.original(&x);
+                let same = |d: &&Elem| matches!(d, Elem::New(_, y) if self.left.original(y) == id);
+                if let Some(d) = left.iter().find(same) {
+                    *c = *d;
+                }
+            }
+        }
+        let base = match e {
+            Elem::Base(b) => self.base.children(self.store, &b),
+            Elem::New(..) => vec![],
+        };
+        let common = |xs: &[Elem]| -> Vec<Elem> {
+            (xs.iter())
+                .filter(|x| left.contains(x) && right.contains(x))
+                .copied()
+                .collect()
+        };
+        let o = common(&base.into_iter().map(Elem::Base).collect::<Vec<_>>());
+        let (ol, or) = (common(&left), common(&right));
+        if ol == o {
+            interleave(right, left)
+        } else {
+            if or != o && ol != or {
+                if let Elem::Base(b) = e {
+                    self.conflict(b, Change::Move, Change::Move);
+                }
+            }
+            interleave(left, right)
+        }
+    }
+}
+
+/// Inserts the elements of `other` missing from `skeleton` after the ones preceding them in `other`.
+fn interleave(mut skeleton: Vec<Elem>, other: Vec<Elem>) -> Vec<Elem> {
+    let mut at = 0;
+    for x in other {
+        if let Some(i) = skeleton.iter().position(|y| *y == x) {
+            at = i + 1;
+        } else {
+            skeleton.insert(at, x);
+            at += 1;
+        }
+    }
+    skeleton
+}
@@ -0,0 +1,185 @@
+//! Rendering of located actions in the output formats of GumTree,
+//! see [`render`] for JSON, [`render_text`] and [`render_xml`].
+//!
+//! Nodes are formatted like `identifier: foo [10,13]`,
+//! a [`ActionKind::MovUpd`] is rendered as a `move-tree` followed by an `update-node`.
+
+use std::fmt::Write;
+
+use super::{ActionKind, Located, LocatedAction};
+
+/// An action as GumTree outputs it
+struct Entry<'a> {
+    action: &'static str,
+    tree: &'a Located,
+    parent: Option<&'a (Located, usize)>,
+    label: Option<&'a str>,
+}
+
+fn entries(actions: &[LocatedAction]) -> Vec<Entry<'_>> {
+    let mut entries = vec![];
+    for a in actions {
+        let entry = |action, tree, at: bool, label: bool| Entry {
+            action,
+            tree,
+            parent: a.parent.as_ref().filter(|_| at),
+            label: a.new.as_deref().filter(|_| label),
+        };
+        match (a.kind, &a.src, &a.dst) {
+            (ActionKind::Delete, Some(src), _) => {
+                entries.push(entry("delete-node", src, false, false))
+            }
+            (ActionKind::Update, Some(src), _) => {
+                entries.push(entry("update-node", src, false, true))
+            }
+            (ActionKind::Move, Some(src), _) => entries.push(entry("move-tree", src, true, false)),
+            (ActionKind::MovUpd, Some(src), _) => {
+                entries.push(entry("move-tree", src, true, false));
+                entries.push(entry("update-node", src, false, true));
+            }
+            (ActionKind::Insert, _, Some(dst)) => {
+                entries.push(entry("insert-node", dst, true, false))
+            }
+            _ => (),
+        }
+    }
+    entries
+}
+
+/// Renders `matches` and `actions` like the JSON output of GumTree.
+pub fn render(matches: &[(Located, Located)], actions: &[LocatedAction]) -> String {
+    let mut out = String::new();
+    out.push_str("{\n  \"matches\": [");
+    for (i, (src, dst)) in matches.iter().enumerate() {
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        write!(
+            out,
+            "    {{\"src\": \"{}\", \"dest\": \"{}\"}}",
+            escape_json(&src.to_string()),
+            escape_json(&dst.to_string())
+        )
+        .unwrap();
+    }
+    out.push_str("\n  ],\n  \"actions\": [");
+    for (i, e) in entries(actions).into_iter().enumerate() {
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        write!(
+            out,
+            "    {{\"action\": \"{}\", \"tree\": \"{}\"",
+            e.action,
+            escape_json(&e.tree.to_string())
+        )
+        .unwrap();
+        if let Some((parent, at)) = e.parent {
+            write!(
+                out,
+                ", \"parent\": \"{}\", \"at\": {}",
+                escape_json(&parent.to_string()),
+                at
+            )
+            .unwrap();
+        }
+        if let Some(label) = e.label {
+            write!(out, ", \"label\": \"{}\"", escape_json(label)).unwrap();
+        }
+        out.push('}');
+    }
+    out.push_str("\n  ]\n}\n");
+    out
+}
+
+/// Renders `matches` and `actions` like the text output of GumTree.
+pub fn render_text(matches: &[(Located, Located)], actions: &[LocatedAction]) -> String {
+    let mut out = String::new();
+    for (src, dst) in matches {
+        writeln!(out, "===\nmatch\n---\n{}\n{}", src, dst).unwrap();
+    }
+    for e in entries(actions) {
+        writeln!(out, "===\n{}\n---\n{}", e.action, e.tree).unwrap();
+        if let Some((parent, at)) = e.parent {
+            writeln!(out, "to\n{}\nat {}", parent, at).unwrap();
+        }
+        if let Some(label) = e.label {
+            writeln!(
+                out,
+                "replace {} by {}",
+                e.tree.label.as_deref().unwrap_or(""),
+                label
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+/// Renders `matches` and `actions` like the XML output of GumTree.
+pub fn render_xml(matches: &[(Located, Located)], actions: &[LocatedAction]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<diff>\n  <matches>\n");
+    for (src, dst) in matches {
+        writeln!(
+            out,
+            "    <match src=\"{}\" dest=\"{}\"/>",
+            escape_xml(&src.to_string()),
+            escape_xml(&dst.to_string())
+        )
+        .unwrap();
+    }
+    out.push_str("  </matches>\n  <actions>\n");
+    for e in entries(actions) {
+        write!(
+            out,
+            "    <action type=\"{}\" tree=\"{}\"",
+            e.action,
+            escape_xml(&e.tree.to_string())
+        )
+        .unwrap();
+        if let Some((parent, at)) = e.parent {
+            write!(
+                out,
+                " parent=\"{}\" at=\"{}\"",
+                escape_xml(&parent.to_string()),
+                at
+            )
+            .unwrap();
+        }
+        if let Some(label) = e.label {
+            write!(out, " label=\"{}\"", escape_xml(label)).unwrap();
+        }
+        out.push_str("/>\n");
+    }
+    out.push_str("  </actions>\n</diff>\n");
+    out
+}
+
+fn escape_json(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' => out.push_str("&#10;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
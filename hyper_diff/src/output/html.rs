@@ -0,0 +1,142 @@
+//! Side-by-side HTML rendering of located actions, see [`render`].
+
+use std::fmt::Write;
+
+use super::{ActionKind, LocatedAction};
+
+const STYLE: &str = r#"
+table { width: 100%; border-collapse: collapse; }
+td { width: 50%; vertical-align: top; border: 1px solid #ccc; }
+pre { margin: 0; padding: 4px; }
+.del { background: #fdd; }
+.ins { background: #dfd; }
+.upd { background: #ffc; }
+.mov { background: #ddf; outline: 1px dashed #88c; }
+"#;
+
+struct Span {
+    start: usize,
+    end: usize,
+    class: &'static str,
+    /// the id of the span and the one of its counterpart in the other version
+    link: Option<(String, String)>,
+    title: String,
+}
+
+/// Renders `src` and `dst` side by side, highlighting the nodes touched by `actions`.
+///
+/// Deleted, inserted, updated and moved nodes are coloured differently,
+/// moved nodes link to their counterpart in the other version.
+/// `actions` must be located in `src` and `dst`, eg. the actions of a single file.
+pub fn render(src: &str, dst: &str, actions: &[LocatedAction]) -> String {
+    let mut src_spans = vec![];
+    let mut dst_spans = vec![];
+    for (i, a) in actions.iter().enumerate() {
+        let (src_class, dst_class) = match a.kind {
+            ActionKind::Delete => ("del", ""),
+            ActionKind::Update => ("upd", "upd"),
+            ActionKind::Move => ("mov", "mov"),
+            ActionKind::MovUpd => ("mov upd", "mov upd"),
+            ActionKind::Insert => ("", "ins"),
+        };
+        let moved = matches!(a.kind, ActionKind::Move | ActionKind::MovUpd);
+        let title = match (&a.src, &a.new) {
+            (Some(x), Some(new)) => format!("{:?}: {} -> {}", a.kind, x, new),
+            (Some(x), None) => format!("{:?}: {}", a.kind, x),
+            (None, _) => format!("{:?}", a.kind),
+        };
+        if let Some(x) = &a.src {
+            src_spans.push(Span {
+                start: x.start,
+                end: x.end,
+                class: src_class,
+                link: moved.then(|| (format!("src-{}", i), format!("dst-{}", i))),
+                title: title.clone(),
+            });
+        }
+        if let Some(x) = &a.dst {
+            dst_spans.push(Span {
+                start: x.start,
+                end: x.end,
+                class: dst_class,
+                link: moved.then(|| (format!("dst-{}", i), format!("src-{}", i))),
+                title,
+            });
+        }
+    }
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>");
+    out.push_str(STYLE);
+    out.push_str("</style>\n</head>\n<body>\n<table>\n<tr>\n<td><pre>");
+    highlight(&mut out, src, src_spans);
+    out.push_str("</pre></td>\n<td><pre>");
+    highlight(&mut out, dst, dst_spans);
+    out.push_str("</pre></td>\n</tr>\n</table>\n</body>\n</html>\n");
+    out
+}
+
+/// Writes `text` with `spans` as nested html spans,
+/// a span overlapping its parent is cut at the end of the parent.
+fn highlight(out: &mut String, text: &str, mut spans: Vec<Span>) {
+    spans.sort_by_key(|s| (s.start, std::cmp::Reverse(s.end)));
+    let mut opened: Vec<usize> = vec![];
+    let mut pos = 0;
+    let close = |out: &mut String, pos: &mut usize, end: usize| {
+        out.push_str(&escape(text.get(*pos..end).unwrap_or("")));
+        out.push_str("</span>");
+        *pos = (*pos).max(end);
+    };
+    for s in spans {
+        let start = s.start.min(text.len());
+        while let Some(&end) = opened.last() {
+            if end > start {
+                break;
+            }
+            close(out, &mut pos, end);
+            opened.pop();
+        }
+        out.push_str(&escape(text.get(pos..start).unwrap_or("")));
+        pos = pos.max(start);
+        let end = opened
+            .last()
+            .map_or(s.end, |&e| s.end.min(e))
+            .min(text.len());
+        write!(
+            out,
+            "<span class=\"{}\" title=\"{}\"",
+            s.class,
+            escape(&s.title)
+        )
+        .unwrap();
+        if let Some((id, other)) = &s.link {
+            write!(out, " id=\"{}\"><a href=\"#{}\">", id, other).unwrap();
+            // the link only wraps the first character to keep nested spans reachable
+            let first =
+                (text.get(pos..end).and_then(|t| t.chars().next())).map_or(0, |c| c.len_utf8());
+            out.push_str(&escape(&text[pos..pos + first]));
+            out.push_str("</a>");
+            pos += first;
+        } else {
+            out.push('>');
+        }
+        opened.push(end);
+    }
+    while let Some(end) = opened.pop() {
+        close(out, &mut pos, end);
+    }
+    out.push_str(&escape(text.get(pos..).unwrap_or("")));
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
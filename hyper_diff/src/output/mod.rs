@@ -0,0 +1,219 @@
+//! Rendering of edit scripts, eg. for code reviews or reports.
+//!
+//! Actions are first located in the source code of both versions with a [`Resolver`],
+//! then rendered as a unified diff with [`unified::render`],
+//! as a side-by-side HTML page with [`html::render`]
+//! or in the output formats of GumTree with [`gumtree::render`] for JSON,
+//! [`gumtree::render_text`] and [`gumtree::render_xml`].
+
+use std::{fmt::Display, path::PathBuf};
+
+use hyper_ast::{
+    position::compute_position,
+    types::{HyperAST, LabelStore, Labeled, NodeStore, TypeStore, WithSerialization},
+};
+use num_traits::ToPrimitive;
+
+use crate::{
+    actions::script_generator2::{Act, SimpleAction},
+    decompressed_tree_store::{
+        CompletePostOrder, DecompressedWithParent, ShallowDecompressedTreeStore,
+    },
+    matchers::{
+        mapping_store::{MonoMappingStore, VecStore},
+        Mapping,
+    },
+    tree::tree_path::{CompressedTreePath, TreePath},
+};
+
+pub mod gumtree;
+pub mod html;
+pub mod unified;
+
+type CDS<T> = CompletePostOrder<T, u32>;
+
+/// A node of one of the versions, located in its source code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Located {
+    /// the file containing the node, relative to the root of its version
+    pub file: PathBuf,
+    /// offset in bytes of the node in its file
+    pub start: usize,
+    pub end: usize,
+    pub kind: String,
+    pub label: Option<String>,
+}
+
+/// Formats the node like GumTree, eg. `identifier: foo [10,13]`
+impl Display for Located {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "{}: {} [{},{}]", self.kind, label, self.start, self.end),
+            None => write!(f, "{} [{},{}]", self.kind, self.start, self.end),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionKind {
+    Delete,
+    Update,
+    Move,
+    MovUpd,
+    Insert,
+}
+
+/// An action of an edit script, located in the source code of both versions
+#[derive(Clone, Debug)]
+pub struct LocatedAction {
+    pub kind: ActionKind,
+    /// the node in the source, for all actions except insertions
+    pub src: Option<Located>,
+    /// the node in the destination, for all actions except deletions
+    pub dst: Option<Located>,
+    /// the parent of the node in the destination and the offset of the node in it,
+    /// for insertions and moves
+    pub parent: Option<(Located, usize)>,
+    /// the new label, for updates
+    pub new: Option<String>,
+}
+
+/// Locates actions and mappings in the source code of the diffed versions
+pub struct Resolver<'a, 'store, HAST: HyperAST<'store>> {
+    stores: &'store HAST,
+    mapping: &'a Mapping<CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+}
+
+impl<'a, 'store, HAST> Resolver<'a, 'store, HAST>
+where
+    HAST: HyperAST<'store>,
+    HAST::IdN: Clone,
+    HAST::T: WithSerialization,
+{
+    /// `mapping` must be the one the actions to locate were generated from,
+    /// eg. the one of [`crate::algorithms::gumtree::diff`].
+    pub fn new(
+        stores: &'store HAST,
+        mapping: &'a Mapping<CDS<HAST::T>, CDS<HAST::T>, VecStore<u32>>,
+    ) -> Self {
+        Self { stores, mapping }
+    }
+
+    pub fn resolve(
+        &self,
+        action: &SimpleAction<HAST::Label, CompressedTreePath<HAST::Idx>, HAST::IdN>,
+    ) -> LocatedAction {
+        let path = |p: &CompressedTreePath<HAST::Idx>| p.iter().collect::<Vec<_>>();
+        let label = |l: &HAST::Label| self.stores.label_store().resolve(l).to_string();
+        let in_src = |p: &[HAST::Idx]| Some(self.locate(self.src_root(), p));
+        let in_dst = |p: &[HAST::Idx]| Some(self.locate(self.dst_root(), p));
+        let parent = |p: &[HAST::Idx]| {
+            let (o, p) = p.split_last()?;
+            Some((self.locate(self.dst_root(), p), o.to_usize().unwrap()))
+        };
+        // the path of insertions and moves is in the destination, the other ones are in the source
+        let ori = path(&action.path.ori);
+        let (kind, src, dst, new) = match &action.action {
+            Act::Delete {} => (ActionKind::Delete, in_src(&ori), None, None),
+            Act::Update { new } => {
+                let dst = self.mapped(&ori).and_then(|p| in_dst(&p));
+                (ActionKind::Update, in_src(&ori), dst, Some(label(new)))
+            }
+            Act::Move { from } => (
+                ActionKind::Move,
+                in_src(&path(&from.ori)),
+                in_dst(&ori),
+                None,
+            ),
+            Act::MovUpd { from, new } => {
+                let src = in_src(&path(&from.ori));
+                (ActionKind::MovUpd, src, in_dst(&ori), Some(label(new)))
+            }
+            Act::Insert { .. } => (ActionKind::Insert, None, in_dst(&ori), None),
+        };
+        let parent = match kind {
+            ActionKind::Insert | ActionKind::Move | ActionKind::MovUpd => parent(&ori),
+            ActionKind::Delete | ActionKind::Update => None,
+        };
+        LocatedAction {
+            kind,
+            src,
+            dst,
+            parent,
+            new,
+        }
+    }
+
+    /// Locates all the mapped nodes, in both versions
+    pub fn matches(&self) -> Vec<(Located, Located)> {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            mappings,
+        } = self.mapping;
+        (mappings.iter())
+            .map(|(x, y)| {
+                let x = self.locate(self.src_root(), &src_arena.path_rooted(&x));
+                let y = self.locate(self.dst_root(), &dst_arena.path_rooted(&y));
+                (x, y)
+            })
+            .collect()
+    }
+
+    fn src_root(&self) -> HAST::IdN {
+        let src_arena = &self.mapping.src_arena;
+        src_arena.original(&src_arena.root())
+    }
+
+    fn dst_root(&self) -> HAST::IdN {
+        let dst_arena = &self.mapping.dst_arena;
+        dst_arena.original(&dst_arena.root())
+    }
+
+    /// the path in the destination of the node mapped with the node at `path` in the source
+    fn mapped(&self, path: &[HAST::Idx]) -> Option<Vec<HAST::Idx>> {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            mappings,
+        } = self.mapping;
+        let x = src_arena.child(self.stores.node_store(), &src_arena.root(), path);
+        let y = mappings.get_dst(&x)?;
+        Some(dst_arena.path_rooted(&y))
+    }
+
+    fn locate(&self, root: HAST::IdN, path: &[HAST::Idx]) -> Located {
+        let (pos, x) = compute_position(root, &mut path.iter().copied(), self.stores);
+        let n = self.stores.node_store().resolve(&x);
+        let kind = self.stores.type_store().resolve_type(&n).to_string();
+        let label = n
+            .try_get_label()
+            .map(|l| self.stores.label_store().resolve(l).to_string());
+        let range = pos.range();
+        Located {
+            file: pos.file().to_path_buf(),
+            start: range.start,
+            end: range.end,
+            kind,
+            label,
+        }
+    }
+}
+
+/// The offsets in bytes of the start of each line of `text`
+fn line_starts(text: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+    if starts.last() == Some(&text.len()) && text.len() > 0 {
+        starts.pop();
+    }
+    starts
+}
+
+/// The index of the line containing the byte at `offset`
+fn line_of(starts: &[usize], offset: usize) -> usize {
+    match starts.binary_search(&offset) {
+        Ok(i) => i,
+        Err(i) => i.saturating_sub(1),
+    }
+}
@@ -0,0 +1,167 @@
+//! Unified diff like rendering of located actions, see [`render`].
+
+use std::fmt::Write;
+
+use crate::utils::sequence_algorithms::longest_common_subsequence;
+
+use super::{line_of, line_starts, ActionKind, LocatedAction};
+
+/// The number of unchanged lines around changes
+const CONTEXT: usize = 3;
+
+#[derive(Clone, Default)]
+struct Mark {
+    changed: bool,
+    /// the line in the other version, for moved lines
+    moved: Option<usize>,
+}
+
+enum Line {
+    Same(usize),
+    Del(usize),
+    Ins(usize),
+}
+
+/// Renders the changes from `src` to `dst` as a unified diff.
+///
+/// The lines touched by `actions` are removed from the source and added to the destination,
+/// moved lines are annotated with the line they are moved to or from,
+/// the other lines are aligned with a longest common subsequence.
+/// `actions` must be located in `src` and `dst`, eg. the actions of a single file.
+pub fn render(
+    src_name: &str,
+    src: &str,
+    dst_name: &str,
+    dst: &str,
+    actions: &[LocatedAction],
+) -> String {
+    let src_starts = line_starts(src);
+    let dst_starts = line_starts(dst);
+    let src_lines = lines(src, &src_starts);
+    let dst_lines = lines(dst, &dst_starts);
+    let mut src_marks = vec![Mark::default(); src_lines.len()];
+    let mut dst_marks = vec![Mark::default(); dst_lines.len()];
+    for a in actions {
+        let moved = matches!(a.kind, ActionKind::Move | ActionKind::MovUpd);
+        let src_line = a.src.as_ref().map(|x| line_of(&src_starts, x.start));
+        let dst_line = a.dst.as_ref().map(|x| line_of(&dst_starts, x.start));
+        if let Some(x) = &a.src {
+            let to = dst_line.filter(|_| moved);
+            mark(&mut src_marks, &src_starts, x.start, x.end, to);
+        }
+        if let Some(x) = &a.dst {
+            let from = src_line.filter(|_| moved);
+            mark(&mut dst_marks, &dst_starts, x.start, x.end, from);
+        }
+    }
+
+    let ops = align(&src_lines, &src_marks, &dst_lines, &dst_marks);
+    let changes: Vec<usize> = (0..ops.len())
+        .filter(|i| !matches!(ops[*i], Line::Same(_)))
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+    let mut out = String::new();
+    writeln!(out, "--- {}", src_name).unwrap();
+    writeln!(out, "+++ {}", dst_name).unwrap();
+    let mut k = 0;
+    while k < changes.len() {
+        let begin = changes[k].saturating_sub(CONTEXT);
+        while k + 1 < changes.len() && changes[k + 1] - changes[k] <= 2 * CONTEXT + 1 {
+            k += 1;
+        }
+        let end = (changes[k] + CONTEXT + 1).min(ops.len());
+        k += 1;
+
+        let (src_before, dst_before) = counts(&ops[..begin]);
+        let (src_len, dst_len) = counts(&ops[begin..end]);
+        let start = |before: usize, len: usize| if len == 0 { before } else { before + 1 };
+        writeln!(
+            out,
+            "@@ -{},{} +{},{} @@",
+            start(src_before, src_len),
+            src_len,
+            start(dst_before, dst_len),
+            dst_len
+        )
+        .unwrap();
+        for op in &ops[begin..end] {
+            match op {
+                Line::Same(i) => writeln!(out, " {}", src_lines[*i]),
+                Line::Del(i) => match src_marks[*i].moved {
+                    Some(to) => writeln!(out, "-{} [moved to +{}]", src_lines[*i], to + 1),
+                    None => writeln!(out, "-{}", src_lines[*i]),
+                },
+                Line::Ins(j) => match dst_marks[*j].moved {
+                    Some(from) => writeln!(out, "+{} [moved from -{}]", dst_lines[*j], from + 1),
+                    None => writeln!(out, "+{}", dst_lines[*j]),
+                },
+            }
+            .unwrap();
+        }
+    }
+    out
+}
+
+fn lines<'a>(text: &'a str, starts: &[usize]) -> Vec<&'a str> {
+    (0..starts.len())
+        .map(|i| {
+            let end = starts.get(i + 1).copied().unwrap_or(text.len());
+            text[starts[i]..end].trim_end_matches('\n')
+        })
+        .collect()
+}
+
+/// Marks the lines overlapping the bytes from `start` to `end`
+fn mark(marks: &mut [Mark], starts: &[usize], start: usize, end: usize, moved: Option<usize>) {
+    let first = line_of(starts, start);
+    let last = line_of(starts, end.saturating_sub(1).max(start));
+    for m in &mut marks[first..=last.min(marks.len() - 1)] {
+        m.changed = true;
+        m.moved = m.moved.or(moved);
+    }
+}
+
+/// Aligns the unchanged lines of both versions, the remaining ones are removed or added
+fn align(
+    src_lines: &[&str],
+    src_marks: &[Mark],
+    dst_lines: &[&str],
+    dst_marks: &[Mark],
+) -> Vec<Line> {
+    let kept = |marks: &[Mark]| {
+        (0..marks.len())
+            .filter(|i| !marks[*i].changed)
+            .collect::<Vec<_>>()
+    };
+    let src_kept = kept(src_marks);
+    let dst_kept = kept(dst_marks);
+    let anchors = longest_common_subsequence::<_, _, usize, _>(&src_kept, &dst_kept, |a, b| {
+        src_lines[*a] == dst_lines[*b]
+    });
+    let mut ops = vec![];
+    let (mut i, mut j) = (0, 0);
+    let anchors = (anchors.into_iter())
+        .map(|(a, b)| (src_kept[a], dst_kept[b]))
+        .chain([(src_lines.len(), dst_lines.len())]);
+    for (x, y) in anchors {
+        ops.extend((i..x).map(Line::Del));
+        ops.extend((j..y).map(Line::Ins));
+        if x < src_lines.len() {
+            ops.push(Line::Same(x));
+        }
+        i = x + 1;
+        j = y + 1;
+    }
+    ops
+}
+
+/// The number of lines of the source and of the destination in `ops`
+fn counts(ops: &[Line]) -> (usize, usize) {
+    ops.iter().fold((0, 0), |(s, d), op| match op {
+        Line::Same(_) => (s + 1, d + 1),
+        Line::Del(_) => (s + 1, d),
+        Line::Ins(_) => (s, d + 1),
+    })
+}
@@ -0,0 +1,438 @@
+//! Classification of the changes between two Java HyperASTs, see [`classify`].
+//!
+//! Instead of the insertions, deletions, updates and moves of an edit script,
+//! changes are described as refactorings or the like, eg. a renamed method or an added parameter.
+//! They are found from the mappings the edit scripts are generated from,
+//! between the declarations given by [`IterDeclarations`] and their members.
+
+use std::fmt::Display;
+
+use hyper_ast::{
+    nodes::TextSerializer,
+    position::{position_accessors::WithPreOrderOffsets, StructuralPosition},
+    store::{defaults::NodeIdentifier, nodes::legion::HashedNodeRef, SimpleStores},
+    types::{HyperType, TypeTrait, Typed},
+};
+use hyper_ast_gen_ts_java::{
+    types::{TIdN, TStore, Type},
+    usage::declarations::IterDeclarations,
+};
+
+use crate::{
+    algorithms::gumtree,
+    decompressed_tree_store::{
+        CompletePostOrder, DecompressedTreeStore, DecompressedWithParent,
+        ShallowDecompressedTreeStore,
+    },
+    matchers::{
+        mapping_store::{MonoMappingStore, VecStore},
+        Mapping,
+    },
+};
+
+type CDS<'store> = CompletePostOrder<HashedNodeRef<'store>, u32>;
+
+/// The mappings between two Java trees, eg. the ones of [`gumtree::diff`]
+pub type JavaMapping<'store> = Mapping<CDS<'store>, CDS<'store>, VecStore<u32>>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    MethodRenamed {
+        from: String,
+        to: String,
+    },
+    ParameterAdded {
+        method: String,
+        parameter: String,
+    },
+    FieldTypeChanged {
+        field: String,
+        from: String,
+        to: String,
+    },
+    /// the method `to` is new and contains statements moved from the method `from`
+    MethodExtracted {
+        from: String,
+        to: String,
+    },
+    ClassMoved {
+        class: String,
+        from: String,
+        to: String,
+    },
+    StatementReordered {
+        method: String,
+    },
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::MethodRenamed { from, to } => {
+                write!(f, "method `{}` renamed to `{}`", from, to)
+            }
+            ChangeKind::ParameterAdded { method, parameter } => {
+                write!(f, "parameter `{}` added to method `{}`", parameter, method)
+            }
+            ChangeKind::FieldTypeChanged { field, from, to } => write!(
+                f,
+                "type of field `{}` changed from `{}` to `{}`",
+                field, from, to
+            ),
+            ChangeKind::MethodExtracted { from, to } => {
+                write!(f, "method `{}` extracted from method `{}`", to, from)
+            }
+            ChangeKind::ClassMoved { class, from, to } => write!(
+                f,
+                "class `{}` moved from package `{}` to package `{}`",
+                class, from, to
+            ),
+            ChangeKind::StatementReordered { method } => {
+                write!(f, "statements reordered in method `{}`", method)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticChange {
+    pub kind: ChangeKind,
+    /// the offsets leading to the changed node from the root of the source
+    pub src: Option<Vec<u16>>,
+    /// the offsets leading to the changed node from the root of the destination
+    pub dst: Option<Vec<u16>>,
+}
+
+impl Display for SemanticChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+/// Matches `src` with `dst` using [`gumtree::diff`], then [`classify`] the changes.
+pub fn diff(
+    stores: &SimpleStores<TStore>,
+    src: NodeIdentifier,
+    dst: NodeIdentifier,
+) -> Vec<SemanticChange> {
+    let diff = gumtree::diff(stores, &src, &dst);
+    classify(stores, &diff.mapper.mapping)
+}
+
+/// Classifies the changes from the source to the destination of `mapping`,
+/// the changes not fitting any [`ChangeKind`] are ignored.
+pub fn classify<'store>(
+    stores: &'store SimpleStores<TStore>,
+    mapping: &JavaMapping<'store>,
+) -> Vec<SemanticChange> {
+    let mut classifier = Classifier {
+        stores,
+        mapping,
+        changes: vec![],
+    };
+    classifier.classify();
+    classifier.changes
+}
+
+struct Classifier<'a, 'store> {
+    stores: &'store SimpleStores<TStore>,
+    mapping: &'a JavaMapping<'store>,
+    changes: Vec<SemanticChange>,
+}
+
+impl<'a, 'store> Classifier<'a, 'store> {
+    fn classify(&mut self) {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            mappings,
+        } = self.mapping;
+        let is_class = |t: Type| t.is_type_declaration() && t != Type::EnumConstant;
+        for x in self.declarations(src_arena) {
+            let Some(y) = mappings.get_dst(&x) else {
+                continue;
+            };
+            if !self.ty(src_arena, x).map_or(false, is_class) {
+                continue;
+            }
+            self.class(x, y);
+            for m in self.members(src_arena, x) {
+                let Some(t) = self.ty(src_arena, m) else {
+                    continue;
+                };
+                let Some(n) = mappings.get_dst(&m) else {
+                    continue;
+                };
+                if t.is_executable_member() {
+                    self.method(m, n);
+                } else if t == Type::FieldDeclaration {
+                    self.field(m, n);
+                }
+            }
+        }
+        for y in self.declarations(dst_arena) {
+            let Some(t) = self.ty(dst_arena, y) else {
+                continue;
+            };
+            if t.is_parameter() && mappings.get_src(&y).is_none() {
+                self.parameter(y);
+            } else if is_class(t) {
+                for m in self.members(dst_arena, y) {
+                    let is_method = self
+                        .ty(dst_arena, m)
+                        .map_or(false, |t| t.is_executable_member());
+                    if is_method && mappings.get_src(&m).is_none() {
+                        self.extracted(m);
+                    }
+                }
+            }
+        }
+    }
+
+    fn method(&mut self, x: u32, y: u32) {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            mappings,
+        } = self.mapping;
+        let from = self.name(src_arena, x);
+        let to = self.name(dst_arena, y);
+        if from != to {
+            let kind = ChangeKind::MethodRenamed { from, to };
+            self.push(kind, Some(x), Some(y));
+        }
+        for b in src_arena.descendants(&self.stores.node_store, &x) {
+            let is_block = self
+                .ty(src_arena, b)
+                .map_or(false, |t| t.is_block_related());
+            if let (true, Some(c)) = (is_block, mappings.get_dst(&b)) {
+                self.block(b, c);
+            }
+        }
+    }
+
+    /// `y` is a new parameter, it is added if its method is still there
+    fn parameter(&mut self, y: u32) {
+        let Mapping {
+            dst_arena,
+            mappings,
+            ..
+        } = self.mapping;
+        let Some(ps) = dst_arena.parent(&y) else {
+            return;
+        };
+        if self.ty(dst_arena, ps) != Some(Type::FormalParameters) {
+            return;
+        }
+        let Some(m) = dst_arena.parent(&ps) else {
+            return;
+        };
+        let Some(x) = mappings.get_src(&m) else {
+            return;
+        };
+        let kind = ChangeKind::ParameterAdded {
+            method: self.name(dst_arena, m),
+            parameter: self.text(dst_arena, y),
+        };
+        self.push(kind, Some(x), Some(y));
+    }
+
+    fn field(&mut self, x: u32, y: u32) {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            ..
+        } = self.mapping;
+        let is_type = |t: Type| !t.is_spaces() && t != Type::Modifiers;
+        let (Some(a), Some(b)) = (
+            self.child(src_arena, x, is_type),
+            self.child(dst_arena, y, is_type),
+        ) else {
+            return;
+        };
+        if src_arena.original(&a) != dst_arena.original(&b) {
+            let kind = ChangeKind::FieldTypeChanged {
+                field: self.name(dst_arena, y),
+                from: self.text(src_arena, a),
+                to: self.text(dst_arena, b),
+            };
+            self.push(kind, Some(a), Some(b));
+        }
+    }
+
+    fn class(&mut self, x: u32, y: u32) {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            ..
+        } = self.mapping;
+        // nested classes move with the top-level ones
+        let top_level = |arena: &CDS<'store>, x: u32| {
+            let p = arena.parent(&x);
+            p.and_then(|p| self.ty(arena, p)) == Some(Type::Program)
+        };
+        if !top_level(src_arena, x) || !top_level(dst_arena, y) {
+            return;
+        }
+        let from = self.package(src_arena, x);
+        let to = self.package(dst_arena, y);
+        if from != to {
+            let kind = ChangeKind::ClassMoved {
+                class: self.name(dst_arena, y),
+                from,
+                to,
+            };
+            self.push(kind, Some(x), Some(y));
+        }
+    }
+
+    fn block(&mut self, x: u32, y: u32) {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            mappings,
+        } = self.mapping;
+        let dst_cs = self.children(dst_arena, y);
+        let positions: Vec<usize> = (self.children(src_arena, x).into_iter())
+            .filter_map(|c| mappings.get_dst(&c))
+            .filter_map(|c| dst_cs.iter().position(|d| *d == c))
+            .collect();
+        if positions.windows(2).any(|w| w[0] > w[1]) {
+            let method = self
+                .ancestor(dst_arena, y, |t| t.is_executable_member())
+                .map_or_else(String::new, |m| self.name(dst_arena, m));
+            self.push(ChangeKind::StatementReordered { method }, Some(x), Some(y));
+        }
+    }
+
+    /// `y` is a new method, it is extracted if it contains statements of a method still there
+    fn extracted(&mut self, y: u32) {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            mappings,
+        } = self.mapping;
+        for d in dst_arena.descendants(&self.stores.node_store, &y) {
+            let is_statement = self
+                .ty(dst_arena, d)
+                .map_or(false, |t| t.is_statement() && !t.is_block_related());
+            if !is_statement {
+                continue;
+            }
+            let Some(s) = mappings.get_src(&d) else {
+                continue;
+            };
+            let Some(m) = self.ancestor(src_arena, s, |t| t.is_executable_member()) else {
+                continue;
+            };
+            if mappings.get_dst(&m).is_some() {
+                let kind = ChangeKind::MethodExtracted {
+                    from: self.name(src_arena, m),
+                    to: self.name(dst_arena, y),
+                };
+                self.push(kind, Some(m), Some(y));
+                return;
+            }
+        }
+    }
+
+    fn push(&mut self, kind: ChangeKind, src: Option<u32>, dst: Option<u32>) {
+        let Mapping {
+            src_arena,
+            dst_arena,
+            ..
+        } = self.mapping;
+        self.changes.push(SemanticChange {
+            kind,
+            src: src.map(|x| src_arena.path_rooted(&x)),
+            dst: dst.map(|x| dst_arena.path_rooted(&x)),
+        });
+    }
+
+    /// the declarations in the tree of `arena`, see [`IterDeclarations`]
+    fn declarations(&self, arena: &CDS<'store>) -> Vec<u32> {
+        let root = arena.original(&arena.root());
+        let path: StructuralPosition<NodeIdentifier, u16> = StructuralPosition::new(root);
+        IterDeclarations::new(self.stores, path, root)
+            .map(|p| {
+                let offsets: Vec<u16> = p.iter_offsets().collect();
+                arena.child(&self.stores.node_store, &arena.root(), &offsets)
+            })
+            .collect()
+    }
+
+    /// the members of the type declaration `x`, eg. its methods and fields
+    fn members(&self, arena: &CDS<'store>, x: u32) -> Vec<u32> {
+        let mut members = vec![];
+        let mut bodies: Vec<u32> = self
+            .child(arena, x, |t| t.is_type_body())
+            .into_iter()
+            .collect();
+        while let Some(b) = bodies.pop() {
+            for c in self.children(arena, b) {
+                if self.ty(arena, c).map_or(false, |t| t.is_type_body()) {
+                    bodies.push(c);
+                } else {
+                    members.push(c);
+                }
+            }
+        }
+        members
+    }
+
+    fn ty(&self, arena: &CDS<'store>, x: u32) -> Option<Type> {
+        let id = arena.original(&x);
+        (self.stores.node_store)
+            .try_resolve_typed::<TIdN<NodeIdentifier>>(&id)
+            .map(|(n, _)| n.get_type())
+    }
+
+    fn children(&self, arena: &CDS<'store>, x: u32) -> Vec<u32> {
+        arena.children(&self.stores.node_store, &x)
+    }
+
+    /// the first child of `x` whose type matches `f`
+    fn child(&self, arena: &CDS<'store>, x: u32, f: impl Fn(Type) -> bool) -> Option<u32> {
+        (self.children(arena, x).into_iter()).find(|c| self.ty(arena, *c).map_or(false, &f))
+    }
+
+    /// `x` or its closest ancestor whose type matches `f`
+    fn ancestor(&self, arena: &CDS<'store>, x: u32, f: impl Fn(Type) -> bool) -> Option<u32> {
+        let mut x = Some(x);
+        while let Some(y) = x {
+            if self.ty(arena, y).map_or(false, &f) {
+                return Some(y);
+            }
+            x = arena.parent(&y);
+        }
+        None
+    }
+
+    /// the name of the declaration `x`
+    fn name(&self, arena: &CDS<'store>, x: u32) -> String {
+        let x = match self.ty(arena, x) {
+            Some(Type::FieldDeclaration) => self
+                .child(arena, x, |t| t == Type::VariableDeclarator)
+                .unwrap_or(x),
+            _ => x,
+        };
+        self.child(arena, x, |t| {
+            t == Type::Identifier || t == Type::TypeIdentifier
+        })
+        .map_or_else(String::new, |c| self.text(arena, c))
+    }
+
+    /// the package of the file containing `x`, empty for the default package
+    fn package(&self, arena: &CDS<'store>, x: u32) -> String {
+        self.ancestor(arena, x, |t| t == Type::Program)
+            .and_then(|f| self.child(arena, f, |t| t == Type::PackageDeclaration))
+            .and_then(|p| self.child(arena, p, |t| t.is_identifier()))
+            .map_or_else(String::new, |p| self.text(arena, p))
+    }
+
+    /// the code of `x` on a single line
+    fn text(&self, arena: &CDS<'store>, x: u32) -> String {
+        let text = TextSerializer::new(self.stores, arena.original(&x)).to_string();
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
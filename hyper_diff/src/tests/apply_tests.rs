@@ -0,0 +1,95 @@
+use hyper_ast::store::{defaults::NodeIdentifier, SimpleStores};
+
+use crate::{
+    actions::{
+        apply::{apply_on, same_structure},
+        Actions,
+    },
+    algorithms,
+};
+
+#[test]
+fn test_apply_java() {
+    use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+    use hyper_ast_gen_ts_java::types::{TIdN, TStore};
+    let src = r#"class A{class C{}class B{{while(1){if(1){}else{}};}}}"#;
+    let dst = r#"class A{class C{int f;}}class B{{while(2){if(1){}else{}};}}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = JavaTreeGen::new(&mut stores, &mut md_cache);
+    let mut parse = |text: &'static str| {
+        let tree = match JavaTreeGen::<TStore>::tree_sitter_parse(text.as_bytes()) {
+            Ok(t) => t,
+            Err(t) => t,
+        };
+        tree_gen
+            .generate_file(b"", text.as_bytes(), tree.walk())
+            .local
+            .compressed_node
+    };
+    let src = parse(src);
+    let dst = parse(dst);
+    round_trip::<TIdN<NodeIdentifier>, _>(&mut stores, src, dst);
+}
+
+#[test]
+fn test_apply_cpp() {
+    use hyper_ast_gen_ts_cpp::legion::CppTreeGen;
+    use hyper_ast_gen_ts_cpp::types::{TIdN, TStore};
+    let src = r#"int f(int a) {
+    return a + 1;
+}
+int g() {
+    return f(1);
+}"#;
+    let dst = r#"int g() {
+    return f(2);
+}
+int f(int a, int b) {
+    return a + b;
+}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let mut md_cache = Default::default();
+    let mut tree_gen = CppTreeGen {
+        line_break: "\n".as_bytes().to_vec(),
+        stores: &mut stores,
+        md_cache: &mut md_cache,
+    };
+    let mut parse = |text: &'static str| {
+        let tree = match CppTreeGen::<TStore>::tree_sitter_parse(text.as_bytes()) {
+            Ok(t) => t,
+            Err(t) => t,
+        };
+        tree_gen
+            .generate_file(b"", text.as_bytes(), tree.walk())
+            .local
+            .compressed_node
+    };
+    let src = parse(src);
+    let dst = parse(dst);
+    round_trip::<TIdN<NodeIdentifier>, _>(&mut stores, src, dst);
+}
+
+/// applying diff(src,dst) on src should give dst
+fn round_trip<TIdN, TS>(stores: &mut SimpleStores<TS>, src: NodeIdentifier, dst: NodeIdentifier)
+where
+    TIdN: 'static + hyper_ast::types::TypedNodeId<IdN = NodeIdentifier>,
+    TIdN::Ty: std::fmt::Debug,
+    for<'a> TS: hyper_ast::types::TypeStore<
+        hyper_ast::store::nodes::legion::HashedNodeRef<'a, NodeIdentifier>,
+        Ty = hyper_ast::types::AnyType,
+    >,
+{
+    let actions = algorithms::gumtree::diff(&*stores, &src, &dst)
+        .actions
+        .unwrap();
+    assert!(actions.len() > 0);
+    let SimpleStores {
+        node_store,
+        label_store,
+        ..
+    } = stores;
+    let applied = apply_on::<TIdN, _>(node_store, label_store, actions, src);
+    assert!(same_structure(node_store, applied, dst));
+    assert_eq!(applied, dst);
+}
@@ -0,0 +1,186 @@
+use std::marker::PhantomData;
+
+use hyper_ast::types::{
+    DecompressedSubtree, Labeled, NodeStore, NodeStoreExt, SimpleHyperAST, Typed,
+};
+
+use crate::{
+    actions::{
+        action_vec::{apply_actions, ActionsVec},
+        compose::{compose, composed, invert, mappings_of, reversed},
+        script_generator2::{ScriptGenerator, SimpleAction},
+        Actions,
+    },
+    decompressed_tree_store::{
+        bfs_wrapper::SimpleBfsMapper, CompletePostOrder, ShallowDecompressedTreeStore,
+    },
+    matchers::mapping_store::{DefaultMappingStore, MappingStore, MonoMappingStore},
+    tests::examples::example_action,
+    tree::{
+        simple_tree::{vpair_to_stores, Tree, TreeRef, NS},
+        tree_path::CompressedTreePath,
+    },
+};
+
+type IdD = u16;
+
+type Script = ActionsVec<SimpleAction<u16, CompressedTreePath<u8>, u16>>;
+
+fn apply(actions: Script, root: u16, node_store: &mut NS<Tree>) -> u16 {
+    let mut root = vec![root];
+    {
+        let node = node_store.resolve(&root[0]);
+        let t = node.get_type();
+        let l = node.try_get_label().cloned();
+        drop(node);
+        node_store.build_then_insert(root[0], t, l, vec![]);
+    }
+    apply_actions::<_, NS<Tree>, _>(actions, &mut root, node_store);
+    *root.last().unwrap()
+}
+
+fn generate(
+    node_store: &NS<Tree>,
+    src_arena: &CompletePostOrder<TreeRef<Tree>, IdD>,
+    dst_arena: &CompletePostOrder<TreeRef<Tree>, IdD>,
+    ms: &DefaultMappingStore<IdD>,
+) -> Script {
+    let dst_arena = SimpleBfsMapper::from(node_store, dst_arena);
+    ScriptGenerator::<
+        _,
+        TreeRef<Tree>,
+        _,
+        SimpleBfsMapper<_, _, CompletePostOrder<_, IdD>, _>,
+        NS<Tree>,
+        _,
+        _,
+    >::_compute_actions(node_store, src_arena, &dst_arena, ms)
+    .unwrap()
+}
+
+/// The mappings of [`example_action`], with moves, renames, insertions and deletions
+fn example_action_mappings(
+    node_store: &NS<Tree>,
+    src_arena: &CompletePostOrder<TreeRef<Tree>, IdD>,
+    dst_arena: &CompletePostOrder<TreeRef<Tree>, IdD>,
+) -> DefaultMappingStore<IdD> {
+    let mut ms = DefaultMappingStore::default();
+    let src = &(src_arena.root());
+    let dst = &(dst_arena.root());
+    ms.topit(src_arena.len(), dst_arena.len());
+    let from_src = |path: &[u8]| src_arena.child(node_store, src, path);
+    let from_dst = |path: &[u8]| dst_arena.child(node_store, dst, path);
+    ms.link(from_src(&[]), from_dst(&[]));
+    ms.link(from_src(&[1]), from_dst(&[0]));
+    ms.link(from_src(&[1, 0]), from_dst(&[0, 0]));
+    ms.link(from_src(&[1, 1]), from_dst(&[0, 1]));
+    ms.link(from_src(&[0]), from_dst(&[1, 0]));
+    ms.link(from_src(&[0, 0]), from_dst(&[1, 0, 0]));
+    ms.link(from_src(&[4]), from_dst(&[3]));
+    ms.link(from_src(&[4, 0]), from_dst(&[3, 0, 0, 0]));
+    ms
+}
+
+#[test]
+fn test_invert_and_compose_with_action_example() {
+    let (_label_store, mut node_store, src, dst) = vpair_to_stores(example_action());
+    let src_arena = CompletePostOrder::<_, IdD>::decompress(&node_store, &src);
+    let dst_arena = CompletePostOrder::<_, IdD>::decompress(&node_store, &dst);
+    let generate =
+        |src_arena: &CompletePostOrder<_, IdD>,
+         dst_arena: &CompletePostOrder<_, IdD>,
+         ms: &DefaultMappingStore<IdD>| { generate(&node_store, src_arena, dst_arena, ms) };
+
+    let ms = example_action_mappings(&node_store, &src_arena, &dst_arena);
+    let forward = generate(&src_arena, &dst_arena, &ms);
+
+    // the mappings are recovered from the script
+    let src_dst: DefaultMappingStore<IdD> =
+        mappings_of(&node_store, &src_arena, &dst_arena, &forward).unwrap();
+    assert_eq!(ms.src_to_dst, src_dst.src_to_dst);
+    assert_eq!(ms.dst_to_src, src_dst.dst_to_src);
+
+    let backward = generate(
+        &dst_arena,
+        &src_arena,
+        &reversed(&src_dst, dst_arena.len(), src_arena.len()),
+    );
+    let dst_src: DefaultMappingStore<IdD> =
+        mappings_of(&node_store, &dst_arena, &src_arena, &backward).unwrap();
+
+    // going there and back keeps all the mapped nodes in place
+    let round = composed(&src_dst, &dst_src, src_arena.len(), src_arena.len());
+    assert_eq!(ms.len(), round.len());
+    assert!(round.iter().all(|(x, y)| x == y));
+    let round = generate(&src_arena, &src_arena, &round);
+
+    assert_eq!(apply(forward, src, &mut node_store), dst);
+    assert_eq!(apply(backward, dst, &mut node_store), src);
+    assert_eq!(apply(round, src, &mut node_store), src);
+}
+
+#[test]
+fn test_invert() {
+    let (label_store, node_store, src, dst) = vpair_to_stores(example_action());
+    let src_arena = CompletePostOrder::<_, IdD>::decompress(&node_store, &src);
+    let dst_arena = CompletePostOrder::<_, IdD>::decompress(&node_store, &dst);
+    let ms = example_action_mappings(&node_store, &src_arena, &dst_arena);
+    let forward = generate(&node_store, &src_arena, &dst_arena, &ms);
+    let stores = SimpleHyperAST {
+        type_store: crate::tree::TStore,
+        node_store,
+        label_store,
+        _phantom: PhantomData::<TreeRef<Tree>>,
+    };
+
+    let backward = invert(&stores, &src, &dst, &forward).unwrap();
+
+    // the inverted script maps the nodes the other way around
+    let dst_src: DefaultMappingStore<IdD> =
+        mappings_of(&stores.node_store, &dst_arena, &src_arena, &backward).unwrap();
+    let expected = reversed(&ms, dst_arena.len(), src_arena.len());
+    assert_eq!(expected.src_to_dst, dst_src.src_to_dst);
+    assert_eq!(expected.dst_to_src, dst_src.dst_to_src);
+
+    let SimpleHyperAST { mut node_store, .. } = stores;
+    assert_eq!(apply(backward, dst, &mut node_store), src);
+}
+
+#[test]
+fn test_compose_with_inverse() {
+    let (label_store, node_store, src, dst) = vpair_to_stores(example_action());
+    let src_arena = CompletePostOrder::<_, IdD>::decompress(&node_store, &src);
+    let dst_arena = CompletePostOrder::<_, IdD>::decompress(&node_store, &dst);
+    let ms = example_action_mappings(&node_store, &src_arena, &dst_arena);
+    let forward = generate(&node_store, &src_arena, &dst_arena, &ms);
+    let stores = SimpleHyperAST {
+        type_store: crate::tree::TStore,
+        node_store,
+        label_store,
+        _phantom: PhantomData::<TreeRef<Tree>>,
+    };
+
+    let backward = invert(&stores, &src, &dst, &forward).unwrap();
+    // from dst back to src then to dst again
+    let dst_dst = compose(&stores, &dst, &src, &dst, &backward, &forward).unwrap();
+    // from src to dst then back to src
+    let src_src = compose(&stores, &src, &dst, &src, &forward, &backward).unwrap();
+
+    // all the mapped nodes stay in place
+    let round: DefaultMappingStore<IdD> =
+        mappings_of(&stores.node_store, &dst_arena, &dst_arena, &dst_dst).unwrap();
+    assert!((0..dst_arena.len() as IdD)
+        .filter(|x| ms.is_dst(x))
+        .all(|x| round.get_src(&x) == Some(x)));
+    assert!(round.iter().all(|(x, y)| x == y));
+    let round: DefaultMappingStore<IdD> =
+        mappings_of(&stores.node_store, &src_arena, &src_arena, &src_src).unwrap();
+    assert!((0..src_arena.len() as IdD)
+        .filter(|x| ms.is_src(x))
+        .all(|x| round.get_dst(&x) == Some(x)));
+    assert!(round.iter().all(|(x, y)| x == y));
+
+    let SimpleHyperAST { mut node_store, .. } = stores;
+    assert_eq!(apply(dst_dst, dst, &mut node_store), dst);
+    assert_eq!(apply(src_src, src, &mut node_store), src);
+}
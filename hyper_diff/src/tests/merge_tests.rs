@@ -0,0 +1,138 @@
+use hyper_ast::store::{defaults::NodeIdentifier, SimpleStores};
+use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+use hyper_ast_gen_ts_java::types::{TIdN, TStore};
+
+use crate::merge::{merge_on, to_source, Change};
+
+fn parse(stores: &mut SimpleStores<TStore>, text: &'static str) -> NodeIdentifier {
+    let mut md_cache = Default::default();
+    let mut tree_gen = JavaTreeGen::new(stores, &mut md_cache);
+    let tree = match JavaTreeGen::<TStore>::tree_sitter_parse(text.as_bytes()) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    tree_gen
+        .generate_file(b"", text.as_bytes(), tree.walk())
+        .local
+        .compressed_node
+}
+
+#[test]
+fn test_merge_java_without_conflicts() {
+    let base = r#"class A{void f(){a();}void g(){b();}}"#;
+    let left = r#"class A{void f(){c();}void g(){b();}}"#;
+    let right = r#"class A{void f(){a();}void g(){d();}}"#;
+    let expected = r#"class A{void f(){c();}void g(){d();}}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let base = parse(&mut stores, base);
+    let left = parse(&mut stores, left);
+    let right = parse(&mut stores, right);
+    let expected = parse(&mut stores, expected);
+    let merge = merge_on::<TIdN<NodeIdentifier>, _>(&mut stores, base, left, right);
+    assert!(merge.conflicts.is_empty());
+    assert_eq!(
+        to_source(&stores, merge.merged),
+        to_source(&stores, expected)
+    );
+    assert_eq!(merge.merged, expected);
+}
+
+#[test]
+fn test_merge_java_with_conflicting_updates() {
+    let base = r#"class A{void f(){a();}}"#;
+    let left = r#"class A{void f(){c();}}"#;
+    let right = r#"class A{void f(){e();}}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let base = parse(&mut stores, base);
+    let left = parse(&mut stores, left);
+    let right = parse(&mut stores, right);
+    let merge = merge_on::<TIdN<NodeIdentifier>, _>(&mut stores, base, left, right);
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].left, Change::Update);
+    assert_eq!(merge.conflicts[0].right, Change::Update);
+    // the left side is followed
+    assert_eq!(merge.merged, left);
+}
+
+#[test]
+fn test_merge_java_with_same_moves() {
+    let base = r#"class A{void f(){a();b();c();}}"#;
+    let left = r#"class A{void f(){b();a();c();}}"#;
+    let right = r#"class A{void f(){b();a();c();}}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let base = parse(&mut stores, base);
+    let left = parse(&mut stores, left);
+    let right = parse(&mut stores, right);
+    let merge = merge_on::<TIdN<NodeIdentifier>, _>(&mut stores, base, left, right);
+    assert!(merge.conflicts.is_empty());
+    assert_eq!(merge.merged, left);
+}
+
+#[test]
+fn test_merge_java_with_conflicting_moves() {
+    let base = r#"class A{void f(){a();b();c();}}"#;
+    let left = r#"class A{void f(){b();a();c();}}"#;
+    let right = r#"class A{void f(){a();c();b();}}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let base = parse(&mut stores, base);
+    let left = parse(&mut stores, left);
+    let right = parse(&mut stores, right);
+    let merge = merge_on::<TIdN<NodeIdentifier>, _>(&mut stores, base, left, right);
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].left, Change::Move);
+    assert_eq!(merge.conflicts[0].right, Change::Move);
+    // the order of the left side is followed
+    assert_eq!(merge.merged, left);
+}
+
+#[test]
+fn test_merge_java_with_delete_modify() {
+    let base = r#"class A{void f(){a();}void g(){b();}}"#;
+    let left = r#"class A{void f(){a();}}"#;
+    let right = r#"class A{void f(){a();}void g(){d();}}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let base = parse(&mut stores, base);
+    let left = parse(&mut stores, left);
+    let right = parse(&mut stores, right);
+    let merge = merge_on::<TIdN<NodeIdentifier>, _>(&mut stores, base, left, right);
+    assert_eq!(merge.conflicts.len(), 1);
+    assert_eq!(merge.conflicts[0].left, Change::Delete);
+    assert_eq!(merge.conflicts[0].right, Change::Modify);
+    // the deletion of the left side is followed
+    assert_eq!(merge.merged, left);
+}
+
+#[test]
+fn test_merge_java_with_inserts_on_both_sides() {
+    let base = r#"class A{void f(){a();}}"#;
+    let left = r#"class A{void f(){a();b();}}"#;
+    let right = r#"class A{void f(){a();c();}}"#;
+    let expected = r#"class A{void f(){a();b();c();}}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let base = parse(&mut stores, base);
+    let left = parse(&mut stores, left);
+    let right = parse(&mut stores, right);
+    let expected = parse(&mut stores, expected);
+    let merge = merge_on::<TIdN<NodeIdentifier>, _>(&mut stores, base, left, right);
+    assert!(merge.conflicts.is_empty());
+    assert_eq!(
+        to_source(&stores, merge.merged),
+        to_source(&stores, expected)
+    );
+    assert_eq!(merge.merged, expected);
+}
+
+#[test]
+fn test_merge_java_with_same_insert_on_both_sides() {
+    let base = r#"class A{void f(){a();}}"#;
+    let left = r#"class A{void f(){a();b();}}"#;
+    let right = r#"class A{void f(){a();b();}}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let base = parse(&mut stores, base);
+    let left = parse(&mut stores, left);
+    let right = parse(&mut stores, right);
+    let merge = merge_on::<TIdN<NodeIdentifier>, _>(&mut stores, base, left, right);
+    assert!(merge.conflicts.is_empty());
+    // inserted once
+    assert_eq!(merge.merged, left);
+}
@@ -1,10 +1,15 @@
 pub mod action_generator2_simple_tests;
 pub mod action_generator2_tests;
 pub mod action_generator_tests;
+pub mod apply_tests;
+pub mod compose_tests;
 #[cfg(test)]
 pub mod examples;
 pub mod hungarian_tests;
 #[cfg(test)]
 pub mod lazy_decompression_tests;
+pub mod merge_tests;
+pub mod output_tests;
 pub mod pair_tests;
+pub mod semantic_tests;
 pub mod simple_examples;
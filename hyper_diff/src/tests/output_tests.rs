@@ -0,0 +1,115 @@
+use hyper_ast::store::{defaults::NodeIdentifier, SimpleStores};
+use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+use hyper_ast_gen_ts_java::types::TStore;
+
+use crate::{
+    algorithms::gumtree,
+    merge::to_source,
+    output::{gumtree as gumtree_out, html, unified, ActionKind, Resolver},
+};
+
+fn parse(stores: &mut SimpleStores<TStore>, text: &'static str) -> NodeIdentifier {
+    let mut md_cache = Default::default();
+    let mut tree_gen = JavaTreeGen::new(stores, &mut md_cache);
+    let tree = match JavaTreeGen::<TStore>::tree_sitter_parse(text.as_bytes()) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    tree_gen
+        .generate_file(b"", text.as_bytes(), tree.walk())
+        .local
+        .compressed_node
+}
+
+#[test]
+fn test_render_java_update() {
+    let src = r#"class A {
+    void f() {
+        a();
+    }
+}"#;
+    let dst = r#"class A {
+    void f() {
+        b();
+    }
+}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let src = parse(&mut stores, src);
+    let dst = parse(&mut stores, dst);
+    let src_text = to_source(&stores, src);
+    let dst_text = to_source(&stores, dst);
+    let diff = gumtree::diff(&stores, &src, &dst);
+    let resolver = Resolver::new(&stores, &diff.mapper.mapping);
+    let actions: Vec<_> = (diff.actions.as_ref().unwrap().iter())
+        .map(|a| resolver.resolve(a))
+        .collect();
+
+    let update = actions
+        .iter()
+        .find(|a| a.kind == ActionKind::Update)
+        .expect("an update");
+    let src_node = update.src.as_ref().unwrap();
+    let dst_node = update.dst.as_ref().unwrap();
+    assert_eq!(&src_text[src_node.start..src_node.end], "a");
+    assert_eq!(&dst_text[dst_node.start..dst_node.end], "b");
+    assert_eq!(update.new.as_deref(), Some("b"));
+
+    let text = unified::render("a/A.java", &src_text, "b/A.java", &dst_text, &actions);
+    assert!(text.starts_with("--- a/A.java\n+++ b/A.java\n@@ -1,5 +1,5 @@\n"));
+    assert!(
+        text.contains("\n-        a();\n+        b();\n"),
+        "{}",
+        text
+    );
+
+    let page = html::render(&src_text, &dst_text, &actions);
+    assert!(page.contains(r#"<span class="upd""#), "{}", page);
+
+    let json = gumtree_out::render(&resolver.matches(), &actions);
+    assert!(json.contains(r#""action": "update-node""#), "{}", json);
+    assert!(json.contains(r#""label": "b""#), "{}", json);
+
+    let text = gumtree_out::render_text(&resolver.matches(), &actions);
+    assert!(text.contains("===\nupdate-node\n---\n"), "{}", text);
+    assert!(text.contains("replace a by b\n"), "{}", text);
+
+    let xml = gumtree_out::render_xml(&resolver.matches(), &actions);
+    assert!(xml.contains(r#"<action type="update-node""#), "{}", xml);
+}
+
+#[test]
+fn test_render_java_move() {
+    let src = r#"class A {
+    void f() {
+        a();
+        b();
+    }
+}"#;
+    let dst = r#"class A {
+    void f() {
+        b();
+        a();
+    }
+}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let src = parse(&mut stores, src);
+    let dst = parse(&mut stores, dst);
+    let src_text = to_source(&stores, src);
+    let dst_text = to_source(&stores, dst);
+    let diff = gumtree::diff(&stores, &src, &dst);
+    let resolver = Resolver::new(&stores, &diff.mapper.mapping);
+    let actions: Vec<_> = (diff.actions.as_ref().unwrap().iter())
+        .map(|a| resolver.resolve(a))
+        .collect();
+    assert!(actions.iter().any(|a| a.kind == ActionKind::Move));
+
+    let text = unified::render("a/A.java", &src_text, "b/A.java", &dst_text, &actions);
+    assert!(text.contains("[moved to +"), "{}", text);
+    assert!(text.contains("[moved from -"), "{}", text);
+
+    let page = html::render(&src_text, &dst_text, &actions);
+    assert!(page.contains(r##"<a href="#dst-"##), "{}", page);
+
+    let json = gumtree_out::render(&resolver.matches(), &actions);
+    assert!(json.contains(r#""action": "move-tree""#), "{}", json);
+}
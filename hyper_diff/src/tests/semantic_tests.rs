@@ -0,0 +1,100 @@
+use hyper_ast::store::{defaults::NodeIdentifier, SimpleStores};
+use hyper_ast_gen_ts_java::legion_with_refs::JavaTreeGen;
+use hyper_ast_gen_ts_java::types::TStore;
+
+use crate::semantic::{diff, ChangeKind};
+
+fn parse(stores: &mut SimpleStores<TStore>, text: &'static str) -> NodeIdentifier {
+    let mut md_cache = Default::default();
+    let mut tree_gen = JavaTreeGen::new(stores, &mut md_cache);
+    let tree = match JavaTreeGen::<TStore>::tree_sitter_parse(text.as_bytes()) {
+        Ok(t) => t,
+        Err(t) => t,
+    };
+    tree_gen
+        .generate_file(b"", text.as_bytes(), tree.walk())
+        .local
+        .compressed_node
+}
+
+#[test]
+fn test_classify_java_changes() {
+    let src = r#"package a;
+class A {
+    int f;
+    void m(int x) {
+        foo(x);
+        bar(x);
+    }
+}"#;
+    let dst = r#"package b;
+class A {
+    long f;
+    void n(int x, int y) {
+        bar(x);
+        foo(x);
+    }
+}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let src = parse(&mut stores, src);
+    let dst = parse(&mut stores, dst);
+    let changes = diff(&stores, src, dst);
+    let kinds: Vec<_> = changes.into_iter().map(|x| x.kind).collect();
+    let expected = [
+        ChangeKind::ClassMoved {
+            class: "A".into(),
+            from: "a".into(),
+            to: "b".into(),
+        },
+        ChangeKind::FieldTypeChanged {
+            field: "f".into(),
+            from: "int".into(),
+            to: "long".into(),
+        },
+        ChangeKind::MethodRenamed {
+            from: "m".into(),
+            to: "n".into(),
+        },
+        ChangeKind::ParameterAdded {
+            method: "n".into(),
+            parameter: "int y".into(),
+        },
+        ChangeKind::StatementReordered { method: "n".into() },
+    ];
+    for x in &expected {
+        assert!(kinds.contains(x), "{} not in {:?}", x, kinds);
+    }
+}
+
+#[test]
+fn test_classify_java_extracted_method() {
+    let src = r#"class A {
+    void m() {
+        foo();
+        bar();
+        baz();
+    }
+}"#;
+    let dst = r#"class A {
+    void m() {
+        foo();
+        n();
+    }
+    void n() {
+        bar();
+        baz();
+    }
+}"#;
+    let mut stores = SimpleStores::<TStore>::default();
+    let src = parse(&mut stores, src);
+    let dst = parse(&mut stores, dst);
+    let kinds: Vec<_> = diff(&stores, src, dst)
+        .into_iter()
+        .map(|x| x.kind)
+        .collect();
+    let expected = ChangeKind::MethodExtracted {
+        from: "m".into(),
+        to: "n".into(),
+    };
+    assert!(kinds.contains(&expected), "{:?}", kinds);
+}
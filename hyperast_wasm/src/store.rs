@@ -33,7 +33,7 @@ impl<'a> hyper_ast::types::TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TSto
     fn resolve_type(&self, n: &HashedNodeRef<'a, NodeIdentifier>) -> Self::Ty {
         let lang = n.get_lang();
         let t: &'static (dyn HyperType + 'static) = match lang {
-            "hyper_ast_gen_ts_ts::types::ts" => {
+            "hyper_ast_gen_ts_ts::types::Lang" => {
                 let raw = n.get_raw_type();
                 let t: &'static (dyn HyperType + 'static) =
                     <hyper_ast_gen_ts_ts::types::Ts as Lang<_>>::make(raw);
@@ -76,8 +76,8 @@ impl<'a> hyper_ast::types::TypeStore<HashedNodeRef<'a, NodeIdentifier>> for TSto
     ) -> hyper_ast::types::LangWrapper<Self::Ty> {
         let lang = n.get_lang();
         let t = match lang {
-            "hyper_ast_gen_ts_ts::types::Ts" => {
-                From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_ts::types::Ts)
+            "hyper_ast_gen_ts_ts::types::Lang" => {
+                From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_ts::types::Lang)
             }
             "hyper_ast_gen_ts_cpp::types::Cpp" => {
                 From::<&'static (dyn LangRef<AnyType>)>::from(&hyper_ast_gen_ts_cpp::types::Lang)